@@ -15,14 +15,19 @@ const (
 	methodTransfer = "accounts.Transfer"
 
 	// GB: InitOwners.
-	methodInitOwners = "accounts.InitOwners"
-	methodPropose    = "accounts.Propose"
-	methodVoteST     = "accounts.VoteST"
+	methodInitOwners      = "accounts.InitOwners"
+	methodPropose         = "accounts.Propose"
+	methodVoteST          = "accounts.VoteST"
+	methodChangeVote      = "accounts.ChangeVote"
+	methodDelegateVote    = "accounts.DelegateVote"
+	methodExecuteProposal = "accounts.ExecuteProposal"
 
 	// GB: insert methodMintST for MintST and methodBurnST.
 	methodMintST = "accounts.MintST"
 	methodBurnST = "accounts.BurnST"
 
+	methodSetMetadata = "accounts.SetMetadata"
+
 	// Queries.
 	methodParameters = "accounts.Parameters"
 	methodNonce      = "accounts.Nonce"
@@ -39,6 +44,10 @@ const (
 	methodBalances         = "accounts.Balances"
 	methodAddresses        = "accounts.Addresses"
 	methodDenominationInfo = "accounts.DenominationInfo"
+	methodTotalSupply      = "accounts.TotalSupply"
+	methodTotalSupplies    = "accounts.TotalSupplies"
+	methodHistory          = "accounts.History"
+	methodMetadata         = "accounts.Metadata"
 )
 
 // This interface seems defined for testing or web3?
@@ -58,6 +67,9 @@ type V1 interface {
 	MintST(to types.Address, amount types.BaseUnits) *client.TransactionBuilder
 	BurnST(amount types.BaseUnits) *client.TransactionBuilder
 
+	// SetMetadata generates an accounts.SetMetadata transaction.
+	SetMetadata(metadata *AccountMetadata) *client.TransactionBuilder
+
 	// Parameters queries the accounts module parameters.
 	Parameters(ctx context.Context, round uint64) (*Parameters, error)
 
@@ -81,6 +93,19 @@ type V1 interface {
 	// DenominationInfo queries the information about a given denomination.
 	DenominationInfo(ctx context.Context, round uint64, denomination types.Denomination) (*DenominationInfo, error)
 
+	// TotalSupply queries the total supply of a given denomination.
+	TotalSupply(ctx context.Context, round uint64, denomination types.Denomination) (*types.Quantity, error)
+
+	// TotalSupplies queries the total supplies of all denominations.
+	TotalSupplies(ctx context.Context, round uint64) (map[types.Denomination]types.Quantity, error)
+
+	// History queries the activity index of the given account, starting after the given sequence
+	// number.
+	History(ctx context.Context, round uint64, address types.Address, startAfter uint64, limit uint16) ([]*Activity, error)
+
+	// Metadata queries the profile metadata of the given account.
+	Metadata(ctx context.Context, round uint64, address types.Address) (*AccountMetadata, error)
+
 	// GetEvents returns all account events emitted in a given block.
 	GetEvents(ctx context.Context, round uint64) ([]*Event, error)
 }
@@ -264,6 +289,53 @@ func (a *v1) DenominationInfo(ctx context.Context, round uint64, denomination ty
 	return &info, nil
 }
 
+// Implements V1.
+func (a *v1) TotalSupply(ctx context.Context, round uint64, denomination types.Denomination) (*types.Quantity, error) {
+	var supply types.Quantity
+	err := a.rc.Query(ctx, round, methodTotalSupply, &TotalSupplyQuery{Denomination: denomination}, &supply)
+	if err != nil {
+		return nil, err
+	}
+	return &supply, nil
+}
+
+// Implements V1.
+func (a *v1) TotalSupplies(ctx context.Context, round uint64) (map[types.Denomination]types.Quantity, error) {
+	var supplies map[types.Denomination]types.Quantity
+	err := a.rc.Query(ctx, round, methodTotalSupplies, nil, &supplies)
+	if err != nil {
+		return nil, err
+	}
+	return supplies, nil
+}
+
+// Implements V1.
+func (a *v1) History(ctx context.Context, round uint64, address types.Address, startAfter uint64, limit uint16) ([]*Activity, error) {
+	var history []*Activity
+	err := a.rc.Query(ctx, round, methodHistory, &HistoryQuery{Address: address, StartAfter: startAfter, Limit: limit}, &history)
+	if err != nil {
+		return nil, err
+	}
+	return history, nil
+}
+
+// Implements V1.
+func (a *v1) SetMetadata(metadata *AccountMetadata) *client.TransactionBuilder {
+	return client.NewTransactionBuilder(a.rc, methodSetMetadata, &SetMetadata{
+		Metadata: metadata,
+	})
+}
+
+// Implements V1.
+func (a *v1) Metadata(ctx context.Context, round uint64, address types.Address) (*AccountMetadata, error) {
+	var metadata *AccountMetadata
+	err := a.rc.Query(ctx, round, methodMetadata, &MetadataQuery{Address: address}, &metadata)
+	if err != nil {
+		return nil, err
+	}
+	return metadata, nil
+}
+
 // Implements V1.
 func (a *v1) GetEvents(ctx context.Context, round uint64) ([]*Event, error) {
 	rawEvs, err := a.rc.GetEventsRaw(ctx, round)
@@ -351,6 +423,21 @@ func NewVoteSTTx(fee *types.Fee, body *VoteProposal) *types.Transaction {
 	return types.NewTransaction(fee, methodVoteST, body)
 }
 
+// NewChangeVoteTx generates a new accounts.ChangeVote transaction.
+func NewChangeVoteTx(fee *types.Fee, body *VoteProposal) *types.Transaction {
+	return types.NewTransaction(fee, methodChangeVote, body)
+}
+
+// NewDelegateVoteTx generates a new accounts.DelegateVote transaction.
+func NewDelegateVoteTx(fee *types.Fee, body *DelegateVote) *types.Transaction {
+	return types.NewTransaction(fee, methodDelegateVote, body)
+}
+
+// NewExecuteProposalTx generates a new accounts.ExecuteProposal transaction.
+func NewExecuteProposalTx(fee *types.Fee, body *ExecuteProposal) *types.Transaction {
+	return types.NewTransaction(fee, methodExecuteProposal, body)
+}
+
 // GB: NewMintSTTx generates a new accounts.MintST transaction.
 func NewMintSTTx(fee *types.Fee, body *MintST) *types.Transaction {
 	return types.NewTransaction(fee, methodMintST, body)
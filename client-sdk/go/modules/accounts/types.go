@@ -1,6 +1,8 @@
 package accounts
 
 import (
+	"github.com/oasisprotocol/oasis-core/go/common/crypto/hash"
+
 	"github.com/oasisprotocol/oasis-sdk/client-sdk/go/types"
 )
 
@@ -8,6 +10,28 @@ import (
 type Transfer struct {
 	To     types.Address   `json:"to"`
 	Amount types.BaseUnits `json:"amount"`
+	Memo   []byte          `json:"memo,omitempty"`
+}
+
+// AccountMetadata is the profile metadata of an account.
+type AccountMetadata struct {
+	// Name is the human readable name of the account.
+	Name string `json:"name,omitempty"`
+	// URI is the URI of further information about the account.
+	URI *string `json:"uri,omitempty"`
+	// Hash is the hash of the document at the URI.
+	Hash *hash.Hash `json:"hash,omitempty"`
+}
+
+// SetMetadata is the body for the accounts.SetMetadata call.
+type SetMetadata struct {
+	// Metadata is the new metadata of the caller's account. If nil, any metadata is removed.
+	Metadata *AccountMetadata `json:"metadata,omitempty"`
+}
+
+// MetadataQuery are the arguments for the accounts.Metadata query.
+type MetadataQuery struct {
+	Address types.Address `json:"address"`
 }
 
 // GB: RoleAddress is the body for the accounts.InitOwners call.
@@ -50,6 +74,20 @@ type VoteProposal struct {
 	Option types.Vote `json:"option"`
 }
 
+// ExecuteProposal is the body for the accounts.ExecuteProposal call.
+type ExecuteProposal struct {
+	ID uint32 `json:"id"`
+}
+
+// DelegateVote is the body for the accounts.DelegateVote call.
+type DelegateVote struct {
+	// Action is the action for which the voting power is delegated.
+	Action types.Action `json:"action"`
+	// Delegate is the address voting on behalf of the caller. If not set, any previous
+	// delegation for the action is removed.
+	Delegate *types.Address `json:"delegate,omitempty"`
+}
+
 // GB: MintST is the body for the accounts.MintST call.
 type MintST struct {
 	To     types.Address   `json:"to"`
@@ -108,6 +146,11 @@ type AddressesQuery struct {
 	Denomination types.Denomination `json:"denomination"`
 }
 
+// TotalSupplyQuery are the arguments for the accounts.TotalSupply query.
+type TotalSupplyQuery struct {
+	Denomination types.Denomination `json:"denomination"`
+}
+
 // DenominationInfoQuery are the arguments for the accounts.DenominationInfo query.
 type DenominationInfoQuery struct {
 	Denomination types.Denomination `json:"denomination"`
@@ -117,6 +160,29 @@ type DenominationInfoQuery struct {
 type DenominationInfo struct {
 	// Decimals is the number of decimals that the denomination is using.
 	Decimals uint8 `json:"decimals"`
+	// Symbol is the ticker symbol of the denomination.
+	Symbol string `json:"symbol,omitempty"`
+	// Name is the human readable name of the denomination.
+	Name string `json:"name,omitempty"`
+	// LogoURI is the URI of the denomination's logo.
+	LogoURI *string `json:"logo_uri,omitempty"`
+}
+
+// HistoryQuery are the arguments for the accounts.History query.
+type HistoryQuery struct {
+	Address    types.Address `json:"address"`
+	StartAfter uint64        `json:"start_after,omitempty"`
+	Limit      uint16        `json:"limit,omitempty"`
+}
+
+// Activity is an entry in the activity index of an account.
+type Activity struct {
+	// Seq is the sequence number of the entry, starting at one.
+	Seq uint64 `json:"seq"`
+	// Round is the round in which the activity took place.
+	Round uint64 `json:"round"`
+	// TxIndex is the index of the transaction within the round, if any.
+	TxIndex *uint32 `json:"tx_index,omitempty"`
 }
 
 // Addresses is the response of the accounts.Addresses or accounts.RoleAddresses query.
@@ -129,8 +195,9 @@ type GasCosts struct {
 	TxMintST uint64 `json:"tx_mintst"`
 	TxBurnST uint64 `json:"tx_burnst"`
 
-	TxInitOwners uint64 `json:"tx_initowners"`
-	TxManageST   uint64 `json:"tx_managest"`
+	TxInitOwners  uint64 `json:"tx_initowners"`
+	TxManageST    uint64 `json:"tx_managest"`
+	TxSetMetadata uint64 `json:"tx_set_metadata,omitempty"`
 }
 
 // Parameters are the parameters for the accounts module.
@@ -138,6 +205,7 @@ type Parameters struct {
 	TransfersDisabled      bool                                    `json:"transfers_disabled"`
 	MintSTDisabled         bool                                    `json:"mintst_disabled"`
 	BurnSTDisabled         bool                                    `json:"burnst_disabled"`
+	ChainInitiator         types.Address                           `json:"chain_initiator"`
 	GasCosts               GasCosts                                `json:"gas_costs"`
 	DebugDisableNonceCheck bool                                    `json:"debug_disable_nonce_check,omitempty"`
 	DenominationInfos      map[types.Denomination]DenominationInfo `json:"denomination_infos,omitempty"`
@@ -160,6 +228,7 @@ type TransferEvent struct {
 	From   types.Address   `json:"from"`
 	To     types.Address   `json:"to"`
 	Amount types.BaseUnits `json:"amount"`
+	Memo   []byte          `json:"memo,omitempty"`
 }
 
 // BurnEvent is the burn event.
@@ -12,8 +12,9 @@ import (
 
 const (
 	// Callable methods.
-	methodCreate = "evm.Create"
-	methodCall   = "evm.Call"
+	methodCreate            = "evm.Create"
+	methodCall              = "evm.Call"
+	methodAdvanceQueryNonce = "evm.AdvanceQueryNonce"
 
 	// Queries.
 	methodStorage      = "evm.Storage"
@@ -39,6 +40,10 @@ type V1 interface {
 	// high enough to cover the EVM gas price multiplied by the EVM gas limit.
 	Call(address []byte, value []byte, data []byte) *client.TransactionBuilder
 
+	// AdvanceQueryNonce generates an evm.AdvanceQueryNonce transaction, which revokes all signed
+	// queries carrying a lower query nonce.
+	AdvanceQueryNonce(queryNonce uint64) *client.TransactionBuilder
+
 	// Storage queries the EVM storage.
 	Storage(ctx context.Context, round uint64, address []byte, index []byte) ([]byte, error)
 
@@ -79,6 +84,13 @@ func (a *v1) Call(address []byte, value []byte, data []byte) *client.Transaction
 	})
 }
 
+// Implements V1.
+func (a *v1) AdvanceQueryNonce(queryNonce uint64) *client.TransactionBuilder {
+	return client.NewTransactionBuilder(a.rtc, methodAdvanceQueryNonce, &AdvanceQueryNonce{
+		QueryNonce: queryNonce,
+	})
+}
+
 // Implements V1.
 func (a *v1) Parameters(ctx context.Context, round uint64) (*Parameters, error) {
 	var params Parameters
@@ -96,6 +108,9 @@ func (a *v1) Storage(ctx context.Context, round uint64, address []byte, index []
 		Address: address,
 		Index:   index,
 	}
+	if round != client.RoundLatest {
+		q.Round = &round
+	}
 	if err := a.rtc.Query(ctx, round, methodStorage, q, &res); err != nil {
 		return nil, err
 	}
@@ -108,6 +123,9 @@ func (a *v1) Code(ctx context.Context, round uint64, address []byte) ([]byte, er
 	q := CodeQuery{
 		Address: address,
 	}
+	if round != client.RoundLatest {
+		q.Round = &round
+	}
 	if err := a.rtc.Query(ctx, round, methodCode, q, &res); err != nil {
 		return nil, err
 	}
@@ -137,6 +155,10 @@ func (a *v1) SimulateCall(ctx context.Context, round uint64, gasPrice []byte, ga
 		Value:    value,
 		Data:     data,
 	}
+	if round != client.RoundLatest {
+		// Make sure the runtime rejects the call instead of silently using different state.
+		q.Round = &round
+	}
 	if err := a.rtc.Query(ctx, round, methodSimulateCall, q, &res); err != nil {
 		return nil, err
 	}
@@ -33,6 +33,10 @@ type Leash struct {
 	BlockNumber uint64 `json:"block_number"`
 	BlockHash   []byte `json:"block_hash"`
 	BlockRange  uint64 `json:"block_range"`
+
+	// QueryNonce is the optional query nonce of the signer. Signed queries carrying a query nonce
+	// lower than the one set via `evm.AdvanceQueryNonce` are rejected.
+	QueryNonce *uint64 `json:"query_nonce,omitempty"`
 }
 
 // NewSignedCallDataPack returns a SignedCallDataPack.
@@ -62,6 +66,23 @@ func makeSignableCall(chainID uint64, caller, callee []byte, gasLimit uint64, ga
 	}
 	gasPriceU256 := math.HexOrDecimal256(*gasPrice)
 
+	leashType := []apitypes.Type{
+		{Name: "nonce", Type: "uint64"},
+		{Name: "blockNumber", Type: "uint64"},
+		{Name: "blockHash", Type: "bytes32"},
+		{Name: "blockRange", Type: "uint64"},
+	}
+	leashMessage := map[string]interface{}{
+		"nonce":       math.NewHexOrDecimal256(int64(leash.Nonce)),
+		"blockNumber": math.NewHexOrDecimal256(int64(leash.BlockNumber)),
+		"blockHash":   leash.BlockHash,
+		"blockRange":  math.NewHexOrDecimal256(int64(leash.BlockRange)),
+	}
+	if leash.QueryNonce != nil {
+		leashType = append(leashType, apitypes.Type{Name: "queryNonce", Type: "uint64"})
+		leashMessage["queryNonce"] = math.NewHexOrDecimal256(int64(*leash.QueryNonce))
+	}
+
 	return apitypes.TypedData{
 		Types: map[string][]apitypes.Type{
 			"EIP712Domain": {
@@ -78,12 +99,7 @@ func makeSignableCall(chainID uint64, caller, callee []byte, gasLimit uint64, ga
 				{Name: "data", Type: "bytes"},
 				{Name: "leash", Type: "Leash"},
 			},
-			"Leash": {
-				{Name: "nonce", Type: "uint64"},
-				{Name: "blockNumber", Type: "uint64"},
-				{Name: "blockHash", Type: "bytes32"},
-				{Name: "blockRange", Type: "uint64"},
-			},
+			"Leash": leashType,
 		},
 		PrimaryType: "Call",
 		Domain: apitypes.TypedDataDomain{
@@ -100,12 +116,7 @@ func makeSignableCall(chainID uint64, caller, callee []byte, gasLimit uint64, ga
 			"gasLimit": math.NewHexOrDecimal256(int64(gasLimit)),
 			"gasPrice": &gasPriceU256,
 			"data":     data,
-			"leash": map[string]interface{}{
-				"nonce":       math.NewHexOrDecimal256(int64(leash.Nonce)),
-				"blockNumber": math.NewHexOrDecimal256(int64(leash.BlockNumber)),
-				"blockHash":   leash.BlockHash,
-				"blockRange":  math.NewHexOrDecimal256(int64(leash.BlockRange)),
-			},
+			"leash":    leashMessage,
 		},
 	}
 }
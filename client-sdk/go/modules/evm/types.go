@@ -16,15 +16,24 @@ type Call struct {
 	Data    []byte `json:"data"`
 }
 
+// AdvanceQueryNonce is an evm.AdvanceQueryNonce transaction.
+type AdvanceQueryNonce struct {
+	QueryNonce uint64 `json:"query_nonce"`
+}
+
 // StorageQuery queries the EVM storage.
 type StorageQuery struct {
 	Address []byte `json:"address"`
 	Index   []byte `json:"index"`
+	// Round is the round whose state is queried.
+	Round *uint64 `json:"round,omitempty"`
 }
 
 // CodeQuery queries the EVM code storage.
 type CodeQuery struct {
 	Address []byte `json:"address"`
+	// Round is the round whose state is queried.
+	Round *uint64 `json:"round,omitempty"`
 }
 
 // BalanceQuery queries the EVM account balance.
@@ -40,6 +49,9 @@ type SimulateCallQuery struct {
 	Address  []byte `json:"address"`
 	Value    []byte `json:"value"`
 	Data     []byte `json:"data"`
+
+	// Round is the round whose state the call is simulated against.
+	Round *uint64 `json:"round,omitempty"`
 }
 
 // GasCosts are the EVM module gas costs.
@@ -58,4 +70,8 @@ type Event struct {
 	Address []byte   `json:"address"`
 	Topics  [][]byte `json:"topics"`
 	Data    []byte   `json:"data"`
+	// TxHash is the hash of the transaction that emitted the log, if known.
+	TxHash []byte `json:"tx_hash,omitempty"`
+	// LogIndex is the index of the log among all logs emitted by the transaction.
+	LogIndex uint64 `json:"log_index,omitempty"`
 }
@@ -2,6 +2,7 @@ package types
 
 import (
 	"fmt"
+	"strings"
 	"unsafe"
 )
 
@@ -99,6 +100,12 @@ type ProposalData struct {
 	WhitelistQuorum *uint8    `json:"whitelist_quorum,omitempty"`
 	BlacklistQuorum *uint8    `json:"blacklist_quorum,omitempty"`
 	ConfigQuorum    *uint8    `json:"config_quorum,omitempty"`
+
+	MultisigMembers   []Address `json:"multisig_members,omitempty"`
+	MultisigThreshold *uint16   `json:"multisig_threshold,omitempty"`
+
+	// WhitelistExpiry is the round after which the whitelisting of a Whitelist proposal expires.
+	WhitelistExpiry *uint64 `json:"whitelist_expiry,omitempty"`
 }
 
 type ProposalDataStr struct {
@@ -125,14 +132,30 @@ func (pd *ProposalData) String(action Action) (map[string]string, error) {
 		result["Role"] = pd.Role.String()
 		result["Address"] = pd.Address.String()
 
-	case Whitelist, Blacklist:
+	case Whitelist, Blacklist, PauseContract, UnpauseContract, Freeze, Unfreeze:
 		if pd.Address == nil {
 			return nil, fmt.Errorf("Failed to output %s.", action.String())
 		}
 
 		result["Address"] = pd.Address.String()
+		if action == Whitelist && pd.WhitelistExpiry != nil {
+			result["WhitelistExpiry"] = fmt.Sprintf("%d", *pd.WhitelistExpiry)
+		}
+
+	case SetMultisigMembers:
+		if pd.Address == nil || pd.MultisigMembers == nil || pd.MultisigThreshold == nil {
+			return nil, fmt.Errorf("Failed to output %s.", action.String())
+		}
+
+		members := make([]string, 0, len(pd.MultisigMembers))
+		for _, member := range pd.MultisigMembers {
+			members = append(members, member.String())
+		}
+		result["Address"] = pd.Address.String()
+		result["MultisigMembers"] = strings.Join(members, ",")
+		result["MultisigThreshold"] = fmt.Sprintf("%d", *pd.MultisigThreshold)
 
-	case Mint, Burn:
+	case Mint, Burn, SpendCommonPool:
 		if pd.Address == nil || pd.Amount == nil {
 			return nil, fmt.Errorf("Failed to output %s.", action.String())
 		}
@@ -31,6 +31,9 @@ const (
 	BlacklistedUser
 
 	User
+
+	// Auditors may request decryption of historical confidential transactions.
+	Auditor
 )
 
 func RoleFromString(roleStr string) (Role, error) {
@@ -59,6 +62,8 @@ func RoleFromString(roleStr string) (Role, error) {
 		return BlacklistedUser, nil
 	case "user":
 		return User, nil
+	case "auditor":
+		return Auditor, nil
 	default:
 		return User, fmt.Errorf("unknown role: %s", roleStr)
 	}
@@ -105,6 +110,8 @@ func (r Role) String() string {
 		return "Blacklisted_User"
 	case User:
 		return "User"
+	case Auditor:
+		return "Auditor"
 	default:
 		return fmt.Sprintf("Unknown Role: %d", r)
 	}
@@ -128,6 +135,12 @@ const (
 	Whitelist
 	Blacklist
 	Config
+	PauseContract
+	UnpauseContract
+	Freeze
+	Unfreeze
+	SetMultisigMembers
+	SpendCommonPool
 )
 
 const ActionSize = int(unsafe.Sizeof(Action(0)))
@@ -146,6 +159,18 @@ func ActionFromString(actionStr string) (Action, error) {
 		return Blacklist, nil
 	case "config":
 		return Config, nil
+	case "pausecontract":
+		return PauseContract, nil
+	case "unpausecontract":
+		return UnpauseContract, nil
+	case "freeze":
+		return Freeze, nil
+	case "unfreeze":
+		return Unfreeze, nil
+	case "setmultisigmembers":
+		return SetMultisigMembers, nil
+	case "spendcommonpool":
+		return SpendCommonPool, nil
 	default:
 		return 0, fmt.Errorf("illegal action input!")
 	}
@@ -180,6 +205,18 @@ func (a Action) String() string {
 		return "Blacklist"
 	case Config:
 		return "Config"
+	case PauseContract:
+		return "PauseContract"
+	case UnpauseContract:
+		return "UnpauseContract"
+	case Freeze:
+		return "Freeze"
+	case Unfreeze:
+		return "Unfreeze"
+	case SetMultisigMembers:
+		return "SetMultisigMembers"
+	case SpendCommonPool:
+		return "SpendCommonPool"
 	default:
 		return fmt.Sprintf("Unknown action: %d", a)
 	}
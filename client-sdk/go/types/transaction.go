@@ -303,6 +303,7 @@ type AuthInfo struct {
 	Fee        Fee          `json:"fee"`
 	NotBefore  *uint64      `json:"not_before,omitempty"`
 	NotAfter   *uint64      `json:"not_after,omitempty"`
+	FeeGranter *Address     `json:"fee_granter,omitempty"`
 }
 
 // Fee contains the transaction fee information.
@@ -17,8 +17,16 @@ export const ERR_INSUFFICIENT_BALANCE_CODE = 6;
 export const ERROR_FORBIDDEN_CODE = 7;
 export const ERROR_REVERTED_CODE = 8;
 export const ERROR_SIMULATION_TOO_EXPENSIVE = 8;
+export const ERR_UNSUPPORTED_TX_TYPE_CODE = 17;
+export const ERR_VALUE_TOO_LARGE_CODE = 18;
+export const ERR_CONTRACT_PAUSED_CODE = 19;
 
 export const EVENT_LOG_CODE = 1;
+export const EVENT_EXECUTION_CODE = 4;
+
+export const EXECUTION_STATUS_SUCCEEDED = 0;
+export const EXECUTION_STATUS_REVERTED = 1;
+export const EXECUTION_STATUS_FAILED = 2;
 
 // Callable methods.
 export const METHOD_CREATE = 'evm.Create';
@@ -59,7 +67,10 @@ export class Wrapper extends wrapper.Base {
     }
 }
 
-export function moduleEventHandler(codes: {[EVENT_LOG_CODE]?: event.Handler<types.EVMLogEvent>}) {
+export function moduleEventHandler(codes: {
+    [EVENT_LOG_CODE]?: event.Handler<types.EVMLogEvent>;
+    [EVENT_EXECUTION_CODE]?: event.Handler<types.EVMExecutionEvent>;
+}) {
     return [MODULE_NAME, codes] as event.ModuleHandler;
 }
 
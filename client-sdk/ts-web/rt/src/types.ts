@@ -128,6 +128,9 @@ export interface AccountsDenominationInfoQuery {
  */
 export interface AccountsDenominationInfo {
     decimals: number;
+    symbol?: string;
+    name?: string;
+    logo_uri?: string;
 }
 
 /**
@@ -136,6 +139,7 @@ export interface AccountsDenominationInfo {
 export interface AccountsTransfer {
     to: Uint8Array;
     amount: BaseUnits;
+    memo?: Uint8Array;
 }
 
 // GB: insert AccountsMintST & AccountsBurnST
@@ -155,6 +159,7 @@ export interface AccountsTransferEvent {
     from: Uint8Array;
     to: Uint8Array;
     amount: BaseUnits;
+    memo?: Uint8Array;
 }
 
 /**
@@ -218,6 +223,7 @@ export interface AuthInfo {
     fee: Fee;
     not_before?: oasis.types.longnum;
     not_after?: oasis.types.longnum;
+    fee_granter?: Uint8Array;
 }
 
 /**
@@ -433,6 +439,10 @@ export interface EVMCall {
 export interface EVMStorageQuery {
     address: Uint8Array;
     index: Uint8Array;
+    /**
+     * Round whose state should be queried.
+     */
+    round?: oasis.types.longnum;
 }
 
 /**
@@ -440,6 +450,10 @@ export interface EVMStorageQuery {
  */
 export interface EVMCodeQuery {
     address: Uint8Array;
+    /**
+     * Round whose state should be queried.
+     */
+    round?: oasis.types.longnum;
 }
 
 /**
@@ -467,12 +481,21 @@ export interface Leash {
     block_number: oasis.types.longnum;
     block_hash: Uint8Array;
     block_range: oasis.types.longnum;
+    query_nonce?: oasis.types.longnum;
 }
 
 export interface EVMLogEvent {
     address: Uint8Array;
     topics: Uint8Array[];
     data: Uint8Array;
+    tx_hash?: Uint8Array;
+    log_index?: oasis.types.longnum;
+}
+
+export interface EVMExecutionEvent {
+    tx_hash?: Uint8Array;
+    gas_used: oasis.types.longnum;
+    status: number;
 }
 
 /**
@@ -6,8 +6,10 @@ use crate::{
     event::Event,
     memory::HostRegion,
     types::{
-        address::Address, event::Event as RawEvent, message::Message, token, CallFormat,
-        ExecutionContext, ExecutionOk, ExecutionResult, InstanceId,
+        address::{self, Address},
+        event::Event as RawEvent,
+        message::Message,
+        token, CallFormat, ExecutionContext, ExecutionOk, ExecutionResult, InstanceId,
     },
 };
 
@@ -101,6 +103,10 @@ where
     // Load context.
     let ec = HostRegion::from_args(ctx_ptr, ctx_len).into_vec();
     let ec: ExecutionContext = cbor::from_slice(&ec).unwrap();
+    // Use the same human readable part of Bech32-encoded addresses as the runtime.
+    if !ec.address_bech32_hrp.is_empty() {
+        address::set_bech32_hrp(&ec.address_bech32_hrp);
+    }
     // Load request.
     let request = HostRegion::from_args(request_ptr, request_len).into_vec();
     let request = cbor::from_slice(&request).unwrap(); // TODO: Handle errors gracefully?
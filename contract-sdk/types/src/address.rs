@@ -1,5 +1,5 @@
 //! A minimal representation of an Oasis Runtime SDK address.
-use std::convert::TryFrom;
+use std::{convert::TryFrom, sync::Mutex};
 
 use bech32::{self, FromBase32, ToBase32, Variant};
 use thiserror::Error;
@@ -8,10 +8,36 @@ const ADDRESS_VERSION_SIZE: usize = 1;
 const ADDRESS_DATA_SIZE: usize = 20;
 const ADDRESS_SIZE: usize = ADDRESS_VERSION_SIZE + ADDRESS_DATA_SIZE;
 
+/// Default human readable part for Bech32-encoded addresses.
 // MZ, change it to hela
-const ADDRESS_BECH32_HRP: &str = "hela0";
+pub const ADDRESS_BECH32_HRP: &str = "hela0";
 // const ADDRESS_BECH32_HRP: &str = "oasis";
 
+static BECH32_HRP: Mutex<Option<&'static str>> = Mutex::new(None);
+
+/// Return the configured human readable part for Bech32-encoded addresses.
+pub fn bech32_hrp() -> &'static str {
+    BECH32_HRP.lock().unwrap().unwrap_or(ADDRESS_BECH32_HRP)
+}
+
+/// Configure the human readable part for Bech32-encoded addresses.
+///
+/// # Panics
+///
+/// This function will panic in case a different human readable part was already set.
+pub fn set_bech32_hrp(hrp: &str) {
+    let mut guard = BECH32_HRP.lock().unwrap();
+    match *guard {
+        Some(existing) if existing != hrp => {
+            drop(guard); // Avoid poisoning the global lock.
+            panic!("bech32 human readable part already set: {existing}");
+        }
+        Some(_) => {}
+        // The human readable part can only be set once, so at most one copy is leaked.
+        None => *guard = Some(Box::leak(hrp.to_owned().into_boxed_str())),
+    }
+}
+
 /// Error.
 #[derive(Error, Debug)]
 pub enum Error {
@@ -44,8 +70,13 @@ impl Address {
 
     /// Tries to create a new address from Bech32-encoded string.
     pub fn from_bech32(data: &str) -> Result<Self, Error> {
+        Self::from_bech32_with_hrp(data, bech32_hrp())
+    }
+
+    /// Tries to create a new address from Bech32-encoded string with the given human readable part.
+    pub fn from_bech32_with_hrp(data: &str, expected_hrp: &str) -> Result<Self, Error> {
         let (hrp, data, variant) = bech32::decode(data).map_err(|_| Error::MalformedAddress)?;
-        if hrp != ADDRESS_BECH32_HRP {
+        if hrp != expected_hrp {
             return Err(Error::MalformedAddress);
         }
         if variant != Variant::Bech32 {
@@ -58,7 +89,12 @@ impl Address {
 
     /// Converts an address to Bech32 representation.
     pub fn to_bech32(self) -> String {
-        bech32::encode(ADDRESS_BECH32_HRP, self.0.to_base32(), Variant::Bech32).unwrap()
+        self.to_bech32_with_hrp(bech32_hrp())
+    }
+
+    /// Converts an address to Bech32 representation with the given human readable part.
+    pub fn to_bech32_with_hrp(self, hrp: &str) -> String {
+        bech32::encode(hrp, self.0.to_base32(), Variant::Bech32).unwrap()
     }
 }
 
@@ -132,4 +168,14 @@ mod test {
             Error::MalformedAddress,
         ));
     }
+
+    #[test]
+    #[should_panic(expected = "bech32 human readable part already set")]
+    fn test_set_bech32_hrp_conflict() {
+        // Setting the same human readable part again is fine.
+        set_bech32_hrp(ADDRESS_BECH32_HRP);
+        set_bech32_hrp(ADDRESS_BECH32_HRP);
+
+        set_bech32_hrp("other");
+    }
 }
@@ -1,5 +1,9 @@
 //! Environment query-related types.
-use crate::{address::Address, token::Denomination};
+use crate::{
+    address::Address,
+    modules::accounts::{ProposalState, Role},
+    token::Denomination,
+};
 
 /// A query request.
 #[non_exhaustive]
@@ -49,6 +53,18 @@ pub enum AccountsQuery {
         address: Address,
         denomination: Denomination,
     },
+
+    /// Query an account's primary role.
+    #[cbor(rename = "role")]
+    Role { address: Address },
+
+    /// Query all roles held by an account.
+    #[cbor(rename = "roles")]
+    Roles { address: Address },
+
+    /// Query the state of a proposal.
+    #[cbor(rename = "proposal_state")]
+    ProposalState { id: u32 },
 }
 
 impl From<AccountsQuery> for QueryRequest {
@@ -63,6 +79,15 @@ impl From<AccountsQuery> for QueryRequest {
 pub enum AccountsResponse {
     /// An account's balance of the given denomination.
     Balance { balance: u128 },
+
+    /// An account's primary role.
+    Role { role: Role },
+
+    /// All roles held by an account.
+    Roles { roles: Vec<Role> },
+
+    /// State of a proposal.
+    ProposalState { state: ProposalState },
 }
 
 impl From<AccountsResponse> for QueryResponse {
@@ -126,6 +126,11 @@ pub struct ExecutionContext {
     /// Transaction's call format.
     #[cbor(optional, skip_serializing_if = "CallFormat::is_plain")]
     pub call_format: CallFormat,
+    /// Human readable part used by the runtime for Bech32-encoded addresses.
+    ///
+    /// Empty in case the runtime did not provide it, in which case the default is used.
+    #[cbor(optional)]
+    pub address_bech32_hrp: String,
 }
 
 /// Contract execution result.
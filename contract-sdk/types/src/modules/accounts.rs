@@ -0,0 +1,174 @@
+//! Types for calling into the accounts module.
+use crate::{address::Address, token};
+
+/// Transfer call body, e.g. for moving stable tokens to another account.
+///
+/// Emit it as a call message to the `accounts.Transfer` method.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct Transfer {
+    pub to: Address,
+    pub amount: token::BaseUnits,
+}
+
+/// Role of an account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Role {
+    Admin = 0,
+    MintProposer = 1,
+    MintVoter = 2,
+    BurnProposer = 3,
+    BurnVoter = 4,
+    WhitelistProposer = 5,
+    WhitelistVoter = 6,
+    BlacklistProposer = 7,
+    BlacklistVoter = 8,
+    WhitelistedUser = 9,
+    BlacklistedUser = 10,
+    User = 11,
+    Auditor = 12,
+}
+
+impl Role {
+    /// Tries to create a role from its numeric representation.
+    pub fn from_u8(v: u8) -> Option<Self> {
+        Some(match v {
+            0 => Role::Admin,
+            1 => Role::MintProposer,
+            2 => Role::MintVoter,
+            3 => Role::BurnProposer,
+            4 => Role::BurnVoter,
+            5 => Role::WhitelistProposer,
+            6 => Role::WhitelistVoter,
+            7 => Role::BlacklistProposer,
+            8 => Role::BlacklistVoter,
+            9 => Role::WhitelistedUser,
+            10 => Role::BlacklistedUser,
+            11 => Role::User,
+            12 => Role::Auditor,
+            _ => return None,
+        })
+    }
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Role::User
+    }
+}
+
+impl cbor::Encode for Role {
+    fn into_cbor_value(self) -> cbor::Value {
+        cbor::Value::ByteString(vec![self as u8])
+    }
+}
+
+impl cbor::Decode for Role {
+    fn try_from_cbor_value(value: cbor::Value) -> Result<Self, cbor::DecodeError> {
+        match value {
+            cbor::Value::ByteString(data) if data.len() == 1 => {
+                Self::from_u8(data[0]).ok_or(cbor::DecodeError::UnexpectedType)
+            }
+            _ => Err(cbor::DecodeError::UnexpectedType),
+        }
+    }
+}
+
+#[cfg(feature = "oasis-runtime-sdk")]
+impl From<oasis_runtime_sdk::types::role::Role> for Role {
+    fn from(r: oasis_runtime_sdk::types::role::Role) -> Self {
+        // Both types share the same single byte representation.
+        Self::from_u8(r.marshal_binary()[0]).unwrap_or_default()
+    }
+}
+
+/// State of a proposal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ProposalState {
+    Active = 0,
+    Passed = 1,
+    Rejected = 2,
+    Expired = 3,
+    Cancelled = 4,
+}
+
+impl ProposalState {
+    /// Tries to create a proposal state from its numeric representation.
+    pub fn from_u8(v: u8) -> Option<Self> {
+        Some(match v {
+            0 => ProposalState::Active,
+            1 => ProposalState::Passed,
+            2 => ProposalState::Rejected,
+            3 => ProposalState::Expired,
+            4 => ProposalState::Cancelled,
+            _ => return None,
+        })
+    }
+}
+
+impl Default for ProposalState {
+    fn default() -> Self {
+        ProposalState::Active
+    }
+}
+
+impl cbor::Encode for ProposalState {
+    fn into_cbor_value(self) -> cbor::Value {
+        cbor::Value::ByteString(vec![self as u8])
+    }
+}
+
+impl cbor::Decode for ProposalState {
+    fn try_from_cbor_value(value: cbor::Value) -> Result<Self, cbor::DecodeError> {
+        match value {
+            cbor::Value::ByteString(data) if data.len() == 1 => {
+                Self::from_u8(data[0]).ok_or(cbor::DecodeError::UnexpectedType)
+            }
+            _ => Err(cbor::DecodeError::UnexpectedType),
+        }
+    }
+}
+
+#[cfg(feature = "oasis-runtime-sdk")]
+impl From<oasis_runtime_sdk::types::proposal::ProposalState> for ProposalState {
+    fn from(s: oasis_runtime_sdk::types::proposal::ProposalState) -> Self {
+        use oasis_runtime_sdk::types::proposal::ProposalState as RuntimeProposalState;
+
+        match s {
+            RuntimeProposalState::Active => Self::Active,
+            RuntimeProposalState::Passed => Self::Passed,
+            RuntimeProposalState::Rejected => Self::Rejected,
+            RuntimeProposalState::Expired => Self::Expired,
+            RuntimeProposalState::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_role_roundtrip() {
+        for v in 0..=12u8 {
+            let role = Role::from_u8(v).unwrap();
+            let enc = cbor::to_vec(role);
+            let dec: Role = cbor::from_slice(&enc).unwrap();
+            assert_eq!(dec, role, "role should round-trip");
+        }
+        assert!(Role::from_u8(13).is_none());
+        assert!(cbor::from_slice::<Role>(&cbor::to_vec(vec![0u8, 1u8])).is_err());
+    }
+
+    #[test]
+    fn test_proposal_state_roundtrip() {
+        for v in 0..=4u8 {
+            let state = ProposalState::from_u8(v).unwrap();
+            let enc = cbor::to_vec(state);
+            let dec: ProposalState = cbor::from_slice(&enc).unwrap();
+            assert_eq!(dec, state, "proposal state should round-trip");
+        }
+        assert!(ProposalState::from_u8(5).is_none());
+    }
+}
@@ -19,6 +19,7 @@ impl sdk::Runtime for Runtime {
 
     // Define the module that provides the core API.
     type Core = modules::core::Module<Config>;
+    type TransferHooks = ();
 
     // Define the modules that the runtime will be composed of. Here we just use
     // the core and accounts modules from the SDK. Later on we will go into
@@ -3,7 +3,7 @@ use oasis_contract_sdk_types::{
     env::{AccountsQuery, AccountsResponse, QueryRequest, QueryResponse},
     InstanceId,
 };
-use oasis_runtime_sdk::{context::Context, modules::accounts::API as _};
+use oasis_runtime_sdk::{context::Context, error::Error as _, modules::accounts::API as _};
 
 use super::{memory::Region, OasisV1};
 use crate::{
@@ -142,6 +142,36 @@ fn dispatch_accounts_query<Cfg: Config, C: Context>(
             AccountsResponse::Balance { balance }.into()
         }
 
+        AccountsQuery::Role { address } => {
+            let role = Cfg::Accounts::get_role(ctx.runtime_state(), address.into())
+                .unwrap_or_default();
+
+            AccountsResponse::Role { role: role.into() }.into()
+        }
+
+        AccountsQuery::Roles { address } => {
+            let roles = Cfg::Accounts::get_roles(ctx.runtime_state(), address.into())
+                .into_iter()
+                .map(Into::into)
+                .collect();
+
+            AccountsResponse::Roles { roles }.into()
+        }
+
+        AccountsQuery::ProposalState { id } => {
+            match Cfg::Accounts::get_proposal(ctx.runtime_state(), id) {
+                Ok(proposal) => AccountsResponse::ProposalState {
+                    state: proposal.state.into(),
+                }
+                .into(),
+                Err(err) => QueryResponse::Error {
+                    module: err.module_name().to_string(),
+                    code: err.code(),
+                    message: err.to_string(),
+                },
+            }
+        }
+
         _ => QueryResponse::Error {
             module: "".to_string(),
             code: 1,
@@ -1,6 +1,11 @@
 //! The Oasis ABIs.
 use oasis_contract_sdk_types as contract_sdk;
-use oasis_runtime_sdk::{context::Context, modules::core, runtime::Runtime, types::token};
+use oasis_runtime_sdk::{
+    context::Context,
+    modules::core,
+    runtime::Runtime,
+    types::{address, token},
+};
 
 use super::{gas, Abi, ExecutionContext, ExecutionResult, Info};
 use crate::{wasm::ContractError, Config, Error, Parameters};
@@ -63,9 +68,10 @@ impl<Cfg: Config> OasisV1<Cfg> {
             ..Default::default()
         };
         if ctx.code_info.abi_sv >= 1 {
-            // Supports read only and call format flags.
+            // Supports read only and call format flags and the Bech32 human readable part.
             ec.read_only = ctx.read_only;
             ec.call_format = ctx.call_format.into();
+            ec.address_bech32_hrp = address::bech32_hrp().to_string();
         }
         let context_dst = Self::serialize_and_allocate(instance, ec)
             .map_err(|err| Error::ExecutionFailed(err.into()))?;
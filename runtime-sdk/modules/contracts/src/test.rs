@@ -698,6 +698,7 @@ impl Runtime for ContractRuntime {
     const VERSION: Version = Version::new(0, 0, 0);
 
     type Core = Core<CoreConfig>;
+    type TransferHooks = ();
 
     type Modules = (Core<CoreConfig>, Accounts, Contracts);
 
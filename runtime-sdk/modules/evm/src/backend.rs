@@ -1,12 +1,17 @@
 //! EVM backend.
-use std::{cell::RefCell, marker::PhantomData};
+use std::{
+    cell::{Cell, RefCell},
+    marker::PhantomData,
+};
 
 use evm::backend::{Apply, Backend as EVMBackend, Basic, Log};
 
 use oasis_runtime_sdk::{
     core::common::crypto::hash::Hash,
+    event::{etag_for_event, Event as _, EventTag},
+    keymanager,
     modules::{accounts::API as _, core::API as _},
-    types::token,
+    types::{address::Address, token},
     Context, Runtime,
 };
 
@@ -27,6 +32,73 @@ pub(crate) const RNG_MAX_BYTES: u64 = 1024;
 pub struct Vicinity {
     pub gas_price: U256,
     pub origin: H160,
+    /// Value of the `TIMESTAMP` opcode.
+    #[cbor(optional)]
+    pub block_timestamp: u64,
+    /// Value of the `BASEFEE` opcode.
+    #[cbor(optional)]
+    pub block_base_fee: U256,
+    /// Value of the `GASLIMIT` opcode.
+    #[cbor(optional)]
+    pub block_gas_limit: u64,
+    /// Value of the `COINBASE` opcode.
+    #[cbor(optional)]
+    pub block_coinbase: H160,
+}
+
+impl Vicinity {
+    /// Creates the vicinity for a transaction, sourcing the block environment from the runtime
+    /// header and the core module parameters.
+    pub fn new<C: Context, Cfg: Config>(ctx: &mut C, gas_price: U256, origin: H160) -> Self {
+        Self {
+            gas_price,
+            origin,
+            block_timestamp: ctx.runtime_header().timestamp,
+            block_base_fee: <C::Runtime as Runtime>::Core::min_gas_price(
+                ctx,
+                &Cfg::TOKEN_DENOMINATION,
+            )
+            .into(),
+            block_gas_limit: <C::Runtime as Runtime>::Core::max_batch_gas(ctx),
+            block_coinbase: Cfg::BLOCK_COINBASE,
+        }
+    }
+}
+
+/// Context key for the journal of EVM state changes made while a snapshot is active.
+const CONTEXT_KEY_JOURNAL: &str = "evm.Journal";
+
+/// A point that EVM state changes can be reverted to, see [`Backend::snapshot`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Number of journal entries when the snapshot was taken.
+    position: usize,
+    /// Number of active snapshots, including this one.
+    depth: usize,
+}
+
+/// EVM state changes made while a snapshot is active, in the order they were made.
+#[derive(Default)]
+struct Journal {
+    /// Number of active snapshots.
+    depth: usize,
+    entries: Vec<JournalEntry>,
+}
+
+/// A single EVM state change, recording what is needed to undo it.
+enum JournalEntry {
+    /// Previous balance of an account.
+    Balance(Address, u128),
+    /// Previous nonce of an account.
+    Nonce(Address, u64),
+    /// Previous code of a contract.
+    Code(H160, Option<Vec<u8>>),
+    /// Previous value of a contract storage slot.
+    Storage(H160, H256, Option<H256>),
+    /// Raw entries of a contract storage that has been cleared.
+    StorageReset(H160, Vec<(&'static [u8], Vec<u8>, Vec<u8>)>),
+    /// An event tag, which is only emitted once the outermost snapshot is committed.
+    EventTag(EventTag),
 }
 
 /// This macro is like `fn with_storage(ctx, addr, f: FnOnce(impl Storage) -> T) ->T`
@@ -52,6 +124,10 @@ pub struct Backend<'ctx, C: Context, Cfg: Config> {
     ctx: RefCell<&'ctx mut C>,
     _cfg: PhantomData<Cfg>,
     internal: bool,
+    /// Number of consensus message slots reserved by precompiles during execution.
+    reserved_messages: Cell<u32>,
+    /// Index of the first log emitted by this execution among all logs of the transaction.
+    first_log_index: u64,
 }
 
 impl<'ctx, C: Context, Cfg: Config> Backend<'ctx, C, Cfg> {
@@ -61,6 +137,8 @@ impl<'ctx, C: Context, Cfg: Config> Backend<'ctx, C, Cfg> {
             ctx: RefCell::new(ctx),
             _cfg: PhantomData,
             internal: false,
+            reserved_messages: Cell::new(0),
+            first_log_index: 0,
         }
     }
 
@@ -70,12 +148,136 @@ impl<'ctx, C: Context, Cfg: Config> Backend<'ctx, C, Cfg> {
             ctx: RefCell::new(ctx),
             _cfg: PhantomData,
             internal: true,
+            reserved_messages: Cell::new(0),
+            first_log_index: 0,
         }
     }
 
+    /// Sets the index of the first log emitted by this execution, so that log indices are unique
+    /// across multiple executions within the same transaction.
+    pub fn with_first_log_index(mut self, first_log_index: u64) -> Self {
+        self.first_log_index = first_log_index;
+        self
+    }
+
     pub fn is_internal(&self) -> bool {
         self.internal
     }
+
+    /// Whether the Ethereum address of the given account has already been recorded.
+    pub fn has_address_alias(&self, address: primitive_types::H160) -> bool {
+        let mut ctx = self.ctx.borrow_mut();
+        state::has_address_alias(ctx.runtime_state(), Cfg::map_address(address))
+    }
+
+    /// Returns the number of storage entries of the given contract, counting at most `limit`.
+    pub fn storage_entries(&self, address: &H160, limit: u64) -> u64 {
+        let mut ctx = self.ctx.borrow_mut();
+        state::contract_storage_entries(ctx.runtime_state(), address, limit)
+    }
+
+    /// Takes a snapshot of the EVM state.
+    ///
+    /// Until the snapshot is either reverted or committed, all changes applied by any backend
+    /// using the same context are journaled, so modules calling into the EVM can undo them in case
+    /// their own logic fails afterwards. Events emitted by the EVM are held back until the
+    /// outermost snapshot is committed. Snapshots may be nested, but must be reverted or committed
+    /// in reverse order of creation.
+    pub fn snapshot(ctx: &mut C) -> Snapshot {
+        let journal = ctx.value::<Journal>(CONTEXT_KEY_JOURNAL).or_default();
+        journal.depth += 1;
+        Snapshot {
+            position: journal.entries.len(),
+            depth: journal.depth,
+        }
+    }
+
+    /// Reverts all EVM state changes made since the given snapshot was taken and discards any
+    /// events emitted in the meantime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot is not the most recent active snapshot.
+    pub fn revert_to_snapshot(ctx: &mut C, snapshot: Snapshot) {
+        let mut journal = Self::take_journal(ctx, snapshot);
+        for entry in journal.entries.drain(snapshot.position..).rev() {
+            match entry {
+                JournalEntry::Balance(address, amount) => {
+                    Cfg::Accounts::set_balance(
+                        ctx.runtime_state(),
+                        address,
+                        &token::BaseUnits::new(amount, Cfg::TOKEN_DENOMINATION),
+                    );
+                }
+                JournalEntry::Nonce(address, nonce) => {
+                    Cfg::Accounts::set_nonce(ctx.runtime_state(), address, nonce);
+                }
+                JournalEntry::Code(address, code) => {
+                    let mut store = state::codes(ctx.runtime_state());
+                    match code {
+                        Some(code) => store.insert(address, code),
+                        None => store.remove(address),
+                    }
+                }
+                JournalEntry::Storage(address, index, value) => match value {
+                    Some(value) => {
+                        with_storage!(&mut *ctx, &address, |store| store.insert(index, value))
+                    }
+                    None => with_storage!(&mut *ctx, &address, |store| store.remove(index)),
+                },
+                JournalEntry::StorageReset(address, entries) => {
+                    state::restore_raw_contract_storage(ctx.runtime_state(), &address, entries);
+                }
+                JournalEntry::EventTag(_) => {}
+            }
+        }
+        Self::finish_snapshot(ctx, journal);
+    }
+
+    /// Keeps all EVM state changes made since the given snapshot was taken. In case this is the
+    /// outermost snapshot, the held back events are emitted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot is not the most recent active snapshot.
+    pub fn commit_snapshot(ctx: &mut C, snapshot: Snapshot) {
+        let journal = Self::take_journal(ctx, snapshot);
+        Self::finish_snapshot(ctx, journal);
+    }
+
+    /// Emits the given event tag, unless it must be held back until the active snapshots have been
+    /// committed.
+    fn emit_etag(&mut self, journal: &mut Option<Vec<JournalEntry>>, etag: EventTag) {
+        match journal {
+            Some(journal) => journal.push(JournalEntry::EventTag(etag)),
+            None => self.ctx.get_mut().emit_etag(etag),
+        }
+    }
+
+    fn take_journal(ctx: &mut C, snapshot: Snapshot) -> Journal {
+        let journal = ctx
+            .value::<Journal>(CONTEXT_KEY_JOURNAL)
+            .take()
+            .expect("snapshot should be active");
+        assert_eq!(
+            journal.depth, snapshot.depth,
+            "only the most recent snapshot can be reverted or committed"
+        );
+        journal
+    }
+
+    fn finish_snapshot(ctx: &mut C, mut journal: Journal) {
+        journal.depth -= 1;
+        if journal.depth > 0 {
+            ctx.value(CONTEXT_KEY_JOURNAL).set(journal);
+            return;
+        }
+        for entry in journal.entries {
+            if let JournalEntry::EventTag(etag) = entry {
+                ctx.emit_etag(etag);
+            }
+        }
+    }
 }
 
 impl<'ctx, C: Context, Cfg: Config> EVMBackend for Backend<'ctx, C, Cfg> {
@@ -103,12 +305,11 @@ impl<'ctx, C: Context, Cfg: Config> EVMBackend for Backend<'ctx, C, Cfg> {
     }
 
     fn block_coinbase(&self) -> primitive_types::H160 {
-        // Does not make sense in runtime context.
-        primitive_types::H160::default()
+        self.vicinity.block_coinbase.into()
     }
 
     fn block_timestamp(&self) -> primitive_types::U256 {
-        self.ctx.borrow().runtime_header().timestamp.into()
+        self.vicinity.block_timestamp.into()
     }
 
     fn block_difficulty(&self) -> primitive_types::U256 {
@@ -117,15 +318,11 @@ impl<'ctx, C: Context, Cfg: Config> EVMBackend for Backend<'ctx, C, Cfg> {
     }
 
     fn block_gas_limit(&self) -> primitive_types::U256 {
-        <C::Runtime as Runtime>::Core::max_batch_gas(&mut self.ctx.borrow_mut()).into()
+        self.vicinity.block_gas_limit.into()
     }
 
     fn block_base_fee_per_gas(&self) -> primitive_types::U256 {
-        <C::Runtime as Runtime>::Core::min_gas_price(
-            &mut self.ctx.borrow_mut(),
-            &Cfg::TOKEN_DENOMINATION,
-        )
-        .into()
+        self.vicinity.block_base_fee.into()
     }
 
     fn chain_id(&self) -> primitive_types::U256 {
@@ -200,12 +397,57 @@ pub(crate) trait EVMBackendExt {
     /// Returns at most `num_bytes` bytes of cryptographically secure random bytes.
     /// The optional personalization string may be included to increase domain separation.
     fn random_bytes(&self, num_bytes: u64, pers: &[u8]) -> Vec<u8>;
+
+    /// Reserves a consensus message slot for a message that will be emitted after execution.
+    /// Returns `false` in case no more messages can be emitted by the current transaction.
+    fn reserve_message_slot(&self) -> bool;
+
+    /// Returns the symmetric key that the given contract may use to seal data, derived by the key
+    /// manager. Returns `None` in case the key manager is not available.
+    fn contract_sealing_key(&self, address: primitive_types::H160) -> Option<[u8; 32]>;
+
+    /// Returns the balance of the given account in the given SDK denomination.
+    fn token_balance(
+        &self,
+        address: primitive_types::H160,
+        denomination: &token::Denomination,
+    ) -> u128;
+
+    /// Returns the total supply of the given SDK denomination.
+    fn token_total_supply(&self, denomination: &token::Denomination) -> u128;
+
+    /// Returns the number of decimals of the given SDK denomination.
+    fn token_decimals(&self, denomination: &token::Denomination) -> u8;
 }
 
 impl<T: EVMBackendExt> EVMBackendExt for &T {
     fn random_bytes(&self, num_bytes: u64, pers: &[u8]) -> Vec<u8> {
         (*self).random_bytes(num_bytes, pers)
     }
+
+    fn reserve_message_slot(&self) -> bool {
+        (*self).reserve_message_slot()
+    }
+
+    fn contract_sealing_key(&self, address: primitive_types::H160) -> Option<[u8; 32]> {
+        (*self).contract_sealing_key(address)
+    }
+
+    fn token_balance(
+        &self,
+        address: primitive_types::H160,
+        denomination: &token::Denomination,
+    ) -> u128 {
+        (*self).token_balance(address, denomination)
+    }
+
+    fn token_total_supply(&self, denomination: &token::Denomination) -> u128 {
+        (*self).token_total_supply(denomination)
+    }
+
+    fn token_decimals(&self, denomination: &token::Denomination) -> u8 {
+        (*self).token_decimals(denomination)
+    }
 }
 
 impl<'ctx, C: Context, Cfg: Config> EVMBackendExt for Backend<'ctx, C, Cfg> {
@@ -220,6 +462,56 @@ impl<'ctx, C: Context, Cfg: Config> EVMBackendExt for Backend<'ctx, C, Cfg> {
         rand_core::RngCore::try_fill_bytes(&mut rng, &mut rand_bytes).expect("RNG is inoperable");
         rand_bytes
     }
+
+    fn reserve_message_slot(&self) -> bool {
+        // Internal calls are not executed on behalf of a transaction and cannot emit messages.
+        if self.is_internal() {
+            return false;
+        }
+
+        let reserved = self.reserved_messages.get();
+        if reserved >= self.ctx.borrow().remaining_messages() {
+            return false;
+        }
+        self.reserved_messages.set(reserved + 1);
+        true
+    }
+
+    fn contract_sealing_key(&self, address: primitive_types::H160) -> Option<[u8; 32]> {
+        let ctx = self.ctx.borrow();
+        let key_id = keymanager::get_key_pair_id([
+            state::CONTRACT_SEALING_KEY_PAIR_ID_CONTEXT_BASE,
+            address.as_bytes(),
+        ]);
+        let keypair = ctx.key_manager()?.get_or_create_keys(key_id).ok()?;
+        Some(keypair.state_key.0)
+    }
+
+    fn token_balance(
+        &self,
+        address: primitive_types::H160,
+        denomination: &token::Denomination,
+    ) -> u128 {
+        let mut ctx = self.ctx.borrow_mut();
+        let sdk_address = Cfg::map_address(address);
+        Cfg::Accounts::get_balance(ctx.runtime_state(), sdk_address, denomination.clone())
+            .unwrap_or_default()
+    }
+
+    fn token_total_supply(&self, denomination: &token::Denomination) -> u128 {
+        let mut ctx = self.ctx.borrow_mut();
+        Cfg::Accounts::get_total_supplies(ctx.runtime_state())
+            .ok()
+            .and_then(|supplies| supplies.get(denomination).copied())
+            .unwrap_or_default()
+    }
+
+    fn token_decimals(&self, denomination: &token::Denomination) -> u8 {
+        let mut ctx = self.ctx.borrow_mut();
+        Cfg::Accounts::get_denomination_info(ctx.runtime_state(), denomination)
+            .map(|info| info.decimals)
+            .unwrap_or_default()
+    }
 }
 
 /// EVM backend that can apply changes and return an exit value.
@@ -246,30 +538,73 @@ impl<'c, C: Context, Cfg: Config> ApplyBackendResult for Backend<'c, C, Cfg> {
         // Keep origin handy for nonce sanity checks.
         let origin = self.vicinity.origin;
         let is_simulation = self.ctx.get_mut().is_simulation();
+        // Changes are only journaled while a snapshot is active. Address aliases are never
+        // reverted as they only depend on the addresses themselves.
+        let mut journal = self
+            .ctx
+            .get_mut()
+            .value::<Journal>(CONTEXT_KEY_JOURNAL)
+            .get()
+            .map(|_| Vec::new());
+
+        // Balances locked by vesting schedules must not be spent. Check this before applying any
+        // changes so that a failure leaves the state untouched.
+        let values: Vec<_> = values.into_iter().collect();
+        for apply in &values {
+            let (address, balance) = match apply {
+                Apply::Delete { address } => (*address, 0),
+                Apply::Modify { address, basic, .. } => (*address, basic.balance.as_u128()),
+            };
+            let address = Cfg::map_address(address);
+            let ctx: &mut C = self.ctx.get_mut();
+            let locked = Cfg::Accounts::get_locked_balance(ctx, address, &Cfg::TOKEN_DENOMINATION);
+            let old_balance =
+                Cfg::Accounts::get_balance(ctx.runtime_state(), address, Cfg::TOKEN_DENOMINATION)
+                    .unwrap();
+            if balance < locked && balance < old_balance {
+                return evm::ExitError::Other("balance is locked by a vesting schedule".into())
+                    .into();
+            }
+        }
 
         for apply in values {
             match apply {
-                Apply::Delete { .. } => {
-                    // Apply::Delete indicates a SELFDESTRUCT action which is not supported.
-                    // This assumes that Apply::Delete is ALWAYS and ONLY invoked in SELFDESTRUCT opcodes, which indeed is the case:
-                    // https://github.com/rust-blockchain/evm/blob/0fbde9fa7797308290f89111c6abe5cee55a5eac/runtime/src/eval/system.rs#L258-L267
-                    //
-                    // NOTE: We cannot just check the executors ExitReason if the reason was suicide,
-                    //       because that doesn't work in case of cross-contract suicide calls, as only
-                    //       the top-level exit reason is returned.
-                    return evm::ExitFatal::Other("SELFDESTRUCT not supported".into()).into();
+                Apply::Delete { address } => {
+                    // Apply::Delete indicates a SELFDESTRUCT action. By this point the account's
+                    // balance has already been transferred to the beneficiary, so remove its
+                    // balance, code and storage.
+                    let addr: H160 = address.into();
+                    let address = Cfg::map_address(address);
+
+                    let mut state = self.ctx.get_mut().runtime_state();
+                    let old_amount =
+                        Cfg::Accounts::get_balance(&mut state, address, Cfg::TOKEN_DENOMINATION)
+                            .unwrap();
+                    total_supply_sub = total_supply_sub.checked_add(old_amount).unwrap();
+                    if let Some(journal) = journal.as_mut() {
+                        journal.push(JournalEntry::Balance(address, old_amount));
+                        journal.push(JournalEntry::Code(addr, state::codes(&mut state).get(addr)));
+                        journal.push(JournalEntry::StorageReset(
+                            addr,
+                            state::raw_contract_storage(&mut state, &addr),
+                        ));
+                    }
+                    Cfg::Accounts::set_balance(
+                        &mut state,
+                        address,
+                        &token::BaseUnits::new(0, Cfg::TOKEN_DENOMINATION),
+                    );
+
+                    state::codes(&mut state).remove(addr);
+                    state::remove_contract_storage(&mut state, &addr);
                 }
                 Apply::Modify {
                     address,
                     basic,
                     code,
                     storage,
-                    reset_storage: _,
+                    reset_storage,
                 } => {
-                    // Reset storage is ignored since storage cannot be efficiently reset as this
-                    // would require iterating over all of the storage keys. This is fine as reset_storage
-                    // is only ever called on non-empty storage when doing SELFDESTRUCT, which we don't support.
-
                     let addr: H160 = address.into();
                     // Derive SDK account address from the Ethereum address.
                     let address = Cfg::map_address(address);
@@ -278,6 +613,7 @@ impl<'c, C: Context, Cfg: Config> ApplyBackendResult for Backend<'c, C, Cfg> {
 
                     // Update account balance and nonce.
                     let mut state = self.ctx.get_mut().runtime_state();
+                    state::register_address_alias(&mut state, addr, address);
                     let amount = basic.balance.as_u128();
                     let old_amount =
                         Cfg::Accounts::get_balance(&mut state, address, Cfg::TOKEN_DENOMINATION)
@@ -289,6 +625,13 @@ impl<'c, C: Context, Cfg: Config> ApplyBackendResult for Backend<'c, C, Cfg> {
                         total_supply_sub =
                             total_supply_sub.checked_add(old_amount - amount).unwrap();
                     }
+                    if let Some(journal) = journal.as_mut() {
+                        journal.push(JournalEntry::Balance(address, old_amount));
+                        journal.push(JournalEntry::Nonce(
+                            address,
+                            Cfg::Accounts::get_nonce(&mut state, address).unwrap(),
+                        ));
+                    }
                     let amount = token::BaseUnits::new(amount, Cfg::TOKEN_DENOMINATION);
                     // Setting the balance like this is dangerous, but we have a sanity check below
                     // to ensure that this never results in any tokens being either minted or
@@ -320,15 +663,34 @@ impl<'c, C: Context, Cfg: Config> ApplyBackendResult for Backend<'c, C, Cfg> {
                     if let Some(code) = code {
                         let state = self.ctx.get_mut().runtime_state();
                         let mut store = state::codes(state);
+                        if let Some(journal) = journal.as_mut() {
+                            journal.push(JournalEntry::Code(addr, store.get(addr)));
+                        }
                         store.insert(addr, code);
                     }
 
-                    // Handle storage updates.
+                    // Handle storage updates. Storage is reset whenever a contract is created at
+                    // the address, which only matters when it previously self-destructed.
+                    if reset_storage {
+                        let mut state = self.ctx.get_mut().runtime_state();
+                        if let Some(journal) = journal.as_mut() {
+                            journal.push(JournalEntry::StorageReset(
+                                addr,
+                                state::raw_contract_storage(&mut state, &addr),
+                            ));
+                        }
+                        state::remove_contract_storage(state, &addr);
+                    }
                     for (index, value) in storage {
                         let idx: H256 = index.into();
                         let val: H256 = value.into();
 
                         let ctx = self.ctx.get_mut();
+                        if let Some(journal) = journal.as_mut() {
+                            let old_value: Option<H256> =
+                                with_storage!(*ctx, &addr, |store| store.get(idx));
+                            journal.push(JournalEntry::Storage(addr, idx, old_value));
+                        }
                         if value == primitive_types::H256::default() {
                             with_storage!(*ctx, &addr, |store| store.remove(idx));
                         } else {
@@ -346,13 +708,47 @@ impl<'c, C: Context, Cfg: Config> ApplyBackendResult for Backend<'c, C, Cfg> {
             "evm execution would lead to invariant violation ({total_supply_add} != {total_supply_sub})",
         );
 
-        // Emit logs as events.
-        for log in logs {
-            self.ctx.get_mut().emit_event(crate::Event::Log {
+        // Emit logs as events. The originating transaction is only known when executing a
+        // transaction from a batch, not in queries.
+        let tx = self.ctx.get_mut().get_tx();
+        let tx_hash = if tx.is_empty() {
+            None
+        } else {
+            Some(Hash::digest_bytes(tx))
+        };
+        for (index, log) in logs.into_iter().enumerate() {
+            let log_index = self.first_log_index + index as u64;
+            if Cfg::EMIT_LOG_TOPIC_TAGS {
+                for (position, topic) in log.topics.iter().enumerate() {
+                    let mut etag = etag_for_event(
+                        crate::MODULE_NAME,
+                        crate::LOG_TOPIC_TAG_CODE,
+                        cbor::to_value(log_index),
+                    );
+                    etag.key.extend_from_slice(log.address.as_bytes());
+                    etag.key.push(position as u8);
+                    etag.key.extend_from_slice(topic.as_bytes());
+                    self.emit_etag(&mut journal, etag);
+                }
+            }
+
+            let event = crate::Event::Log {
                 address: log.address.into(),
                 topics: log.topics.iter().map(|&topic| topic.into()).collect(),
                 data: log.data,
-            });
+                tx_hash,
+                log_index,
+            };
+            self.emit_etag(&mut journal, event.into_event_tag());
+        }
+
+        if let Some(entries) = journal {
+            self.ctx
+                .get_mut()
+                .value::<Journal>(CONTEXT_KEY_JOURNAL)
+                .or_default()
+                .entries
+                .extend(entries);
         }
 
         evm::ExitSucceed::Returned.into()
@@ -3,32 +3,60 @@ use sha3::Digest as _;
 use oasis_runtime_sdk::{
     crypto::signature::secp256k1,
     types::{
-        address::SignatureAddressSpec,
+        address::{Address, SignatureAddressSpec},
         transaction::{AddressSpec, AuthInfo, CallerAddress},
     },
 };
 
 use crate::{types::H160, Error};
 
+/// Domain separation context for callers derived from native SDK addresses.
+const SDK_ADDRESS_CALLER_CONTEXT: &[u8] = b"oasis-runtime-sdk/evm: sdk address caller";
+
 pub fn from_bytes(b: &[u8]) -> H160 {
     H160::from_slice(&sha3::Keccak256::digest(b)[32 - 20..])
 }
 
-pub fn from_secp256k1_public_key(public_key: &secp256k1::PublicKey) -> H160 {
-    from_bytes(&public_key.to_uncompressed_untagged_bytes())
+pub fn from_secp256k1_public_key(public_key: &secp256k1::PublicKey) -> Result<H160, Error> {
+    let bytes = public_key
+        .try_to_uncompressed_untagged_bytes()
+        .map_err(|_| Error::InvalidArgument)?;
+    Ok(from_bytes(&bytes))
+}
+
+/// Derives the caller address of a native SDK account (e.g. an Ed25519 or a multisig account).
+///
+/// The derivation is domain separated so that the result cannot be produced by a secp256k1 key,
+/// but note that the EVM balance of the caller is held by the account the caller maps to and not
+/// by the native SDK account itself. Values sent by such callers are taken from the mapped
+/// account, which must be funded separately (e.g. with an `accounts.Transfer` to the address
+/// returned by the `evm.DeriveCaller` query), while transaction fees and their refunds remain
+/// with the native account.
+pub fn from_sdk_address(address: &Address) -> H160 {
+    from_bytes(&[SDK_ADDRESS_CALLER_CONTEXT, address.as_ref()].concat())
 }
 
 pub fn from_sigspec(spec: &SignatureAddressSpec) -> Result<H160, Error> {
     match spec {
-        SignatureAddressSpec::Secp256k1Eth(pk) => Ok(from_secp256k1_public_key(pk)),
+        SignatureAddressSpec::Secp256k1Eth(pk) => from_secp256k1_public_key(pk),
+        SignatureAddressSpec::Ed25519(_) => Ok(from_sdk_address(
+            &Address::from_sigspec(spec).map_err(|_| Error::InvalidArgument)?,
+        )),
         _ => Err(Error::InvalidSignerType),
     }
 }
 
-pub fn from_tx_auth_info(ai: &AuthInfo) -> Result<H160, Error> {
-    match &ai.signer_info[0].address_spec {
+pub fn from_address_spec(spec: &AddressSpec) -> Result<H160, Error> {
+    match spec {
         AddressSpec::Signature(spec) => from_sigspec(spec),
+        AddressSpec::Multisig(config) => {
+            Ok(from_sdk_address(&Address::from_multisig(config.clone())))
+        }
         AddressSpec::Internal(CallerAddress::EthAddress(address)) => Ok(address.into()),
         _ => Err(Error::InvalidSignerType),
     }
 }
+
+pub fn from_tx_auth_info(ai: &AuthInfo) -> Result<H160, Error> {
+    from_address_spec(&ai.signer_info[0].address_spec)
+}
@@ -0,0 +1,120 @@
+//! Ethereum JSON-RPC compatibility shim.
+//!
+//! Maps the `eth_*` methods served by the web3 gateway onto the EVM module's queries so that the
+//! translation logic lives next to the queries themselves and the two can't drift apart.
+//!
+//! Transaction receipts and logs are not kept in runtime state, so `eth_getTransactionReceipt`
+//! and `eth_getLogs` are rejected as unsupported and must be served from the gateway's indexer.
+use crate::{types, Error};
+
+/// Method name of `eth_chainId`.
+pub const METHOD_CHAIN_ID: &str = "eth_chainId";
+/// Method name of `eth_call`.
+pub const METHOD_CALL: &str = "eth_call";
+/// Method name of `eth_estimateGas`.
+pub const METHOD_ESTIMATE_GAS: &str = "eth_estimateGas";
+/// Method name of `eth_getStorageAt`.
+pub const METHOD_GET_STORAGE_AT: &str = "eth_getStorageAt";
+/// Method name of `eth_getCode`.
+pub const METHOD_GET_CODE: &str = "eth_getCode";
+/// Method name of `eth_getBalance`.
+pub const METHOD_GET_BALANCE: &str = "eth_getBalance";
+/// Method name of `eth_getTransactionReceipt`, which is not supported.
+pub const METHOD_GET_TRANSACTION_RECEIPT: &str = "eth_getTransactionReceipt";
+/// Method name of `eth_getLogs`, which is not supported.
+pub const METHOD_GET_LOGS: &str = "eth_getLogs";
+
+/// Query body for the `evm.EthRpc` query.
+#[derive(Clone, Debug, cbor::Encode, cbor::Decode)]
+pub struct EthRpcQuery {
+    /// Name of the `eth_*` method.
+    pub method: String,
+    /// Method parameters, encoded as the body of the corresponding EVM module query.
+    #[cbor(optional)]
+    pub params: Option<cbor::Value>,
+}
+
+/// A decoded `eth_*` request.
+#[derive(Clone, Debug)]
+pub enum EthRpcRequest {
+    /// `eth_chainId`.
+    ChainId,
+    /// `eth_call`, served by `evm.SimulateCall`.
+    Call(types::SimulateCallQuery),
+    /// `eth_estimateGas`, served by `evm.EstimateGas`.
+    EstimateGas(types::EstimateGasQuery),
+    /// `eth_getStorageAt`, served by `evm.Storage`.
+    GetStorageAt(types::StorageQuery),
+    /// `eth_getCode`, served by `evm.Code`.
+    GetCode(types::CodeQuery),
+    /// `eth_getBalance`, served by `evm.Balance`.
+    GetBalance(types::BalanceQuery),
+}
+
+impl EthRpcRequest {
+    /// Decodes an `eth_*` request from the given query.
+    pub fn decode(query: EthRpcQuery) -> Result<Self, Error> {
+        fn params<T: cbor::Decode>(params: Option<cbor::Value>) -> Result<T, Error> {
+            let params = params.ok_or(Error::InvalidArgument)?;
+            cbor::from_value(params).map_err(|_| Error::InvalidArgument)
+        }
+
+        match query.method.as_str() {
+            METHOD_CHAIN_ID => Ok(Self::ChainId),
+            METHOD_CALL => Ok(Self::Call(params(query.params)?)),
+            METHOD_ESTIMATE_GAS => Ok(Self::EstimateGas(params(query.params)?)),
+            METHOD_GET_STORAGE_AT => Ok(Self::GetStorageAt(params(query.params)?)),
+            METHOD_GET_CODE => Ok(Self::GetCode(params(query.params)?)),
+            METHOD_GET_BALANCE => Ok(Self::GetBalance(params(query.params)?)),
+            _ => Err(Error::UnsupportedRpcMethod(query.method)),
+        }
+    }
+
+    /// Name of the EVM module query serving this request, if any.
+    pub fn query_method(&self) -> Option<&'static str> {
+        match self {
+            Self::ChainId => None,
+            Self::Call(_) => Some("evm.SimulateCall"),
+            Self::EstimateGas(_) => Some("evm.EstimateGas"),
+            Self::GetStorageAt(_) => Some("evm.Storage"),
+            Self::GetCode(_) => Some("evm.Code"),
+            Self::GetBalance(_) => Some("evm.Balance"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_request() {
+        let query = EthRpcQuery {
+            method: METHOD_GET_CODE.to_string(),
+            params: Some(cbor::to_value(types::CodeQuery::default())),
+        };
+        let req = EthRpcRequest::decode(query).unwrap();
+        assert!(matches!(req, EthRpcRequest::GetCode(_)));
+        assert_eq!(req.query_method(), Some("evm.Code"));
+
+        let query = EthRpcQuery {
+            method: METHOD_GET_CODE.to_string(),
+            params: None,
+        };
+        assert!(matches!(
+            EthRpcRequest::decode(query),
+            Err(Error::InvalidArgument)
+        ));
+
+        for method in [METHOD_GET_TRANSACTION_RECEIPT, METHOD_GET_LOGS] {
+            let query = EthRpcQuery {
+                method: method.to_string(),
+                params: None,
+            };
+            assert!(matches!(
+                EthRpcRequest::decode(query),
+                Err(Error::UnsupportedRpcMethod(_))
+            ));
+        }
+    }
+}
@@ -5,6 +5,7 @@
 
 pub mod backend;
 pub mod derive_caller;
+pub mod eth_rpc;
 pub mod precompile;
 pub mod raw_tx;
 mod signed_call;
@@ -14,6 +15,7 @@ pub mod types;
 use std::str::FromStr;
 
 use evm::{
+    backend::Apply,
     executor::stack::{MemoryStackState, StackExecutor, StackSubstateMetadata},
     Config as EVMConfig,
 };
@@ -22,16 +24,18 @@ use thiserror::Error;
 
 use oasis_runtime_sdk::{
     callformat,
+    core::common::crypto::{hash::Hash, mrae::deoxysii},
     context::{BatchContext, Context, TxContext, Mode},
     error::Error as _,
     dispatcher::INFO_CACHE,
-    handler,
+    handler, metrics,
     module::{self, Module as _},
     modules::{
         self,
         accounts::API as _,
         core::{Error as CoreError, API as _},
         consensus_accounts::types::{
+            ConsensusError,
             ConsensusWithdrawContext,
             ConsensusTransferContext,
         },
@@ -44,11 +48,11 @@ use oasis_runtime_sdk::{
     },
     runtime::Runtime,
     sdk_derive,
-    storage,
+    storage::{self, Prefix},
     types::{
         address::{self, Address},
-        token,
-        transaction::{self, Transaction},
+        role, token,
+        transaction::{self, AuthInfo, Transaction},
         message::MessageEvent,
     },
 };
@@ -61,11 +65,21 @@ fn slice_to_array_32<T>(slice: &[T]) -> &[T; 32] {
     unsafe {&*ptr}
 }
 
+/// Converts an EVM value into an amount of the native token. SDK balances are `u128`, so any value
+/// that fits is accepted, including `u128::MAX` itself.
 fn u256_to_u128(value: U256) -> Result<u128, Error> {
-    if value < U256::from(u128::MAX) {
+    if value.bits() <= 128 {
         Ok(value.low_u128())
     } else {
-        Err(Error::Reverted("too large value".to_string()))
+        Err(Error::ValueTooLarge(value))
+    }
+}
+
+/// Converts an error of an internal call into the error reported in deposit and withdrawal events.
+fn internal_call_error(err: Error) -> ConsensusError {
+    ConsensusError {
+        module: err.module_name().to_string(),
+        code: err.code(),
     }
 }
 
@@ -84,6 +98,8 @@ fn u128_to_h256(v: u128) -> H256 {
 
 const DW_SYSTEM_ADDRESS: &str = "0x052cc647E136C85ED9F6Bf5DBB5E79952Be0499F";
 const DW_CONTRACT_ADDRESS: &str = "0xBE75FDe9DeDe700635E3dDBe7e29b5db1A76C125";
+/// Gas budget of the internal calls into the deposit/withdrawal contract.
+const DW_CALL_GAS_LIMIT: u64 = 1_085_479;
 
 
 #[cfg(test)]
@@ -92,6 +108,44 @@ mod test;
 /// Unique module name.
 const MODULE_NAME: &str = "evm";
 
+/// Intrinsic gas of a plain transfer in case it is not configured.
+const DEFAULT_TX_TRANSFER_GAS: u64 = 21_000;
+
+/// Gas charged per removed storage entry in case it is not configured.
+const DEFAULT_STORAGE_REMOVAL_GAS: u64 = 5_000;
+
+/// Gas charged per recorded address alias in case it is not configured.
+const DEFAULT_ADDRESS_ALIAS_GAS: u64 = 20_000;
+
+/// Maximum number of entries returned by a single `evm.StorageRange` query.
+const MAX_STORAGE_RANGE_LIMIT: u64 = 1000;
+
+/// Context key for the number of logs emitted by the current transaction.
+const CONTEXT_KEY_LOG_COUNT: &str = "evm.LogCount";
+
+/// Context key for the gas prices and gas used of EVM transactions in the current block.
+const CONTEXT_KEY_BLOCK_TX_FEES: &str = "evm.BlockTxFees";
+
+/// Context key for whether the current transaction is an `evm.StaticCall`.
+const CONTEXT_KEY_STATIC_CALL: &str = "evm.StaticCall";
+
+/// Context key for the contract the current transaction called and the amount it may still
+/// deposit from the signer's consensus account.
+const CONTEXT_KEY_CONSENSUS_DEPOSIT: &str = "evm.ConsensusDeposit";
+
+/// Metric counting executed EVM transactions.
+const METRIC_EXECUTIONS: &str = "evm.Executions";
+/// Metric counting executions that reverted.
+const METRIC_REVERTS: &str = "evm.Reverts";
+/// Metric counting executions that failed for a reason other than a revert.
+const METRIC_FAILURES: &str = "evm.Failures";
+/// Metric counting calls into precompiles.
+const METRIC_PRECOMPILE_CALLS: &str = "evm.PrecompileCalls";
+/// Metric with the distribution of gas used by executions.
+const METRIC_GAS_USED: &str = "evm.GasUsed";
+/// Metric with the distribution of the wall time of executions, in microseconds.
+const METRIC_EXECUTION_TIME_MICROS: &str = "evm.ExecutionTimeMicros";
+
 /// Module configuration.
 pub trait Config: 'static {
     /// AdditionalPrecompileSet is the type used for the additional precompiles.
@@ -110,6 +164,39 @@ pub trait Config: 'static {
     /// Whether to use confidential storage by default, and transaction data encryption.
     const CONFIDENTIAL: bool = false;
 
+    /// Whether contracts may request consensus layer deposits, withdrawals, delegations and
+    /// undelegations via the consensus staking precompiles.
+    const CONSENSUS_PRECOMPILES: bool = false;
+
+    /// Whether accounts module denominations other than the native EVM token are presented to
+    /// contracts as ERC-20 tokens via the facade precompiles, see
+    /// [`precompile::erc20::facade_address`].
+    const ERC20_FACADE_PRECOMPILES: bool = false;
+
+    /// Whether accounts with the auditor role may request decryption of historical confidential
+    /// transactions via `evm.AuditDecrypt`. Only meaningful when `CONFIDENTIAL` is set.
+    const CONFIDENTIAL_AUDIT: bool = false;
+
+    /// Whether to emit a dedicated event tag for each topic of emitted logs, see
+    /// [`LOG_TOPIC_TAG_CODE`]. Disabled by default as the tags grow the size of emitted events.
+    const EMIT_LOG_TOPIC_TAGS: bool = false;
+
+    /// Address returned by the `COINBASE` opcode. Transaction fees are paid into the fee
+    /// accumulator rather than to a block producer, so this defaults to the zero address.
+    const BLOCK_COINBASE: H160 = H160::zero();
+
+    /// Name of the EIP-712 domain that signed queries are verified against, see
+    /// [`types::SignedCallDataPack`]. Deployments may override it to present their own branding in
+    /// wallet prompts.
+    const SIGNED_QUERY_DOMAIN_NAME: &'static str = "oasis-runtime-sdk/evm: signed query";
+
+    /// Version of the EIP-712 domain that signed queries are verified against.
+    const SIGNED_QUERY_DOMAIN_VERSION: &'static str = "1.0.0";
+
+    /// Name of the hardfork whose rules the config returned by [`Config::evm_config`] follows, as
+    /// reported by the `evm.Config` query. Override it together with `evm_config`.
+    const HARDFORK: &'static str = "london";
+
     /// Maps an Ethereum address into an SDK account address.
     fn map_address(address: primitive_types::H160) -> Address {
         Address::new(
@@ -119,6 +206,24 @@ pub trait Config: 'static {
         )
     }
 
+    /// Returns the account sponsoring the fee of an EVM transaction made by the given caller
+    /// (e.g. a paymaster), or `None` in case the caller pays its own fee.
+    ///
+    /// The maximum fee is still pre-paid by the transaction's fee payer when the transaction is
+    /// authenticated. Once the transaction has been executed, the sponsor is charged the actual
+    /// fee and the pre-paid fee is refunded to the fee payer in full.
+    fn fee_sponsor<C: Context>(_ctx: &mut C, _caller: primitive_types::H160) -> Option<Address> {
+        None
+    }
+
+    /// Returns the round from which the precompile at the given address is available, if its
+    /// activation is scheduled. Before that round calls to the address behave as calls to an empty
+    /// account, which allows rolling out new precompiles (builtin or additional ones) without
+    /// changing the outcome of historical transactions.
+    fn precompile_activation_round(_address: primitive_types::H160) -> Option<u64> {
+        None
+    }
+
     /// Provides additional precompiles that should be available to the EVM.
     ///
     /// If any of the precompile addresses returned is the same as for one of
@@ -202,9 +307,49 @@ pub enum Error {
     #[sdk_error(code = 10)]
     InvalidSignedSimulateCall(&'static str),
 
+    #[error("unsupported rpc method: {0}")]
+    #[sdk_error(code = 11)]
+    UnsupportedRpcMethod(String),
+
+    #[error("code too large: {0} bytes exceeds the limit of {1} bytes")]
+    #[sdk_error(code = 12)]
+    CodeTooLarge(u64, u64),
+
+    #[error("audit decryption failed: {0}")]
+    #[sdk_error(code = 13)]
+    AuditDecryptionFailed(String),
+
+    #[error("reverted with data: {0}")]
+    #[sdk_error(code = 14)]
+    RevertedWithData(String),
+
+    #[error("state for round {0} is not available (current round: {1})")]
+    #[sdk_error(code = 15)]
+    HistoricalStateUnavailable(u64, u64),
+
+    #[error("signed query revoked: query nonce must be at least {0}")]
+    #[sdk_error(code = 16)]
+    SignedQueryRevoked(u64),
+
+    #[error("unsupported transaction type: {0}")]
+    #[sdk_error(code = 17)]
+    UnsupportedTxType(u8),
+
+    #[error("value too large: {0} exceeds the maximum token amount")]
+    #[sdk_error(code = 18)]
+    ValueTooLarge(U256),
+
+    #[error("contract is paused")]
+    #[sdk_error(code = 19)]
+    ContractPaused,
+
     #[error("core: {0}")]
     #[sdk_error(transparent)]
     Core(#[from] CoreError),
+
+    #[error("consensus accounts: {0}")]
+    #[sdk_error(transparent)]
+    ConsensusAccounts(#[from] modules::consensus_accounts::Error),
 }
 
 impl From<evm::ExitError> for Error {
@@ -249,6 +394,50 @@ impl From<evm::ExitFatal> for Error {
     }
 }
 
+/// Records metrics of a single EVM execution.
+fn record_execution_metrics(
+    exit_reason: &evm::ExitReason,
+    gas_used: u64,
+    started: std::time::Instant,
+    precompile_calls: u64,
+) {
+    metrics::inc_counter(METRIC_EXECUTIONS, 1);
+    if exit_reason.is_revert() {
+        metrics::inc_counter(METRIC_REVERTS, 1);
+    } else if !exit_reason.is_succeed() {
+        metrics::inc_counter(METRIC_FAILURES, 1);
+    }
+    metrics::inc_counter(METRIC_PRECOMPILE_CALLS, precompile_calls);
+    metrics::observe(METRIC_GAS_USED, gas_used);
+    metrics::observe(
+        METRIC_EXECUTION_TIME_MICROS,
+        started.elapsed().as_micros().try_into().unwrap_or(u64::MAX),
+    );
+}
+
+/// Describes a Solidity panic code, see
+/// <https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require>.
+fn panic_reason(code: primitive_types::U256) -> String {
+    if code > primitive_types::U256::from(u8::MAX) {
+        return format!("unknown panic code ({:#x})", code);
+    }
+    let code = code.low_u32();
+    let reason = match code {
+        0x00 => "generic compiler panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to uninitialized function",
+        _ => "unknown panic code",
+    };
+    format!("{} (0x{:02x})", reason, code)
+}
+
 /// Process an EVM result to return either a successful result or a (readable) error reason.
 fn process_evm_result(exit_reason: evm::ExitReason, data: Vec<u8>) -> Result<Vec<u8>, Error> {
     match exit_reason {
@@ -274,16 +463,22 @@ fn process_evm_result(exit_reason: evm::ExitReason, data: Vec<u8>) -> Result<Vec
             const MIN_SIZE: usize = FIELD_REASON_START;
             const MAX_REASON_SIZE: usize = 1024;
 
+            const PANIC_SELECTOR: &[u8] = &[0x4e, 0x48, 0x7b, 0x71]; // Keccak256("Panic(uint256)")
+            const PANIC_SIZE: usize = 4 + 32;
+
             let max_raw_len = if data.len() > MAX_REASON_SIZE {
                 MAX_REASON_SIZE
             } else {
                 data.len()
             };
+            if data.len() == PANIC_SIZE && data.starts_with(PANIC_SELECTOR) {
+                let code = primitive_types::U256::from(&data[4..PANIC_SIZE]);
+                return Err(Error::Reverted(format!("panic: {}", panic_reason(code))));
+            }
             if data.len() < MIN_SIZE || !data.starts_with(ERROR_STRING_SELECTOR) {
-                return Err(Error::Reverted(format!(
-                    "invalid reason prefix: '{}'",
-                    base64::encode(&data[..max_raw_len])
-                )));
+                // Not an `Error(string)` payload (e.g. a custom Solidity error identified by its
+                // 4-byte selector), return the raw revert data so that clients can decode it.
+                return Err(Error::RevertedWithData(base64::encode(&data)));
             }
             // Decode and validate length.
             let mut length =
@@ -311,19 +506,250 @@ fn process_evm_result(exit_reason: evm::ExitReason, data: Vec<u8>) -> Result<Vec
 
 /// Gas costs.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
-pub struct GasCosts {}
+pub struct GasCosts {
+    /// Additional gas charged per byte of deployed contract code.
+    #[cbor(optional)]
+    pub code_byte: u64,
+    /// Additional gas charged for each contract creation transaction.
+    #[cbor(optional)]
+    pub tx_create: u64,
+    /// Multiplier (in percent) applied to gas costs recorded by precompiles. Zero means that
+    /// precompile gas costs are not scaled.
+    #[cbor(optional)]
+    pub precompile_multiplier: u64,
+    /// Intrinsic gas charged for a plain transfer to an account without code. Zero means that
+    /// the Ethereum default of 21000 is used.
+    #[cbor(optional)]
+    pub tx_transfer: u64,
+    /// Gas charged per zero byte of plain transfer call data.
+    #[cbor(optional)]
+    pub tx_data_zero_byte: u64,
+    /// Gas charged per non-zero byte of plain transfer call data.
+    #[cbor(optional)]
+    pub tx_data_nonzero_byte: u64,
+    /// Gas charged per storage entry removed when a contract self-destructs or is created again
+    /// at the same address. Zero means that the default of 5000 is used.
+    #[cbor(optional)]
+    pub storage_removal: u64,
+    /// Gas charged for recording the Ethereum address of an account the first time it is used in
+    /// the EVM. Zero means that the default of 20000 is used.
+    #[cbor(optional)]
+    pub address_alias: u64,
+    /// Gas costs of the standard Ethereum precompiles.
+    #[cbor(optional)]
+    pub precompiles: PrecompileGasCosts,
+}
+
+/// Gas costs of the standard Ethereum precompiles. Zero means that the Ethereum default is used.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct PrecompileGasCosts {
+    /// Gas charged by `ecrecover`. Default: 3000.
+    #[cbor(optional)]
+    pub ecrecover: u64,
+    /// Base gas charged by `sha256`. Default: 60.
+    #[cbor(optional)]
+    pub sha256_base: u64,
+    /// Gas charged by `sha256` per word of input. Default: 12.
+    #[cbor(optional)]
+    pub sha256_word: u64,
+    /// Base gas charged by `ripemd160`. Default: 600.
+    #[cbor(optional)]
+    pub ripemd160_base: u64,
+    /// Gas charged by `ripemd160` per word of input. Default: 120.
+    #[cbor(optional)]
+    pub ripemd160_word: u64,
+    /// Base gas charged by the identity precompile. Default: 15.
+    #[cbor(optional)]
+    pub identity_base: u64,
+    /// Gas charged by the identity precompile per word of input. Default: 3.
+    #[cbor(optional)]
+    pub identity_word: u64,
+    /// Minimum gas charged by `modexp`. Default: 200.
+    #[cbor(optional)]
+    pub modexp_min: u64,
+    /// Divisor of the EIP-2565 `modexp` gas formula, lower values make `modexp` more
+    /// expensive. Default: 3.
+    #[cbor(optional)]
+    pub modexp_divisor: u64,
+}
+
+impl PrecompileGasCosts {
+    /// Returns the gas costs with all unset costs replaced by the Ethereum defaults.
+    pub fn with_defaults(&self) -> Self {
+        fn or(value: u64, default: u64) -> u64 {
+            match value {
+                0 => default,
+                value => value,
+            }
+        }
+
+        Self {
+            ecrecover: or(self.ecrecover, 3_000),
+            sha256_base: or(self.sha256_base, 60),
+            sha256_word: or(self.sha256_word, 12),
+            ripemd160_base: or(self.ripemd160_base, 600),
+            ripemd160_word: or(self.ripemd160_word, 120),
+            identity_base: or(self.identity_base, 15),
+            identity_word: or(self.identity_word, 3),
+            modexp_min: or(self.modexp_min, 200),
+            modexp_divisor: or(self.modexp_divisor, 3),
+        }
+    }
+}
+
+impl GasCosts {
+    /// Gas charged per removed storage entry.
+    pub fn storage_removal_gas(&self) -> u64 {
+        match self.storage_removal {
+            0 => DEFAULT_STORAGE_REMOVAL_GAS,
+            gas => gas,
+        }
+    }
+
+    /// Gas charged per recorded address alias.
+    pub fn address_alias_gas(&self) -> u64 {
+        match self.address_alias {
+            0 => DEFAULT_ADDRESS_ALIAS_GAS,
+            gas => gas,
+        }
+    }
+
+    /// Intrinsic gas of a plain transfer with the given call data.
+    pub fn transfer_gas(&self, data: &[u8]) -> Result<u64, Error> {
+        let base = match self.tx_transfer {
+            0 => DEFAULT_TX_TRANSFER_GAS,
+            gas => gas,
+        };
+        let zero_bytes = data.iter().filter(|&&b| b == 0).count() as u64;
+        let nonzero_bytes = data.len() as u64 - zero_bytes;
+        let data_gas = self
+            .tx_data_zero_byte
+            .checked_mul(zero_bytes)
+            .zip(self.tx_data_nonzero_byte.checked_mul(nonzero_bytes))
+            .and_then(|(zero, nonzero)| zero.checked_add(nonzero));
+        data_gas
+            .and_then(|gas| gas.checked_add(base))
+            .ok_or(Error::FeeOverflow)
+    }
+}
+
+/// Argument layout of `function(address account, uint256 amount, bool by_system)`.
+pub const SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT_FLAG: u8 = 1;
+/// Argument layout of `function(address account, uint256 amount)`.
+pub const SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT: u8 = 2;
+
+/// ABI of a function of the deposit/withdrawal system contract.
+#[derive(Clone, Debug, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct SystemContractFunction {
+    /// Function selector.
+    pub selector: [u8; 4],
+    /// Version of the argument layout, one of the `SYSTEM_CONTRACT_LAYOUT_*` constants.
+    pub layout_version: u8,
+}
+
+impl SystemContractFunction {
+    /// Mint function of the deposit/withdrawal contract deployed at genesis.
+    pub const DEFAULT_MINT: Self = Self {
+        selector: [0xd1, 0xa1, 0xbe, 0xb4],
+        layout_version: SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT_FLAG,
+    };
+    /// Burn function of the deposit/withdrawal contract deployed at genesis.
+    pub const DEFAULT_BURN: Self = Self {
+        selector: [0x76, 0xfd, 0x4f, 0xdf],
+        layout_version: SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT_FLAG,
+    };
+
+    /// Whether the argument layout version is supported.
+    pub fn is_supported(&self) -> bool {
+        matches!(
+            self.layout_version,
+            SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT_FLAG | SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT
+        )
+    }
+
+    /// Encodes the call data of a call to this function.
+    pub fn encode_call(
+        &self,
+        account: &H160,
+        amount: &H256,
+        by_system: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let mut args = vec![
+            ethabi::Token::Address(account.0.into()),
+            ethabi::Token::Uint(ethabi::Uint::from_big_endian(amount.as_bytes())),
+        ];
+        match self.layout_version {
+            SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT_FLAG => args.push(ethabi::Token::Bool(by_system)),
+            SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT => {}
+            _ => return Err(Error::InvalidArgument),
+        }
+        Ok([&self.selector[..], &ethabi::encode(&args)].concat())
+    }
+}
 
 /// Parameters for the EVM module.
 #[derive(Clone, Default, Debug, cbor::Encode, cbor::Decode)]
 pub struct Parameters {
     /// Gas costs.
     pub gas_costs: GasCosts,
+
+    /// Maximum size (in bytes) of deployed contract code. Init code may be at most twice as large.
+    /// Zero means that code size is not limited.
+    #[cbor(optional)]
+    pub max_code_size: u64,
+
+    /// Whether contract creation is disabled.
+    #[cbor(optional)]
+    pub create_disabled: bool,
+    /// Whether contract calls are disabled. Plain transfers remain allowed.
+    #[cbor(optional)]
+    pub call_disabled: bool,
+
+    /// If set, only addresses holding the given role may deploy contracts.
+    #[cbor(optional)]
+    pub create_role: Option<role::Role>,
+
+    /// ABI of the mint function of the deposit/withdrawal system contract. Defaults to
+    /// [`SystemContractFunction::DEFAULT_MINT`].
+    #[cbor(optional)]
+    pub system_mint: Option<SystemContractFunction>,
+    /// ABI of the burn function of the deposit/withdrawal system contract. Defaults to
+    /// [`SystemContractFunction::DEFAULT_BURN`].
+    #[cbor(optional)]
+    pub system_burn: Option<SystemContractFunction>,
+}
+
+impl Parameters {
+    /// Maximum size (in bytes) of contract init code, if limited.
+    pub fn max_init_code_size(&self) -> Option<u64> {
+        if self.max_code_size == 0 {
+            return None;
+        }
+        Some(self.max_code_size.saturating_mul(2))
+    }
+
+    /// ABI of the mint function of the deposit/withdrawal system contract.
+    pub fn system_mint(&self) -> SystemContractFunction {
+        self.system_mint
+            .clone()
+            .unwrap_or(SystemContractFunction::DEFAULT_MINT)
+    }
+
+    /// ABI of the burn function of the deposit/withdrawal system contract.
+    pub fn system_burn(&self) -> SystemContractFunction {
+        self.system_burn
+            .clone()
+            .unwrap_or(SystemContractFunction::DEFAULT_BURN)
+    }
 }
 
 impl module::Parameters for Parameters {
     type Error = ();
 
     fn validate_basic(&self) -> Result<(), Self::Error> {
+        if !self.system_mint().is_supported() || !self.system_burn().is_supported() {
+            return Err(());
+        }
         Ok(())
     }
 }
@@ -332,18 +758,39 @@ impl module::Parameters for Parameters {
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
 pub struct Genesis {
     pub parameters: Parameters,
+
+    /// Contracts installed at fixed addresses in genesis (e.g. a CREATE2 deployer).
+    #[cbor(optional)]
+    pub contracts: Vec<GenesisContract>,
+}
+
+/// A contract installed at a fixed address in genesis.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct GenesisContract {
+    /// Address of the contract.
+    pub address: H160,
+    /// Deployed (runtime) bytecode of the contract.
+    pub code: Vec<u8>,
 }
 
 /// Local configuration that can be provided by the node operator.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
 pub struct LocalConfig {
-    /// Maximum gas limit that can be passed to the `evm.SimulateCall` query. Queries
-    /// with a higher gas limit will be rejected. A special value of `0` indicates
+    /// Maximum gas limit that can be passed to the `evm.SimulateCall` and `evm.EstimateGas`
+    /// queries. Queries with a higher gas limit will be rejected. A special value of `0` indicates
     /// no limit. Default: 0.
     #[cbor(optional)]
     pub query_simulate_call_max_gas: u64,
 }
 
+/// Code of the event tags emitted for each topic of a log, in addition to the `Log` event. The
+/// tag key is followed by the log address, the topic position and the topic, so that the host can
+/// efficiently filter transactions by them. The value is the index of the log among the logs of
+/// the same transaction.
+///
+/// The code must not be used by any variant of [`Event`].
+pub const LOG_TOPIC_TAG_CODE: u32 = 3;
+
 /// Events emitted by the EVM module.
 #[derive(Debug, cbor::Encode, oasis_runtime_sdk::Event)]
 #[cbor(untagged)]
@@ -353,6 +800,32 @@ pub enum Event {
         address: H160,
         topics: Vec<H256>,
         data: Vec<u8>,
+        /// Hash of the transaction that emitted the log, if known.
+        #[cbor(optional)]
+        tx_hash: Option<Hash>,
+        /// Index of the log among all logs emitted by the transaction.
+        #[cbor(optional)]
+        log_index: u64,
+    },
+
+    #[sdk_event(code = 2)]
+    AuditAccess {
+        auditor: Address,
+        epoch: u64,
+        envelope_hash: Hash,
+    },
+
+    /// Emitted once per EVM transaction (also when it fails), reporting the gas it used. Code 3
+    /// is reserved for [`LOG_TOPIC_TAG_CODE`].
+    #[sdk_event(code = 4)]
+    Execution {
+        /// Hash of the transaction, if known.
+        #[cbor(optional)]
+        tx_hash: Option<Hash>,
+        /// Gas used by the transaction.
+        gas_used: u64,
+        /// Outcome of the execution.
+        status: types::ExecutionStatus,
     },
 }
 
@@ -386,6 +859,20 @@ pub trait API {
         data: Vec<u8>,
     ) -> Result<Vec<u8>, Error>;
 
+    /// Perform an Ethereum CALL on behalf of another module.
+    ///
+    /// Unlike [`API::call`] this does not require a transaction context, so it can also be used
+    /// from message result handlers. No fees are charged, but the gas used (at most `gas_limit`)
+    /// is accounted for against the batch gas limit.
+    fn internal_call<C: Context>(
+        ctx: &mut C,
+        caller: H160,
+        address: H160,
+        value: U256,
+        data: Vec<u8>,
+        gas_limit: u64,
+    ) -> Result<Vec<u8>, Error>;
+
     fn call_sc_mint<C: Context>(
         ctx: &mut C,
         address: &H160,
@@ -412,15 +899,39 @@ pub trait API {
     /// Get EVM account balance.
     fn get_balance<C: Context>(ctx: &mut C, address: H160) -> Result<u128, Error>;
 
+    /// Look up the Ethereum address that maps to the given SDK address.
+    ///
+    /// Only addresses that have been used in the EVM are known.
+    fn resolve_eth_address<C: Context>(ctx: &mut C, address: Address) -> Option<H160>;
+
     /// Simulate an Ethereum CALL.
     ///
     /// If the EVM is confidential, it may accept _signed queries_, which are formatted as
     /// an either a [`sdk::types::transaction::Call`] or [`types::SignedCallDataPack`] encoded
     /// and packed into the `data` field of the [`types::SimulateCallQuery`].
+    ///
+    /// In case the query specifies a round, the call is only simulated if the context state is
+    /// at that round.
     fn simulate_call<C: Context>(
         ctx: &mut C,
         call: types::SimulateCallQuery,
     ) -> Result<Vec<u8>, Error>;
+
+    /// Estimate the minimal amount of gas an EVM call or contract creation needs to succeed.
+    ///
+    /// The estimate only covers the EVM execution and does not include the transaction size and
+    /// signature verification costs charged by the core module.
+    fn estimate_gas<C: Context>(ctx: &mut C, query: types::EstimateGasQuery)
+        -> Result<u64, Error>;
+
+    /// Execute several read-only calls in order against a shared simulation state. Effects of
+    /// earlier calls are visible to later ones and the gas limit is shared by all calls.
+    ///
+    /// Fails in case any call that does not allow failure fails.
+    fn multicall<C: Context>(
+        ctx: &mut C,
+        query: types::MulticallQuery,
+    ) -> Result<Vec<types::MulticallResult>, Error>;
 }
 
 impl<Cfg: Config> API for Module<Cfg> {
@@ -430,6 +941,9 @@ impl<Cfg: Config> API for Module<Cfg> {
         init_code: Vec<u8>,
     ) -> Result<Vec<u8>, Error> {
         let caller = Self::derive_caller(ctx)?;
+        // The value is moved from the caller to the new contract as part of the execution, so it
+        // is only debited in case the creation succeeds.
+        u256_to_u128(value)?;
 
         if !ctx.should_execute_contracts() {
             // Only fast checks are allowed.
@@ -443,6 +957,17 @@ impl<Cfg: Config> API for Module<Cfg> {
             Self::decode_call_data(ctx, init_code, ctx.tx_call_format(), ctx.tx_index(), true)?
                 .expect("processing always proceeds");
 
+        // Charge the contract creation surcharge.
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_create)?;
+
+        if let Some(max_init_code_size) = params.max_init_code_size() {
+            let init_code_size = init_code.len() as u64;
+            if init_code_size > max_init_code_size {
+                return Err(Error::CodeTooLarge(init_code_size, max_init_code_size));
+            }
+        }
+
         Self::do_evm(
             caller,
             ctx,
@@ -473,21 +998,31 @@ impl<Cfg: Config> API for Module<Cfg> {
         value: U256,
         data: Vec<u8>,
     ) -> Result<Vec<u8>, Error> {
+        let caller = Self::derive_caller(ctx)?;
+        Self::ensure_not_blacklisted(ctx, &[caller, address])?;
+
+        // Reject dust transfers as part of the fast checks.
+        let u128value = u256_to_u128(value)?;
+        let amount = token::BaseUnits::new(u128value, Cfg::TOKEN_DENOMINATION);
+        Cfg::Accounts::ensure_min_transfer_amount(ctx.runtime_state(), &amount)
+            .map_err(|_| Error::InvalidArgument)?;
+
         if !ctx.should_execute_contracts() {
             // Only fast checks are allowed.
             return Ok(vec![]);
         }
 
-        let caller = Self::derive_caller(ctx)?;
-
         let from = Cfg::map_address(caller.into());
 
         let to = Cfg::map_address(address.into());
+        let new_aliases = [(caller, from), (address, to)]
+            .into_iter()
+            .filter(|(eth_address, address)| {
+                state::register_address_alias(ctx.runtime_state(), *eth_address, *address)
+            })
+            .count() as u64;
 
-        let u128value = u256_to_u128(value)?;
-        let amount = token::BaseUnits::new(u128value, Cfg::TOKEN_DENOMINATION);
-
-        let (_, tx_metadata) =
+        let (data, tx_metadata) =
             Self::decode_call_data(ctx, data, ctx.tx_call_format(), ctx.tx_index(), true)?
                 .expect("processing always proceeds");
 
@@ -500,8 +1035,10 @@ impl<Cfg: Config> API for Module<Cfg> {
             .checked_mul(primitive_types::U256::from(gas_limit))
             .ok_or(Error::FeeOverflow)?;
 
-        let gas_used = 21000;
-        let fee = gas_price * gas_used;
+        let gas_costs = Self::params(ctx.runtime_state()).gas_costs;
+        let gas_used = gas_costs
+            .transfer_gas(&data)?
+            .saturating_add(new_aliases.saturating_mul(gas_costs.address_alias_gas()));
 
         let my_result: Result<(), Error> =
             Cfg::Accounts::transfer(ctx, from, to, &amount).map_err(|_| Error::InvalidArgument);
@@ -511,18 +1048,21 @@ impl<Cfg: Config> API for Module<Cfg> {
             return Err(my_result.unwrap_err());
         }
 
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
+
+        // Charge for the emitted events so that the cost is covered by the fee.
+        let gas_used = gas_used + <C::Runtime as Runtime>::Core::charge_event_gas(ctx)?;
+        let fee = gas_price * gas_used;
+        Self::record_tx_fee(ctx, gas_price.low_u128(), gas_used);
+
         // Return the difference between the pre-paid max_gas and actually used gas.
         let return_fee = max_gas_fee
             .checked_sub(fee)
             .ok_or(Error::InsufficientBalance)?;
 
-        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
-
-        // Move the difference from the fee accumulator back to the caller.
-        let caller_address = Cfg::map_address(caller.into());
-        Cfg::Accounts::move_from_fee_accumulator(
+        // Move the difference from the fee accumulator back to the fee payer.
+        Cfg::Accounts::refund_fee(
             ctx,
-            caller_address,
             &token::BaseUnits::new(return_fee.as_u128(), fee_denomination),
         )
         .map_err(|_| Error::InsufficientBalance)?;
@@ -542,6 +1082,14 @@ impl<Cfg: Config> API for Module<Cfg> {
         data: Vec<u8>,
     ) -> Result<Vec<u8>, Error> {
         let caller = Self::derive_caller(ctx)?;
+        Self::ensure_not_blacklisted(ctx, &[caller, address])?;
+
+        // Contracts may be paused by governance, see `Action::PauseContract` in the accounts
+        // module.
+        if Cfg::Accounts::is_contract_paused(ctx.runtime_state(), Cfg::map_address(address.into()))
+        {
+            return Err(Error::ContractPaused);
+        }
 
         if !ctx.should_execute_contracts() {
             // Only fast checks are allowed.
@@ -579,47 +1127,20 @@ impl<Cfg: Config> API for Module<Cfg> {
         amount: &H256,
         by_system: bool,
     ) -> Result<Vec<u8>, Error> {
-        //let caller = Self::derive_caller(ctx)?;
-
         let caller = H160::from_str(DW_SYSTEM_ADDRESS).unwrap();
         let sc_addr = H160::from_str(DW_CONTRACT_ADDRESS).unwrap();
-        let flag = if by_system {
-            H256::from_low_u64_be(1)
-        } else {
-            H256::from_low_u64_be(0)
-        };
-
-        let mut data = vec![0xd1, 0xa1, 0xbe, 0xb4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];    //mint() signature
-        data.append(&mut address.as_bytes().to_vec());
-        data.append(&mut amount.as_bytes().to_vec());
-        data.append(&mut flag.as_bytes().to_vec());
-
-        if !ctx.should_execute_contracts() {
-            // Only fast checks are allowed.
-            return Ok(vec![]);
-        }
+        let data = Self::params(ctx.runtime_state())
+            .system_mint()
+            .encode_call(address, amount, by_system)?;
 
-        let tx_metadata = callformat::Metadata::Empty;
-
-        let evm_result = Self::do_sc_evm(
-            caller,
+        Self::internal_call(
             ctx,
-            |exec, gas_limit| {
-                exec.transact_call(
-                    caller.into(),
-                    sc_addr.into(),
-                    primitive_types::U256::zero(),
-                    data,
-                    gas_limit,
-                    vec![],
-                )
-            },
-            // If in simulation, this must be EstimateGas query.
-            // Use estimate mode if not doing binary search for exact gas costs.
-            ctx.is_simulation()
-                && <C::Runtime as Runtime>::Core::estimate_gas_search_max_iters(ctx) == 0,
-        );
-        Self::encode_evm_result(ctx, evm_result, tx_metadata)
+            caller,
+            sc_addr,
+            U256::zero(),
+            data,
+            DW_CALL_GAS_LIMIT,
+        )
     }
 
     fn call_sc_burn<C: Context>(
@@ -628,47 +1149,58 @@ impl<Cfg: Config> API for Module<Cfg> {
         amount: &H256,
         by_system: bool,
     ) -> Result<Vec<u8>, Error> {
-        //let caller = Self::derive_caller(ctx)?;
-
         let caller = H160::from_str(DW_SYSTEM_ADDRESS).unwrap();
         let sc_addr = H160::from_str(DW_CONTRACT_ADDRESS).unwrap();
-        let flag = if by_system {
-            H256::from_low_u64_be(1)
-        } else {
-            H256::from_low_u64_be(0)
-        };
+        let data = Self::params(ctx.runtime_state())
+            .system_burn()
+            .encode_call(address, amount, by_system)?;
 
-        let mut data = vec![0x76, 0xfd, 0x4f, 0xdf, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];    //mint() signature
-        data.append(&mut address.as_bytes().to_vec());
-        data.append(&mut amount.as_bytes().to_vec());
-        data.append(&mut flag.as_bytes().to_vec());
+        Self::internal_call(
+            ctx,
+            caller,
+            sc_addr,
+            U256::zero(),
+            data,
+            DW_CALL_GAS_LIMIT,
+        )
+    }
 
+    fn internal_call<C: Context>(
+        ctx: &mut C,
+        caller: H160,
+        address: H160,
+        value: U256,
+        data: Vec<u8>,
+        gas_limit: u64,
+    ) -> Result<Vec<u8>, Error> {
         if !ctx.should_execute_contracts() {
             // Only fast checks are allowed.
             return Ok(vec![]);
         }
 
-        let tx_metadata = callformat::Metadata::Empty;
+        // Internal calls must not go over the remaining batch gas.
+        let gas_limit = gas_limit.min(<C::Runtime as Runtime>::Core::remaining_batch_gas(ctx));
+        // If in simulation, this must be EstimateGas query.
+        // Use estimate mode if not doing binary search for exact gas costs.
+        let estimate_gas = ctx.is_simulation()
+            && <C::Runtime as Runtime>::Core::estimate_gas_search_max_iters(ctx) == 0;
 
-        let evm_result = Self::do_sc_evm(
+        Self::do_internal_evm(
             caller,
             ctx,
             |exec, gas_limit| {
                 exec.transact_call(
                     caller.into(),
-                    sc_addr.into(),
-                    primitive_types::U256::zero(),
+                    address.into(),
+                    value.into(),
                     data,
                     gas_limit,
                     vec![],
                 )
             },
-            // If in simulation, this must be EstimateGas query.
-            // Use estimate mode if not doing binary search for exact gas costs.
-            ctx.is_simulation()
-                && <C::Runtime as Runtime>::Core::estimate_gas_search_max_iters(ctx) == 0,
-        );
-        Self::encode_evm_result(ctx, evm_result, tx_metadata)
+            gas_limit,
+            estimate_gas,
+        )
     }
 
     fn get_storage<C: Context>(ctx: &mut C, address: H160, index: H256) -> Result<Vec<u8>, Error> {
@@ -690,10 +1222,16 @@ impl<Cfg: Config> API for Module<Cfg> {
         Ok(Cfg::Accounts::get_balance(state, address, Cfg::TOKEN_DENOMINATION).unwrap_or_default())
     }
 
+    fn resolve_eth_address<C: Context>(ctx: &mut C, address: Address) -> Option<H160> {
+        state::address_aliases(ctx.runtime_state()).get(address)
+    }
+
     fn simulate_call<C: Context>(
         ctx: &mut C,
         call: types::SimulateCallQuery,
     ) -> Result<Vec<u8>, Error> {
+        Self::ensure_query_round(ctx, call.round)?;
+
         let (
             types::SimulateCallQuery {
                 gas_price,
@@ -702,6 +1240,7 @@ impl<Cfg: Config> API for Module<Cfg> {
                 address,
                 value,
                 data,
+                ..
             },
             tx_metadata,
         ) = Self::decode_simulate_call_query(ctx, call)?;
@@ -716,6 +1255,7 @@ impl<Cfg: Config> API for Module<Cfg> {
                         address,
                         value,
                         data: data.clone(),
+                        ..Default::default()
                     }),
                     ..Default::default()
                 },
@@ -756,47 +1296,448 @@ impl<Cfg: Config> API for Module<Cfg> {
         });
         Self::encode_evm_result(ctx, evm_result, tx_metadata)
     }
-}
-
-impl<Cfg: Config> Module<Cfg> {
-    fn do_evm<C, F>(source: H160, ctx: &mut C, f: F, estimate_gas: bool) -> Result<Vec<u8>, Error>
-    where
-        F: FnOnce(
-            &mut StackExecutor<
-                'static,
-                '_,
-                MemoryStackState<'_, 'static, backend::Backend<'_, C, Cfg>>,
-                precompile::Precompiles<Cfg, backend::Backend<'_, C, Cfg>>,
-            >,
-            u64,
-        ) -> (evm::ExitReason, Vec<u8>),
-        C: TxContext,
-    {
-        let cfg = Cfg::evm_config(estimate_gas);
-        let gas_limit: u64 = <C::Runtime as Runtime>::Core::remaining_tx_gas(ctx);
-        let gas_price: primitive_types::U256 = ctx.tx_auth_info().fee.gas_price().into();
-        let fee_denomination = ctx.tx_auth_info().fee.amount.denomination().clone();
 
-        let vicinity = backend::Vicinity {
-            gas_price: gas_price.into(),
-            origin: source,
+    fn estimate_gas<C: Context>(
+        ctx: &mut C,
+        query: types::EstimateGasQuery,
+    ) -> Result<u64, Error> {
+        let max_iters = <C::Runtime as Runtime>::Core::estimate_gas_search_max_iters(ctx);
+        let cap = match query.gas_limit {
+            0 => <C::Runtime as Runtime>::Core::max_batch_gas(ctx),
+            gas_limit => gas_limit,
         };
 
-        // The maximum gas fee has already been withdrawn in authenticate_tx().
-        let max_gas_fee = gas_price
-            .checked_mul(primitive_types::U256::from(gas_limit))
-            .ok_or(Error::FeeOverflow)?;
+        // Simulate with the maximum gas limit first. A failure here means that the call fails
+        // regardless of the gas limit, so report it.
+        let used = Self::simulate_gas(ctx, &query, cap, max_iters == 0)?;
+        if max_iters == 0 {
+            return Ok(used);
+        }
 
-        let mut backend = backend::Backend::<'_, C, Cfg>::new(ctx, vicinity);
-        let metadata = StackSubstateMetadata::new(gas_limit, cfg);
-        let stackstate = MemoryStackState::new(metadata, &backend);
-        let precompiles = precompile::Precompiles::new(&backend);
-        let mut executor = StackExecutor::new_with_precompiles(stackstate, cfg, &precompiles);
+        // Any gas limit below the used gas is bound to fail while the maximum gas limit is known
+        // to succeed. Start with the used gas as in most cases it is already sufficient, and
+        // then binary search the rest of the range.
+        let (mut lo, mut hi) = (used.saturating_sub(1), cap);
+        let (mut mid, mut iters) = (used, 0);
+        while lo + 1 < hi && iters < max_iters {
+            iters += 1;
+            // Since the call succeeded with the maximum gas limit, any failure here is caused by
+            // an insufficient gas limit, regardless of the actual error.
+            match Self::simulate_gas(ctx, &query, mid, false) {
+                Ok(_) => hi = mid,
+                Err(_) => lo = mid,
+            }
+            mid = lo + (hi - lo) / 2;
+        }
+        Ok(hi)
+    }
 
-        // Run EVM and process the result.
-        let (exit_reason, exit_value) = f(&mut executor, gas_limit);
+    fn multicall<C: Context>(
+        ctx: &mut C,
+        query: types::MulticallQuery,
+    ) -> Result<Vec<types::MulticallResult>, Error> {
+        let mut calls = Vec::with_capacity(query.calls.len());
+        for call in query.calls {
+            let allow_failure = call.allow_failure;
+            let decoded = Self::decode_simulate_call_query(
+                ctx,
+                types::SimulateCallQuery {
+                    gas_price: query.gas_price,
+                    gas_limit: query.gas_limit,
+                    caller: query.caller,
+                    address: call.address,
+                    value: call.value,
+                    data: call.data,
+                    round: None,
+                },
+            )?;
+            calls.push((decoded, allow_failure));
+        }
+
+        let evm_results = ctx.with_simulation(|mut sctx| {
+            let mut remaining_gas = query.gas_limit;
+            let mut evm_results = Vec::with_capacity(calls.len());
+            for (index, ((call, _), allow_failure)) in calls.iter().enumerate() {
+                let types::SimulateCallQuery {
+                    gas_price,
+                    caller,
+                    address,
+                    value,
+                    data,
+                    ..
+                } = call.clone();
+                let call_tx = transaction::Transaction {
+                    version: 1,
+                    call: transaction::Call {
+                        format: transaction::CallFormat::Plain,
+                        method: "evm.Call".to_owned(),
+                        body: cbor::to_value(types::Call {
+                            address,
+                            value,
+                            data: data.clone(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                    auth_info: transaction::AuthInfo {
+                        signer_info: vec![],
+                        fee: transaction::Fee {
+                            amount: token::BaseUnits::new(
+                                gas_price
+                                    .checked_mul(U256::from(remaining_gas))
+                                    .ok_or(Error::FeeOverflow)?
+                                    .as_u128(),
+                                Cfg::TOKEN_DENOMINATION,
+                            ),
+                            gas: remaining_gas,
+                            consensus_messages: 0,
+                        },
+                        ..Default::default()
+                    },
+                };
+                let (evm_result, gas_used) =
+                    sctx.with_tx(index, 0, call_tx, |mut txctx, _call| {
+                        let evm_result = Self::do_evm(
+                            caller,
+                            &mut txctx,
+                            |exec, gas_limit| {
+                                exec.transact_call(
+                                    caller.into(),
+                                    address.into(),
+                                    value.into(),
+                                    data,
+                                    gas_limit,
+                                    vec![],
+                                )
+                            },
+                            false,
+                        );
+                        let gas_used = <C::Runtime as Runtime>::Core::used_tx_gas(&mut txctx);
+                        if evm_result.is_ok() {
+                            // Make the effects visible to subsequent calls.
+                            txctx.commit();
+                        }
+                        (evm_result, gas_used)
+                    });
+                remaining_gas = remaining_gas.saturating_sub(gas_used);
+
+                match evm_result {
+                    Err(err) if !allow_failure => return Err(err),
+                    evm_result => evm_results.push(evm_result),
+                }
+            }
+            Ok(evm_results)
+        })?;
+
+        Ok(evm_results
+            .into_iter()
+            .zip(calls)
+            .map(|(evm_result, ((_, tx_metadata), _))| {
+                match Self::encode_evm_result(ctx, evm_result, tx_metadata) {
+                    Ok(data) => types::MulticallResult {
+                        success: true,
+                        data,
+                        error: None,
+                    },
+                    Err(err) => types::MulticallResult {
+                        success: false,
+                        data: vec![],
+                        error: Some(err.to_string()),
+                    },
+                }
+            })
+            .collect())
+    }
+}
+
+impl<Cfg: Config> Module<Cfg> {
+    /// Ensures that the state of the requested round is being queried.
+    ///
+    /// Historical state is selected by the host based on the round the query was submitted at, so
+    /// this makes sure it matches what the caller asked for instead of silently serving different
+    /// state.
+    fn ensure_query_round<C: Context>(ctx: &mut C, round: Option<u64>) -> Result<(), Error> {
+        if let Some(round) = round {
+            let current = ctx.runtime_header().round;
+            if round != current {
+                return Err(Error::HistoricalStateUnavailable(round, current));
+            }
+        }
+        Ok(())
+    }
+
+    /// Simulates the given estimate gas query with a specific gas limit and returns the amount
+    /// of gas used.
+    fn simulate_gas<C: Context>(
+        ctx: &mut C,
+        query: &types::EstimateGasQuery,
+        gas_limit: u64,
+        estimate_gas: bool,
+    ) -> Result<u64, Error> {
+        let types::EstimateGasQuery {
+            gas_price,
+            caller,
+            address,
+            value,
+            data,
+            ..
+        } = query.clone();
+
+        ctx.with_simulation(|mut sctx| {
+            let call = match address {
+                Some(address) => transaction::Call {
+                    format: transaction::CallFormat::Plain,
+                    method: "evm.Call".to_owned(),
+                    body: cbor::to_value(types::Call {
+                        address,
+                        value,
+                        data: data.clone(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                None => transaction::Call {
+                    format: transaction::CallFormat::Plain,
+                    method: "evm.Create".to_owned(),
+                    body: cbor::to_value(types::Create {
+                        value,
+                        init_code: data.clone(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            };
+            let tx = transaction::Transaction {
+                version: 1,
+                call,
+                auth_info: transaction::AuthInfo {
+                    signer_info: vec![],
+                    fee: transaction::Fee {
+                        amount: token::BaseUnits::new(
+                            gas_price
+                                .checked_mul(U256::from(gas_limit))
+                                .ok_or(Error::FeeOverflow)?
+                                .as_u128(),
+                            Cfg::TOKEN_DENOMINATION,
+                        ),
+                        gas: gas_limit,
+                        consensus_messages: 0,
+                    },
+                    ..Default::default()
+                },
+            };
+            sctx.with_tx(0, 0, tx, |mut txctx, _call| {
+                match address {
+                    Some(address) => Self::do_evm(
+                        caller,
+                        &mut txctx,
+                        |exec, gas_limit| {
+                            exec.transact_call(
+                                caller.into(),
+                                address.into(),
+                                value.into(),
+                                data,
+                                gas_limit,
+                                vec![],
+                            )
+                        },
+                        estimate_gas,
+                    )?,
+                    None => {
+                        let params = Self::params(txctx.runtime_state());
+                        <C::Runtime as Runtime>::Core::use_tx_gas(
+                            &mut txctx,
+                            params.gas_costs.tx_create,
+                        )?;
+                        if let Some(max_init_code_size) = params.max_init_code_size() {
+                            let init_code_size = data.len() as u64;
+                            if init_code_size > max_init_code_size {
+                                return Err(Error::CodeTooLarge(
+                                    init_code_size,
+                                    max_init_code_size,
+                                ));
+                            }
+                        }
+                        Self::do_evm(
+                            caller,
+                            &mut txctx,
+                            |exec, gas_limit| {
+                                exec.transact_create(
+                                    caller.into(),
+                                    value.into(),
+                                    data,
+                                    gas_limit,
+                                    vec![],
+                                )
+                            },
+                            estimate_gas,
+                        )?
+                    }
+                };
+                Ok(<C::Runtime as Runtime>::Core::used_tx_gas(&mut txctx))
+            })
+        })
+    }
+
+    /// Adds the state touched by the given EIP-2930 access list to the prefetch set.
+    fn prefetch_access_list(
+        add_prefix: &mut dyn FnMut(Prefix),
+        access_list: &[types::AccessListItem],
+    ) {
+        use blake3::traits::digest::Digest as _;
+
+        for item in access_list {
+            add_prefix(Prefix::from(
+                [MODULE_NAME.as_bytes(), state::CODES, item.address.as_ref()].concat(),
+            ));
+
+            // Confidential storage keys are encrypted and cannot be prefetched.
+            if Cfg::CONFIDENTIAL {
+                continue;
+            }
+            for key in &item.storage_keys {
+                add_prefix(Prefix::from(
+                    [
+                        MODULE_NAME.as_bytes(),
+                        state::STORAGES,
+                        item.address.as_ref(),
+                        blake3::Hasher::digest(key.as_ref()).as_slice(),
+                        key.as_ref(),
+                    ]
+                    .concat(),
+                ));
+            }
+        }
+    }
+
+    /// Whether applying the given change would modify any state, ignoring the nonce increment of
+    /// the transaction's source account that every transaction performs.
+    fn is_state_modification<B: evm::backend::Backend>(
+        backend: &B,
+        source: H160,
+        val: &Apply<Vec<(primitive_types::H256, primitive_types::H256)>>,
+    ) -> bool {
+        match val {
+            Apply::Modify {
+                address,
+                basic,
+                code,
+                storage,
+                reset_storage,
+            } => {
+                if code.is_some() || *reset_storage || !storage.is_empty() {
+                    return true;
+                }
+                let current = backend.basic(*address);
+                let expected_nonce = if *address == primitive_types::H160::from(source) {
+                    current.nonce.saturating_add(primitive_types::U256::one())
+                } else {
+                    current.nonce
+                };
+                basic.balance != current.balance || basic.nonce != expected_nonce
+            }
+            Apply::Delete { .. } => true,
+        }
+    }
+
+    fn do_evm<C, F>(source: H160, ctx: &mut C, f: F, estimate_gas: bool) -> Result<Vec<u8>, Error>
+    where
+        F: FnOnce(
+            &mut StackExecutor<
+                'static,
+                '_,
+                MemoryStackState<'_, 'static, backend::Backend<'_, C, Cfg>>,
+                precompile::Precompiles<Cfg, backend::Backend<'_, C, Cfg>>,
+            >,
+            u64,
+        ) -> (evm::ExitReason, Vec<u8>),
+        C: TxContext,
+    {
+        let result = Self::execute_evm(source, ctx, f, estimate_gas);
+
+        // Report the outcome even when the transaction fails, so that the gas consumption can be
+        // tracked without tracing.
+        let tx = ctx.get_tx();
+        let tx_hash = if tx.is_empty() {
+            None
+        } else {
+            Some(Hash::digest_bytes(tx))
+        };
+        let status = match &result {
+            Ok(_) => types::ExecutionStatus::Succeeded,
+            Err(Error::Reverted(_)) | Err(Error::RevertedWithData(_)) => {
+                types::ExecutionStatus::Reverted
+            }
+            Err(_) => types::ExecutionStatus::Failed,
+        };
+        let gas_used = <C::Runtime as Runtime>::Core::used_tx_gas(ctx);
+        ctx.emit_unconditional_event(Event::Execution {
+            tx_hash,
+            gas_used,
+            status,
+        });
+
+        result
+    }
+
+    fn execute_evm<C, F>(
+        source: H160,
+        ctx: &mut C,
+        f: F,
+        estimate_gas: bool,
+    ) -> Result<Vec<u8>, Error>
+    where
+        F: FnOnce(
+            &mut StackExecutor<
+                'static,
+                '_,
+                MemoryStackState<'_, 'static, backend::Backend<'_, C, Cfg>>,
+                precompile::Precompiles<Cfg, backend::Backend<'_, C, Cfg>>,
+            >,
+            u64,
+        ) -> (evm::ExitReason, Vec<u8>),
+        C: TxContext,
+    {
+        let cfg = Cfg::evm_config(estimate_gas);
+        let params = Self::params(ctx.runtime_state());
+        let gas_limit: u64 = <C::Runtime as Runtime>::Core::remaining_tx_gas(ctx);
+        let gas_price: primitive_types::U256 = ctx.tx_auth_info().fee.gas_price().into();
+        let fee_denomination = ctx.tx_auth_info().fee.amount.denomination().clone();
+
+        let vicinity = backend::Vicinity::new::<_, Cfg>(ctx, gas_price.into(), source);
+
+        // The maximum gas fee has already been withdrawn in authenticate_tx().
+        let max_gas_fee = gas_price
+            .checked_mul(primitive_types::U256::from(gas_limit))
+            .ok_or(Error::FeeOverflow)?;
+
+        // Only executed transactions are reflected in metrics, not checks and simulations.
+        let record_metrics = ctx.mode() == Mode::ExecuteTx;
+        let static_call = ctx
+            .tx_value::<bool>(CONTEXT_KEY_STATIC_CALL)
+            .get()
+            .copied()
+            .unwrap_or_default();
+        let round = ctx.runtime_header().round;
+        let first_log_index = *ctx.tx_value::<u64>(CONTEXT_KEY_LOG_COUNT).or_default();
+
+        let mut backend = backend::Backend::<'_, C, Cfg>::new(ctx, vicinity)
+            .with_first_log_index(first_log_index);
+        let metadata = StackSubstateMetadata::new(gas_limit, cfg);
+        let stackstate = MemoryStackState::new(metadata, &backend);
+        let precompiles = precompile::Precompiles::new(&backend)
+            .with_gas_multiplier(params.gas_costs.precompile_multiplier)
+            .with_gas_costs(&params.gas_costs.precompiles)
+            .with_round(round);
+        let mut executor = StackExecutor::new_with_precompiles(stackstate, cfg, &precompiles);
+
+        // Run EVM and process the result.
+        let started = std::time::Instant::now();
+        let (exit_reason, exit_value) = f(&mut executor, gas_limit);
         let gas_used = executor.used_gas();
-        let fee = executor.fee(gas_price);
+        if record_metrics {
+            record_execution_metrics(&exit_reason, gas_used, started, precompiles.calls());
+        }
 
         let exit_value = match process_evm_result(exit_reason, exit_value) {
             Ok(exit_value) => exit_value,
@@ -806,27 +1747,174 @@ impl<Cfg: Config> Module<Cfg> {
             }
         };
 
-        // Return the difference between the pre-paid max_gas and actually used gas.
-        let return_fee = max_gas_fee
-            .checked_sub(fee)
-            .ok_or(Error::InsufficientBalance)?;
-
         let (vals, logs) = executor.into_state().deconstruct();
+        let vals: Vec<_> = vals
+            .into_iter()
+            .map(|val| match val {
+                Apply::Modify {
+                    address,
+                    basic,
+                    code,
+                    storage,
+                    reset_storage,
+                } => Apply::Modify {
+                    address,
+                    basic,
+                    code,
+                    storage: storage.into_iter().collect::<Vec<_>>(),
+                    reset_storage,
+                },
+                Apply::Delete { address } => Apply::Delete { address },
+            })
+            .collect();
+        let logs: Vec<_> = logs.into_iter().collect();
+
+        // Static calls must not result in any modifications, similar to read-only transactions.
+        if static_call
+            && (!logs.is_empty()
+                || vals
+                    .iter()
+                    .any(|val| Self::is_state_modification(&backend, source, val)))
+        {
+            <C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
+            return Err(CoreError::ReadOnlyTransaction.into());
+        }
+
+        // Enforce the code size limit and charge for storing any newly deployed code.
+        let code_sizes: Vec<u64> = vals
+            .iter()
+            .filter_map(|val| match val {
+                evm::backend::Apply::Modify {
+                    code: Some(code), ..
+                } => Some(code.len() as u64),
+                _ => None,
+            })
+            .collect();
+        if params.max_code_size > 0 {
+            if let Some(code_size) = code_sizes.iter().find(|size| **size > params.max_code_size) {
+                <C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
+                return Err(Error::CodeTooLarge(*code_size, params.max_code_size));
+            }
+        }
+        let code_bytes: u64 = code_sizes.iter().sum();
+        let mut gas_used =
+            gas_used.saturating_add(code_bytes.saturating_mul(params.gas_costs.code_byte));
+
+        // Charge for removing the storage of contracts that self-destructed or were created again,
+        // so that the number of entries removed at once is bounded by the gas limit. Counting
+        // stops as soon as the gas limit would be exceeded.
+        let removal_gas = params.gas_costs.storage_removal_gas();
+        for val in &vals {
+            let address = match val {
+                Apply::Delete { address }
+                | Apply::Modify {
+                    address,
+                    reset_storage: true,
+                    ..
+                } => H160::from(*address),
+                _ => continue,
+            };
+            let limit = (gas_limit.saturating_sub(gas_used) / removal_gas).saturating_add(1);
+            let entries = backend.storage_entries(&address, limit);
+            gas_used = gas_used.saturating_add(entries.saturating_mul(removal_gas));
+        }
+
+        // Charge for recording the Ethereum addresses of accounts used for the first time.
+        let new_aliases = vals
+            .iter()
+            .filter(|val| match val {
+                Apply::Modify { address, .. } => !backend.has_address_alias(*address),
+                Apply::Delete { .. } => false,
+            })
+            .count() as u64;
+        gas_used = gas_used
+            .saturating_add(new_aliases.saturating_mul(params.gas_costs.address_alias_gas()));
+        if gas_used > gas_limit {
+            // Fails with an out of gas error.
+            <C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
+            return Err(CoreError::OutOfGas(gas_limit, gas_used).into());
+        }
+        // Split out consensus operations requested through the staking precompiles, these are
+        // only executed once the EVM state has been applied.
+        let (consensus_ops, logs): (Vec<_>, Vec<_>) = logs
+            .into_iter()
+            .partition(|log| {
+                Cfg::CONSENSUS_PRECOMPILES
+                    && precompile::consensus::ConsensusOperation::from_log(log).is_some()
+            });
+
+        // Transfers made through the ERC-20 facade precompiles are kept as logs since they are
+        // regular ERC-20 events, but are only applied once the EVM state has been applied.
+        let facade_transfers: Vec<_> = if Cfg::ERC20_FACADE_PRECOMPILES {
+            // The native EVM token is not presented through a facade as its balances are already
+            // visible to the EVM.
+            logs.iter()
+                .filter_map(precompile::erc20::FacadeTransfer::from_log)
+                .filter(|transfer| transfer.denomination != Cfg::TOKEN_DENOMINATION)
+                .collect()
+        } else {
+            Vec::new()
+        };
 
         // Apply can fail in case of unsupported actions.
+        let log_count = logs.len() as u64;
         let exit_reason = backend.apply(vals, logs);
         if let Err(err) = process_evm_result(exit_reason, Vec::new()) {
             <C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
             return Err(err);
         };
+        *ctx.tx_value::<u64>(CONTEXT_KEY_LOG_COUNT).or_default() += log_count;
 
         <C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
 
-        // Move the difference from the fee accumulator back to the caller.
+        for log in consensus_ops {
+            if let Some((kind, op)) = precompile::consensus::ConsensusOperation::from_log(&log) {
+                Self::process_consensus_operation(ctx, kind, op)?;
+            }
+        }
+
+        for transfer in facade_transfers {
+            Cfg::Accounts::transfer(
+                ctx,
+                Cfg::map_address(transfer.from),
+                Cfg::map_address(transfer.to),
+                &token::BaseUnits::new(transfer.amount, transfer.denomination),
+            )
+            .map_err(|_| Error::InsufficientBalance)?;
+        }
+
+        // Charge for the emitted logs so that the cost is covered by the fee.
+        let gas_used =
+            gas_used.saturating_add(<C::Runtime as Runtime>::Core::charge_event_gas(ctx)?);
+        let fee = gas_price
+            .checked_mul(primitive_types::U256::from(gas_used))
+            .ok_or(Error::FeeOverflow)?;
+        Self::record_tx_fee(ctx, gas_price.low_u128(), gas_used);
+
+        // Return the difference between the pre-paid max_gas and actually used gas.
+        let mut return_fee = max_gas_fee
+            .checked_sub(fee)
+            .ok_or(Error::InsufficientBalance)?;
+
+        // In case the transaction is sponsored, charge the sponsor the actual fee instead so that
+        // the whole pre-paid fee can be returned.
         let caller_address = Cfg::map_address(source.into());
-        Cfg::Accounts::move_from_fee_accumulator(
+        if let Some(sponsor) =
+            Cfg::fee_sponsor(ctx, source.into()).filter(|sponsor| *sponsor != caller_address)
+        {
+            Cfg::Accounts::move_into_fee_accumulator(
+                ctx,
+                sponsor,
+                &token::BaseUnits::new(fee.as_u128(), fee_denomination.clone()),
+            )
+            .map_err(|_| Error::InsufficientBalance)?;
+            return_fee = max_gas_fee;
+        }
+
+        // Move the difference from the fee accumulator back to the fee payer, which for native
+        // (e.g. Ed25519 or multisig) signers is not the account their EVM caller maps to.
+        Cfg::Accounts::refund_fee(
             ctx,
-            caller_address,
             &token::BaseUnits::new(return_fee.as_u128(), fee_denomination),
         )
         .map_err(|_| Error::InsufficientBalance)?;
@@ -834,10 +1922,98 @@ impl<Cfg: Config> Module<Cfg> {
         Ok(exit_value)
     }
 
-    fn do_sc_evm<C, F>(
+    /// Records the gas price and gas used of a transaction for the fee history of the block.
+    fn record_tx_fee<C: TxContext>(ctx: &mut C, gas_price: u128, gas_used: u64) {
+        ctx.value::<Vec<(u128, u64)>>(CONTEXT_KEY_BLOCK_TX_FEES)
+            .or_default()
+            .push((gas_price, gas_used));
+    }
+
+    /// Executes a consensus operation requested by a contract through a staking precompile.
+    fn process_consensus_operation<C: TxContext>(
+        ctx: &mut C,
+        kind: precompile::consensus::ConsensusOperationKind,
+        op: precompile::consensus::ConsensusOperation,
+    ) -> Result<(), Error> {
+        use modules::consensus_accounts::API as _;
+        use precompile::consensus::ConsensusOperationKind;
+
+        type ConsensusAccounts<Cfg> = modules::consensus_accounts::Module<
+            <Cfg as Config>::Accounts,
+            modules::consensus::Module,
+        >;
+
+        let denomination =
+            <modules::consensus::Module as modules::consensus::API>::consensus_denomination(ctx)
+                .map_err(modules::consensus_accounts::Error::from)?;
+        let amount = token::BaseUnits::new(op.amount, denomination);
+        let nonce = ctx
+            .tx_auth_info()
+            .signer_info
+            .first()
+            .map(|si| si.nonce)
+            .unwrap_or_default();
+        let caller: primitive_types::H160 = op.caller.into();
+        let contract = Cfg::map_address(caller);
+
+        match kind {
+            ConsensusOperationKind::Deposit => {
+                // Contracts may only pull funds from the consensus account of the transaction
+                // signer, as otherwise any contract could spend the allowances that other accounts
+                // have given the runtime. Even then, only the contract the signer called may do so
+                // and only up to the limit the signer explicitly opted into.
+                let (target, remaining) = ctx
+                    .tx_value::<(H160, u128)>(CONTEXT_KEY_CONSENSUS_DEPOSIT)
+                    .get()
+                    .copied()
+                    .unwrap_or_default();
+                if op.account != ctx.tx_caller_address()
+                    || op.caller != target
+                    || op.amount > remaining
+                {
+                    return Err(Error::Forbidden);
+                }
+                ctx.tx_value::<(H160, u128)>(CONTEXT_KEY_CONSENSUS_DEPOSIT)
+                    .set((target, remaining - op.amount));
+
+                ConsensusAccounts::<Cfg>::deposit(
+                    ctx,
+                    op.account,
+                    nonce,
+                    contract,
+                    caller.into(),
+                    amount,
+                )?
+            }
+            ConsensusOperationKind::Withdraw => ConsensusAccounts::<Cfg>::withdraw(
+                ctx,
+                contract,
+                caller.into(),
+                nonce,
+                op.account,
+                amount,
+            )?,
+            ConsensusOperationKind::Delegate => ConsensusAccounts::<Cfg>::delegate(
+                ctx,
+                contract,
+                nonce,
+                op.account,
+                amount,
+            )?,
+            ConsensusOperationKind::Undelegate => {
+                ConsensusAccounts::<Cfg>::undelegate(ctx, op.account, nonce, contract, op.amount)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the EVM on behalf of another module, see [`API::internal_call`].
+    fn do_internal_evm<C, F>(
         source: H160,
         ctx: &mut C,
         f: F,
+        gas_limit: u64,
         estimate_gas: bool,
     ) -> Result<Vec<u8>, Error>
     where
@@ -853,62 +2029,64 @@ impl<Cfg: Config> Module<Cfg> {
         C: Context,
     {
         let cfg = Cfg::evm_config(estimate_gas);
-        let gas_limit: u64 = 1085479;
-        let gas_price: primitive_types::U256 = primitive_types::U256::from_str("0x03e8").unwrap(); //primitive_types::U256::zero();
-        //let fee_denomination = token::Denomination::NATIVE;
+        let params = Self::params(ctx.runtime_state());
+        // No fees are charged for internal calls, but contracts still observe the minimum gas
+        // price through the `GASPRICE` opcode.
+        let gas_price =
+            <C::Runtime as Runtime>::Core::min_gas_price(ctx, &Cfg::TOKEN_DENOMINATION);
 
-        let vicinity = backend::Vicinity {
-            gas_price: gas_price.into(),
-            origin: source,
-        };
+        let vicinity = backend::Vicinity::new::<_, Cfg>(ctx, gas_price.into(), source);
+        let round = ctx.runtime_header().round;
 
         let mut backend = backend::Backend::<'_, C, Cfg>::new_internal(ctx, vicinity);
         let metadata = StackSubstateMetadata::new(gas_limit, cfg);
         let stackstate = MemoryStackState::new(metadata, &backend);
-        let precompiles = precompile::Precompiles::new(&backend);
+        let precompiles = precompile::Precompiles::new(&backend)
+            .with_gas_multiplier(params.gas_costs.precompile_multiplier)
+            .with_gas_costs(&params.gas_costs.precompiles)
+            .with_round(round);
         let mut executor = StackExecutor::new_with_precompiles(stackstate, cfg, &precompiles);
 
         // Run EVM and process the result.
         let (exit_reason, exit_value) = f(&mut executor, gas_limit);
-        //let gas_used = executor.used_gas();
-        //let fee = executor.fee(gas_price);
+        let gas_used = executor.used_gas();
 
         let exit_value = match process_evm_result(exit_reason, exit_value) {
             Ok(exit_value) => exit_value,
             Err(err) => {
-                //<C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
+                <C::Runtime as Runtime>::Core::use_batch_gas(ctx, gas_used)?;
                 return Err(err);
             }
         };
 
-        // Return the difference between the pre-paid max_gas and actually used gas.
-        //let return_fee = 0;
-
         let (vals, logs) = executor.into_state().deconstruct();
 
         // Apply can fail in case of unsupported actions.
         let exit_reason = backend.apply(vals, logs);
-        if let Err(err) = process_evm_result(exit_reason, Vec::new()) {
-            //<C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
-            return Err(err);
-        };
-
-        //<C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas_used)?;
-
-        // Move the difference from the fee accumulator back to the caller.
-        /*
-        let caller_address = Cfg::map_address(source.into());
-        Cfg::Accounts::move_from_fee_accumulator(
-            ctx,
-            caller_address,
-            &token::BaseUnits::new(return_fee.as_u128(), fee_denomination),
-        )
-        .map_err(|_| Error::InsufficientBalance)?;
-        */
+        <C::Runtime as Runtime>::Core::use_batch_gas(ctx, gas_used)?;
+        process_evm_result(exit_reason, Vec::new())?;
 
         Ok(exit_value)
     }
 
+    /// Fails with [`Error::Forbidden`] in case any of the given accounts is blacklisted.
+    ///
+    /// Payers are already checked when authenticating transactions, but accounts may also get
+    /// blacklisted within the same block.
+    fn ensure_not_blacklisted<C: Context>(ctx: &mut C, addresses: &[H160]) -> Result<(), Error> {
+        for address in addresses {
+            let role = Cfg::Accounts::get_role(
+                ctx.runtime_state(),
+                Cfg::map_address((*address).into()),
+            )
+            .unwrap_or_default();
+            if role == role::Role::BlacklistedUser {
+                return Err(Error::Forbidden);
+            }
+        }
+        Ok(())
+    }
+
     fn derive_caller<C>(ctx: &mut C) -> Result<H160, Error>
     where
         C: TxContext,
@@ -1028,16 +2206,61 @@ impl<Cfg: Config> Module<Cfg> {
 
 #[sdk_derive(MethodHandler)]
 impl<Cfg: Config> Module<Cfg> {
+    #[handler(prefetch = "evm.Create")]
+    fn prefetch_create(
+        add_prefix: &mut dyn FnMut(Prefix),
+        body: cbor::Value,
+        _auth_info: &AuthInfo,
+    ) -> Result<(), oasis_runtime_sdk::error::RuntimeError> {
+        // Encrypted calls can't be decoded, so prefetching is best-effort.
+        if let Ok(args) = cbor::from_value::<types::Create>(body) {
+            Self::prefetch_access_list(add_prefix, &args.access_list);
+        }
+        Ok(())
+    }
+
     #[handler(call = "evm.Create")]
     fn tx_create<C: TxContext>(ctx: &mut C, body: types::Create) -> Result<Vec<u8>, Error> {
+        let params = Self::params(ctx.runtime_state());
+        if params.create_disabled {
+            return Err(Error::Forbidden);
+        }
+        if let Some(create_role) = params.create_role {
+            let caller = Cfg::map_address(Self::derive_caller(ctx)?.into());
+            let role = Cfg::Accounts::get_role(ctx.runtime_state(), caller)
+                .map_err(|_| Error::Forbidden)?;
+            if role != create_role {
+                return Err(Error::Forbidden);
+            }
+        }
         Self::create(ctx, body.value, body.init_code)
     }
 
+    #[handler(prefetch = "evm.Call")]
+    fn prefetch_call(
+        add_prefix: &mut dyn FnMut(Prefix),
+        body: cbor::Value,
+        _auth_info: &AuthInfo,
+    ) -> Result<(), oasis_runtime_sdk::error::RuntimeError> {
+        // Encrypted calls can't be decoded, so prefetching is best-effort.
+        if let Ok(args) = cbor::from_value::<types::Call>(body) {
+            add_prefix(Prefix::from(
+                [MODULE_NAME.as_bytes(), state::CODES, args.address.as_ref()].concat(),
+            ));
+            Self::prefetch_access_list(add_prefix, &args.access_list);
+        }
+        Ok(())
+    }
+
     #[handler(call = "evm.Call")]
     fn tx_call<C: TxContext>(ctx: &mut C, body: types::Call) -> Result<Vec<u8>, Error> {
 
         let code = Self::get_code(ctx, body.address)?;
 
+        // Remember the consensus deposit the signer allowed the called contract to make.
+        ctx.tx_value::<(H160, u128)>(CONTEXT_KEY_CONSENSUS_DEPOSIT)
+            .set((body.address, body.max_consensus_deposit));
+
         // Cache transaction information at check time for use in subsequent split transactions
         if ctx.mode() == Mode::CheckTx {
             let key = ctx.get_tx().to_vec();
@@ -1053,25 +2276,284 @@ impl<Cfg: Config> Module<Cfg> {
         if code.is_empty() {
             Self::transfer(ctx, body.address, body.value, body.data)
         } else {
+            if Self::params(ctx.runtime_state()).call_disabled {
+                return Err(Error::Forbidden);
+            }
             Self::call(ctx, body.address, body.value, body.data)
         }
     }
 
+    /// Performs an EVM call that must not modify any state, similar to a `STATICCALL`. The call
+    /// fails in case the contract attempts any modifications.
+    #[handler(call = "evm.StaticCall")]
+    fn tx_static_call<C: TxContext>(ctx: &mut C, body: types::Call) -> Result<Vec<u8>, Error> {
+        if !body.value.is_zero() {
+            return Err(Error::InvalidArgument);
+        }
+        if Self::params(ctx.runtime_state()).call_disabled {
+            return Err(Error::Forbidden);
+        }
+        ctx.tx_value::<bool>(CONTEXT_KEY_STATIC_CALL).set(true);
+        Self::call(ctx, body.address, body.value, body.data)
+    }
+
+    #[handler(call = "evm.AuditDecrypt")]
+    fn tx_audit_decrypt<C: TxContext>(
+        ctx: &mut C,
+        body: types::AuditDecrypt,
+    ) -> Result<types::AuditDecryptResult, Error> {
+        if !Cfg::CONFIDENTIAL || !Cfg::CONFIDENTIAL_AUDIT {
+            return Err(Error::Forbidden);
+        }
+        let auditor = ctx.tx_caller_address();
+        if !Cfg::Accounts::has_role(ctx.runtime_state(), auditor, role::Role::Auditor) {
+            return Err(Error::Forbidden);
+        }
+        let key_manager = ctx
+            .key_manager()
+            .ok_or_else(|| Error::AuditDecryptionFailed("key manager unavailable".to_string()))?;
+
+        // The key manager may be unreachable while checking transactions.
+        if ctx.is_check_only() {
+            return Ok(Default::default());
+        }
+
+        // Ephemeral keys are only served for a limited number of past epochs, so transactions
+        // older than that can no longer be audited.
+        let keypair = key_manager
+            .get_or_create_ephemeral_keys(callformat::get_key_pair_id(body.epoch), body.epoch)
+            .map_err(|err| Error::AuditDecryptionFailed(err.to_string()))?;
+        let sk = keypair.input_keypair.sk;
+        let data = deoxysii::box_open(
+            &body.envelope.nonce,
+            body.envelope.data.clone(),
+            vec![],
+            &body.envelope.pk,
+            &sk.0,
+        )
+        .map_err(|err| Error::AuditDecryptionFailed(err.to_string()))?;
+
+        // Results must be deterministic, so derive the nonce from the transaction's position.
+        let mut nonce = [0u8; deoxysii::NONCE_SIZE];
+        let seed = Hash::digest_bytes_list(&[
+            &ctx.runtime_header().round.to_be_bytes(),
+            &(ctx.tx_index() as u64).to_be_bytes(),
+        ]);
+        nonce.copy_from_slice(&seed.as_ref()[..deoxysii::NONCE_SIZE]);
+        let data = deoxysii::box_seal(&nonce, data, vec![], &body.auditor_pk, &sk.0)
+            .map_err(|err| Error::AuditDecryptionFailed(err.to_string()))?;
+
+        // Record the access on chain.
+        ctx.emit_event(Event::AuditAccess {
+            auditor,
+            epoch: body.epoch,
+            envelope_hash: Hash::digest_bytes(&cbor::to_vec(body.envelope)),
+        });
+
+        Ok(types::AuditDecryptResult {
+            pk: keypair.input_keypair.pk.0,
+            nonce,
+            data,
+        })
+    }
+
+    #[handler(prefetch = "evm.CallV2")]
+    fn prefetch_call_v2(
+        add_prefix: &mut dyn FnMut(Prefix),
+        body: cbor::Value,
+        auth_info: &AuthInfo,
+    ) -> Result<(), oasis_runtime_sdk::error::RuntimeError> {
+        Self::prefetch_call(add_prefix, body, auth_info)
+    }
+
+    /// Same as `evm.Call`, but also reports the gas used and the number of emitted logs so that
+    /// the effective fee can be computed without looking up the receipt.
+    #[handler(call = "evm.CallV2")]
+    fn tx_call_v2<C: TxContext>(
+        ctx: &mut C,
+        body: types::Call,
+    ) -> Result<types::CallOutput, Error> {
+        let data = Self::tx_call(ctx, body)?;
+        Ok(types::CallOutput {
+            data,
+            gas_used: <C::Runtime as Runtime>::Core::used_tx_gas(ctx),
+            logs: *ctx.tx_value::<u64>(CONTEXT_KEY_LOG_COUNT).or_default(),
+        })
+    }
+
+    /// Advances the caller's signed query nonce, revoking all signed queries whose leash carries
+    /// a lower query nonce.
+    #[handler(call = "evm.AdvanceQueryNonce")]
+    fn tx_advance_query_nonce<C: TxContext>(
+        ctx: &mut C,
+        body: types::AdvanceQueryNonce,
+    ) -> Result<(), Error> {
+        if !Cfg::CONFIDENTIAL {
+            return Err(Error::Forbidden);
+        }
+        let caller = Self::derive_caller(ctx)?;
+        let mut query_nonces = state::query_nonces(ctx.runtime_state());
+        let current: u64 = query_nonces.get(caller).unwrap_or_default();
+        if body.query_nonce <= current {
+            return Err(Error::InvalidArgument);
+        }
+        query_nonces.insert(caller, body.query_nonce);
+        Ok(())
+    }
+
     #[handler(query = "evm.Storage")]
     fn query_storage<C: Context>(ctx: &mut C, body: types::StorageQuery) -> Result<Vec<u8>, Error> {
+        Self::ensure_query_round(ctx, body.round)?;
         Self::get_storage(ctx, body.address, body.index)
     }
 
+    #[handler(query = "evm.StorageRange", expensive)]
+    fn query_storage_range<C: Context>(
+        ctx: &mut C,
+        body: types::StorageRangeQuery,
+    ) -> Result<types::StorageRangeResult, Error> {
+        // Confidential storage can't be exposed.
+        if Cfg::CONFIDENTIAL {
+            return Err(Error::Forbidden);
+        }
+
+        let limit = match body.limit {
+            0 => MAX_STORAGE_RANGE_LIMIT,
+            limit => limit.min(MAX_STORAGE_RANGE_LIMIT),
+        };
+        let (entries, next) = state::public_storage_range(
+            ctx.runtime_state(),
+            &body.address,
+            &body.start,
+            limit as usize,
+        );
+        Ok(types::StorageRangeResult {
+            entries: entries
+                .into_iter()
+                .map(|(key, value)| types::StorageEntry { key, value })
+                .collect(),
+            next,
+        })
+    }
+
+    /// Returns the base fees, gas usage and priority fee percentiles of recent blocks.
+    #[handler(query = "evm.FeeHistory")]
+    fn query_fee_history<C: Context>(
+        ctx: &mut C,
+        body: types::FeeHistoryQuery,
+    ) -> Result<types::FeeHistoryResult, Error> {
+        if body.reward_percentiles.iter().any(|p| *p > 100) {
+            return Err(Error::InvalidArgument);
+        }
+        let newest_round = body
+            .newest_round
+            .unwrap_or_else(|| ctx.runtime_header().round);
+        let block_count = body.block_count.min(state::FEE_HISTORY_WINDOW_SIZE);
+
+        let fee_history = state::fee_history(ctx.runtime_state());
+        let mut blocks: Vec<types::FeeHistoryBlock> = Vec::new();
+        let mut oldest_round = newest_round.saturating_add(1);
+        for round in (0..=newest_round).rev().take(block_count as usize) {
+            match fee_history.get(round.to_be_bytes()) {
+                Some(block) => blocks.push(block),
+                None => break,
+            }
+            oldest_round = round;
+        }
+        blocks.reverse();
+
+        Ok(types::FeeHistoryResult {
+            oldest_round,
+            base_fees: blocks.iter().map(|block| block.base_fee).collect(),
+            gas_used: blocks.iter().map(|block| block.gas_used).collect(),
+            gas_limits: blocks.iter().map(|block| block.gas_limit).collect(),
+            rewards: blocks
+                .iter()
+                .map(|block| {
+                    body.reward_percentiles
+                        .iter()
+                        .map(|percentile| block.reward_percentile(*percentile))
+                        .collect()
+                })
+                .collect(),
+        })
+    }
+
     #[handler(query = "evm.Code")]
     fn query_code<C: Context>(ctx: &mut C, body: types::CodeQuery) -> Result<Vec<u8>, Error> {
+        Self::ensure_query_round(ctx, body.round)?;
         Self::get_code(ctx, body.address)
     }
 
+    /// Returns the hash and size of the code deployed at the given address without returning the
+    /// code itself.
+    #[handler(query = "evm.CodeMetadata")]
+    fn query_code_metadata<C: Context>(
+        ctx: &mut C,
+        body: types::CodeQuery,
+    ) -> Result<types::CodeMetadata, Error> {
+        use sha3::Digest as _;
+
+        let code = Self::get_code(ctx, body.address)?;
+        Ok(types::CodeMetadata {
+            code_hash: H256::from_slice(&sha3::Keccak256::digest(&code)),
+            size: code.len() as u64,
+        })
+    }
+
     #[handler(query = "evm.Balance")]
     fn query_balance<C: Context>(ctx: &mut C, body: types::BalanceQuery) -> Result<u128, Error> {
         Self::get_balance(ctx, body.address)
     }
 
+    #[handler(query = "evm.ChainId")]
+    fn query_chain_id<C: Context>(_ctx: &mut C, _args: ()) -> Result<u64, Error> {
+        Ok(Cfg::CHAIN_ID)
+    }
+
+    /// Returns the module configuration so that external tooling can configure itself against
+    /// the runtime.
+    #[handler(query = "evm.Config")]
+    fn query_config<C: Context>(_ctx: &mut C, _args: ()) -> Result<types::ModuleConfig, Error> {
+        Ok(types::ModuleConfig {
+            chain_id: Cfg::CHAIN_ID,
+            hardfork: Cfg::HARDFORK.to_string(),
+            confidential: Cfg::CONFIDENTIAL,
+            token_denomination: Cfg::TOKEN_DENOMINATION,
+        })
+    }
+
+    /// Returns the EVM caller address that transactions authenticated by the given address spec
+    /// are executed as.
+    #[handler(query = "evm.DeriveCaller")]
+    fn query_derive_caller<C: Context>(
+        _ctx: &mut C,
+        body: types::DeriveCallerQuery,
+    ) -> Result<H160, Error> {
+        derive_caller::from_address_spec(&body.address_spec)
+    }
+
+    /// Resolves between the Ethereum and SDK representations of an address. SDK addresses can
+    /// only be resolved once they have been used in the EVM.
+    #[handler(query = "evm.ResolveAddress")]
+    fn query_resolve_address<C: Context>(
+        ctx: &mut C,
+        body: types::ResolveAddressQuery,
+    ) -> Result<Option<types::AddressAlias>, Error> {
+        match (body.eth_address, body.address) {
+            (Some(eth_address), None) => Ok(Some(types::AddressAlias {
+                eth_address,
+                address: Cfg::map_address(eth_address.into()),
+            })),
+            (None, Some(address)) => Ok(Self::resolve_eth_address(ctx, address)
+                .map(|eth_address| types::AddressAlias {
+                    eth_address,
+                    address,
+                })),
+            _ => Err(Error::InvalidArgument),
+        }
+    }
+
     #[handler(query = "evm.SimulateCall", expensive, allow_private_km)]
     fn query_simulate_call<C: Context>(
         ctx: &mut C,
@@ -1086,6 +2568,62 @@ impl<Cfg: Config> Module<Cfg> {
         Self::simulate_call(ctx, body)
     }
 
+    #[handler(query = "evm.EstimateGas", expensive)]
+    fn query_estimate_gas<C: Context>(
+        ctx: &mut C,
+        body: types::EstimateGasQuery,
+    ) -> Result<u64, Error> {
+        let cfg: LocalConfig = ctx.local_config(MODULE_NAME).unwrap_or_default();
+        if cfg.query_simulate_call_max_gas > 0 && body.gas_limit > cfg.query_simulate_call_max_gas {
+            return Err(Error::SimulationTooExpensive(
+                cfg.query_simulate_call_max_gas,
+            ));
+        }
+        let body = types::EstimateGasQuery {
+            gas_limit: match body.gas_limit {
+                0 => cfg.query_simulate_call_max_gas,
+                gas_limit => gas_limit,
+            },
+            ..body
+        };
+        Self::estimate_gas(ctx, body)
+    }
+
+    #[handler(query = "evm.Multicall", expensive, allow_private_km)]
+    fn query_multicall<C: Context>(
+        ctx: &mut C,
+        body: types::MulticallQuery,
+    ) -> Result<Vec<types::MulticallResult>, Error> {
+        let cfg: LocalConfig = ctx.local_config(MODULE_NAME).unwrap_or_default();
+        if cfg.query_simulate_call_max_gas > 0 && body.gas_limit > cfg.query_simulate_call_max_gas {
+            return Err(Error::SimulationTooExpensive(
+                cfg.query_simulate_call_max_gas,
+            ));
+        }
+        Self::multicall(ctx, body)
+    }
+
+    #[handler(query = "evm.EthRpc", expensive, allow_private_km)]
+    fn query_eth_rpc<C: Context>(
+        ctx: &mut C,
+        body: eth_rpc::EthRpcQuery,
+    ) -> Result<cbor::Value, Error> {
+        use eth_rpc::EthRpcRequest;
+
+        match EthRpcRequest::decode(body)? {
+            EthRpcRequest::ChainId => Ok(cbor::to_value(Cfg::CHAIN_ID)),
+            EthRpcRequest::Call(query) => Self::query_simulate_call(ctx, query).map(cbor::to_value),
+            EthRpcRequest::EstimateGas(query) => {
+                Self::query_estimate_gas(ctx, query).map(cbor::to_value)
+            }
+            EthRpcRequest::GetStorageAt(query) => {
+                Self::query_storage(ctx, query).map(cbor::to_value)
+            }
+            EthRpcRequest::GetCode(query) => Self::query_code(ctx, query).map(cbor::to_value),
+            EthRpcRequest::GetBalance(query) => Self::query_balance(ctx, query).map(cbor::to_value),
+        }
+    }
+
     #[handler(message_result = CONSENSUS_WITHDRAW_HANDLER)]
     fn message_result_withdraw<C: Context>(
         ctx: &mut C,
@@ -1111,16 +2649,18 @@ impl<Cfg: Config> Module<Cfg> {
         let addr = H160::from_slice(&context.eth_addr);
         let amt = u128_to_h256(context.amount.amount());
 
-        let _ = Self::call_sc_mint(ctx, &addr, &amt, false);
+        let error = Self::call_sc_mint(ctx, &addr, &amt, false)
+            .err()
+            .map(internal_call_error);
 
-        // Emit deposit successful event.
+        // Emit deposit event, which is only successful in case the mint succeeded.
         ctx.emit_event(_Event::Deposit {
             from: context.from,
             nonce: context.nonce,
             to: context.address,
             eth_to: context.eth_addr,
             amount: context.amount.clone(),
-            error: None,
+            error,
         });
     }
 
@@ -1153,7 +2693,11 @@ impl<Cfg: Config> Module<Cfg> {
             */
             let to = H160::from_slice(&context.eth_addr);
             let amt = u128_to_h256(context.amount.amount());
-            let _ = Self::call_sc_mint(ctx, &to, &amt, true);
+            // In case the refund fails as well, report its error instead.
+            let error = match Self::call_sc_mint(ctx, &to, &amt, true) {
+                Ok(_) => me.into(),
+                Err(err) => internal_call_error(err),
+            };
 
             // Emit withdraw failed event.
             ctx.emit_event(_Event::Withdraw {
@@ -1162,7 +2706,7 @@ impl<Cfg: Config> Module<Cfg> {
                 nonce: context.nonce,
                 to: context.to,
                 amount: context.amount.clone(),
-                error: Some(me.into()),
+                error: Some(error),
             });
             return;
         }
@@ -1174,16 +2718,18 @@ impl<Cfg: Config> Module<Cfg> {
         */
         let addr = H160::from_str(DW_SYSTEM_ADDRESS).unwrap();
         let amt = u128_to_h256(context.amount.amount());
-        let _ = Self::call_sc_burn(ctx, &addr, &amt, false);
+        let error = Self::call_sc_burn(ctx, &addr, &amt, false)
+            .err()
+            .map(internal_call_error);
 
-        // Emit withdraw successful event.
+        // Emit withdraw event, which is only successful in case the burn succeeded.
         ctx.emit_event(_Event::Withdraw {
             from: context.address,
             eth_from: context.eth_addr,
             nonce: context.nonce,
             to: context.to,
             amount: context.amount.clone(),
-            error: None,
+            error,
         });
     }
 }
@@ -1193,6 +2739,26 @@ impl<Cfg: Config> Module<Cfg> {
     fn init<C: Context>(ctx: &mut C, genesis: Genesis) {
         // Set genesis parameters.
         Self::set_params(ctx.runtime_state(), genesis.parameters);
+
+        // Install genesis contracts.
+        for contract in genesis.contracts {
+            let address: primitive_types::H160 = contract.address.into();
+            assert!(
+                !contract.code.is_empty(),
+                "genesis contract {address:?} has no code"
+            );
+            let mut codes = state::codes(ctx.runtime_state());
+            assert!(
+                codes.get::<_, Vec<u8>>(address).is_none(),
+                "duplicate genesis contract {address:?}"
+            );
+            codes.insert(address, contract.code);
+
+            // Contract accounts start with a nonce of one (EIP-161).
+            let sdk_address = Cfg::map_address(address);
+            Cfg::Accounts::set_nonce(ctx.runtime_state(), sdk_address, 1);
+            state::register_address_alias(ctx.runtime_state(), address, sdk_address);
+        }
     }
 
     /// Migrate state from a previous version.
@@ -1230,10 +2796,17 @@ impl<Cfg: Config> module::TransactionHandler for Module<Cfg> {
         body: &[u8],
     ) -> Result<Option<Transaction>, CoreError> {
         match scheme {
-            "evm.ethereum.v0" => Ok(Some(
-                raw_tx::decode(body, Some(Cfg::CHAIN_ID))
-                    .map_err(CoreError::MalformedTransaction)?,
-            )),
+            "evm.ethereum.v0" => match raw_tx::decode(body, Some(Cfg::CHAIN_ID)) {
+                Ok(tx) => Ok(Some(tx)),
+                Err(Error::Core(err)) => Err(err),
+                // Keep the module name and code of EVM module errors so that clients can handle
+                // them specifically.
+                Err(err) => Err(CoreError::TxSimulationFailed(
+                    err.into_call_result()
+                        .try_into()
+                        .expect("errors always convert to failed call results"),
+                )),
+            },
             _ => Ok(None),
         }
     }
@@ -1253,7 +2826,97 @@ impl<Cfg: Config> module::BlockHandler for Module<Cfg> {
             let start_number = current_number - state::BLOCK_HASH_WINDOW_SIZE;
             block_hashes.remove(start_number.to_be_bytes());
         }
+
+        // Update the fee history of recent blocks.
+        let base_fee =
+            <C::Runtime as Runtime>::Core::min_gas_price(ctx, &Cfg::TOKEN_DENOMINATION);
+        let gas_limit = <C::Runtime as Runtime>::Core::max_batch_gas(ctx);
+        let gas_used =
+            gas_limit.saturating_sub(<C::Runtime as Runtime>::Core::remaining_batch_gas(ctx));
+        let mut txs: Vec<_> = ctx
+            .value::<Vec<(u128, u64)>>(CONTEXT_KEY_BLOCK_TX_FEES)
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(gas_price, gas_used)| types::FeeHistoryTx {
+                priority_fee: gas_price.saturating_sub(base_fee),
+                gas_used,
+            })
+            .collect();
+        txs.sort_by_key(|tx| tx.priority_fee);
+
+        let mut fee_history = state::fee_history(ctx.runtime_state());
+        fee_history.insert(
+            block_number.to_be_bytes(),
+            types::FeeHistoryBlock {
+                base_fee,
+                gas_used,
+                gas_limit,
+                txs,
+            },
+        );
+        if current_number > state::FEE_HISTORY_WINDOW_SIZE {
+            let start_number = current_number - state::FEE_HISTORY_WINDOW_SIZE;
+            fee_history.remove(start_number.to_be_bytes());
+        }
     }
 }
 
-impl<Cfg: Config> module::InvariantHandler for Module<Cfg> {}
+impl<Cfg: Config> module::InvariantHandler for Module<Cfg> {
+    /// Check invariants.
+    fn check_invariants<C: Context>(ctx: &mut C) -> Result<(), CoreError> {
+        // Every deployed code must belong to an account. Contract accounts are created with a
+        // non-zero nonce, so a zero nonce means that the account does not exist.
+        for address in state::code_addresses(ctx.runtime_state()) {
+            let nonce =
+                Cfg::Accounts::get_nonce(ctx.runtime_state(), Cfg::map_address(address.into()))
+                    .unwrap_or_default();
+            if nonce == 0 {
+                return Err(CoreError::InvariantViolation(format!(
+                    "code of {address:?} does not belong to an existing account"
+                )));
+            }
+        }
+
+        // Only the hashes of blocks within the window may be kept.
+        let current_round = ctx.runtime_header().round;
+        let rounds = state::block_hash_rounds(ctx.runtime_state());
+        if rounds.len() as u64 > state::BLOCK_HASH_WINDOW_SIZE {
+            return Err(CoreError::InvariantViolation(format!(
+                "{} block hashes stored, but the window only has {}",
+                rounds.len(),
+                state::BLOCK_HASH_WINDOW_SIZE
+            )));
+        }
+        if let Some(round) = rounds.iter().find(|round| **round > current_round) {
+            return Err(CoreError::InvariantViolation(format!(
+                "block hash stored for future round {round} (current round: {current_round})"
+            )));
+        }
+
+        // Gas used by the EVM transactions of a block can't exceed the gas used by the block.
+        let fee_history_rounds = state::fee_history_rounds(ctx.runtime_state());
+        let fee_history = state::fee_history(ctx.runtime_state());
+        for round in fee_history_rounds {
+            let block: types::FeeHistoryBlock = match fee_history.get(round.to_be_bytes()) {
+                Some(block) => block,
+                None => continue,
+            };
+            let tx_gas_used = block
+                .txs
+                .iter()
+                .try_fold(0u64, |sum, tx| sum.checked_add(tx.gas_used));
+            match tx_gas_used {
+                Some(tx_gas_used)
+                    if tx_gas_used <= block.gas_used && block.gas_used <= block.gas_limit => {}
+                _ => {
+                    return Err(CoreError::InvariantViolation(format!(
+                        "gas used by block {round} is inconsistent"
+                    )))
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
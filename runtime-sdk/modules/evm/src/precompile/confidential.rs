@@ -27,6 +27,8 @@ const X25519_KEY_DERIVATION_BASE_COST: u64 = 100_000;
 const DEOXYSII_BASE_COST: u64 = 50_000;
 /// The cost for encryption and decryption per word of input.
 const DEOXYSII_WORD_COST: u64 = 100;
+/// The base cost of deriving a contract sealing key from the key manager.
+const CONTRACT_SEALING_KEY_BASE_COST: u64 = 100_000;
 
 /// The cost of a key pair generation operation, per method.
 static KEYPAIR_GENERATE_BASE_COST: Lazy<HashMap<SignatureType, u64>> = Lazy::new(|| {
@@ -200,6 +202,87 @@ pub(super) fn call_deoxysii_open(handle: &mut impl PrecompileHandle) -> Precompi
     }
 }
 
+fn decode_contract_sealing_call_args(
+    input: &[u8],
+) -> Result<([u8; NONCE_SIZE], Vec<u8>, Vec<u8>), PrecompileFailure> {
+    let mut call_args = ethabi::decode(
+        &[
+            ParamType::FixedBytes(32), // nonce
+            ParamType::Bytes,          // plain or ciphertext
+            ParamType::Bytes,          // associated data
+        ],
+        input,
+    )
+    .map_err(|e| PrecompileFailure::Error {
+        exit_status: ExitError::Other(e.to_string().into()),
+    })?;
+    let ad = call_args.pop().unwrap().into_bytes().unwrap();
+    let text = call_args.pop().unwrap().into_bytes().unwrap();
+    let nonce_bytes = call_args.pop().unwrap().into_fixed_bytes().unwrap();
+
+    let mut nonce = [0u8; NONCE_SIZE];
+    nonce.copy_from_slice(&nonce_bytes[..NONCE_SIZE]);
+
+    Ok((nonce, text, ad))
+}
+
+/// Returns the sealing key of the contract calling the precompile.
+fn contract_sealing_key<B: EVMBackendExt>(
+    handle: &impl PrecompileHandle,
+    backend: &B,
+) -> Result<[u8; KEY_SIZE], PrecompileFailure> {
+    backend
+        .contract_sealing_key(handle.context().caller)
+        .ok_or_else(|| PrecompileFailure::Error {
+            exit_status: ExitError::Other("key manager unavailable".into()),
+        })
+}
+
+pub(super) fn call_contract_seal<B: EVMBackendExt>(
+    handle: &mut impl PrecompileHandle,
+    backend: &B,
+) -> PrecompileResult {
+    record_linear_cost(
+        handle,
+        handle.input().len() as u64,
+        DEOXYSII_BASE_COST + CONTRACT_SEALING_KEY_BASE_COST,
+        DEOXYSII_WORD_COST,
+    )?;
+    let (nonce, text, ad) = decode_contract_sealing_call_args(handle.input())?;
+    let key = contract_sealing_key(&*handle, backend)?;
+    let deoxysii = DeoxysII::new(&key);
+    let encrypted = deoxysii.seal(&nonce, text, ad);
+    Ok(PrecompileOutput {
+        exit_status: ExitSucceed::Returned,
+        output: encrypted,
+    })
+}
+
+pub(super) fn call_contract_open<B: EVMBackendExt>(
+    handle: &mut impl PrecompileHandle,
+    backend: &B,
+) -> PrecompileResult {
+    record_linear_cost(
+        handle,
+        handle.input().len() as u64,
+        DEOXYSII_BASE_COST + CONTRACT_SEALING_KEY_BASE_COST,
+        DEOXYSII_WORD_COST,
+    )?;
+    let (nonce, ciphertext, ad) = decode_contract_sealing_call_args(handle.input())?;
+    let key = contract_sealing_key(&*handle, backend)?;
+    let deoxysii = DeoxysII::new(&key);
+    match deoxysii.open(&nonce, ciphertext, ad) {
+        Ok(decrypted) => Ok(PrecompileOutput {
+            exit_status: ExitSucceed::Returned,
+            output: decrypted,
+        }),
+        Err(_) => Err(PrecompileFailure::Revert {
+            exit_status: ExitRevert::Reverted,
+            output: vec![],
+        }),
+    }
+}
+
 pub(super) fn call_keypair_generate(handle: &mut impl PrecompileHandle) -> PrecompileResult {
     let mut call_args = ethabi::decode(
         &[
@@ -497,6 +580,58 @@ mod test {
         assert_eq!(plaintext.as_slice(), ret_pt.output);
     }
 
+    #[test]
+    fn test_contract_sealing() {
+        let nonce = b"complete noncence, and too long.";
+        let plaintext = b"0123456789";
+        let ad = b"additional data";
+        let ret_ct = call_contract(
+            H160([
+                0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x08,
+            ]),
+            &ethabi::encode(&[
+                Token::FixedBytes(nonce.to_vec()),
+                Token::Bytes(plaintext.to_vec()),
+                Token::Bytes(ad.to_vec()),
+            ]),
+            10_000_000,
+        )
+        .expect("call should return something")
+        .expect("call should succeed");
+        assert_ne!(plaintext.as_slice(), ret_ct.output);
+
+        // The ciphertext can't be opened using the generic precompile without the contract key.
+        call_contract(
+            H160([
+                0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x04,
+            ]),
+            &ethabi::encode(&[
+                Token::FixedBytes(b"this must be the excelentest key".to_vec()),
+                Token::FixedBytes(nonce.to_vec()),
+                Token::Bytes(ret_ct.output.clone()),
+                Token::Bytes(ad.to_vec()),
+            ]),
+            10_000_000,
+        )
+        .expect("call should return something")
+        .expect_err("call should fail");
+
+        let ret_pt = call_contract(
+            H160([
+                0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x09,
+            ]),
+            &ethabi::encode(&[
+                Token::FixedBytes(nonce.to_vec()),
+                Token::Bytes(ret_ct.output),
+                Token::Bytes(ad.to_vec()),
+            ]),
+            10_000_000,
+        )
+        .expect("call should return something")
+        .expect("call should succeed");
+        assert_eq!(plaintext.as_slice(), ret_pt.output);
+    }
+
     #[test]
     fn test_random_bytes() {
         let ret = call_contract(
@@ -0,0 +1,257 @@
+//! Consensus staking precompiles.
+//!
+//! These precompiles allow contracts to request consensus layer operations (deposits, withdrawals,
+//! delegations and undelegations) through the `consensus_accounts` module. Since consensus messages can only be
+//! emitted from a transaction context, each request is recorded as a log of the precompile and is
+//! turned into a consensus message after the EVM execution has completed successfully. This way
+//! requests made in frames that get reverted are discarded together with the rest of the frame.
+//!
+//! Deposits pull funds from the transaction signer's consensus account, so they are only executed
+//! for the contract called by an `evm.Call` transaction whose `max_consensus_deposit` covers them.
+use std::convert::TryFrom;
+
+use ethabi::{ParamType, Token};
+use evm::{
+    backend::Log,
+    executor::stack::{PrecompileFailure, PrecompileHandle, PrecompileOutput},
+    ExitError, ExitSucceed,
+};
+use primitive_types::H160;
+
+use oasis_runtime_sdk::types::address::Address;
+
+use crate::{backend::EVMBackendExt, types};
+
+use super::{ensure_direct_call, record_linear_cost, PrecompileResult};
+
+/// The base cost of a request for a consensus operation.
+const CONSENSUS_MESSAGE_BASE_COST: u64 = 100_000;
+
+/// Address of the consensus deposit precompile.
+pub const DEPOSIT_ADDRESS: H160 = H160([
+    2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+]);
+/// Address of the consensus withdraw precompile.
+pub const WITHDRAW_ADDRESS: H160 = H160([
+    2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,
+]);
+/// Address of the consensus delegate precompile.
+pub const DELEGATE_ADDRESS: H160 = H160([
+    2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3,
+]);
+/// Address of the consensus undelegate precompile.
+pub const UNDELEGATE_ADDRESS: H160 = H160([
+    2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4,
+]);
+
+/// Kind of a consensus operation requested by a contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsensusOperationKind {
+    /// Deposit from the transaction signer's consensus account into the calling contract.
+    Deposit,
+    /// Withdraw from the calling contract into a consensus account.
+    Withdraw,
+    /// Delegate from the calling contract to a consensus account.
+    Delegate,
+    /// Reclaim shares the calling contract delegated to a consensus account.
+    Undelegate,
+}
+
+/// A consensus operation requested by a contract through one of the staking precompiles.
+#[derive(Clone, Debug, cbor::Encode, cbor::Decode)]
+pub struct ConsensusOperation {
+    /// Address of the contract that requested the operation.
+    pub caller: types::H160,
+    /// Consensus account that is the source (deposit, undelegate) or destination (withdraw,
+    /// delegate).
+    ///
+    /// Deposits are only executed in case the source is the transaction signer's account.
+    pub account: Address,
+    /// Amount in the consensus denomination, or number of shares for undelegations.
+    pub amount: u128,
+}
+
+impl ConsensusOperation {
+    /// Decodes a consensus operation from a log emitted by one of the staking precompiles.
+    ///
+    /// Returns `None` in case the log was not emitted by a staking precompile.
+    pub fn from_log(log: &Log) -> Option<(ConsensusOperationKind, Self)> {
+        let kind = if log.address == DEPOSIT_ADDRESS {
+            ConsensusOperationKind::Deposit
+        } else if log.address == WITHDRAW_ADDRESS {
+            ConsensusOperationKind::Withdraw
+        } else if log.address == DELEGATE_ADDRESS {
+            ConsensusOperationKind::Delegate
+        } else if log.address == UNDELEGATE_ADDRESS {
+            ConsensusOperationKind::Undelegate
+        } else {
+            return None;
+        };
+        let op = cbor::from_slice(&log.data).ok()?;
+        Some((kind, op))
+    }
+}
+
+/// Whether the given address belongs to one of the staking precompiles.
+pub(super) fn is_consensus_precompile(address: H160) -> bool {
+    address == DEPOSIT_ADDRESS
+        || address == WITHDRAW_ADDRESS
+        || address == DELEGATE_ADDRESS
+        || address == UNDELEGATE_ADDRESS
+}
+
+pub(super) fn call_consensus_operation<B: EVMBackendExt>(
+    handle: &mut impl PrecompileHandle,
+    backend: &B,
+) -> PrecompileResult {
+    record_linear_cost(
+        handle,
+        handle.input().len() as u64,
+        CONSENSUS_MESSAGE_BASE_COST,
+        0,
+    )?;
+
+    if handle.is_static() {
+        return Err(PrecompileFailure::Error {
+            exit_status: ExitError::Other(
+                "consensus operations are not allowed in static calls".into(),
+            ),
+        });
+    }
+    // The operation acts on behalf of the caller, which must not be spoofable by delegating.
+    ensure_direct_call(handle)?;
+
+    // Input encoding: bytes account || uint128 amount.
+    let mut call_args = ethabi::decode(&[ParamType::Bytes, ParamType::Uint(128)], handle.input())
+        .map_err(|e| PrecompileFailure::Error {
+            exit_status: ExitError::Other(e.to_string().into()),
+        })?;
+    let amount = call_args.pop().unwrap().into_uint().unwrap();
+    let account = call_args.pop().unwrap().into_bytes().unwrap();
+
+    let amount = u128::try_from(amount).map_err(|_| PrecompileFailure::Error {
+        exit_status: ExitError::Other("amount too large".into()),
+    })?;
+    let account = Address::from_bytes(&account).map_err(|_| PrecompileFailure::Error {
+        exit_status: ExitError::Other("invalid consensus address".into()),
+    })?;
+
+    if !backend.reserve_message_slot() {
+        return Err(PrecompileFailure::Error {
+            exit_status: ExitError::Other("out of consensus message slots".into()),
+        });
+    }
+
+    let address = handle.code_address();
+    let op = ConsensusOperation {
+        caller: handle.context().caller.into(),
+        account,
+        amount,
+    };
+    handle.log(address, vec![], cbor::to_vec(op))?;
+
+    Ok(PrecompileOutput {
+        exit_status: ExitSucceed::Returned,
+        output: ethabi::encode(&[Token::Bool(true)]),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use evm::Context;
+
+    use super::*;
+    use crate::precompile::test::call_contract_with_context;
+
+    fn operation_input(account: &Address, amount: u128) -> Vec<u8> {
+        ethabi::encode(&[
+            Token::Bytes(account.as_ref().to_vec()),
+            Token::Uint(amount.into()),
+        ])
+    }
+
+    #[test]
+    fn test_consensus_operation() {
+        let account = Address::from_module("test", "account");
+        let caller = H160::repeat_byte(0x11);
+        for (address, kind) in [
+            (DEPOSIT_ADDRESS, ConsensusOperationKind::Deposit),
+            (WITHDRAW_ADDRESS, ConsensusOperationKind::Withdraw),
+            (DELEGATE_ADDRESS, ConsensusOperationKind::Delegate),
+        ] {
+            let context = Context {
+                address,
+                caller,
+                apparent_value: From::from(0),
+            };
+            let (result, logs) = call_contract_with_context(
+                address,
+                &operation_input(&account, 1_000),
+                1_000_000,
+                &context,
+            );
+            let output = result
+                .expect("call should return something")
+                .expect("call should succeed")
+                .output;
+            assert_eq!(
+                ethabi::decode(&[ParamType::Bool], &output).unwrap(),
+                vec![Token::Bool(true)]
+            );
+
+            // The operation is recorded as a log of the precompile on behalf of the caller.
+            assert_eq!(logs.len(), 1);
+            let (op_kind, op) = ConsensusOperation::from_log(&logs[0]).unwrap();
+            assert_eq!(op_kind, kind);
+            assert_eq!(H160::from(op.caller), caller);
+            assert_eq!(op.account, account);
+            assert_eq!(op.amount, 1_000);
+        }
+    }
+
+    #[test]
+    fn test_consensus_operation_delegated() {
+        // A contract delegating to the precompile must not be able to act for its caller.
+        let context = Context {
+            address: H160::repeat_byte(0x22),
+            caller: H160::repeat_byte(0x11),
+            apparent_value: From::from(0),
+        };
+        let (result, logs) = call_contract_with_context(
+            WITHDRAW_ADDRESS,
+            &operation_input(&Address::from_module("test", "account"), 1_000),
+            1_000_000,
+            &context,
+        );
+        assert!(matches!(
+            result.expect("call should return something"),
+            Err(PrecompileFailure::Error { .. })
+        ));
+        assert!(logs.is_empty());
+    }
+
+    #[test]
+    fn test_consensus_operation_invalid_input() {
+        let context = Context {
+            address: DELEGATE_ADDRESS,
+            caller: H160::repeat_byte(0x11),
+            apparent_value: From::from(0),
+        };
+        let input = ethabi::encode(&[Token::Bytes(vec![1, 2, 3]), Token::Uint(1_000.into())]);
+        let (result, logs) =
+            call_contract_with_context(DELEGATE_ADDRESS, &input, 1_000_000, &context);
+        assert!(matches!(
+            result.expect("call should return something"),
+            Err(PrecompileFailure::Error { .. })
+        ));
+        assert!(logs.is_empty());
+
+        // Logs of other addresses are not consensus operations.
+        let log = Log {
+            address: H160::repeat_byte(0x22),
+            topics: vec![],
+            data: vec![],
+        };
+        assert!(ConsensusOperation::from_log(&log).is_none());
+    }
+}
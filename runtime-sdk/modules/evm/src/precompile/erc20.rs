@@ -0,0 +1,357 @@
+//! ERC-20 facade precompiles.
+//!
+//! These precompiles present accounts module denominations as ERC-20 tokens so that contracts can
+//! use them without wrapping contracts. Each denomination is served at an address that encodes
+//! the denomination itself, see [`facade_address`]. Transfers are recorded as the standard
+//! ERC-20 `Transfer` log of the precompile and are only applied to the accounts module after the
+//! EVM execution has completed successfully, so transfers made in reverted frames are discarded
+//! together with the rest of the frame.
+use std::convert::TryFrom;
+
+use ethabi::{ParamType, Token};
+use evm::{
+    backend::Log,
+    executor::stack::{PrecompileFailure, PrecompileHandle, PrecompileOutput},
+    ExitError, ExitRevert, ExitSucceed,
+};
+use primitive_types::{H160, H256, U256};
+
+use oasis_runtime_sdk::types::token::Denomination;
+
+use crate::backend::EVMBackendExt;
+
+use super::{ensure_direct_call, PrecompileResult};
+
+/// First byte of the address of each ERC-20 facade precompile.
+const FACADE_ADDRESS_PREFIX: u8 = 3;
+
+/// Maximum length of a denomination that can be presented through a facade precompile.
+pub const MAX_FACADE_DENOMINATION_LENGTH: usize = 18;
+
+/// The cost of reading a balance, the total supply or the number of decimals.
+const QUERY_COST: u64 = 2_100;
+/// The cost of a transfer.
+const TRANSFER_COST: u64 = 25_000;
+
+/// Topic of the ERC-20 `Transfer(address,address,uint256)` event.
+pub const TRANSFER_EVENT_TOPIC: H256 = H256([
+    0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa,
+    0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+]);
+
+const TOTAL_SUPPLY_SELECTOR: &[u8] = &[0x18, 0x16, 0x0d, 0xdd]; // totalSupply()
+const DECIMALS_SELECTOR: &[u8] = &[0x31, 0x3c, 0xe5, 0x67]; // decimals()
+const BALANCE_OF_SELECTOR: &[u8] = &[0x70, 0xa0, 0x82, 0x31]; // balanceOf(address)
+const TRANSFER_SELECTOR: &[u8] = &[0xa9, 0x05, 0x9c, 0xbb]; // transfer(address,uint256)
+
+/// Returns the address of the facade precompile of the given denomination.
+///
+/// The address is `0x03 || len || denomination || 0...`, so denominations longer than
+/// [`MAX_FACADE_DENOMINATION_LENGTH`] bytes cannot be presented.
+pub fn facade_address(denomination: &Denomination) -> Option<H160> {
+    let denomination = denomination.as_ref();
+    if denomination.len() > MAX_FACADE_DENOMINATION_LENGTH {
+        return None;
+    }
+    let mut address = H160::zero();
+    address.0[0] = FACADE_ADDRESS_PREFIX;
+    address.0[1] = denomination.len() as u8;
+    address.0[2..2 + denomination.len()].copy_from_slice(denomination);
+    Some(address)
+}
+
+/// Returns the denomination presented by the facade precompile at the given address, if any.
+pub fn facade_denomination(address: H160) -> Option<Denomination> {
+    if address[0] != FACADE_ADDRESS_PREFIX {
+        return None;
+    }
+    let len = address[1] as usize;
+    if len > MAX_FACADE_DENOMINATION_LENGTH || address[2 + len..].iter().any(|b| *b != 0) {
+        return None;
+    }
+    Denomination::try_from(&address[2..2 + len]).ok()
+}
+
+/// A transfer of an SDK denomination made through one of the facade precompiles.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FacadeTransfer {
+    /// Transferred denomination.
+    pub denomination: Denomination,
+    /// Account that the amount is transferred from.
+    pub from: H160,
+    /// Account that the amount is transferred to.
+    pub to: H160,
+    /// Transferred amount.
+    pub amount: u128,
+}
+
+impl FacadeTransfer {
+    /// Decodes a transfer from a log emitted by one of the facade precompiles.
+    ///
+    /// Returns `None` in case the log was not emitted by a facade precompile.
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let denomination = facade_denomination(log.address)?;
+        if log.topics.len() != 3 || log.topics[0] != TRANSFER_EVENT_TOPIC {
+            return None;
+        }
+        let amount = u128::try_from(U256::from_big_endian(&log.data)).ok()?;
+        Some(Self {
+            denomination,
+            from: log.topics[1].into(),
+            to: log.topics[2].into(),
+            amount,
+        })
+    }
+}
+
+pub(super) fn call_erc20_facade<B: EVMBackendExt>(
+    handle: &mut impl PrecompileHandle,
+    backend: &B,
+) -> PrecompileResult {
+    let address = handle.code_address();
+    let denomination = facade_denomination(address).ok_or(PrecompileFailure::Error {
+        exit_status: ExitError::Other("invalid facade address".into()),
+    })?;
+    if handle.input().len() < 4 {
+        return Err(PrecompileFailure::Error {
+            exit_status: ExitError::Other("invalid input".into()),
+        });
+    }
+    let input = handle.input().to_vec();
+    let (selector, args) = input.split_at(4);
+    let decode_args = |types: &[ParamType]| {
+        ethabi::decode(types, args).map_err(|e| PrecompileFailure::Error {
+            exit_status: ExitError::Other(e.to_string().into()),
+        })
+    };
+
+    let output = match selector {
+        TOTAL_SUPPLY_SELECTOR => {
+            handle.record_cost(QUERY_COST)?;
+            Token::Uint(backend.token_total_supply(&denomination).into())
+        }
+        DECIMALS_SELECTOR => {
+            handle.record_cost(QUERY_COST)?;
+            Token::Uint(backend.token_decimals(&denomination).into())
+        }
+        BALANCE_OF_SELECTOR => {
+            handle.record_cost(QUERY_COST)?;
+            let mut call_args = decode_args(&[ParamType::Address])?;
+            let owner = call_args.pop().unwrap().into_address().unwrap();
+            Token::Uint(backend.token_balance(H160(owner.0), &denomination).into())
+        }
+        TRANSFER_SELECTOR => {
+            handle.record_cost(TRANSFER_COST)?;
+            if handle.is_static() {
+                return Err(PrecompileFailure::Error {
+                    exit_status: ExitError::Other(
+                        "transfers are not allowed in static calls".into(),
+                    ),
+                });
+            }
+            // Transfers move the caller's tokens, which must not be spoofable by delegating.
+            ensure_direct_call(handle)?;
+            let mut call_args = decode_args(&[ParamType::Address, ParamType::Uint(256)])?;
+            let amount = call_args.pop().unwrap().into_uint().unwrap();
+            let to = H160(call_args.pop().unwrap().into_address().unwrap().0);
+            let amount = u128::try_from(amount).map_err(|_| PrecompileFailure::Error {
+                exit_status: ExitError::Other("amount too large".into()),
+            })?;
+
+            // Transfers are applied once execution completes, so the accounts module still has
+            // the final say in case an account spends more than its balance in multiple calls.
+            let from = handle.context().caller;
+            if backend.token_balance(from, &denomination) < amount {
+                return Err(PrecompileFailure::Revert {
+                    exit_status: ExitRevert::Reverted,
+                    output: vec![],
+                });
+            }
+            handle.log(
+                address,
+                vec![TRANSFER_EVENT_TOPIC, from.into(), to.into()],
+                ethabi::encode(&[Token::Uint(amount.into())]),
+            )?;
+            Token::Bool(true)
+        }
+        _ => {
+            return Err(PrecompileFailure::Error {
+                exit_status: ExitError::Other("unknown selector".into()),
+            })
+        }
+    };
+
+    Ok(PrecompileOutput {
+        exit_status: ExitSucceed::Returned,
+        output: ethabi::encode(&[output]),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use evm::Context;
+
+    use super::*;
+    use crate::precompile::test::{call_contract, call_contract_with_context};
+
+    fn call_facade(input: &[u8]) -> Vec<u8> {
+        let address = facade_address(&"TEST".parse().unwrap()).unwrap();
+        call_contract(address, input, 1_000_000)
+            .expect("call should return something")
+            .expect("call should succeed")
+            .output
+    }
+
+    #[test]
+    fn test_facade_address() {
+        let denomination: Denomination = "TEST".parse().unwrap();
+        let address = facade_address(&denomination).unwrap();
+        assert_eq!(
+            hex::encode(address),
+            "0304544553540000000000000000000000000000"
+        );
+        assert_eq!(facade_denomination(address), Some(denomination));
+        assert_eq!(
+            facade_denomination(facade_address(&Denomination::NATIVE).unwrap()),
+            Some(Denomination::NATIVE)
+        );
+
+        let long: Denomination = "A_VERY_LONG_DENOMINATION".parse().unwrap();
+        assert!(facade_address(&long).is_none());
+
+        let mut address = address;
+        address.0[19] = 1;
+        assert!(facade_denomination(address).is_none(), "trailing bytes must be zero");
+    }
+
+    #[test]
+    fn test_facade_queries() {
+        let output = call_facade(TOTAL_SUPPLY_SELECTOR);
+        assert_eq!(
+            ethabi::decode(&[ParamType::Uint(256)], &output).unwrap(),
+            vec![Token::Uint(1_000_000.into())]
+        );
+
+        let output = call_facade(DECIMALS_SELECTOR);
+        assert_eq!(
+            ethabi::decode(&[ParamType::Uint(256)], &output).unwrap(),
+            vec![Token::Uint(18.into())]
+        );
+
+        // The mock backend reports the low bytes of the address as the balance.
+        let owner = H160::from_low_u64_be(42);
+        let input = [
+            BALANCE_OF_SELECTOR,
+            &ethabi::encode(&[Token::Address(owner.0.into())])[..],
+        ]
+        .concat();
+        let output = call_facade(&input);
+        assert_eq!(
+            ethabi::decode(&[ParamType::Uint(256)], &output).unwrap(),
+            vec![Token::Uint(42.into())]
+        );
+    }
+
+    #[test]
+    fn test_facade_transfer() {
+        // The caller in tests is the zero address, which has no balance in the mock backend.
+        let input = [
+            TRANSFER_SELECTOR,
+            &ethabi::encode(&[
+                Token::Address(H160::repeat_byte(0x01).0.into()),
+                Token::Uint(1.into()),
+            ])[..],
+        ]
+        .concat();
+        let address = facade_address(&"TEST".parse().unwrap()).unwrap();
+        let result =
+            call_contract(address, &input, 1_000_000).expect("call should return something");
+        assert!(matches!(result, Err(PrecompileFailure::Revert { .. })));
+
+        let input = [
+            TRANSFER_SELECTOR,
+            &ethabi::encode(&[
+                Token::Address(H160::repeat_byte(0x01).0.into()),
+                Token::Uint(0.into()),
+            ])[..],
+        ]
+        .concat();
+        let output = call_facade(&input);
+        assert_eq!(
+            ethabi::decode(&[ParamType::Bool], &output).unwrap(),
+            vec![Token::Bool(true)]
+        );
+    }
+
+    #[test]
+    fn test_facade_transfer_delegated() {
+        // A contract delegating to the facade must not be able to move its caller's tokens.
+        let address = facade_address(&"TEST".parse().unwrap()).unwrap();
+        let context = Context {
+            address: H160::repeat_byte(0x22),
+            caller: H160::from_low_u64_be(42),
+            apparent_value: From::from(0),
+        };
+        let input = [
+            TRANSFER_SELECTOR,
+            &ethabi::encode(&[
+                Token::Address(H160::repeat_byte(0x22).0.into()),
+                Token::Uint(1.into()),
+            ])[..],
+        ]
+        .concat();
+        let (result, logs) = call_contract_with_context(address, &input, 1_000_000, &context);
+        assert!(matches!(
+            result.expect("call should return something"),
+            Err(PrecompileFailure::Error { .. })
+        ));
+        assert!(logs.is_empty());
+
+        // Calling the facade directly transfers the caller's tokens.
+        let context = Context { address, ..context };
+        let (result, logs) = call_contract_with_context(address, &input, 1_000_000, &context);
+        result
+            .expect("call should return something")
+            .expect("call should succeed");
+        assert_eq!(
+            FacadeTransfer::from_log(&logs[0]).map(|transfer| transfer.from),
+            Some(H160::from_low_u64_be(42))
+        );
+    }
+
+    #[test]
+    fn test_facade_native_denomination() {
+        // The native EVM token of the test configuration has no facade.
+        let address = facade_address(&Denomination::NATIVE).unwrap();
+        assert!(call_contract(address, TOTAL_SUPPLY_SELECTOR, 1_000_000).is_none());
+    }
+
+    #[test]
+    fn test_facade_transfer_from_log() {
+        let denomination: Denomination = "TEST".parse().unwrap();
+        let log = Log {
+            address: facade_address(&denomination).unwrap(),
+            topics: vec![
+                TRANSFER_EVENT_TOPIC,
+                H160::repeat_byte(0x01).into(),
+                H160::repeat_byte(0x02).into(),
+            ],
+            data: ethabi::encode(&[Token::Uint(100.into())]),
+        };
+        assert_eq!(
+            FacadeTransfer::from_log(&log),
+            Some(FacadeTransfer {
+                denomination,
+                from: H160::repeat_byte(0x01),
+                to: H160::repeat_byte(0x02),
+                amount: 100,
+            })
+        );
+
+        let log = Log {
+            address: H160::repeat_byte(0x01),
+            ..log
+        };
+        assert_eq!(FacadeTransfer::from_log(&log), None);
+    }
+}
@@ -1,17 +1,20 @@
 //! EVM precompiles.
 
+use std::cell::Cell;
 use std::marker::PhantomData;
 use std::cmp::min;
 
 use evm::{
     executor::stack::{PrecompileFailure, PrecompileHandle, PrecompileOutput, PrecompileSet},
-    ExitError,
+    Context, ExitError, ExitReason, Transfer,
 };
-use primitive_types::H160;
+use primitive_types::{H160, H256};
 
-use crate::{backend::EVMBackendExt, Config};
+use crate::{backend::EVMBackendExt, Config, PrecompileGasCosts};
 
 mod confidential;
+pub mod consensus;
+pub mod erc20;
 mod standard;
 
 #[cfg(test)]
@@ -69,8 +72,80 @@ fn record_multilinear_cost(
     Ok(())
 }
 
+/// Ensures that the precompile is being called directly rather than through `DELEGATECALL` or
+/// `CALLCODE`, in which case the precompile would act on behalf of the delegating contract's
+/// caller.
+fn ensure_direct_call(handle: &impl PrecompileHandle) -> Result<(), PrecompileFailure> {
+    if handle.context().address != handle.code_address() {
+        return Err(PrecompileFailure::Error {
+            exit_status: ExitError::Other("precompile must be called directly".into()),
+        });
+    }
+    Ok(())
+}
+
+/// Precompile handle wrapper that scales all recorded gas costs by a multiplier (in percent).
+struct ScaledPrecompileHandle<'a, H: PrecompileHandle> {
+    inner: &'a mut H,
+    multiplier: u64,
+}
+
+impl<H: PrecompileHandle> PrecompileHandle for ScaledPrecompileHandle<'_, H> {
+    fn call(
+        &mut self,
+        to: H160,
+        transfer: Option<Transfer>,
+        input: Vec<u8>,
+        gas_limit: Option<u64>,
+        is_static: bool,
+        context: &Context,
+    ) -> (ExitReason, Vec<u8>) {
+        self.inner.call(to, transfer, input, gas_limit, is_static, context)
+    }
+
+    fn record_cost(&mut self, cost: u64) -> Result<(), ExitError> {
+        let cost = cost
+            .checked_mul(self.multiplier)
+            .map(|cost| cost / 100)
+            .ok_or(ExitError::OutOfGas)?;
+        self.inner.record_cost(cost)
+    }
+
+    fn remaining_gas(&self) -> u64 {
+        self.inner.remaining_gas()
+    }
+
+    fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError> {
+        self.inner.log(address, topics, data)
+    }
+
+    fn code_address(&self) -> H160 {
+        self.inner.code_address()
+    }
+
+    fn input(&self) -> &[u8] {
+        self.inner.input()
+    }
+
+    fn context(&self) -> &Context {
+        self.inner.context()
+    }
+
+    fn is_static(&self) -> bool {
+        self.inner.is_static()
+    }
+
+    fn gas_limit(&self) -> Option<u64> {
+        self.inner.gas_limit()
+    }
+}
+
 pub(crate) struct Precompiles<'a, Cfg: Config, B: EVMBackendExt> {
     backend: &'a B,
+    gas_multiplier: u64,
+    gas_costs: PrecompileGasCosts,
+    round: Option<u64>,
+    calls: Cell<u64>,
     config: PhantomData<Cfg>,
 }
 
@@ -78,23 +153,68 @@ impl<'a, Cfg: Config, B: EVMBackendExt> Precompiles<'a, Cfg, B> {
     pub(crate) fn new(backend: &'a B) -> Self {
         Self {
             backend,
+            gas_multiplier: 0,
+            gas_costs: PrecompileGasCosts::default().with_defaults(),
+            round: None,
+            calls: Cell::new(0),
             config: PhantomData,
         }
     }
-}
 
-impl<Cfg: Config, B: EVMBackendExt> PrecompileSet for Precompiles<'_, Cfg, B> {
-    fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
-        let address = handle.code_address();
-        if !self.is_precompile(address) {
-            return None;
+    /// Scale gas costs of all precompiles by the given multiplier (in percent). Zero
+    /// means that gas costs are not scaled.
+    pub(crate) fn with_gas_multiplier(mut self, gas_multiplier: u64) -> Self {
+        self.gas_multiplier = gas_multiplier;
+        self
+    }
+
+    /// Sets the gas costs of the standard Ethereum precompiles. Unset costs use the Ethereum
+    /// defaults.
+    pub(crate) fn with_gas_costs(mut self, gas_costs: &PrecompileGasCosts) -> Self {
+        self.gas_costs = gas_costs.with_defaults();
+        self
+    }
+
+    /// Sets the round that the precompiles are executed in, used to check activation rounds
+    /// configured via [`Config::precompile_activation_round`]. Without a round all precompiles are
+    /// active.
+    pub(crate) fn with_round(mut self, round: u64) -> Self {
+        self.round = Some(round);
+        self
+    }
+
+    fn is_active(&self, address: H160) -> bool {
+        match (self.round, Cfg::precompile_activation_round(address)) {
+            (Some(round), Some(activation_round)) => round >= activation_round,
+            _ => true,
         }
+    }
+
+    /// Whether the given address belongs to the ERC-20 facade of an SDK denomination other than
+    /// the native EVM token, whose balances are already visible to the EVM.
+    fn is_erc20_facade_precompile(address: H160) -> bool {
+        Cfg::ERC20_FACADE_PRECOMPILES
+            && erc20::facade_denomination(address)
+                .map(|denomination| denomination != Cfg::TOKEN_DENOMINATION)
+                .unwrap_or_default()
+    }
+
+    /// Number of precompile calls made so far.
+    pub(crate) fn calls(&self) -> u64 {
+        self.calls.get()
+    }
+
+    fn execute_inner(
+        &self,
+        handle: &mut impl PrecompileHandle,
+        address: H160,
+    ) -> Option<PrecompileResult> {
         Some(match (address[0], address[19]) {
-            (0, 1) => standard::call_ecrecover(handle),
-            (0, 2) => standard::call_sha256(handle),
-            (0, 3) => standard::call_ripemd160(handle),
-            (0, 4) => standard::call_datacopy(handle),
-            (0, 5) => standard::call_bigmodexp(handle),
+            (0, 1) => standard::call_ecrecover(handle, &self.gas_costs),
+            (0, 2) => standard::call_sha256(handle, &self.gas_costs),
+            (0, 3) => standard::call_ripemd160(handle, &self.gas_costs),
+            (0, 4) => standard::call_datacopy(handle, &self.gas_costs),
+            (0, 5) => standard::call_bigmodexp(handle, &self.gas_costs),
             (1, 1) => confidential::call_random_bytes(handle, self.backend),
             (1, 2) => confidential::call_x25519_derive(handle),
             (1, 3) => confidential::call_deoxysii_seal(handle),
@@ -102,20 +222,50 @@ impl<Cfg: Config, B: EVMBackendExt> PrecompileSet for Precompiles<'_, Cfg, B> {
             (1, 5) => confidential::call_keypair_generate(handle),
             (1, 6) => confidential::call_sign(handle),
             (1, 7) => confidential::call_verify(handle),
+            (1, 8) => confidential::call_contract_seal(handle, self.backend),
+            (1, 9) => confidential::call_contract_open(handle, self.backend),
+            (2, 1..=4) => consensus::call_consensus_operation(handle, self.backend),
+            (3, _) if Self::is_erc20_facade_precompile(address) => {
+                erc20::call_erc20_facade(handle, self.backend)
+            }
             _ => return Cfg::additional_precompiles().and_then(|pc| pc.execute(handle)),
         })
     }
+}
+
+impl<Cfg: Config, B: EVMBackendExt> PrecompileSet for Precompiles<'_, Cfg, B> {
+    fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
+        let address = handle.code_address();
+        if !self.is_precompile(address) {
+            return None;
+        }
+        self.calls.set(self.calls.get() + 1);
+        if self.gas_multiplier == 0 {
+            return self.execute_inner(handle, address);
+        }
+        self.execute_inner(
+            &mut ScaledPrecompileHandle {
+                inner: handle,
+                multiplier: self.gas_multiplier,
+            },
+            address,
+        )
+    }
 
     fn is_precompile(&self, address: H160) -> bool {
         // All Ethereum precompiles are zero except for the last byte, which is no more than five.
-        // Otherwise, when confidentiality is enabled, Oasis precompiles start with one and have a last byte of no more than four.
+        // Otherwise, when confidentiality is enabled, Oasis precompiles start with one and have a last byte of no more than nine.
+        // When enabled, consensus staking precompiles start with two and have a last byte of no more than four.
+        // When enabled, ERC-20 facade precompiles start with three, see `erc20::facade_address`.
         let addr_bytes = address.as_bytes();
         let (first, last) = (address[0], addr_bytes[19]);
         (address[1..19].iter().all(|b| *b == 0)
             && matches!(
                 (first, last, Cfg::CONFIDENTIAL),
-                (0, 1..=5, _) | (1, 1..=7, true)
+                (0, 1..=5, _) | (1, 1..=9, true)
             ))
+            || (Cfg::CONSENSUS_PRECOMPILES && consensus::is_consensus_precompile(address))
+            || Self::is_erc20_facade_precompile(address)
             || Cfg::additional_precompiles()
                 .map(|pc| pc.is_precompile(address))
                 .unwrap_or_default()
@@ -14,14 +14,15 @@ use ripemd160::{Digest as _, Ripemd160};
 use sha2::Sha256;
 use sha3::{Digest as _, Keccak256};
 
-use super::{read_input, record_linear_cost, PrecompileResult};
+use crate::PrecompileGasCosts;
 
-/// Minimum gas cost of ModExp contract from eip-2565
-/// https://eips.ethereum.org/EIPS/eip-2565
-const MIN_GAS_COST: u64 = 200;
+use super::{read_input, record_linear_cost, PrecompileResult};
 
-pub(super) fn call_ecrecover(handle: &mut impl PrecompileHandle) -> PrecompileResult {
-    record_linear_cost(handle, handle.input().len() as u64, 3000, 0)?;
+pub(super) fn call_ecrecover(
+    handle: &mut impl PrecompileHandle,
+    costs: &PrecompileGasCosts,
+) -> PrecompileResult {
+    record_linear_cost(handle, handle.input().len() as u64, costs.ecrecover, 0)?;
 
     // Make right padding for input.
     let input = handle.input();
@@ -97,8 +98,16 @@ pub(super) fn call_ecrecover(handle: &mut impl PrecompileHandle) -> PrecompileRe
     })
 }
 
-pub(super) fn call_sha256(handle: &mut impl PrecompileHandle) -> PrecompileResult {
-    record_linear_cost(handle, handle.input().len() as u64, 60, 12)?;
+pub(super) fn call_sha256(
+    handle: &mut impl PrecompileHandle,
+    costs: &PrecompileGasCosts,
+) -> PrecompileResult {
+    record_linear_cost(
+        handle,
+        handle.input().len() as u64,
+        costs.sha256_base,
+        costs.sha256_word,
+    )?;
 
     let mut hasher = Sha256::new();
     hasher.update(handle.input());
@@ -110,8 +119,16 @@ pub(super) fn call_sha256(handle: &mut impl PrecompileHandle) -> PrecompileResul
     })
 }
 
-pub(super) fn call_ripemd160(handle: &mut impl PrecompileHandle) -> PrecompileResult {
-    record_linear_cost(handle, handle.input().len() as u64, 600, 120)?;
+pub(super) fn call_ripemd160(
+    handle: &mut impl PrecompileHandle,
+    costs: &PrecompileGasCosts,
+) -> PrecompileResult {
+    record_linear_cost(
+        handle,
+        handle.input().len() as u64,
+        costs.ripemd160_base,
+        costs.ripemd160_word,
+    )?;
 
     let mut hasher = Ripemd160::new();
     hasher.update(handle.input());
@@ -124,8 +141,16 @@ pub(super) fn call_ripemd160(handle: &mut impl PrecompileHandle) -> PrecompileRe
     })
 }
 
-pub(super) fn call_datacopy(handle: &mut impl PrecompileHandle) -> PrecompileResult {
-    record_linear_cost(handle, handle.input().len() as u64, 15, 3)?;
+pub(super) fn call_datacopy(
+    handle: &mut impl PrecompileHandle,
+    costs: &PrecompileGasCosts,
+) -> PrecompileResult {
+    record_linear_cost(
+        handle,
+        handle.input().len() as u64,
+        costs.identity_base,
+        costs.identity_word,
+    )?;
 
     Ok(PrecompileOutput {
         exit_status: ExitSucceed::Returned,
@@ -133,7 +158,10 @@ pub(super) fn call_datacopy(handle: &mut impl PrecompileHandle) -> PrecompileRes
     })
 }
 
-pub(super) fn call_bigmodexp(handle: &mut impl PrecompileHandle) -> PrecompileResult {
+pub(super) fn call_bigmodexp(
+    handle: &mut impl PrecompileHandle,
+    costs: &PrecompileGasCosts,
+) -> PrecompileResult {
     let input = handle.input();
     if input.len() < 96 {
         return Err(PrecompileFailure::Error {
@@ -184,7 +212,7 @@ pub(super) fn call_bigmodexp(handle: &mut impl PrecompileHandle) -> PrecompileRe
 
     // Gas formula allows arbitrary large exp_len when base and modulus are empty, so we need to handle empty base first.
     let (r, gas_cost) = if base_len == 0 && mod_len == 0 {
-        (BigUint::zero(), MIN_GAS_COST)
+        (BigUint::zero(), costs.modexp_min)
     } else {
         // read the numbers themselves.
         let base_start = 96; // previous 3 32-byte fields
@@ -195,8 +223,13 @@ pub(super) fn call_bigmodexp(handle: &mut impl PrecompileHandle) -> PrecompileRe
 
         // do our gas accounting
         // TODO: we could technically avoid reading base first...
-        let gas_cost =
-            calculate_modexp_gas_cost(base_len as u64, exp_len as u64, mod_len as u64, &exponent)?;
+        let gas_cost = calculate_modexp_gas_cost(
+            base_len as u64,
+            exp_len as u64,
+            mod_len as u64,
+            &exponent,
+            costs,
+        )?;
 
             if let Some(gas_limit) = handle.gas_limit() {
                 if gas_limit < gas_cost {
@@ -283,17 +316,18 @@ fn calculate_modexp_gas_cost(
     exp_length: u64,
     mod_length: u64,
     exponent: &BigUint,
+    costs: &PrecompileGasCosts,
 ) -> Result<u64, PrecompileFailure> {
     let multiplication_complexity = calculate_multiplication_complexity(base_length, mod_length)?;
     let iteration_count = calculate_iteration_count(exp_length, exponent);
     let gas = max(
-        MIN_GAS_COST,
+        costs.modexp_min,
         multiplication_complexity
             .checked_mul(iteration_count)
             .ok_or(PrecompileFailure::Error {
                 exit_status: ExitError::OutOfGas,
             })?
-            / 3,
+            / costs.modexp_divisor,
     );
 
     Ok(gas)
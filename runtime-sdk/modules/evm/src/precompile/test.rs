@@ -1,4 +1,5 @@
 use evm::{
+    backend::Log,
     executor::stack::{PrecompileHandle, PrecompileSet},
     Context, ExitError, ExitReason, Transfer,
 };
@@ -19,6 +20,31 @@ impl crate::Config for TestConfig {
     const TOKEN_DENOMINATION: Denomination = Denomination::NATIVE;
 
     const CONFIDENTIAL: bool = true;
+
+    const CONSENSUS_PRECOMPILES: bool = true;
+
+    const ERC20_FACADE_PRECOMPILES: bool = true;
+}
+
+/// Round from which the scheduled precompile of [`ScheduledTestConfig`] is active.
+const SCHEDULED_PRECOMPILE_ROUND: u64 = 10;
+
+struct ScheduledTestConfig;
+
+impl crate::Config for ScheduledTestConfig {
+    type Accounts = Module;
+    type AdditionalPrecompileSet = ();
+    const CHAIN_ID: u64 = 0;
+
+    const TOKEN_DENOMINATION: Denomination = Denomination::NATIVE;
+
+    fn precompile_activation_round(address: H160) -> Option<u64> {
+        if address == H160::from_low_u64_be(2) {
+            Some(SCHEDULED_PRECOMPILE_ROUND)
+        } else {
+            None
+        }
+    }
 }
 
 struct MockBackend;
@@ -29,6 +55,28 @@ impl crate::backend::EVMBackendExt for MockBackend {
             .chain((pers.len()..(num_bytes as usize)).map(|i| i as u8))
             .collect()
     }
+
+    fn reserve_message_slot(&self) -> bool {
+        true
+    }
+
+    fn contract_sealing_key(&self, address: H160) -> Option<[u8; 32]> {
+        let mut key = [0u8; 32];
+        key[..20].copy_from_slice(address.as_bytes());
+        Some(key)
+    }
+
+    fn token_balance(&self, address: H160, _denomination: &Denomination) -> u128 {
+        address.to_low_u64_be().into()
+    }
+
+    fn token_total_supply(&self, _denomination: &Denomination) -> u128 {
+        1_000_000
+    }
+
+    fn token_decimals(&self, _denomination: &Denomination) -> u8 {
+        18
+    }
 }
 
 struct MockPrecompileHandle<'a> {
@@ -37,6 +85,7 @@ struct MockPrecompileHandle<'a> {
     context: &'a Context,
     gas_limit: u64,
     gas_cost: u64,
+    logs: Vec<Log>,
 }
 
 impl<'a> PrecompileHandle for MockPrecompileHandle<'a> {
@@ -65,7 +114,12 @@ impl<'a> PrecompileHandle for MockPrecompileHandle<'a> {
         self.gas_limit.saturating_sub(self.gas_cost)
     }
 
-    fn log(&mut self, _address: H160, _topics: Vec<H256>, _data: Vec<u8>) -> Result<(), ExitError> {
+    fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError> {
+        self.logs.push(Log {
+            address,
+            topics,
+            data,
+        });
         Ok(())
     }
 
@@ -93,17 +147,154 @@ impl<'a> PrecompileHandle for MockPrecompileHandle<'a> {
 
 pub fn call_contract(address: H160, input: &[u8], gas_limit: u64) -> Option<PrecompileResult> {
     let context: Context = Context {
-        address: Default::default(),
+        address,
         caller: Default::default(),
         apparent_value: From::from(0),
     };
+    call_contract_with_context(address, input, gas_limit, &context).0
+}
+
+/// Calls the precompile at the given address in the given execution context, returning the
+/// result together with the logs emitted by the precompile.
+pub fn call_contract_with_context(
+    address: H160,
+    input: &[u8],
+    gas_limit: u64,
+    context: &Context,
+) -> (Option<PrecompileResult>, Vec<Log>) {
     let precompiles: Precompiles<'_, TestConfig, MockBackend> = Precompiles::new(&MockBackend);
     let mut handle = MockPrecompileHandle {
         address,
         input,
-        context: &context,
+        context,
         gas_limit,
         gas_cost: 0,
+        logs: vec![],
     };
-    precompiles.execute(&mut handle)
+    let result = precompiles.execute(&mut handle);
+    (result, handle.logs)
+}
+
+#[test]
+fn test_scaled_precompile_handle() {
+    let context = Context {
+        address: H160::zero(),
+        caller: Default::default(),
+        apparent_value: From::from(0),
+    };
+    let mut handle = MockPrecompileHandle {
+        address: H160::zero(),
+        input: &[],
+        context: &context,
+        gas_limit: 1_000,
+        gas_cost: 0,
+        logs: vec![],
+    };
+    let mut scaled = super::ScaledPrecompileHandle {
+        inner: &mut handle,
+        multiplier: 150,
+    };
+
+    scaled
+        .record_cost(100)
+        .expect("recording cost should succeed");
+    assert_eq!(scaled.remaining_gas(), 850);
+    // Scaled costs are checked against the gas remaining in the inner handle.
+    assert!(matches!(scaled.record_cost(600), Err(ExitError::OutOfGas)));
+    assert!(matches!(
+        scaled.record_cost(u64::MAX),
+        Err(ExitError::OutOfGas)
+    ));
+    assert_eq!(handle.gas_cost, 150);
+}
+
+#[test]
+fn test_precompile_gas_multiplier() {
+    let address = H160::from_low_u64_be(2);
+    let context = Context {
+        address,
+        caller: Default::default(),
+        apparent_value: From::from(0),
+    };
+
+    // The sha256 precompile charges 60 gas for an empty input by default.
+    for (multiplier, expected) in [(0, 60), (50, 30), (200, 120)] {
+        let precompiles: Precompiles<'_, TestConfig, MockBackend> =
+            Precompiles::new(&MockBackend).with_gas_multiplier(multiplier);
+        let mut handle = MockPrecompileHandle {
+            address,
+            input: &[],
+            context: &context,
+            gas_limit: 1_000,
+            gas_cost: 0,
+            logs: vec![],
+        };
+        precompiles
+            .execute(&mut handle)
+            .unwrap()
+            .expect("call should succeed");
+        assert_eq!(
+            handle.gas_cost, expected,
+            "gas costs should be scaled by the multiplier"
+        );
+    }
+}
+
+#[test]
+fn test_precompile_activation_round() {
+    let scheduled = H160::from_low_u64_be(2);
+    let precompiles: Precompiles<'_, ScheduledTestConfig, MockBackend> =
+        Precompiles::new(&MockBackend).with_round(SCHEDULED_PRECOMPILE_ROUND - 1);
+    assert!(
+        !precompiles.is_precompile(scheduled),
+        "precompile should not be active before its activation round"
+    );
+    assert!(precompiles.is_precompile(H160::from_low_u64_be(1)));
+
+    let precompiles: Precompiles<'_, ScheduledTestConfig, MockBackend> =
+        Precompiles::new(&MockBackend).with_round(SCHEDULED_PRECOMPILE_ROUND);
+    assert!(precompiles.is_precompile(scheduled));
+
+    let precompiles: Precompiles<'_, ScheduledTestConfig, MockBackend> =
+        Precompiles::new(&MockBackend);
+    assert!(
+        precompiles.is_precompile(scheduled),
+        "precompiles should be active when the round is unknown"
+    );
+}
+
+#[test]
+fn test_precompile_gas_costs() {
+    let context: Context = Context {
+        address: Default::default(),
+        caller: Default::default(),
+        apparent_value: From::from(0),
+    };
+    let input = [0u8; 40];
+    let used_gas = |precompiles: &Precompiles<'_, TestConfig, MockBackend>| {
+        let mut handle = MockPrecompileHandle {
+            address: H160::from_low_u64_be(2),
+            input: &input,
+            context: &context,
+            gas_limit: 1_000_000,
+            gas_cost: 0,
+            logs: vec![],
+        };
+        precompiles
+            .execute(&mut handle)
+            .expect("call should return something")
+            .expect("call should succeed");
+        handle.gas_cost
+    };
+
+    // Two words of input at the default costs of 60 + 12 per word.
+    let precompiles: Precompiles<'_, TestConfig, MockBackend> = Precompiles::new(&MockBackend);
+    assert_eq!(used_gas(&precompiles), 84);
+
+    let precompiles: Precompiles<'_, TestConfig, MockBackend> =
+        Precompiles::new(&MockBackend).with_gas_costs(&crate::PrecompileGasCosts {
+            sha256_word: 100,
+            ..Default::default()
+        });
+    assert_eq!(used_gas(&precompiles), 260, "unset costs should use the defaults");
 }
@@ -6,10 +6,11 @@ use k256::elliptic_curve::scalar::IsHigh;
 
 use oasis_runtime_sdk::{
     crypto::signature,
+    modules::core::Error as CoreError,
     types::{address, token, transaction},
 };
 
-use crate::types;
+use crate::{types, Error};
 use lazy_static::lazy_static;
 use lru::LruCache;
 use parking_lot::Mutex;
@@ -61,9 +62,33 @@ pub fn recover_low(
     Ok(verifying_key)
 }
 
+/// Highest type of an EIP-2718 typed transaction envelope. Legacy transactions are RLP lists and
+/// their encoding starts with a byte of at least 0xc0.
+const MAX_TX_TYPE: u8 = 0x7f;
+
+/// Types of the typed transaction envelopes that can be decoded (EIP-2930 and EIP-1559).
+const SUPPORTED_TX_TYPES: &[u8] = &[1, 2];
+
 pub fn decode(
     body: &[u8],
     expected_chain_id: Option<u64>,
+) -> Result<transaction::Transaction, Error> {
+    // Reject typed envelopes that are well-formed but unsupported (e.g. EIP-4844 blob
+    // transactions) with a dedicated error so that clients can tell them apart from garbage.
+    if let Some(&tx_type) = body
+        .first()
+        .filter(|&&ty| ty <= MAX_TX_TYPE && !SUPPORTED_TX_TYPES.contains(&ty))
+    {
+        return Err(Error::UnsupportedTxType(tx_type));
+    }
+
+    decode_supported(body, expected_chain_id)
+        .map_err(|err| CoreError::MalformedTransaction(err).into())
+}
+
+fn decode_supported(
+    body: &[u8],
+    expected_chain_id: Option<u64>,
 ) -> Result<transaction::Transaction, anyhow::Error> {
     let (
         chain_id,
@@ -76,6 +101,7 @@ pub fn decode(
         eth_nonce,
         eth_gas_price,
         eth_gas_limit,
+        eth_access_list,
     ) = match ethereum::TransactionV2::decode(body)
             .map_err(|_| anyhow!("decoding transaction rlp"))?
     {
@@ -100,6 +126,7 @@ pub fn decode(
                 message.nonce,
                 message.gas_price,
                 message.gas_limit,
+                vec![],
             )
         }
         ethereum::TransactionV2::EIP2930(eth_tx) => {
@@ -121,6 +148,7 @@ pub fn decode(
                 message.nonce,
                 message.gas_price,
                 message.gas_limit,
+                message.access_list,
             )
         }
         ethereum::TransactionV2::EIP1559(eth_tx) => {
@@ -146,6 +174,7 @@ pub fn decode(
                 message.nonce,
                 resolved_gas_price,
                 message.gas_limit,
+                message.access_list,
             )
         }
     };
@@ -156,6 +185,13 @@ pub fn decode(
             expected_chain_id
         ));
     }
+    let access_list: Vec<types::AccessListItem> = eth_access_list
+        .into_iter()
+        .map(|item| types::AccessListItem {
+            address: item.address.into(),
+            storage_keys: item.storage_keys.into_iter().map(Into::into).collect(),
+        })
+        .collect();
     let (method, body) = match eth_action {
         ethereum::TransactionAction::Call(eth_address) => (
             "evm.Call",
@@ -163,6 +199,9 @@ pub fn decode(
                 address: eth_address.into(),
                 value: eth_value.into(),
                 data: eth_input,
+                access_list,
+                // Ethereum transactions can not opt into consensus deposits.
+                max_consensus_deposit: 0,
             }),
         ),
         ethereum::TransactionAction::Create => (
@@ -170,6 +209,7 @@ pub fn decode(
             cbor::to_value(types::Create {
                 value: eth_value.into(),
                 init_code: eth_input,
+                access_list,
             }),
         ),
     };
@@ -222,9 +262,9 @@ mod test {
 
     use hex::FromHex as _;
 
-    use oasis_runtime_sdk::types::token;
+    use oasis_runtime_sdk::{modules::core::Error as CoreError, types::token};
 
-    use crate::{derive_caller, types};
+    use crate::{derive_caller, types, Error};
 
     use super::decode;
 
@@ -407,4 +447,24 @@ mod test {
             "cd2a3d9f938e13cd947ec05abc7fe734df8dd826",
         );
     }
+
+    #[test]
+    fn test_decode_unsupported_type() {
+        // Truncated EIP-4844 blob transaction envelope.
+        assert!(matches!(
+            decode(&Vec::from_hex("03f8").unwrap(), None),
+            Err(Error::UnsupportedTxType(3))
+        ));
+        assert!(matches!(decode(&[0x7f], None), Err(Error::UnsupportedTxType(0x7f))));
+
+        // Supported but malformed envelopes are still reported as malformed transactions.
+        assert!(matches!(
+            decode(&Vec::from_hex("02f8").unwrap(), None),
+            Err(Error::Core(CoreError::MalformedTransaction(_)))
+        ));
+        assert!(matches!(
+            decode(&[], None),
+            Err(Error::Core(CoreError::MalformedTransaction(_)))
+        ));
+    }
 }
@@ -1,7 +1,6 @@
 use std::convert::TryFrom as _;
 
 use ethabi::Token;
-use once_cell::sync::OnceCell;
 use sha3::{Digest as _, Keccak256};
 
 use oasis_runtime_sdk::{
@@ -49,6 +48,19 @@ pub(crate) fn verify<C: Context, Cfg: Config>(
         return Err(Error::InvalidSignedSimulateCall("stale nonce"));
     }
 
+    // Once the signer has advanced its query nonce, signed queries must carry a query nonce that
+    // is not lower than the current one. This lets signers revoke all previously signed queries
+    // at once, but does not prevent replays of queries that have not been revoked: queries can
+    // not modify state, so the query nonce can not be consumed when a query is made.
+    let current_query_nonce: u64 = state::query_nonces(&mut state)
+        .get(query.caller)
+        .unwrap_or_default();
+    match leash.query_nonce {
+        Some(query_nonce) if query_nonce >= current_query_nonce => {}
+        None if current_query_nonce == 0 => {}
+        _ => return Err(Error::SignedQueryRevoked(current_query_nonce)),
+    }
+
     let block_hashes = state::block_hashes(state);
     let base_block_hash = match block_hashes.get::<_, Hash>(&leash.block_number.to_be_bytes()) {
         Some(hash) => hash,
@@ -72,6 +84,9 @@ pub(crate) fn verify<C: Context, Cfg: Config>(
 
 macro_rules! leash_type_str {
     () => {
+        leash_type_str!("")
+    };
+    ($extra_members:literal) => {
         concat!(
             "Leash",
             "(",
@@ -79,37 +94,47 @@ macro_rules! leash_type_str {
             ",uint64 blockNumber",
             ",bytes32 blockHash",
             ",uint64 blockRange",
+            $extra_members,
             ")",
         )
     };
 }
 
+macro_rules! call_type_str {
+    ($($leash_type_str:tt)+) => {
+        concat!(
+            "Call",
+            "(",
+            "address from",
+            ",address to",
+            ",uint64 gasLimit",
+            ",uint256 gasPrice",
+            ",uint256 value",
+            ",bytes data",
+            ",Leash leash",
+            ")",
+            $($leash_type_str)+
+        )
+    };
+}
+
 fn hash_call_toplevel<Cfg: Config>(query: &SimulateCallQuery, leash: &Leash) -> [u8; 32] {
     let call_struct_hash = hash_call(query, leash);
     let domain_separator = hash_domain::<Cfg>();
     let mut encoded_call = [0u8; 66];
     encoded_call[0..2].copy_from_slice(b"\x19\x01");
-    encoded_call[2..34].copy_from_slice(domain_separator);
+    encoded_call[2..34].copy_from_slice(&domain_separator);
     encoded_call[34..].copy_from_slice(&call_struct_hash);
     Keccak256::digest(encoded_call).into()
 }
 
 fn hash_call(query: &SimulateCallQuery, leash: &Leash) -> [u8; 32] {
-    const CALL_TYPE_STR: &str = concat!(
-        "Call",
-        "(",
-        "address from",
-        ",address to",
-        ",uint64 gasLimit",
-        ",uint256 gasPrice",
-        ",uint256 value",
-        ",bytes data",
-        ",Leash leash",
-        ")",
-        leash_type_str!()
-    );
+    let call_type_str = match leash.query_nonce {
+        Some(_) => call_type_str!(leash_type_str!(",uint64 queryNonce")),
+        None => call_type_str!(leash_type_str!()),
+    };
     hash_encoded(&[
-        encode_bytes(CALL_TYPE_STR),
+        encode_bytes(call_type_str),
         Token::Address(query.caller.0.into()),
         Token::Address(query.address.0.into()),
         Token::Uint(query.gas_limit.into()),
@@ -121,26 +146,30 @@ fn hash_call(query: &SimulateCallQuery, leash: &Leash) -> [u8; 32] {
 }
 
 fn hash_leash(leash: &Leash) -> [u8; 32] {
-    hash_encoded(&[
+    let mut tokens = vec![
         encode_bytes(leash_type_str!()),
         Token::Uint(leash.nonce.into()),
         Token::Uint(leash.block_number.into()),
         Token::Uint(leash.block_hash.0.into()),
         Token::Uint(leash.block_range.into()),
-    ])
+    ];
+    if let Some(query_nonce) = leash.query_nonce {
+        tokens[0] = encode_bytes(leash_type_str!(",uint64 queryNonce"));
+        tokens.push(Token::Uint(query_nonce.into()));
+    }
+    hash_encoded(&tokens)
 }
 
-fn hash_domain<Cfg: Config>() -> &'static [u8; 32] {
-    static DOMAIN_SEPARATOR: OnceCell<[u8; 32]> = OnceCell::new(); // Not `Lazy` because of generic.
-    DOMAIN_SEPARATOR.get_or_init(|| {
-        const DOMAIN_TYPE_STR: &str = "EIP712Domain(string name,string version,uint256 chainId)";
-        hash_encoded(&[
-            encode_bytes(DOMAIN_TYPE_STR),
-            encode_bytes("oasis-runtime-sdk/evm: signed query"),
-            encode_bytes("1.0.0"),
-            Token::Uint(Cfg::CHAIN_ID.into()),
-        ])
-    })
+fn hash_domain<Cfg: Config>() -> [u8; 32] {
+    // Not cached in a static since statics are shared between all instantiations of a generic
+    // function, while the domain depends on the configuration.
+    const DOMAIN_TYPE_STR: &str = "EIP712Domain(string name,string version,uint256 chainId)";
+    hash_encoded(&[
+        encode_bytes(DOMAIN_TYPE_STR),
+        encode_bytes(Cfg::SIGNED_QUERY_DOMAIN_NAME),
+        encode_bytes(Cfg::SIGNED_QUERY_DOMAIN_VERSION),
+        Token::Uint(Cfg::CHAIN_ID.into()),
+    ])
 }
 
 fn encode_bytes(s: impl AsRef<[u8]>) -> Token {
@@ -155,7 +184,7 @@ fn hash_encoded(tokens: &[Token]) -> [u8; 32] {
 mod test {
     use super::*;
 
-    use oasis_runtime_sdk::testing::mock;
+    use oasis_runtime_sdk::{testing::mock, types::token::Denomination};
 
     use crate::{
         test::{ConfidentialEVMConfig as C10lCfg, EVMConfig as Cfg},
@@ -163,6 +192,19 @@ mod test {
         Module as EVMModule,
     };
 
+    /// Confidential config presenting its own EIP-712 domain in wallet prompts.
+    struct BrandedC10lCfg;
+
+    impl Config for BrandedC10lCfg {
+        type Accounts = <C10lCfg as Config>::Accounts;
+        type AdditionalPrecompileSet = ();
+        const CHAIN_ID: u64 = C10lCfg::CHAIN_ID;
+        const TOKEN_DENOMINATION: Denomination = C10lCfg::TOKEN_DENOMINATION;
+        const CONFIDENTIAL: bool = true;
+        const SIGNED_QUERY_DOMAIN_NAME: &'static str = "branded: signed query";
+        const SIGNED_QUERY_DOMAIN_VERSION: &'static str = "2.0.0";
+    }
+
     /// This was generated using the `@oasislabs/sapphire-paratime` JS lib.
     const SIGNED_CALL_DATA_PACK: &str =
 "a36464617461a164626f64794401020304656c65617368a4656e6f6e63651903e76a626c6f636b5f686173685820c92b675c7013e33aa88feaae520eb0ede155e7cacb3c4587e0923cba9953f8bb6b626c6f636b5f72616e6765036c626c6f636b5f6e756d626572182a697369676e6174757265584148bca100e84d13a80b131c62b9b87caf07e4da6542a9e1ea16d8042ba08cc1e31f10ae924d8c137882204e9217423194014ce04fa2130c14f27b148858733c7b1c";
@@ -182,6 +224,7 @@ mod test {
                     .unwrap(),
                 value: 42u64.into(),
                 data: cbor::from_value(data_pack.data.body.clone()).unwrap(),
+                round: None,
             },
             data_pack,
         )
@@ -292,6 +335,74 @@ mod test {
         ));
     }
 
+    #[test]
+    fn test_verify_custom_domain() {
+        let (query, data_pack) = make_signed_call();
+        assert_ne!(hash_domain::<BrandedC10lCfg>(), hash_domain::<C10lCfg>());
+
+        let mut mock = mock::Mock::default();
+        mock.runtime_header.round = data_pack.leash.block_number;
+        let mut ctx = mock.create_ctx();
+
+        setup_nonce(&mut ctx, &query.caller, &data_pack.leash);
+        setup_block(&mut ctx, &data_pack.leash);
+
+        // The call was signed over the default domain.
+        assert!(matches!(
+            verify::<_, BrandedC10lCfg>(&mut ctx, query, data_pack.leash, data_pack.signature)
+                .unwrap_err(),
+            Error::InvalidSignedSimulateCall("signer != caller")
+        ));
+    }
+
+    #[test]
+    fn test_verify_query_nonce() {
+        let (query, data_pack) = make_signed_call();
+
+        let mut mock = mock::Mock::default();
+        mock.runtime_header.round = data_pack.leash.block_number;
+        let mut ctx = mock.create_ctx();
+
+        setup_nonce(&mut ctx, &query.caller, &data_pack.leash);
+        setup_block(&mut ctx, &data_pack.leash);
+        state::query_nonces(ctx.runtime_state()).insert(query.caller, 1u64);
+
+        // Queries without a query nonce are rejected once the signer has advanced it.
+        let leash = data_pack.leash.clone();
+        assert!(matches!(
+            verify::<_, C10lCfg>(&mut ctx, query.clone(), leash, data_pack.signature).unwrap_err(),
+            Error::SignedQueryRevoked(1)
+        ));
+
+        let signer = k256::ecdsa::SigningKey::from_bytes(&Keccak256::digest(b"signer")).unwrap();
+        let signer_pk = signer.verifying_key().to_encoded_point(false);
+        let caller = H160::from_slice(&Keccak256::digest(&signer_pk.as_bytes()[1..])[12..]);
+        let query = SimulateCallQuery { caller, ..query };
+
+        setup_nonce(&mut ctx, &caller, &data_pack.leash);
+        state::query_nonces(ctx.runtime_state()).insert(caller, 1u64);
+
+        for (query_nonce, valid) in [(0, false), (1, true), (2, true)] {
+            let leash = Leash {
+                query_nonce: Some(query_nonce),
+                ..data_pack.leash.clone()
+            };
+            let (sig, sig_recid) = signer
+                .sign_prehash_recoverable(&hash_call_toplevel::<C10lCfg>(&query, &leash))
+                .unwrap();
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&sig.to_bytes());
+            signature[64] = sig_recid.to_byte();
+
+            let result = verify::<_, C10lCfg>(&mut ctx, query.clone(), leash, signature);
+            if valid {
+                result.unwrap();
+            } else {
+                assert!(matches!(result, Err(Error::SignedQueryRevoked(1))));
+            }
+        }
+    }
+
     #[test]
     fn test_decode_simulate_call_query() {
         let (unsigned_body, data_pack) = make_signed_call();
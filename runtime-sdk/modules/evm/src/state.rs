@@ -1,6 +1,10 @@
-use crate::types::H160;
+use crate::types::{H160, H256};
 
-use oasis_runtime_sdk::{context::Context, storage};
+use oasis_runtime_sdk::{
+    context::Context,
+    storage::{self, Store as _},
+    types::address::Address,
+};
 
 /// Prefix for Ethereum account code in our storage (maps H160 -> Vec<u8>).
 pub const CODES: &[u8] = &[0x01];
@@ -11,14 +15,27 @@ pub const STORAGES: &[u8] = &[0x02];
 pub const BLOCK_HASHES: &[u8] = &[0x03];
 /// Prefix for Ethereum account storage in our confidential storage (maps H160||H256 -> H256).
 pub const CONFIDENTIAL_STORAGES: &[u8] = &[0x04];
+/// Prefix for the signed query nonces of callers in our storage (maps H160 -> u64).
+pub const QUERY_NONCES: &[u8] = &[0x05];
+/// Prefix for the fee history of recent blocks in our storage (maps Round -> FeeHistoryBlock).
+pub const FEE_HISTORY: &[u8] = &[0x06];
+/// Prefix for the Ethereum addresses of SDK addresses in our storage (maps Address -> H160).
+///
+/// The opposite direction is not stored as it is given by `Config::map_address`.
+pub const ADDRESS_ALIASES: &[u8] = &[0x07];
 
 /// Confidential store key pair ID domain separation context base.
 pub const CONFIDENTIAL_STORE_KEY_PAIR_ID_CONTEXT_BASE: &[u8] = b"oasis-runtime-sdk/evm: state";
+/// Contract sealing key pair ID domain separation context base.
+pub const CONTRACT_SEALING_KEY_PAIR_ID_CONTEXT_BASE: &[u8] = b"oasis-runtime-sdk/evm: sealing";
 const CONTEXT_KEY_CONFIDENTIAL_STORE_INSTANCE_COUNT: &str = "evm.ConfidentialStoreCounter";
 
 /// The number of hash blocks that can be obtained from the current blockchain.
 pub const BLOCK_HASH_WINDOW_SIZE: u64 = 256;
 
+/// The number of recent blocks for which the fee history is kept.
+pub const FEE_HISTORY_WINDOW_SIZE: u64 = 1024;
+
 pub fn public_storage<'a, C: Context>(
     ctx: &'a mut C,
     address: &'a H160,
@@ -80,6 +97,84 @@ fn contract_storage<'a, S: storage::Store + 'a>(
     storage::PrefixStore::new(storages, address)
 }
 
+/// Remove all public and confidential storage of the given contract.
+pub fn remove_contract_storage<S: storage::Store>(mut state: S, address: &H160) {
+    for prefix in [STORAGES, CONFIDENTIAL_STORAGES] {
+        let mut store = contract_storage(&mut state, prefix, address);
+        // Keys are removed as-is, so there is no need to decrypt confidential storage.
+        let keys: Vec<Vec<u8>> = store.iter().map(|(key, _)| key).collect();
+        for key in keys {
+            store.remove(&key);
+        }
+    }
+}
+
+/// Returns the number of public and confidential storage entries of the given contract, counting
+/// at most `limit` entries.
+pub fn contract_storage_entries<S: storage::Store>(
+    mut state: S,
+    address: &H160,
+    limit: u64,
+) -> u64 {
+    let mut count = 0;
+    for prefix in [STORAGES, CONFIDENTIAL_STORAGES] {
+        let store = contract_storage(&mut state, prefix, address);
+        let remaining = usize::try_from(limit - count).unwrap_or(usize::MAX);
+        count += store.iter().take(remaining).count() as u64;
+    }
+    count
+}
+
+/// Returns the raw (possibly encrypted) public and confidential storage entries of the given
+/// contract, together with the prefix of the storage they belong to.
+pub fn raw_contract_storage<S: storage::Store>(
+    mut state: S,
+    address: &H160,
+) -> Vec<(&'static [u8], Vec<u8>, Vec<u8>)> {
+    let mut entries = Vec::new();
+    for prefix in [STORAGES, CONFIDENTIAL_STORAGES] {
+        let store = contract_storage(&mut state, prefix, address);
+        entries.extend(store.iter().map(|(key, value)| (prefix, key, value)));
+    }
+    entries
+}
+
+/// Restores storage entries of the given contract previously returned by
+/// [`raw_contract_storage`].
+pub fn restore_raw_contract_storage<S: storage::Store>(
+    mut state: S,
+    address: &H160,
+    entries: Vec<(&'static [u8], Vec<u8>, Vec<u8>)>,
+) {
+    for (prefix, key, value) in entries {
+        contract_storage(&mut state, prefix, address).insert(&key, &value);
+    }
+}
+
+/// Returns up to `limit` public storage entries of the given contract, starting at the given
+/// (hashed) key, together with the key of the next entry if there are more.
+///
+/// Storage keys are hashed, so the returned keys are the hashes of the storage slots.
+pub fn public_storage_range<S: storage::Store>(
+    state: S,
+    address: &H160,
+    start: &[u8],
+    limit: usize,
+) -> (Vec<(Vec<u8>, H256)>, Option<Vec<u8>>) {
+    let store = contract_storage(state, STORAGES, address);
+    let mut it = store.iter();
+    it.seek(start);
+
+    let mut entries = Vec::new();
+    for (key, value) in it {
+        if entries.len() >= limit {
+            return (entries, Some(key));
+        }
+        entries.push((key, cbor::from_slice(&value).unwrap_or_default()));
+    }
+    (entries, None)
+}
+
 /// Get a typed store for codes of all contracts.
 pub fn codes<'a, S: storage::Store + 'a>(
     state: S,
@@ -95,3 +190,76 @@ pub fn block_hashes<'a, S: storage::Store + 'a>(
     let store = storage::PrefixStore::new(state, &crate::MODULE_NAME);
     storage::TypedStore::new(storage::PrefixStore::new(store, &BLOCK_HASHES))
 }
+
+/// Get a typed store for the signed query nonces of callers.
+pub fn query_nonces<'a, S: storage::Store + 'a>(
+    state: S,
+) -> storage::TypedStore<impl storage::Store + 'a> {
+    let store = storage::PrefixStore::new(state, &crate::MODULE_NAME);
+    storage::TypedStore::new(storage::PrefixStore::new(store, &QUERY_NONCES))
+}
+
+/// Get a typed store for the fee history of recent blocks.
+pub fn fee_history<'a, S: storage::Store + 'a>(
+    state: S,
+) -> storage::TypedStore<impl storage::Store + 'a> {
+    let store = storage::PrefixStore::new(state, &crate::MODULE_NAME);
+    storage::TypedStore::new(storage::PrefixStore::new(store, &FEE_HISTORY))
+}
+
+/// Get a typed store for the Ethereum addresses of SDK addresses.
+pub fn address_aliases<'a, S: storage::Store + 'a>(
+    state: S,
+) -> storage::TypedStore<impl storage::Store + 'a> {
+    let store = storage::PrefixStore::new(state, &crate::MODULE_NAME);
+    storage::TypedStore::new(storage::PrefixStore::new(store, &ADDRESS_ALIASES))
+}
+
+/// Whether the Ethereum address of the given SDK address is already known.
+pub fn has_address_alias<S: storage::Store>(state: S, address: Address) -> bool {
+    address_aliases(state).get::<_, H160>(address).is_some()
+}
+
+/// Records that the given Ethereum address maps to the given SDK address, unless already known.
+///
+/// Returns whether a new alias has been recorded.
+pub fn register_address_alias<S: storage::Store>(
+    mut state: S,
+    eth_address: H160,
+    address: Address,
+) -> bool {
+    if has_address_alias(&mut state, address) {
+        return false;
+    }
+    address_aliases(state).insert(address, eth_address);
+    true
+}
+
+/// Returns the addresses of all accounts with deployed code.
+pub fn code_addresses<S: storage::Store>(state: S) -> Vec<H160> {
+    let store = storage::PrefixStore::new(state, &crate::MODULE_NAME);
+    let codes = storage::PrefixStore::new(store, &CODES);
+    codes
+        .iter()
+        .filter_map(|(key, _)| H160::try_from(key.as_slice()).ok())
+        .collect()
+}
+
+fn stored_rounds<S: storage::Store>(state: S, prefix: &[u8]) -> Vec<u64> {
+    let store = storage::PrefixStore::new(state, &crate::MODULE_NAME);
+    let rounds = storage::PrefixStore::new(store, prefix);
+    rounds
+        .iter()
+        .filter_map(|(key, _)| Some(u64::from_be_bytes(key.try_into().ok()?)))
+        .collect()
+}
+
+/// Returns the rounds of all stored historic block hashes.
+pub fn block_hash_rounds<S: storage::Store>(state: S) -> Vec<u64> {
+    stored_rounds(state, BLOCK_HASHES)
+}
+
+/// Returns the rounds of all blocks in the stored fee history.
+pub fn fee_history_rounds<S: storage::Store>(state: S) -> Vec<u64> {
+    stored_rounds(state, FEE_HISTORY)
+}
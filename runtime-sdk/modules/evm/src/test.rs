@@ -13,15 +13,20 @@ use oasis_runtime_sdk::{
         accounts::{self, Module as Accounts},
         core::{self, Module as Core},
     },
+    storage,
     testing::{keys, mock},
-    types::{address::SignatureAddressSpec, token::Denomination, transaction},
+    types::{
+        address::{Address, SignatureAddressSpec},
+        token::{self, Denomination},
+        transaction,
+    },
     BatchContext, Context, Runtime, Version,
 };
 
 use crate::{
     derive_caller, process_evm_result,
     types::{self, H160},
-    Config, Error, Genesis, Module as EVMModule,
+    Config, Error, Genesis, Module as EVMModule, API as _,
 };
 
 /// Test contract code.
@@ -96,6 +101,40 @@ fn test_evm_caller_addr_derivation() {
     assert_eq!(derived, expected);
 }
 
+#[test]
+fn test_evm_caller_addr_derivation_native() {
+    let derived = derive_caller::from_sigspec(&keys::alice::sigspec()).unwrap();
+    assert_eq!(derived, derive_caller::from_sdk_address(&keys::alice::address()));
+    assert_ne!(derived, derive_caller::from_sigspec(&keys::bob::sigspec()).unwrap());
+    assert!(
+        derive_caller::from_sigspec(&keys::frank::sigspec()).is_err(),
+        "sr25519 signers should be rejected"
+    );
+
+    let config = crypto::multisig::Config {
+        signers: vec![crypto::multisig::Signer {
+            public_key: keys::bob::pk(),
+            weight: 1,
+        }],
+        threshold: 1,
+    };
+    let derived =
+        derive_caller::from_address_spec(&transaction::AddressSpec::Multisig(config.clone()))
+            .unwrap();
+    assert_eq!(derived, derive_caller::from_sdk_address(&Address::from_multisig(config)));
+
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+    let queried = EVMModule::<EVMConfig>::query_derive_caller(
+        &mut ctx,
+        types::DeriveCallerQuery {
+            address_spec: transaction::AddressSpec::Signature(keys::alice::sigspec()),
+        },
+    )
+    .unwrap();
+    assert_eq!(queried, derive_caller::from_sigspec(&keys::alice::sigspec()).unwrap());
+}
+
 fn do_test_evm_calls<C: Config>(force_plain: bool) {
     let mut mock = mock::Mock::default();
     let mut ctx = mock.create_ctx();
@@ -183,6 +222,7 @@ fn do_test_evm_calls<C: Config>(force_plain: bool) {
         &mut ctx,
         Genesis {
             parameters: Default::default(),
+            ..Default::default()
         },
     );
 
@@ -197,6 +237,7 @@ fn do_test_evm_calls<C: Config>(force_plain: bool) {
             body: cbor::to_value(types::Create {
                 value: 0.into(),
                 init_code: encode_data!(erc20),
+                ..Default::default()
             }),
             ..Default::default()
         },
@@ -235,7 +276,8 @@ fn do_test_evm_calls<C: Config>(force_plain: bool) {
             body: cbor::to_value(types::Call {
                 address: erc20_addr,
                 value: 0.into(),
-                data: encode_data!(name_method),
+                data: encode_data!(name_method.clone()),
+                ..Default::default()
             }),
             ..Default::default()
         },
@@ -274,6 +316,103 @@ fn do_test_evm_calls<C: Config>(force_plain: bool) {
     assert_eq!(erc20_name.len(), 96);
     assert_eq!(erc20_name[63], 0x04); // Name is 4 bytes long.
     assert_eq!(erc20_name[64..68], vec![0x54, 0x65, 0x73, 0x74]); // "Test".
+
+    // Test gas estimation of the same call.
+    let gas = EVMModule::<C>::estimate_gas(
+        &mut ctx,
+        types::EstimateGasQuery {
+            caller: derive_caller::from_sigspec(&keys::dave::sigspec()).unwrap(),
+            address: Some(erc20_addr),
+            data: name_method.clone(),
+            ..Default::default()
+        },
+    )
+    .expect("gas estimation should succeed");
+    assert!(gas > 21_000, "estimate should include the intrinsic gas");
+
+    // Test the structured call result.
+    if !C::CONFIDENTIAL {
+        let call_name_tx = transaction::Transaction {
+            version: 1,
+            call: transaction::Call {
+                format: transaction::CallFormat::Plain,
+                method: "evm.CallV2".to_owned(),
+                body: cbor::to_value(types::Call {
+                    address: erc20_addr,
+                    value: 0.into(),
+                    data: name_method.clone(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            auth_info: transaction::AuthInfo {
+                signer_info: vec![transaction::SignerInfo::new_sigspec(
+                    keys::dave::sigspec(),
+                    2,
+                )],
+                fee: transaction::Fee {
+                    amount: Default::default(),
+                    gas: 25000,
+                    consensus_messages: 0,
+                },
+                ..Default::default()
+            },
+        };
+        Accounts::authenticate_tx(&mut ctx, &call_name_tx).unwrap();
+
+        let output = ctx.with_tx(0, 0, call_name_tx, |mut tx_ctx, call| {
+            EVMModule::<C>::tx_call_v2(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("call should succeed")
+        });
+        assert_eq!(output.data, erc20_name);
+        assert!(output.gas_used > 21_000, "gas used should include the intrinsic gas");
+        assert_eq!(output.logs, 0);
+
+        // Test aggregating calls.
+        let caller = derive_caller::from_sigspec(&keys::dave::sigspec()).unwrap();
+        let name_call = types::MulticallCall {
+            address: erc20_addr,
+            data: name_method,
+            ..Default::default()
+        };
+        let bad_call = types::MulticallCall {
+            address: erc20_addr,
+            data: vec![0xde, 0xad, 0xbe, 0xef],
+            allow_failure: true,
+            ..Default::default()
+        };
+        let results = EVMModule::<C>::multicall(
+            &mut ctx,
+            types::MulticallQuery {
+                gas_limit: 100_000,
+                caller,
+                calls: vec![name_call.clone(), bad_call.clone(), name_call],
+                ..Default::default()
+            },
+        )
+        .expect("multicall should succeed");
+        assert_eq!(results.len(), 3);
+        assert!(results[0].success);
+        assert_eq!(results[0].data, erc20_name);
+        assert!(!results[1].success);
+        assert!(results[1].error.is_some());
+        assert!(results[2].success);
+        assert_eq!(results[2].data, erc20_name);
+
+        EVMModule::<C>::multicall(
+            &mut ctx,
+            types::MulticallQuery {
+                gas_limit: 100_000,
+                caller,
+                calls: vec![types::MulticallCall {
+                    allow_failure: false,
+                    ..bad_call
+                }],
+                ..Default::default()
+            },
+        )
+        .expect_err("multicall should fail when a call that does not allow failure fails");
+    }
 }
 
 #[test]
@@ -325,6 +464,7 @@ fn test_c10l_evm_balance_transfer() {
         &mut ctx,
         Genesis {
             parameters: Default::default(),
+            ..Default::default()
         },
     );
 
@@ -338,6 +478,7 @@ fn test_c10l_evm_balance_transfer() {
                 address: recipient.into(),
                 value: 12345u64.into(),
                 data: vec![],
+                ..Default::default()
             }),
             ..Default::default()
         },
@@ -411,6 +552,8 @@ impl<C: Config> Runtime for EVMRuntime<C> {
 
     type Core = Core<CoreConfig>;
 
+    type TransferHooks = ();
+
     type Modules = (Core<CoreConfig>, Accounts, EVMModule<C>);
 
     fn genesis_state() -> <Self::Modules as module::MigrationHandler>::Genesis {
@@ -441,6 +584,7 @@ impl<C: Config> Runtime for EVMRuntime<C> {
             },
             Genesis {
                 parameters: Default::default(),
+                ..Default::default()
             },
         )
     }
@@ -511,6 +655,7 @@ fn do_test_evm_runtime<C: Config>() {
             body: cbor::to_value(types::Create {
                 value: 0.into(),
                 init_code: encode_data!(erc20.clone()),
+                ..Default::default()
             }),
             ..Default::default()
         },
@@ -548,6 +693,7 @@ fn do_test_evm_runtime<C: Config>() {
             body: cbor::to_value(types::Create {
                 value: 0.into(),
                 init_code: encode_data!(erc20),
+                ..Default::default()
             }),
             ..Default::default()
         },
@@ -600,6 +746,7 @@ fn do_test_evm_runtime<C: Config>() {
                 address: erc20_addr,
                 value: 0.into(),
                 data: encode_data!(name_method),
+                ..Default::default()
             }),
             ..Default::default()
         },
@@ -681,6 +828,7 @@ fn do_test_evm_runtime<C: Config>() {
                 address: erc20_addr,
                 value: 0.into(),
                 data: encode_data!(transfer_method.clone()),
+                ..Default::default()
             }),
             ..Default::default()
         },
@@ -731,6 +879,7 @@ fn do_test_evm_runtime<C: Config>() {
                 address: erc20_addr,
                 value: 0.into(),
                 data: encode_data!(transfer_method),
+                ..Default::default()
             }),
             ..Default::default()
         },
@@ -825,18 +974,26 @@ fn test_revert_reason_decoding() {
         (long_reason_hex, &long_reason_str[..1024]),
         // No revert reason.
         ("", "no revert reason"),
-        // Malformed output, incorrect selector and bad length.
+        // Panic codes.
         (
-            "BADBADBADBADBADBAD",
-            "invalid reason prefix: 'utututututut'",
+            "4e487b71\
+            0000000000000000000000000000000000000000000000000000000000000011",
+            "panic: arithmetic overflow or underflow (0x11)",
         ),
-        // Malformed output, bad selector.
         (
-            "BAAAAAAD\
-            0000000000000000000000000000000000000000000000000000000000000020\
-            0000000000000000000000000000000000000000000000000000000000000018\
-            4461692f696e73756666696369656e742d62616c616e63650000000000000000",
-            "invalid reason prefix: 'uqqqrQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABhEYWkvaW5zdWZmaWNpZW50LWJhbGFuY2UAAAAAAAAAAA=='",
+            "4e487b71\
+            0000000000000000000000000000000000000000000000000000000000000012",
+            "panic: division or modulo by zero (0x12)",
+        ),
+        (
+            "4e487b71\
+            0000000000000000000000000000000000000000000000000000000000000032",
+            "panic: array index out of bounds (0x32)",
+        ),
+        (
+            "4e487b71\
+            00000000000000000000000000000000000000000000000000000000000000ff",
+            "panic: unknown panic code (0xff)",
         ),
         // Malformed output, corrupted length.
         (
@@ -859,4 +1016,1617 @@ fn test_revert_reason_decoding() {
             _ => panic!("expected Error::Reverted(_) variant"),
         }
     }
+
+    // Payloads that are not `Error(string)` are returned as raw data.
+    let tcs = vec![
+        // Malformed output, incorrect selector and bad length.
+        ("BADBADBADBADBADBAD", "utututututut"),
+        // Custom error without arguments, only the 4-byte selector.
+        ("cf479181", "Z0eRgQ=="),
+        // Custom error, e.g. `InsufficientBalance(uint256)`.
+        (
+            "cf479181\
+            0000000000000000000000000000000000000000000000000000000000000064",
+            "Z0eRgQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABk",
+        ),
+    ];
+
+    for tc in tcs {
+        let raw = hex::decode(tc.0).unwrap();
+        let err = process_evm_result(evm::ExitReason::Revert(evm::ExitRevert::Reverted), raw)
+            .unwrap_err();
+        match err {
+            Error::RevertedWithData(data) => {
+                assert_eq!(&data, tc.1, "raw revert data should be preserved");
+            }
+            _ => panic!("expected Error::RevertedWithData(_) variant"),
+        }
+    }
+}
+
+#[test]
+fn test_gas_costs_create() {
+    // Init code deploying ten zero bytes of code.
+    const INIT_CODE: &str = "600a600c600039600a6000f300000000000000000000";
+
+    fn create_gas_used(gas_costs: crate::GasCosts) -> u64 {
+        let mut mock = mock::Mock::default();
+        let mut ctx = mock.create_ctx();
+
+        Core::<CoreConfig>::init(
+            &mut ctx,
+            core::Genesis {
+                parameters: core::Parameters {
+                    max_batch_gas: 10_000_000,
+                    ..Default::default()
+                },
+            },
+        );
+
+        Accounts::init(
+            &mut ctx,
+            accounts::Genesis {
+                balances: BTreeMap::from([(
+                    keys::dave::address(),
+                    BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+                )]),
+                total_supplies: BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+                ..Default::default()
+            },
+        );
+
+        EVMModule::<EVMConfig>::init(
+            &mut ctx,
+            Genesis {
+                parameters: crate::Parameters {
+                    gas_costs,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let tx = transaction::Transaction {
+            version: 1,
+            call: transaction::Call {
+                format: transaction::CallFormat::Plain,
+                method: "evm.Create".to_owned(),
+                body: cbor::to_value(types::Create {
+                    value: 0u64.into(),
+                    init_code: Vec::from_hex(INIT_CODE).unwrap(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            auth_info: transaction::AuthInfo {
+                signer_info: vec![transaction::SignerInfo::new_sigspec(
+                    keys::dave::sigspec(),
+                    0,
+                )],
+                fee: transaction::Fee {
+                    amount: Default::default(),
+                    gas: 1_000_000,
+                    consensus_messages: 0,
+                },
+                ..Default::default()
+            },
+        };
+        Accounts::authenticate_tx(&mut ctx, &tx).unwrap();
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            EVMModule::<EVMConfig>::tx_create(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("create should succeed");
+            <Core<CoreConfig> as core::API>::used_tx_gas(&mut tx_ctx)
+        })
+    }
+
+    let base = create_gas_used(Default::default());
+    let gas_used = create_gas_used(crate::GasCosts {
+        code_byte: 100,
+        tx_create: 5_000,
+        ..Default::default()
+    });
+    assert_eq!(
+        gas_used,
+        base + 5_000 + 10 * 100,
+        "creation and deployed code should be charged"
+    );
+}
+
+#[test]
+fn test_max_init_code_size() {
+    let params = crate::Parameters::default();
+    assert_eq!(params.max_init_code_size(), None, "code size should not be limited by default");
+
+    let params = crate::Parameters {
+        max_code_size: 24_576,
+        ..Default::default()
+    };
+    assert_eq!(params.max_init_code_size(), Some(49_152));
+}
+
+#[test]
+fn test_audit_decrypt() {
+    use oasis_runtime_sdk::core::common::crypto::{hash::Hash, mrae::deoxysii};
+
+    struct AuditEVMConfig;
+
+    impl Config for AuditEVMConfig {
+        type Accounts = Accounts;
+        type AdditionalPrecompileSet = ();
+        const CHAIN_ID: u64 = 0x5afe;
+
+        const TOKEN_DENOMINATION: Denomination = Denomination::NATIVE;
+
+        const CONFIDENTIAL: bool = true;
+
+        const CONFIDENTIAL_AUDIT: bool = true;
+    }
+
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    Accounts::init(
+        &mut ctx,
+        accounts::Genesis {
+            accounts: BTreeMap::from([(
+                keys::alice::address(),
+                accounts::types::Account {
+                    role: oasis_runtime_sdk::types::role::Role::Auditor,
+                    ..Default::default()
+                },
+            )]),
+            ..Default::default()
+        },
+    );
+
+    // Encrypt a call to the runtime's ephemeral key of the current epoch.
+    let client_keypair = deoxysii::generate_key_pair();
+    let inner_call = transaction::Call {
+        format: transaction::CallFormat::EncryptedX25519DeoxysII,
+        method: "".into(),
+        body: cbor::to_value(types::Call::default()),
+        ..Default::default()
+    };
+    let encrypted = callformat::encode_call(&ctx, inner_call.clone(), &client_keypair).unwrap();
+    let envelope: oasis_runtime_sdk::types::callformat::CallEnvelopeX25519DeoxysII =
+        cbor::from_value(encrypted.body).unwrap();
+
+    let epoch = ctx.epoch();
+    let auditor_keypair = deoxysii::generate_key_pair();
+    let audit_tx = |signer: SignatureAddressSpec| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "evm.AuditDecrypt".to_owned(),
+            body: cbor::to_value(types::AuditDecrypt {
+                epoch,
+                envelope: envelope.clone(),
+                auditor_pk: auditor_keypair.0,
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(signer, 0)],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1_000_000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+
+    // Audits are rejected when confidential auditing is disabled.
+    let tx = audit_tx(keys::alice::sigspec());
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = EVMModule::<ConfidentialEVMConfig>::tx_audit_decrypt(
+            &mut tx_ctx,
+            cbor::from_value(call.body).unwrap(),
+        );
+        assert!(matches!(result, Err(Error::Forbidden)));
+    });
+
+    // Only auditors may request decryption.
+    let tx = audit_tx(keys::bob::sigspec());
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = EVMModule::<AuditEVMConfig>::tx_audit_decrypt(
+            &mut tx_ctx,
+            cbor::from_value(call.body).unwrap(),
+        );
+        assert!(matches!(result, Err(Error::Forbidden)));
+    });
+
+    // The decrypted call is sealed to the auditor's key and the access is recorded.
+    let tx = audit_tx(keys::alice::sigspec());
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = EVMModule::<AuditEVMConfig>::tx_audit_decrypt(
+            &mut tx_ctx,
+            cbor::from_value(call.body).unwrap(),
+        )
+        .expect("audit decryption should succeed");
+        let data = deoxysii::box_open(
+            &result.nonce,
+            result.data,
+            vec![],
+            &result.pk,
+            &auditor_keypair.1,
+        )
+        .expect("result should be sealed to the auditor's key");
+        assert_eq!(data, cbor::to_vec(inner_call));
+
+        let (etags, _) = tx_ctx.commit();
+        assert_eq!(
+            etags.get(&[b"evm".as_slice(), &2u32.to_be_bytes()].concat()),
+            Some(&vec![cbor::to_value(crate::Event::AuditAccess {
+                auditor: keys::alice::address(),
+                epoch,
+                envelope_hash: Hash::digest_bytes(&cbor::to_vec(envelope.clone())),
+            })]),
+            "audit access event should be emitted"
+        );
+    });
+}
+
+#[test]
+fn test_system_contract_function() {
+    use crate::{SystemContractFunction, SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT};
+
+    let account = H160::repeat_byte(0x42);
+    let amount = types::H256::from_low_u64_be(1_000);
+
+    // The default layout matches the legacy hand-packed call data.
+    let params = crate::Parameters::default();
+    let data = params.system_mint().encode_call(&account, &amount, true).unwrap();
+    let expected = [
+        &[0xd1, 0xa1, 0xbe, 0xb4][..],
+        &[0; 12],
+        account.as_bytes(),
+        amount.as_bytes(),
+        types::H256::from_low_u64_be(1).as_bytes(),
+    ]
+    .concat();
+    assert_eq!(data, expected);
+    assert_eq!(params.system_burn(), SystemContractFunction::DEFAULT_BURN);
+
+    // An upgraded contract may use a different selector and layout.
+    let params = crate::Parameters {
+        system_mint: Some(SystemContractFunction {
+            selector: [0x40, 0xc1, 0x0f, 0x19],
+            layout_version: SYSTEM_CONTRACT_LAYOUT_ACCOUNT_AMOUNT,
+        }),
+        ..Default::default()
+    };
+    module::Parameters::validate_basic(&params).expect("parameters should be valid");
+    let data = params.system_mint().encode_call(&account, &amount, true).unwrap();
+    assert_eq!(
+        data,
+        [&[0x40, 0xc1, 0x0f, 0x19][..], &expected[4..4 + 64]].concat(),
+        "the flag should not be encoded"
+    );
+
+    let params = crate::Parameters {
+        system_burn: Some(SystemContractFunction {
+            selector: [0; 4],
+            layout_version: 0,
+        }),
+        ..Default::default()
+    };
+    module::Parameters::validate_basic(&params).expect_err("unknown layouts should be rejected");
+    assert!(matches!(
+        params.system_burn().encode_call(&account, &amount, false),
+        Err(Error::InvalidArgument)
+    ));
+}
+
+#[test]
+fn test_remove_contract_storage() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    let address = H160::repeat_byte(0x42);
+    let other = H160::repeat_byte(0x43);
+    let index = types::H256::repeat_byte(0x01);
+    let value = types::H256::repeat_byte(0xff);
+    for addr in [&address, &other] {
+        crate::state::public_storage(&mut ctx, addr).insert(index, value);
+    }
+    crate::state::public_storage(&mut ctx, &address).insert(types::H256::repeat_byte(0x02), value);
+
+    // Removal is charged per entry, counting stops at the given limit.
+    let entries = crate::state::contract_storage_entries(ctx.runtime_state(), &address, 10);
+    assert_eq!(entries, 2, "all entries of the contract should be counted");
+    let entries = crate::state::contract_storage_entries(ctx.runtime_state(), &address, 1);
+    assert_eq!(entries, 1, "counting should stop at the limit");
+
+    crate::state::remove_contract_storage(ctx.runtime_state(), &address);
+
+    let removed: Option<types::H256> =
+        crate::state::public_storage(&mut ctx, &address).get(index);
+    assert!(removed.is_none(), "storage of the contract should be removed");
+    let kept: Option<types::H256> = crate::state::public_storage(&mut ctx, &other).get(index);
+    assert_eq!(kept, Some(value), "storage of other contracts should be kept");
+}
+
+#[test]
+fn test_vicinity_block_environment() {
+    let mut mock = mock::Mock::default();
+    mock.runtime_header.timestamp = 1_700_000_000;
+    let mut ctx = mock.create_ctx();
+
+    Core::<CoreConfig>::init(
+        &mut ctx,
+        core::Genesis {
+            parameters: core::Parameters {
+                max_batch_gas: 10_000_000,
+                min_gas_price: BTreeMap::from([(Denomination::NATIVE, 100)]),
+                ..Default::default()
+            },
+        },
+    );
+
+    let vicinity = crate::backend::Vicinity::new::<_, EVMConfig>(
+        &mut ctx,
+        1_000.into(),
+        H160::repeat_byte(0x01),
+    );
+    assert_eq!(vicinity.block_timestamp, 1_700_000_000);
+    assert_eq!(vicinity.block_base_fee, 100.into());
+    assert_eq!(vicinity.block_gas_limit, 10_000_000);
+    assert_eq!(vicinity.block_coinbase, H160::zero());
+}
+
+#[test]
+fn test_simulate_call_round() {
+    let mut mock = mock::Mock::default();
+    mock.runtime_header.round = 10;
+    let mut ctx = mock.create_ctx();
+
+    let err = EVMModule::<EVMConfig>::simulate_call(
+        &mut ctx,
+        types::SimulateCallQuery {
+            round: Some(5),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::HistoricalStateUnavailable(5, 10)));
+}
+
+#[test]
+fn test_storage_and_code_round() {
+    let mut mock = mock::Mock::default();
+    mock.runtime_header.round = 10;
+    let mut ctx = mock.create_ctx();
+
+    let address = H160::repeat_byte(0x42);
+    crate::state::codes(ctx.runtime_state()).insert(address, vec![0x00]);
+
+    let code = EVMModule::<EVMConfig>::query_code(
+        &mut ctx,
+        types::CodeQuery {
+            address,
+            round: Some(10),
+        },
+    )
+    .expect("code query at the current round should succeed");
+    assert_eq!(code, vec![0x00]);
+
+    let err = EVMModule::<EVMConfig>::query_code(
+        &mut ctx,
+        types::CodeQuery {
+            address,
+            round: Some(5),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::HistoricalStateUnavailable(5, 10)));
+
+    EVMModule::<EVMConfig>::query_storage(
+        &mut ctx,
+        types::StorageQuery {
+            address,
+            round: Some(10),
+            ..Default::default()
+        },
+    )
+    .expect("storage query at the current round should succeed");
+
+    let err = EVMModule::<EVMConfig>::query_storage(
+        &mut ctx,
+        types::StorageQuery {
+            address,
+            round: Some(5),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::HistoricalStateUnavailable(5, 10)));
+}
+
+#[test]
+fn test_storage_range() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    let address = H160::repeat_byte(0x42);
+    let other = H160::repeat_byte(0x43);
+    for i in 0..5u8 {
+        let index = types::H256::repeat_byte(i);
+        crate::state::public_storage(&mut ctx, &address)
+            .insert(index, types::H256::repeat_byte(0xff));
+    }
+    crate::state::public_storage(&mut ctx, &other)
+        .insert(types::H256::zero(), types::H256::repeat_byte(0xee));
+
+    let mut entries = Vec::new();
+    let mut start = Vec::new();
+    loop {
+        let result = EVMModule::<EVMConfig>::query_storage_range(
+            &mut ctx,
+            types::StorageRangeQuery {
+                address,
+                start,
+                limit: 2,
+            },
+        )
+        .expect("storage range query should succeed");
+        assert!(result.entries.len() <= 2);
+        entries.extend(result.entries);
+        match result.next {
+            Some(next) => start = next,
+            None => break,
+        }
+    }
+    assert_eq!(entries.len(), 5, "all entries of the contract should be returned");
+    assert!(entries
+        .iter()
+        .all(|entry| entry.value == types::H256::repeat_byte(0xff)));
+}
+
+#[test]
+fn test_log_topic_tags() {
+    use crate::backend::{ApplyBackendResult as _, Backend, Vicinity};
+
+    struct LogTopicTagsConfig;
+
+    impl Config for LogTopicTagsConfig {
+        type Accounts = Accounts;
+        type AdditionalPrecompileSet = ();
+        const CHAIN_ID: u64 = 0xa515;
+
+        const TOKEN_DENOMINATION: Denomination = Denomination::NATIVE;
+
+        const EMIT_LOG_TOPIC_TAGS: bool = true;
+    }
+
+    let mut mock = mock::Mock::default();
+    let address = H160::repeat_byte(0x42);
+    let topics = vec![
+        primitive_types::H256::repeat_byte(0x01),
+        primitive_types::H256::repeat_byte(0x02),
+    ];
+    let log = || evm::backend::Log {
+        address: address.into(),
+        topics: topics.clone(),
+        data: vec![],
+    };
+    let no_values =
+        Vec::<evm::backend::Apply<Vec<(primitive_types::H256, primitive_types::H256)>>>::new;
+
+    // Topic tags are not emitted by default.
+    let mut ctx = mock.create_ctx();
+    Backend::<'_, _, EVMConfig>::new(&mut ctx, Vicinity::default()).apply(no_values(), vec![log()]);
+    let (etags, _) = ctx.commit();
+    let prefix = [b"evm".as_slice(), &crate::LOG_TOPIC_TAG_CODE.to_be_bytes()].concat();
+    assert!(
+        etags.keys().all(|key| !key.starts_with(&prefix)),
+        "topic tags should not be emitted by default"
+    );
+
+    // Simulate a log emitted by a second execution within the same transaction.
+    let mut ctx = mock.create_ctx();
+    ctx.set_tx(b"raw transaction");
+    let mut backend = Backend::<'_, _, LogTopicTagsConfig>::new(&mut ctx, Vicinity::default())
+        .with_first_log_index(2);
+    backend.apply(no_values(), vec![log()]);
+
+    let (etags, _) = ctx.commit();
+    let key = [b"evm".as_slice(), &1u32.to_be_bytes()].concat();
+    assert_eq!(
+        etags.get(&key),
+        Some(&vec![cbor::to_value(crate::Event::Log {
+            address,
+            topics: topics.iter().map(|&topic| topic.into()).collect(),
+            data: vec![],
+            tx_hash: Some(oasis_runtime_sdk::core::common::crypto::hash::Hash::digest_bytes(
+                b"raw transaction"
+            )),
+            log_index: 2,
+        })]),
+        "log event should include the transaction hash and log index"
+    );
+    for (position, topic) in topics.iter().enumerate() {
+        let key = [
+            b"evm".as_slice(),
+            &crate::LOG_TOPIC_TAG_CODE.to_be_bytes(),
+            address.as_bytes(),
+            &[position as u8],
+            topic.as_bytes(),
+        ]
+        .concat();
+        assert_eq!(
+            etags.get(&key),
+            Some(&vec![cbor::to_value(2u64)]),
+            "topic tag should be emitted"
+        );
+    }
+}
+
+#[test]
+fn test_backend_snapshot() {
+    use crate::backend::{ApplyBackendResult as _, Backend, Vicinity};
+
+    fn apply<C: Context>(ctx: &mut C, address: H160, value: u8) {
+        let mut backend = Backend::<'_, C, EVMConfig>::new(ctx, Vicinity::default());
+        backend.apply(
+            vec![evm::backend::Apply::Modify {
+                address: address.into(),
+                basic: evm::backend::Basic {
+                    balance: primitive_types::U256::zero(),
+                    nonce: primitive_types::U256::one(),
+                },
+                code: Some(vec![value]),
+                storage: vec![(
+                    primitive_types::H256::zero(),
+                    primitive_types::H256::repeat_byte(value),
+                )],
+                reset_storage: false,
+            }],
+            vec![evm::backend::Log {
+                address: address.into(),
+                topics: vec![],
+                data: vec![value],
+            }],
+        );
+    }
+    fn code<C: Context>(ctx: &mut C, address: H160) -> Option<Vec<u8>> {
+        crate::state::codes(ctx.runtime_state()).get(address)
+    }
+    fn slot<C: Context>(ctx: &mut C, address: H160) -> Option<types::H256> {
+        crate::state::public_storage(ctx, &address).get(types::H256::zero())
+    }
+
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+    let address = H160::repeat_byte(0x42);
+    let other = H160::repeat_byte(0x43);
+
+    apply(&mut ctx, address, 1);
+
+    // Nested snapshots can be reverted and committed independently.
+    let outer = Backend::<'_, _, EVMConfig>::snapshot(&mut ctx);
+    apply(&mut ctx, address, 2);
+    let inner = Backend::<'_, _, EVMConfig>::snapshot(&mut ctx);
+    apply(&mut ctx, address, 3);
+    apply(&mut ctx, other, 3);
+    Backend::<'_, _, EVMConfig>::revert_to_snapshot(&mut ctx, inner);
+    assert_eq!(code(&mut ctx, address), Some(vec![2]));
+    assert_eq!(
+        slot(&mut ctx, address),
+        Some(types::H256::repeat_byte(2)),
+        "storage should be reverted"
+    );
+    assert_eq!(code(&mut ctx, other), None, "code should be removed");
+    assert_eq!(
+        <Accounts as accounts::API>::get_nonce(
+            ctx.runtime_state(),
+            EVMConfig::map_address(other.into())
+        )
+        .unwrap(),
+        0,
+        "nonce should be reverted"
+    );
+    Backend::<'_, _, EVMConfig>::commit_snapshot(&mut ctx, outer);
+    assert_eq!(code(&mut ctx, address), Some(vec![2]));
+
+    // Only events of committed changes are emitted.
+    let (etags, _) = ctx.commit();
+    let key = [b"evm".as_slice(), &1u32.to_be_bytes()].concat();
+    let log = |value: u8| {
+        cbor::to_value(crate::Event::Log {
+            address,
+            topics: vec![],
+            data: vec![value],
+            tx_hash: None,
+            log_index: 0,
+        })
+    };
+    assert_eq!(etags.get(&key), Some(&vec![log(1), log(2)]));
+}
+
+#[test]
+fn test_transfer_gas() {
+    let gas_costs = crate::GasCosts::default();
+    assert_eq!(gas_costs.transfer_gas(&[]).unwrap(), 21_000);
+    assert_eq!(gas_costs.transfer_gas(&[0, 1, 2]).unwrap(), 21_000);
+
+    let gas_costs = crate::GasCosts {
+        tx_transfer: 30_000,
+        tx_data_zero_byte: 4,
+        tx_data_nonzero_byte: 16,
+        ..Default::default()
+    };
+    assert_eq!(gas_costs.transfer_gas(&[]).unwrap(), 30_000);
+    assert_eq!(gas_costs.transfer_gas(&[0, 1, 2]).unwrap(), 30_036);
+    assert_eq!(crate::GasCosts::default().storage_removal_gas(), 5_000);
+    assert_eq!(crate::GasCosts::default().address_alias_gas(), 20_000);
+}
+
+#[test]
+fn test_code_metadata() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    let address = H160::repeat_byte(0x42);
+    let code = load_erc20();
+    crate::state::codes(ctx.runtime_state()).insert(address, code.clone());
+
+    let metadata = EVMModule::<EVMConfig>::query_code_metadata(
+        &mut ctx,
+        types::CodeQuery {
+            address,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(metadata.size, code.len() as u64);
+    assert_eq!(
+        metadata.code_hash,
+        types::H256::from_slice(&sha3::Keccak256::digest(&code))
+    );
+
+    // Accounts without code report the hash of empty code.
+    let metadata = EVMModule::<EVMConfig>::query_code_metadata(
+        &mut ctx,
+        types::CodeQuery {
+            address: H160::repeat_byte(0x43),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(metadata.size, 0);
+    assert_eq!(
+        hex::encode(metadata.code_hash),
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    );
+}
+
+#[test]
+fn test_fee_history() {
+    let mut mock = mock::Mock::default();
+    mock.runtime_header.round = 5;
+    let mut ctx = mock.create_ctx();
+
+    Core::<CoreConfig>::init(
+        &mut ctx,
+        core::Genesis {
+            parameters: core::Parameters {
+                max_batch_gas: 10_000_000,
+                min_gas_price: BTreeMap::from([(Denomination::NATIVE, 100)]),
+                ..Default::default()
+            },
+        },
+    );
+
+    ctx.value::<Vec<(u128, u64)>>(crate::CONTEXT_KEY_BLOCK_TX_FEES)
+        .or_default()
+        .extend([(300, 29_000), (100, 21_000), (150, 50_000)]);
+    <EVMModule<EVMConfig> as module::BlockHandler>::end_block(&mut ctx);
+
+    let result = EVMModule::<EVMConfig>::query_fee_history(
+        &mut ctx,
+        types::FeeHistoryQuery {
+            block_count: 10,
+            newest_round: None,
+            reward_percentiles: vec![0, 50, 100],
+        },
+    )
+    .unwrap();
+    assert_eq!(result.oldest_round, 5, "only the current block should be available");
+    assert_eq!(result.base_fees, vec![100]);
+    assert_eq!(result.gas_limits, vec![10_000_000]);
+    assert_eq!(result.rewards, vec![vec![0, 50, 200]]);
+
+    let result = EVMModule::<EVMConfig>::query_fee_history(
+        &mut ctx,
+        types::FeeHistoryQuery {
+            block_count: 10,
+            newest_round: Some(4),
+            reward_percentiles: vec![],
+        },
+    )
+    .unwrap();
+    assert!(result.base_fees.is_empty());
+    assert!(result.rewards.is_empty());
+
+    assert!(EVMModule::<EVMConfig>::query_fee_history(
+        &mut ctx,
+        types::FeeHistoryQuery {
+            block_count: 1,
+            newest_round: None,
+            reward_percentiles: vec![101],
+        },
+    )
+    .is_err());
+}
+
+#[test]
+fn test_query_config() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    let chain_id = EVMModule::<EVMConfig>::query_chain_id(&mut ctx, ()).unwrap();
+    assert_eq!(chain_id, 0xa515);
+
+    let config = EVMModule::<ConfidentialEVMConfig>::query_config(&mut ctx, ()).unwrap();
+    assert_eq!(
+        config,
+        types::ModuleConfig {
+            chain_id: 0x5afe,
+            hardfork: "london".to_string(),
+            confidential: true,
+            token_denomination: Denomination::NATIVE,
+        }
+    );
+}
+
+#[test]
+fn test_resolve_address() {
+    use crate::backend::{ApplyBackendResult as _, Backend, Vicinity};
+
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    let eth_address = H160::repeat_byte(0x42);
+    let address = EVMConfig::map_address(eth_address.into());
+
+    // Ethereum addresses can always be resolved.
+    let alias = EVMModule::<EVMConfig>::query_resolve_address(
+        &mut ctx,
+        types::ResolveAddressQuery {
+            eth_address: Some(eth_address),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        alias,
+        Some(types::AddressAlias {
+            eth_address,
+            address
+        })
+    );
+
+    // SDK addresses can only be resolved once used in the EVM.
+    let query = types::ResolveAddressQuery {
+        address: Some(address),
+        ..Default::default()
+    };
+    let alias = EVMModule::<EVMConfig>::query_resolve_address(&mut ctx, query.clone()).unwrap();
+    assert_eq!(alias, None, "unused addresses should not be known");
+
+    let mut backend = Backend::<'_, _, EVMConfig>::new(&mut ctx, Vicinity::default());
+    backend.apply(
+        vec![evm::backend::Apply::Modify {
+            address: eth_address.into(),
+            basic: Default::default(),
+            code: None,
+            storage: Vec::<(primitive_types::H256, primitive_types::H256)>::new(),
+            reset_storage: false,
+        }],
+        Vec::<evm::backend::Log>::new(),
+    );
+
+    let alias = EVMModule::<EVMConfig>::query_resolve_address(&mut ctx, query).unwrap();
+    assert_eq!(
+        alias,
+        Some(types::AddressAlias {
+            eth_address,
+            address
+        })
+    );
+    assert_eq!(
+        EVMModule::<EVMConfig>::resolve_eth_address(&mut ctx, address),
+        Some(eth_address)
+    );
+    assert!(
+        !crate::state::register_address_alias(ctx.runtime_state(), eth_address, address),
+        "known aliases should not be recorded again"
+    );
+
+    let err = EVMModule::<EVMConfig>::query_resolve_address(
+        &mut ctx,
+        types::ResolveAddressQuery {
+            eth_address: Some(eth_address),
+            address: Some(address),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::InvalidArgument));
+}
+
+#[test]
+fn test_prefetch_access_list() {
+    use oasis_runtime_sdk::storage::Store as _;
+
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    let address = H160::repeat_byte(0x42);
+    let key = types::H256::repeat_byte(0x01);
+    crate::state::codes(ctx.runtime_state()).insert(address, vec![0x00]);
+    crate::state::public_storage(&mut ctx, &address).insert(key, types::H256::repeat_byte(0xff));
+
+    let body = cbor::to_value(types::Call {
+        address,
+        access_list: vec![types::AccessListItem {
+            address,
+            storage_keys: vec![key],
+        }],
+        ..Default::default()
+    });
+    let mut prefixes = vec![];
+    EVMModule::<EVMConfig>::prefetch_call(
+        &mut |prefix| prefixes.push(prefix),
+        body.clone(),
+        &Default::default(),
+    )
+    .unwrap();
+
+    // All state of the contract must be covered by the prefetched prefixes.
+    let keys: Vec<Vec<u8>> = ctx
+        .runtime_state()
+        .iter()
+        .map(|(key, _)| key)
+        .filter(|key| key.starts_with(b"evm"))
+        .collect();
+    assert_eq!(keys.len(), 2);
+    for key in keys {
+        assert!(
+            prefixes.contains(&storage::Prefix::from(key.clone())),
+            "key {} should be prefetched",
+            hex::encode(&key)
+        );
+    }
+
+    // Confidential storage can't be prefetched, only code is.
+    let mut prefixes = vec![];
+    EVMModule::<ConfidentialEVMConfig>::prefetch_call(
+        &mut |prefix| prefixes.push(prefix),
+        body,
+        &Default::default(),
+    )
+    .unwrap();
+    assert_eq!(prefixes.len(), 2, "code should be prefetched for the callee and access list");
+}
+
+#[test]
+fn test_invariants() {
+    let mut mock = mock::Mock::default();
+    mock.runtime_header.round = 1_000;
+    let mut ctx = mock.create_ctx();
+    EVMModule::<EVMConfig>::check_invariants(&mut ctx).expect("invariants should hold");
+
+    // Code must belong to an existing account.
+    let address = H160::repeat_byte(0x42);
+    crate::state::codes(ctx.runtime_state()).insert(address, vec![0x00]);
+    EVMModule::<EVMConfig>::check_invariants(&mut ctx)
+        .expect_err("code without an account should be detected");
+    <Accounts as accounts::API>::set_nonce(
+        ctx.runtime_state(),
+        EVMConfig::map_address(address.into()),
+        1,
+    );
+    EVMModule::<EVMConfig>::check_invariants(&mut ctx).expect("invariants should hold");
+
+    // Block hashes must be kept within the window.
+    for round in 0..=crate::state::BLOCK_HASH_WINDOW_SIZE {
+        crate::state::block_hashes(ctx.runtime_state())
+            .insert(round.to_be_bytes(), types::H256::repeat_byte(0x01));
+    }
+    EVMModule::<EVMConfig>::check_invariants(&mut ctx)
+        .expect_err("too many block hashes should be detected");
+    crate::state::block_hashes(ctx.runtime_state()).remove(0u64.to_be_bytes());
+    EVMModule::<EVMConfig>::check_invariants(&mut ctx).expect("invariants should hold");
+
+    // Gas used by transactions can't exceed the gas used by the block.
+    crate::state::fee_history(ctx.runtime_state()).insert(
+        999u64.to_be_bytes(),
+        types::FeeHistoryBlock {
+            base_fee: 0,
+            gas_used: 10,
+            gas_limit: 100,
+            txs: vec![types::FeeHistoryTx {
+                priority_fee: 0,
+                gas_used: 20,
+            }],
+        },
+    );
+    EVMModule::<EVMConfig>::check_invariants(&mut ctx)
+        .expect_err("inconsistent gas use should be detected");
+}
+
+#[test]
+fn test_genesis_contracts() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    let address = H160::repeat_byte(0x4e);
+    let code = Vec::from_hex("602a60005500").unwrap();
+    EVMModule::<EVMConfig>::init(
+        &mut ctx,
+        Genesis {
+            contracts: vec![crate::GenesisContract {
+                address,
+                code: code.clone(),
+            }],
+            ..Default::default()
+        },
+    );
+
+    let result = EVMModule::<EVMConfig>::query_code(
+        &mut ctx,
+        types::CodeQuery {
+            address,
+            ..Default::default()
+        },
+    )
+    .expect("code query should succeed");
+    assert_eq!(result, code);
+
+    let sdk_address = EVMConfig::map_address(address.into());
+    assert_eq!(
+        <Accounts as accounts::API>::get_nonce(ctx.runtime_state(), sdk_address).unwrap(),
+        1,
+        "genesis contracts should have an account"
+    );
+    assert_eq!(
+        EVMModule::<EVMConfig>::resolve_eth_address(&mut ctx, sdk_address),
+        Some(address)
+    );
+    EVMModule::<EVMConfig>::check_invariants(&mut ctx).expect("invariants should hold");
+}
+
+#[test]
+fn test_u256_to_u128() {
+    let max = types::U256::from(u128::MAX);
+    assert_eq!(crate::u256_to_u128(max).unwrap(), u128::MAX);
+    assert_eq!(crate::u256_to_u128(types::U256::zero()).unwrap(), 0);
+    assert!(matches!(
+        crate::u256_to_u128(max + 1),
+        Err(Error::ValueTooLarge(value)) if value == max + 1
+    ));
+}
+
+#[test]
+fn test_evm_create_value() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    Core::<CoreConfig>::init(
+        &mut ctx,
+        core::Genesis {
+            parameters: core::Parameters {
+                max_batch_gas: 10_000_000,
+                ..Default::default()
+            },
+        },
+    );
+
+    Accounts::init(
+        &mut ctx,
+        accounts::Genesis {
+            balances: BTreeMap::from([(
+                keys::dave::address(),
+                BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+            )]),
+            total_supplies: BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+            ..Default::default()
+        },
+    );
+
+    EVMModule::<EVMConfig>::init(
+        &mut ctx,
+        Genesis {
+            parameters: Default::default(),
+            ..Default::default()
+        },
+    );
+
+    let caller = derive_caller::from_sigspec(&keys::dave::sigspec()).unwrap();
+    let create_tx = |nonce: u64, value: types::U256, init_code: &str| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "evm.Create".to_owned(),
+            body: cbor::to_value(types::Create {
+                value,
+                init_code: Vec::from_hex(init_code).unwrap(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::dave::sigspec(),
+                nonce,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1_000_000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+    fn balance_of<C: Context>(ctx: &mut C, address: H160) -> u128 {
+        EVMModule::<EVMConfig>::query_balance(ctx, types::BalanceQuery { address }).unwrap()
+    }
+
+    // Successful creation debits the caller and credits the new contract. The init code is a
+    // single STOP, so the deployed code is empty.
+    let tx = create_tx(0, 1_000u64.into(), "00");
+    Accounts::authenticate_tx(&mut ctx, &tx).unwrap();
+    let address = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let address = EVMModule::<EVMConfig>::tx_create(
+            &mut tx_ctx,
+            cbor::from_value(call.body).unwrap(),
+        )
+        .expect("create should succeed");
+        EVMModule::<EVMConfig>::check_invariants(&mut tx_ctx).expect("invariants should hold");
+        let gas_used = <Core<CoreConfig> as core::API>::used_tx_gas(&mut tx_ctx);
+        let (etags, _) = tx_ctx.commit();
+        assert_eq!(
+            etags.get(&[b"evm".as_slice(), &4u32.to_be_bytes()].concat()),
+            Some(&vec![cbor::to_value(crate::Event::Execution {
+                tx_hash: None,
+                gas_used,
+                status: types::ExecutionStatus::Succeeded,
+            })]),
+            "execution event should be emitted"
+        );
+        H160::from_slice(&address)
+    });
+    assert_eq!(balance_of(&mut ctx, address), 1_000);
+    assert_eq!(balance_of(&mut ctx, caller), 999_000);
+
+    // Reverted creation leaves the value with the caller. The init code is REVERT(0, 0).
+    let tx = create_tx(1, 1_000u64.into(), "60006000fd");
+    Accounts::authenticate_tx(&mut ctx, &tx).unwrap();
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = EVMModule::<EVMConfig>::tx_create(
+            &mut tx_ctx,
+            cbor::from_value(call.body).unwrap(),
+        );
+        assert!(matches!(result, Err(Error::Reverted(_))));
+        EVMModule::<EVMConfig>::check_invariants(&mut tx_ctx).expect("invariants should hold");
+
+        // The execution event is also emitted for failed transactions.
+        let gas_used = <Core<CoreConfig> as core::API>::used_tx_gas(&mut tx_ctx);
+        let etags = tx_ctx.rollback();
+        assert_eq!(
+            etags.get(&[b"evm".as_slice(), &4u32.to_be_bytes()].concat()),
+            Some(&vec![cbor::to_value(crate::Event::Execution {
+                tx_hash: None,
+                gas_used,
+                status: types::ExecutionStatus::Reverted,
+            })]),
+            "execution event should be emitted"
+        );
+    });
+    assert_eq!(balance_of(&mut ctx, caller), 999_000);
+
+    // Values that don't fit into a token amount are rejected before execution.
+    let tx = create_tx(2, types::U256::from(u128::MAX) + 1, "00");
+    Accounts::authenticate_tx(&mut ctx, &tx).unwrap();
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = EVMModule::<EVMConfig>::tx_create(
+            &mut tx_ctx,
+            cbor::from_value(call.body).unwrap(),
+        );
+        assert!(matches!(result, Err(Error::ValueTooLarge(_))));
+    });
+    assert_eq!(balance_of(&mut ctx, caller), 999_000);
+}
+
+#[test]
+fn test_native_signer_fee_refund() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    Core::<CoreConfig>::init(
+        &mut ctx,
+        core::Genesis {
+            parameters: core::Parameters {
+                max_batch_gas: 10_000_000,
+                ..Default::default()
+            },
+        },
+    );
+
+    Accounts::init(
+        &mut ctx,
+        accounts::Genesis {
+            balances: BTreeMap::from([(
+                keys::alice::address(),
+                BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+            )]),
+            total_supplies: BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+            ..Default::default()
+        },
+    );
+
+    EVMModule::<EVMConfig>::init(
+        &mut ctx,
+        Genesis {
+            parameters: Default::default(),
+            ..Default::default()
+        },
+    );
+
+    // An Ed25519 signer pre-pays the maximum fee from its own account.
+    let tx = transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "evm.Call".to_owned(),
+            body: cbor::to_value(types::Call {
+                address: H160::repeat_byte(0x42),
+                value: 0u64.into(),
+                data: vec![],
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: token::BaseUnits::new(100_000, Denomination::NATIVE),
+                gas: 100_000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+    Accounts::authenticate_tx(&mut ctx, &tx).unwrap();
+    let gas_used = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        EVMModule::<EVMConfig>::tx_call(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("call should succeed");
+        let gas_used = <Core<CoreConfig> as core::API>::used_tx_gas(&mut tx_ctx);
+        tx_ctx.commit();
+        gas_used as u128
+    });
+
+    // Unused gas is refunded to the signer and not to the account its caller maps to.
+    let mapped = EVMConfig::map_address(
+        derive_caller::from_sigspec(&keys::alice::sigspec())
+            .unwrap()
+            .into(),
+    );
+    assert_eq!(
+        <Accounts as accounts::API>::get_balance(
+            ctx.runtime_state(),
+            keys::alice::address(),
+            Denomination::NATIVE
+        )
+        .unwrap(),
+        1_000_000 - gas_used
+    );
+    assert_eq!(
+        <Accounts as accounts::API>::get_balance(ctx.runtime_state(), mapped, Denomination::NATIVE)
+            .unwrap(),
+        0
+    );
+}
+
+#[test]
+fn test_evm_vesting_locked_balance() {
+    let mut mock = mock::Mock::default();
+    mock.runtime_header.round = 50;
+    let mut ctx = mock.create_ctx();
+
+    Core::<CoreConfig>::init(
+        &mut ctx,
+        core::Genesis {
+            parameters: core::Parameters {
+                max_batch_gas: 10_000_000,
+                ..Default::default()
+            },
+        },
+    );
+
+    // Half of Dave's balance is still locked in round 50.
+    Accounts::init(
+        &mut ctx,
+        accounts::Genesis {
+            accounts: BTreeMap::from([(
+                keys::dave::address(),
+                accounts::types::Account {
+                    vesting: Some(accounts::types::VestingSchedule {
+                        amount: token::BaseUnits::new(1_000_000, Denomination::NATIVE),
+                        start: 0,
+                        cliff: 0,
+                        end: 100,
+                    }),
+                    ..Default::default()
+                },
+            )]),
+            balances: BTreeMap::from([(
+                keys::dave::address(),
+                BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+            )]),
+            total_supplies: BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+            ..Default::default()
+        },
+    );
+
+    EVMModule::<EVMConfig>::init(
+        &mut ctx,
+        Genesis {
+            parameters: Default::default(),
+            ..Default::default()
+        },
+    );
+
+    let caller = derive_caller::from_sigspec(&keys::dave::sigspec()).unwrap();
+    let target = H160::repeat_byte(0x42);
+    let call_tx = |nonce: u64, value: u64| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "evm.Call".to_owned(),
+            body: cbor::to_value(types::Call {
+                address: target,
+                value: value.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::dave::sigspec(),
+                nonce,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1_000_000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+    fn balance_of<C: Context>(ctx: &mut C, address: H160) -> u128 {
+        EVMModule::<EVMConfig>::query_balance(ctx, types::BalanceQuery { address }).unwrap()
+    }
+
+    // Sending from the vested part of the balance succeeds.
+    let tx = call_tx(0, 400_000);
+    Accounts::authenticate_tx(&mut ctx, &tx).unwrap();
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        EVMModule::<EVMConfig>::tx_call(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("transfer of vested funds should succeed");
+        tx_ctx.commit();
+    });
+    assert_eq!(balance_of(&mut ctx, caller), 600_000);
+    assert_eq!(balance_of(&mut ctx, target), 400_000);
+
+    // Sending funds that are still locked fails and leaves the balances untouched.
+    let tx = call_tx(1, 200_000);
+    Accounts::authenticate_tx(&mut ctx, &tx).unwrap();
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        EVMModule::<EVMConfig>::tx_call(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect_err("transfer of locked funds should fail");
+    });
+    assert_eq!(balance_of(&mut ctx, caller), 600_000);
+    assert_eq!(balance_of(&mut ctx, target), 400_000);
+}
+
+#[test]
+fn test_internal_call() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    Core::<CoreConfig>::init(
+        &mut ctx,
+        core::Genesis {
+            parameters: core::Parameters {
+                max_batch_gas: 10_000_000,
+                ..Default::default()
+            },
+        },
+    );
+
+    EVMModule::<EVMConfig>::init(
+        &mut ctx,
+        Genesis {
+            parameters: Default::default(),
+            ..Default::default()
+        },
+    );
+
+    // A contract that stores 42 in slot 0.
+    let address = H160::repeat_byte(0x42);
+    crate::state::codes(ctx.runtime_state())
+        .insert(address, Vec::from_hex("602a60005500").unwrap());
+    let caller = H160::repeat_byte(0x01);
+
+    // Calls that run out of gas fail, but the used gas is still accounted for.
+    let err = EVMModule::<EVMConfig>::internal_call(
+        &mut ctx,
+        caller,
+        address,
+        0u64.into(),
+        vec![],
+        1_000,
+    )
+    .unwrap_err();
+    assert!(matches!(err, Error::ExecutionFailed(_)), "call should run out of gas");
+    assert_eq!(
+        <Core<CoreConfig> as core::API>::remaining_batch_gas(&mut ctx),
+        10_000_000 - 1_000
+    );
+    assert_eq!(
+        EVMModule::<EVMConfig>::get_storage(&mut ctx, address, Default::default()).unwrap(),
+        vec![0; 32],
+        "failed call should not modify storage"
+    );
+
+    EVMModule::<EVMConfig>::internal_call(
+        &mut ctx,
+        caller,
+        address,
+        0u64.into(),
+        vec![],
+        100_000,
+    )
+    .expect("call should succeed");
+    let remaining = <Core<CoreConfig> as core::API>::remaining_batch_gas(&mut ctx);
+    assert!(
+        remaining < 10_000_000 - 1_000 - 21_000,
+        "used gas should be accounted for against the batch"
+    );
+    let mut expected = vec![0; 32];
+    expected[31] = 42;
+    assert_eq!(
+        EVMModule::<EVMConfig>::get_storage(&mut ctx, address, Default::default()).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_static_call() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    Core::<CoreConfig>::init(
+        &mut ctx,
+        core::Genesis {
+            parameters: core::Parameters {
+                max_batch_gas: 10_000_000,
+                ..Default::default()
+            },
+        },
+    );
+
+    Accounts::init(
+        &mut ctx,
+        accounts::Genesis {
+            balances: BTreeMap::from([(
+                keys::dave::address(),
+                BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+            )]),
+            total_supplies: BTreeMap::from([(Denomination::NATIVE, 1_000_000)]),
+            ..Default::default()
+        },
+    );
+
+    EVMModule::<EVMConfig>::init(
+        &mut ctx,
+        Genesis {
+            parameters: Default::default(),
+            ..Default::default()
+        },
+    );
+
+    let dave_tx = |nonce: u64, method: &str, body: cbor::Value| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: method.to_owned(),
+            body,
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::dave::sigspec(),
+                nonce,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1_000_000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+
+    // Deploy a contract that returns 42 and one that writes to storage.
+    let mut addresses = vec![];
+    for (nonce, init_code) in [
+        "600a600c600039600a6000f3602a60005260206000f3",
+        "6006600c60003960066000f3602a60005500",
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let tx = dave_tx(
+            nonce as u64,
+            "evm.Create",
+            cbor::to_value(types::Create {
+                value: 0u64.into(),
+                init_code: Vec::from_hex(init_code).unwrap(),
+                ..Default::default()
+            }),
+        );
+        Accounts::authenticate_tx(&mut ctx, &tx).unwrap();
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let address = EVMModule::<EVMConfig>::tx_create(
+                &mut tx_ctx,
+                cbor::from_value(call.body).unwrap(),
+            )
+            .expect("create should succeed");
+            tx_ctx.commit();
+            addresses.push(H160::from_slice(&address));
+        });
+    }
+
+    let mut results = vec![];
+    let cases = [(2, addresses[0], 0u64), (3, addresses[1], 0), (4, addresses[0], 1)];
+    for (nonce, address, value) in cases {
+        let tx = dave_tx(
+            nonce,
+            "evm.StaticCall",
+            cbor::to_value(types::Call {
+                address,
+                value: value.into(),
+                data: vec![],
+                ..Default::default()
+            }),
+        );
+        Accounts::authenticate_tx(&mut ctx, &tx).unwrap();
+        results.push(ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            EVMModule::<EVMConfig>::tx_static_call(
+                &mut tx_ctx,
+                cbor::from_value(call.body).unwrap(),
+            )
+        }));
+    }
+
+    let output = results.remove(0).expect("static call should succeed");
+    assert_eq!(types::U256::from_big_endian(&output), 42u64.into());
+    assert!(
+        matches!(results[0], Err(Error::Core(core::Error::ReadOnlyTransaction))),
+        "static calls must not modify storage"
+    );
+    assert!(
+        matches!(results[1], Err(Error::InvalidArgument)),
+        "static calls must not transfer value"
+    );
+}
+
+#[test]
+fn test_paused_contract() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    // Pause the contract as a passed governance proposal would.
+    let contract = H160::repeat_byte(0x42);
+    let contract_address = EVMConfig::map_address(contract.into());
+    storage::TypedStore::new(storage::PrefixStore::new(
+        storage::PrefixStore::new(ctx.runtime_state(), &<Accounts as module::Module>::NAME),
+        &accounts::state::PAUSED_CONTRACTS,
+    ))
+    .insert(contract_address, true);
+    assert!(<Accounts as accounts::API>::is_contract_paused(
+        ctx.runtime_state(),
+        contract_address
+    ));
+
+    let tx = transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "evm.Call".to_owned(),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::dave::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1_000_000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, _call| {
+        let result = EVMModule::<EVMConfig>::call(&mut tx_ctx, contract, 0u64.into(), vec![]);
+        assert!(matches!(result, Err(Error::ContractPaused)));
+    });
+}
+
+#[test]
+fn test_blacklisted_accounts() {
+    fn check<C: BatchContext>(ctx: &mut C, target: H160) {
+        let tx = transaction::Transaction {
+            version: 1,
+            call: transaction::Call {
+                format: transaction::CallFormat::Plain,
+                method: "evm.Call".to_owned(),
+                ..Default::default()
+            },
+            auth_info: transaction::AuthInfo {
+                signer_info: vec![transaction::SignerInfo::new_sigspec(
+                    keys::dave::sigspec(),
+                    0,
+                )],
+                fee: transaction::Fee {
+                    amount: Default::default(),
+                    gas: 1_000_000,
+                    consensus_messages: 0,
+                },
+                ..Default::default()
+            },
+        };
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, _call| {
+            let result = EVMModule::<EVMConfig>::call(&mut tx_ctx, target, 0u64.into(), vec![]);
+            assert!(matches!(result, Err(Error::Forbidden)), "call should be forbidden");
+            let result =
+                EVMModule::<EVMConfig>::transfer(&mut tx_ctx, target, 0u64.into(), vec![]);
+            assert!(matches!(result, Err(Error::Forbidden)), "transfer should be forbidden");
+        });
+    }
+
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    // Blacklisted recipients.
+    let target = H160::repeat_byte(0x42);
+    let target_address = EVMConfig::map_address(target.into());
+    <Accounts as accounts::API>::set_role(
+        ctx.runtime_state(),
+        target_address,
+        oasis_runtime_sdk::types::role::Role::BlacklistedUser,
+    );
+    check(&mut ctx, target);
+
+    // Blacklisted callers.
+    let caller = derive_caller::from_sigspec(&keys::dave::sigspec()).unwrap();
+    <Accounts as accounts::API>::set_role(
+        ctx.runtime_state(),
+        target_address,
+        oasis_runtime_sdk::types::role::Role::User,
+    );
+    <Accounts as accounts::API>::set_role(
+        ctx.runtime_state(),
+        EVMConfig::map_address(caller.into()),
+        oasis_runtime_sdk::types::role::Role::BlacklistedUser,
+    );
+    check(&mut ctx, target);
 }
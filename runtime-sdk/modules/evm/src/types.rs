@@ -5,6 +5,9 @@
 pub struct Create {
     pub value: U256,
     pub init_code: Vec<u8>,
+    /// Optional EIP-2930 access list, used as a prefetch hint.
+    #[cbor(optional)]
+    pub access_list: Vec<AccessListItem>,
 }
 
 /// Transaction body for calling an EVM contract.
@@ -13,6 +16,58 @@ pub struct Call {
     pub address: H160,
     pub value: U256,
     pub data: Vec<u8>,
+    /// Optional EIP-2930 access list, used as a prefetch hint.
+    #[cbor(optional)]
+    pub access_list: Vec<AccessListItem>,
+    /// Maximum amount, in the consensus denomination, that the called contract may deposit from
+    /// the signer's consensus account into itself through the consensus deposit precompile.
+    /// Deposits are rejected unless the signer opts in by setting a limit.
+    #[cbor(optional)]
+    pub max_consensus_deposit: u128,
+}
+
+/// Structured result of an `evm.CallV2` transaction.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct CallOutput {
+    /// Data returned by the call, encoded the same way as the result of `evm.Call`.
+    pub data: Vec<u8>,
+    /// Total amount of gas used by the transaction.
+    pub gas_used: u64,
+    /// Number of logs emitted by the call.
+    pub logs: u64,
+}
+
+/// An EIP-2930 access list entry.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct AccessListItem {
+    pub address: H160,
+    #[cbor(optional)]
+    pub storage_keys: Vec<H256>,
+}
+
+/// Transaction body for requesting decryption of a historical confidential transaction.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct AuditDecrypt {
+    /// Epoch in which the transaction was encrypted.
+    pub epoch: u64,
+    /// Envelope of the encrypted call, as included in the transaction.
+    pub envelope: oasis_runtime_sdk::types::callformat::CallEnvelopeX25519DeoxysII,
+    /// Auditor's X25519 public key that the decrypted call is sealed to.
+    pub auditor_pk: [u8; 32],
+}
+
+/// Result of an audit decryption request.
+///
+/// The decrypted call is sealed to the auditor's public key and can be opened using the runtime's
+/// ephemeral public key for the requested epoch.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct AuditDecryptResult {
+    /// Runtime's ephemeral public key for the requested epoch.
+    pub pk: [u8; 32],
+    /// Nonce.
+    pub nonce: [u8; oasis_runtime_sdk::core::common::crypto::mrae::deoxysii::NONCE_SIZE],
+    /// Decrypted call, sealed to the auditor's public key.
+    pub data: Vec<u8>,
 }
 
 /// Transaction body for peeking into EVM storage.
@@ -20,12 +75,124 @@ pub struct Call {
 pub struct StorageQuery {
     pub address: H160,
     pub index: H256,
+    /// Round whose state should be queried. The query must be submitted at that round so that the
+    /// host serves the corresponding state; `None` means any round.
+    #[cbor(optional)]
+    pub round: Option<u64>,
+}
+
+/// Query body for iterating over EVM storage of a contract.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct StorageRangeQuery {
+    pub address: H160,
+    /// Key to start at, as returned in [`StorageRangeResult::next`].
+    #[cbor(optional)]
+    pub start: Vec<u8>,
+    /// Maximum number of entries to return. A value of `0` returns the maximum allowed.
+    #[cbor(optional)]
+    pub limit: u64,
+}
+
+/// A single EVM storage entry.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct StorageEntry {
+    /// Hashed storage slot.
+    pub key: Vec<u8>,
+    pub value: H256,
+}
+
+/// Result of the `evm.StorageRange` query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct StorageRangeResult {
+    pub entries: Vec<StorageEntry>,
+    /// Continuation token for the next page, if there are more entries.
+    #[cbor(optional)]
+    pub next: Option<Vec<u8>>,
+}
+
+/// Fee paid by a transaction, as recorded in the fee history of a block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct FeeHistoryTx {
+    /// Priority fee per gas paid on top of the base fee.
+    pub priority_fee: u128,
+    /// Gas used by the transaction.
+    pub gas_used: u64,
+}
+
+/// Fee history of a single block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct FeeHistoryBlock {
+    /// Base fee (minimum gas price) of the block.
+    pub base_fee: u128,
+    /// Gas used by all transactions in the block.
+    pub gas_used: u64,
+    /// Maximum amount of gas that could have been used in the block.
+    pub gas_limit: u64,
+    /// EVM transactions in the block, sorted by priority fee.
+    pub txs: Vec<FeeHistoryTx>,
+}
+
+/// Query body for the `evm.FeeHistory` query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct FeeHistoryQuery {
+    /// Number of blocks to return.
+    pub block_count: u64,
+    /// Newest round to return, defaults to the latest round.
+    #[cbor(optional)]
+    pub newest_round: Option<u64>,
+    /// Percentiles (0-100) of priority fees, weighted by gas used, to return for each block.
+    #[cbor(optional)]
+    pub reward_percentiles: Vec<u8>,
+}
+
+/// Result of the `evm.FeeHistory` query, oldest block first.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct FeeHistoryResult {
+    /// Round of the oldest returned block.
+    pub oldest_round: u64,
+    /// Base fee of each block.
+    pub base_fees: Vec<u128>,
+    /// Gas used by each block.
+    pub gas_used: Vec<u64>,
+    /// Gas limit of each block.
+    pub gas_limits: Vec<u64>,
+    /// Priority fees at the requested percentiles for each block.
+    pub rewards: Vec<Vec<u128>>,
+}
+
+impl FeeHistoryBlock {
+    /// Returns the priority fee at the given percentile (0-100) of gas used in the block.
+    pub fn reward_percentile(&self, percentile: u8) -> u128 {
+        let total_gas: u64 = self.txs.iter().map(|tx| tx.gas_used).sum();
+        let threshold = total_gas as u128 * percentile.min(100) as u128 / 100;
+        let mut cumulative_gas: u128 = 0;
+        for tx in &self.txs {
+            cumulative_gas += tx.gas_used as u128;
+            if cumulative_gas >= threshold {
+                return tx.priority_fee;
+            }
+        }
+        self.txs.last().map(|tx| tx.priority_fee).unwrap_or_default()
+    }
 }
 
 /// Transaction body for peeking into EVM code storage.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
 pub struct CodeQuery {
     pub address: H160,
+    /// Round whose state should be queried. The query must be submitted at that round so that the
+    /// host serves the corresponding state; `None` means any round.
+    #[cbor(optional)]
+    pub round: Option<u64>,
+}
+
+/// Metadata of the code deployed at an address, returned by the `evm.CodeMetadata` query.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct CodeMetadata {
+    /// Keccak256 hash of the code, as returned by `EXTCODEHASH` for existing accounts.
+    pub code_hash: H256,
+    /// Size of the code in bytes.
+    pub size: u64,
 }
 
 /// Transaction body for fetching EVM account's balance.
@@ -34,6 +201,47 @@ pub struct BalanceQuery {
     pub address: H160,
 }
 
+/// Module configuration, returned by the `evm.Config` query.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct ModuleConfig {
+    /// Chain ID that Ethereum-format transactions must use.
+    pub chain_id: u64,
+    /// Name of the hardfork whose rules the EVM follows.
+    pub hardfork: String,
+    /// Whether confidential storage and transaction data encryption are used.
+    pub confidential: bool,
+    /// Denomination of the native EVM token.
+    pub token_denomination: oasis_runtime_sdk::types::token::Denomination,
+}
+
+/// Query body for resolving between the Ethereum and SDK representations of an address.
+///
+/// Exactly one of the addresses must be set.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct ResolveAddressQuery {
+    /// Ethereum address to resolve.
+    #[cbor(optional)]
+    pub eth_address: Option<H160>,
+    /// SDK address to resolve.
+    #[cbor(optional)]
+    pub address: Option<oasis_runtime_sdk::types::address::Address>,
+}
+
+/// Both representations of an address.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct AddressAlias {
+    /// Ethereum address.
+    pub eth_address: H160,
+    /// SDK address that the Ethereum address maps to.
+    pub address: oasis_runtime_sdk::types::address::Address,
+}
+
+/// Query body for deriving the EVM caller address of a native SDK account.
+#[derive(Clone, Debug, cbor::Encode, cbor::Decode)]
+pub struct DeriveCallerQuery {
+    pub address_spec: oasis_runtime_sdk::types::transaction::AddressSpec,
+}
+
 /// Transaction body for simulating an EVM call.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
 #[cfg_attr(test, derive(PartialEq, Eq))]
@@ -44,6 +252,62 @@ pub struct SimulateCallQuery {
     pub address: H160,
     pub value: U256,
     pub data: Vec<u8>,
+    /// Round whose state the call should be simulated against. The query must be submitted at
+    /// that round so that the host serves the corresponding state; `None` means any round.
+    #[cbor(optional)]
+    pub round: Option<u64>,
+}
+
+/// Query body for estimating the gas needed by an EVM call or contract creation.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct EstimateGasQuery {
+    pub gas_price: U256,
+    /// Upper bound of the search. A value of `0` uses the maximum batch gas.
+    #[cbor(optional)]
+    pub gas_limit: u64,
+    pub caller: H160,
+    /// Called contract, or `None` when estimating a contract creation.
+    #[cbor(optional)]
+    pub address: Option<H160>,
+    pub value: U256,
+    /// Call data or init code.
+    pub data: Vec<u8>,
+}
+
+/// Query body for executing several read-only calls against a shared simulation state.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct MulticallQuery {
+    pub gas_price: U256,
+    /// Gas limit shared by all calls.
+    pub gas_limit: u64,
+    pub caller: H160,
+    pub calls: Vec<MulticallCall>,
+}
+
+/// A single call of a [`MulticallQuery`].
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct MulticallCall {
+    pub address: H160,
+    #[cbor(optional)]
+    pub value: U256,
+    pub data: Vec<u8>,
+    /// Whether the whole query may still succeed in case this call fails.
+    #[cbor(optional)]
+    pub allow_failure: bool,
+}
+
+/// Result of a single call of a [`MulticallQuery`].
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct MulticallResult {
+    /// Whether the call succeeded. Results of encrypted calls are always reported as successful
+    /// and carry the outcome in the encrypted data instead.
+    pub success: bool,
+    /// Data returned by the call, encoded the same way as the result of `evm.SimulateCall`.
+    #[cbor(optional)]
+    pub data: Vec<u8>,
+    /// Error message in case the call failed.
+    #[cbor(optional)]
+    pub error: Option<String>,
 }
 
 /// An envelope containing the encryption-enveloped data of a [`SimulateCallQuery`]
@@ -58,8 +322,8 @@ pub struct SimulateCallQuery {
 /// ```ignore
 /// {
 ///   domain: {
-///     name: 'oasis-runtime-sdk/evm: signed query',
-///     version: '1.0.0',
+///     name: 'oasis-runtime-sdk/evm: signed query', // Config::SIGNED_QUERY_DOMAIN_NAME
+///     version: '1.0.0', // Config::SIGNED_QUERY_DOMAIN_VERSION
 ///     chainId,
 ///   },
 ///   types: {
@@ -77,6 +341,8 @@ pub struct SimulateCallQuery {
 ///       { name: 'blockNumber', type: 'uint64' },
 ///       { name: 'blockHash', type: 'uint256' },
 ///       { name: 'blockRange', type: 'uint64' },
+///       // Only when the leash carries a query nonce:
+///       { name: 'queryNonce', type: 'uint64' },
 ///     ],
 ///   },
 /// }
@@ -100,6 +366,41 @@ pub struct Leash {
     pub block_hash: H256,
     /// The range of the leash past `block_number`.
     pub block_range: u64,
+    /// Optional query nonce of the signer. When present, it is covered by the signature as an
+    /// additional `uint64 queryNonce` member of `Leash` and must not be lower than the signer's
+    /// current query nonce.
+    ///
+    /// The query nonce only allows revoking signed queries. A query is not single-use and can be
+    /// repeated until it is revoked or its leash expires.
+    #[cbor(optional)]
+    pub query_nonce: Option<u64>,
+}
+
+/// Transaction body for advancing the caller's signed query nonce.
+///
+/// Signed queries whose leash carries a lower query nonce are rejected afterwards.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct AdvanceQueryNonce {
+    pub query_nonce: u64,
+}
+
+/// Outcome of an EVM transaction, as reported by the `Execution` event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+#[repr(u8)]
+#[cbor(with_default)]
+pub enum ExecutionStatus {
+    /// Execution completed successfully.
+    Succeeded = 0,
+    /// Execution was reverted by the contract.
+    Reverted = 1,
+    /// Execution failed for any other reason (e.g. out of gas).
+    Failed = 2,
+}
+
+impl Default for ExecutionStatus {
+    fn default() -> Self {
+        Self::Succeeded
+    }
 }
 
 // The rest of the file contains wrappers for primitive_types::{H160, H256, U256},
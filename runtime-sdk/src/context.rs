@@ -240,6 +240,9 @@ pub trait Context {
 
     /// Commit any changes made to storage, return any emitted tags and runtime messages. It
     /// consumes the transaction context.
+    ///
+    /// Values under each tag key are returned in emission order, including any unconditional
+    /// events, which are interleaved with regular events in the order they were emitted.
     fn commit(
         self,
     ) -> (
@@ -250,6 +253,9 @@ pub trait Context {
     /// Rollback any changes made by this context. This method only needs to be called explicitly
     /// in case you want to retrieve possibly emitted unconditional events. Simply dropping the
     /// context without calling `commit` will also result in a rollback.
+    ///
+    /// Values under each tag key are returned in emission order, which is the same relative order
+    /// the unconditional events would have had on commit.
     fn rollback(self) -> EventTags;
 
     /// Fetches a value entry associated with the context.
@@ -463,6 +469,9 @@ pub trait TxContext: Context {
     /// Similar as `emit_event` but the event will persist even in case the transaction that owns
     /// this context fails.
     fn emit_unconditional_event<E: Event>(&mut self, event: E);
+
+    /// Total size in bytes of the (conditional) events emitted so far in this context.
+    fn emitted_events_size(&self) -> u64;
 }
 
 /// Dispatch context for the whole batch.
@@ -767,6 +776,8 @@ impl<'a, R: runtime::Runtime, S: NestedStore> BatchContext for RuntimeBatchConte
             internal: self.internal,
             etags: BTreeMap::new(),
             etags_unconditional: BTreeMap::new(),
+            etags_unconditional_positions: BTreeMap::new(),
+            etags_size: 0,
             max_messages: remaining_messages,
             messages: Vec::new(),
             values: &mut self.values,
@@ -825,6 +836,12 @@ pub struct RuntimeTxContext<'round, 'store, R: runtime::Runtime, S: Store> {
     etags: EventTags,
     /// Emitted unconditional event tags.
     etags_unconditional: EventTags,
+    /// Positions of unconditional event values within the regular event values of the same tag
+    /// key at the time they were emitted, used to preserve emission order on commit.
+    etags_unconditional_positions: BTreeMap<Vec<u8>, Vec<usize>>,
+    /// Total size in bytes of the emitted event tags, maintained on emission so that it doesn't
+    /// need to be recomputed.
+    etags_size: u64,
 
     /// Maximum number of messages that can be emitted.
     max_messages: u32,
@@ -888,18 +905,18 @@ impl<'round, 'store, R: runtime::Runtime, S: Store> Context
     }
 
     fn emit_event<E: Event>(&mut self, event: E) {
-        let etag = event.into_event_tag();
-        let tag = self.etags.entry(etag.key).or_insert_with(Vec::new);
-        tag.push(etag.value);
+        self.emit_etag(event.into_event_tag());
     }
 
     fn emit_etag(&mut self, etag: EventTag) {
+        self.etags_size += etag_size(&etag.key, &etag.value);
         let tag = self.etags.entry(etag.key).or_insert_with(Vec::new);
         tag.push(etag.value);
     }
 
     fn emit_etags(&mut self, etags: EventTags) {
         for (key, val) in etags {
+            self.etags_size += val.iter().map(|value| etag_size(&key, value)).sum::<u64>();
             let tag = self.etags.entry(key).or_insert_with(Vec::new);
             tag.extend(val)
         }
@@ -915,10 +932,18 @@ impl<'round, 'store, R: runtime::Runtime, S: Store> Context
         EventTags,
         Vec<(roothash::Message, MessageEventHookInvocation)>,
     ) {
-        // Merge unconditional events into regular events on success.
+        // Merge unconditional events into regular events on success, preserving emission order.
         for (key, val) in self.etags_unconditional {
+            let positions = self
+                .etags_unconditional_positions
+                .remove(&key)
+                .unwrap_or_default();
             let tag = self.etags.entry(key).or_insert_with(Vec::new);
-            tag.extend(val)
+            // Regular event values are only ever appended, so positions are increasing and each
+            // insertion shifts the following positions by one.
+            for (offset, (pos, value)) in positions.into_iter().zip(val).enumerate() {
+                tag.insert(pos + offset, value);
+            }
         }
 
         self.store.commit();
@@ -1049,12 +1074,26 @@ impl<R: runtime::Runtime, S: Store> TxContext for RuntimeTxContext<'_, '_, R, S>
 
     fn emit_unconditional_event<E: Event>(&mut self, event: E) {
         let etag = event.into_event_tag();
+        let pos = self.etags.get(&etag.key).map(Vec::len).unwrap_or_default();
+        self.etags_unconditional_positions
+            .entry(etag.key.clone())
+            .or_insert_with(Vec::new)
+            .push(pos);
         let tag = self
             .etags_unconditional
             .entry(etag.key)
             .or_insert_with(Vec::new);
         tag.push(etag.value);
     }
+
+    fn emitted_events_size(&self) -> u64 {
+        self.etags_size
+    }
+}
+
+/// Size in bytes of an event tag with the given key and value.
+fn etag_size(key: &[u8], value: &cbor::Value) -> u64 {
+    (key.len() + cbor::to_vec(value.clone()).len()) as u64
 }
 
 /// A per-context arbitrary value.
@@ -1430,6 +1469,64 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_tx_ctx_event_order() {
+        #[derive(Clone, Debug, cbor::Encode, cbor::Decode)]
+        struct TestEvent {
+            n: u64,
+        }
+
+        impl Event for TestEvent {
+            fn module_name() -> &'static str {
+                "test"
+            }
+
+            fn code(&self) -> u32 {
+                1
+            }
+        }
+
+        let key = TestEvent { n: 0 }.into_event_tag().key;
+        let decode = |etags: &EventTags| -> Vec<u64> {
+            etags[&key]
+                .iter()
+                .map(|v| cbor::from_value::<TestEvent>(v.clone()).unwrap().n)
+                .collect()
+        };
+
+        let mut mock = Mock::default();
+        let mut ctx = mock.create_ctx();
+
+        fn emit<C: TxContext>(tx_ctx: &mut C) {
+            tx_ctx.emit_event(TestEvent { n: 1 });
+            tx_ctx.emit_unconditional_event(TestEvent { n: 2 });
+            tx_ctx.emit_event(TestEvent { n: 3 });
+            tx_ctx.emit_unconditional_event(TestEvent { n: 4 });
+            tx_ctx.emit_unconditional_event(TestEvent { n: 5 });
+            tx_ctx.emit_event(TestEvent { n: 6 });
+        }
+
+        ctx.with_tx(0, 0, mock::transaction(), |mut tx_ctx, _call| {
+            emit(&mut tx_ctx);
+            let (etags, _) = tx_ctx.commit();
+            assert_eq!(
+                decode(&etags),
+                vec![1, 2, 3, 4, 5, 6],
+                "committed events should be in emission order"
+            );
+        });
+
+        ctx.with_tx(0, 0, mock::transaction(), |mut tx_ctx, _call| {
+            emit(&mut tx_ctx);
+            let etags = tx_ctx.rollback();
+            assert_eq!(
+                decode(&etags),
+                vec![2, 4, 5],
+                "unconditional events should keep their relative order"
+            );
+        });
+    }
+
     #[test]
     fn test_tx_ctx_metadata() {
         let mut mock = Mock::default();
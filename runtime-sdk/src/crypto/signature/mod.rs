@@ -423,6 +423,27 @@ mod test {
         assert_eq!(vref, sig.as_ref());
     }
 
+    #[test]
+    fn test_secp256k1_uncompressed_bytes() {
+        let pk = crate::testing::keys::dave::pk_secp256k1();
+        let other = crate::testing::keys::erin::pk_secp256k1();
+        secp256k1::PublicKey::warm_up_uncompressed_cache([&pk, &other]);
+        let bytes = pk
+            .try_to_uncompressed_untagged_bytes()
+            .expect("valid public keys should decompress");
+        assert_eq!(bytes.len(), 64);
+        assert_eq!(pk.to_uncompressed_untagged_bytes(), bytes);
+
+        // The x coordinate is not a field element, so this is not a valid curve point.
+        let invalid = secp256k1::PublicKey::from_bytes(&[[0x02].as_slice(), &[0xff; 32]].concat())
+            .expect("encoding should be valid");
+        secp256k1::PublicKey::warm_up_uncompressed_cache([&invalid]);
+        assert!(matches!(
+            invalid.try_to_uncompressed_untagged_bytes(),
+            Err(Error::MalformedPublicKey)
+        ));
+    }
+
     #[test]
     fn test_memory_signer() {
         let ctx = b"oasis-core/test: context";
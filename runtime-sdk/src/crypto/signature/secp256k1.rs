@@ -14,11 +14,19 @@ use k256::{
 use crate::crypto::signature::{Error, Signature};
 
 use once_cell::sync::Lazy;
-use std::{num::NonZeroUsize, sync::Mutex};
+use std::{
+    num::NonZeroUsize,
+    sync::{Mutex, MutexGuard},
+};
 
 static PK_CACHE: Lazy<Mutex<lru::LruCache<Vec<u8>, Vec<u8>>>> =
     Lazy::new(|| Mutex::new(lru::LruCache::new(NonZeroUsize::new(100000).unwrap())));
 
+fn pk_cache() -> MutexGuard<'static, lru::LruCache<Vec<u8>, Vec<u8>>> {
+    // The cache only holds derived values, so a panic while holding the lock can't corrupt it.
+    PK_CACHE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
 /// A Secp256k1 public key (in compressed form).
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PublicKey(k256::EncodedPoint);
@@ -30,30 +38,65 @@ impl PublicKey {
     }
 
     /// Return an alternative byte representation used in deriving Ethereum-compatible addresses. Cached version.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the public key is not a valid curve point, see
+    /// [`PublicKey::try_to_uncompressed_untagged_bytes`] for a fallible variant.
     pub fn to_uncompressed_untagged_bytes(&self) -> Vec<u8> {
-        let key = self.as_bytes().to_vec();
-
-        let cached = {
-            let mut cache = PK_CACHE.lock().unwrap();
-            let val = cache.get(&key);
-            if val.is_some() {
-                Some(val.unwrap().clone())
-            } else {
-                None
-            }
-        };
+        self.try_to_uncompressed_untagged_bytes()
+            .expect("public key should be a valid curve point")
+    }
+
+    /// Return an alternative byte representation used in deriving Ethereum-compatible addresses,
+    /// failing in case the public key is not a valid curve point. Cached version.
+    pub fn try_to_uncompressed_untagged_bytes(&self) -> Result<Vec<u8>, Error> {
+        let key = self.as_bytes();
+        if let Some(val) = pk_cache().get(key) {
+            return Ok(val.clone());
+        }
 
-        if cached.is_some() {
-            return cached.unwrap();
+        // If it does not exist in the cache, decompress the public key and store it in the cache.
+        let val = self.decompress()?;
+        pk_cache().put(key.to_vec(), val.clone());
+
+        Ok(val)
+    }
+
+    /// Populate the cache used by [`PublicKey::to_uncompressed_untagged_bytes`] for all of the
+    /// given public keys at once, e.g. before checking a batch of transactions.
+    ///
+    /// Public keys that are not valid curve points are skipped.
+    pub fn warm_up_uncompressed_cache<'a, I>(keys: I)
+    where
+        I: IntoIterator<Item = &'a PublicKey>,
+    {
+        let missing: Vec<&PublicKey> = {
+            let cache = pk_cache();
+            keys.into_iter()
+                .filter(|pk| !cache.contains(pk.as_bytes()))
+                .collect()
+        };
+        if missing.is_empty() {
+            return;
         }
 
-        // If it does not exist in the cache, create a new public key and store it in the cache
-        let pk = k256::PublicKey::from_encoded_point(&self.0).unwrap();
-        let val = pk.to_encoded_point(false).as_bytes()[1..].to_vec();
+        // Decompress without holding the lock.
+        let decompressed: Vec<(Vec<u8>, Vec<u8>)> = missing
+            .into_iter()
+            .filter_map(|pk| Some((pk.as_bytes().to_vec(), pk.decompress().ok()?)))
+            .collect();
 
-        PK_CACHE.lock().unwrap().put(key, val.clone());
+        let mut cache = pk_cache();
+        for (key, val) in decompressed {
+            cache.put(key, val);
+        }
+    }
 
-        val
+    fn decompress(&self) -> Result<Vec<u8>, Error> {
+        let pk: Option<k256::PublicKey> = k256::PublicKey::from_encoded_point(&self.0).into();
+        let pk = pk.ok_or(Error::MalformedPublicKey)?;
+        Ok(pk.to_encoded_point(false).as_bytes()[1..].to_vec())
     }
 
     /// Construct a public key from a slice of bytes.
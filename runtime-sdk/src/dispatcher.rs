@@ -33,6 +33,7 @@ use oasis_core_runtime::{
 use crate::{
     callformat,
     context::{BatchContext, Context, Mode, RuntimeBatchContext, TxContext},
+    crypto::signature::secp256k1,
     error::{Error as _, RuntimeError},
     event::IntoTags,
     keymanager::{KeyManagerClient, KeyManagerError},
@@ -76,6 +77,8 @@ lazy_static! {
     pub static ref CTX_FEE_ACCUM: Mutex<Vec<BaseUnits>> = Mutex::new(
         Vec::new()
     );
+    /// Number of transaction groups that contributed their fees to `CTX_FEE_ACCUM`.
+    pub static ref CTX_FEE_ACCUM_GROUPS: Mutex<usize> = Mutex::new(0);
 }
 
 /// Unique module name.
@@ -253,7 +256,6 @@ impl<R: Runtime> Dispatcher<R> {
         // println!("gbtest: dispatch_tx_call after decode method {:?}", &call.method);
         // println!("gbtest: dispatch_tx_call after decode body {:?}", &call.body);
 
-        // GBTODO: investigate, blacklist users may insert here.
         // Apply optional method authorization.
         if let Some(method_authorizer) = opts.method_authorizer {
             if !method_authorizer(&call.method) {
@@ -264,6 +266,11 @@ impl<R: Runtime> Dispatcher<R> {
             }
         }
 
+        // Let modules authorize the method, e.g. based on the roles of the signers.
+        if let Err(e) = R::Modules::authorize_call(ctx, &call.method) {
+            return (e.into_call_result(), call_format_metadata);
+        }
+
         // // println!("gbtest: dispatch_tx_call before dispatch_call");
         // GB: further decode values with keys in the Map and dispatch to corresponding functions in modules.
         // 
@@ -301,7 +308,26 @@ impl<R: Runtime> Dispatcher<R> {
         // Run pre-processing hooks.
         if !opts.skip_authentication {
             if let Err(err) = R::Modules::authenticate_tx(ctx, &tx) {
-                return Ok(err.into_call_result().into());
+                // Report the nonces of the offending signer on nonce errors so that clients can
+                // resynchronize their nonce tracking.
+                let sender_metadata = match err {
+                    modules::core::Error::InvalidNonce(expected, provided)
+                    | modules::core::Error::FutureNonce(expected, provided) => tx
+                        .auth_info
+                        .signer_info
+                        .iter()
+                        .find(|si| si.nonce == provided)
+                        .map(|si| SenderMeta {
+                            address: si.address_spec.address(),
+                            tx_nonce: provided,
+                            state_nonce: expected,
+                        })
+                        .unwrap_or_default(),
+                    _ => Default::default(),
+                };
+                let mut result: DispatchResult = err.into_call_result().into();
+                result.sender_metadata = sender_metadata;
+                return Ok(result);
             }
         }
         let tx_auth_info = tx.auth_info.clone();
@@ -425,7 +451,17 @@ impl<R: Runtime> Dispatcher<R> {
                     code,
                     message,
                 },
-                meta: None,
+                // Include sender metadata when available (e.g. on nonce errors).
+                meta: if dispatch.sender_metadata == Default::default() {
+                    None
+                } else {
+                    Some(CheckTxMetadata {
+                        priority: 0,
+                        sender: dispatch.sender_metadata.id(),
+                        sender_seq: dispatch.sender_metadata.tx_nonce,
+                        sender_state_seq: dispatch.sender_metadata.state_nonce,
+                    })
+                },
             }),
 
             module::CallResult::Aborted(err) => Err(err),
@@ -603,6 +639,7 @@ impl<R: Runtime> Dispatcher<R> {
         if th_idx == 0 {
             MSG_HANDLERS.lock().unwrap().clear();
             CTX_FEE_ACCUM.lock().unwrap().clear();
+            *CTX_FEE_ACCUM_GROUPS.lock().unwrap() = 0;
 
             // Handle last round message results.
             Self::handle_last_round_messages(&mut ctx)?;
@@ -622,12 +659,28 @@ impl<R: Runtime> Dispatcher<R> {
                 for (denom, amount) in acc.total_fees.into_iter() {
                     CTX_FEE_ACCUM.lock().unwrap().push(BaseUnits::new(amount, denom));
                 }
+                *CTX_FEE_ACCUM_GROUPS.lock().unwrap() += 1;
             } else {
                 for fee in CTX_FEE_ACCUM.lock().unwrap().iter() {
                     ctx.value::<FeeAccumulator>(CONTEXT_KEY_FEE_ACCUMULATOR)
                         .or_default()
                         .add(fee);
                 }
+
+                // Watchdog: every other group must have contributed its fees.
+                let groups = *CTX_FEE_ACCUM_GROUPS.lock().unwrap();
+                if groups != num_th - 1 {
+                    error!(get_logger("dispatcher"), "fee accumulator merge incomplete";
+                        "groups" => groups,
+                        "expected" => num_th - 1,
+                    );
+                    ctx.emit_event(modules::accounts::Event::FeeAccumulatorViolation {
+                        reason: format!(
+                            "fee accumulator merge incomplete (groups={groups}, expected={})",
+                            num_th - 1,
+                        ),
+                    });
+                }
             }
         }
 
@@ -795,6 +848,7 @@ impl<R: Runtime + Send + Sync> transaction::dispatcher::Dispatcher for Dispatche
                 // Execute the batch.
                 let mut results = Vec::with_capacity(batch.len());
                 for (index, (tx_size, tx)) in txs.into_iter().enumerate() {
+                    ctx.set_tx(&batch[index]);
                     results.push(Self::execute_tx(ctx, tx_size, tx, index)?);
                 }
 
@@ -862,6 +916,7 @@ impl<R: Runtime + Send + Sync> transaction::dispatcher::Dispatcher for Dispatche
 
                         // Determine the current transaction index.
                         let tx_index = new_batch.len();
+                        ctx.set_tx(&raw_tx);
 
                         // First run the transaction in check tx mode in a separate subcontext. If
                         // that fails, skip and (sometimes) reject transaction.
@@ -869,7 +924,7 @@ impl<R: Runtime + Send + Sync> transaction::dispatcher::Dispatcher for Dispatche
                             ctx.with_child(Mode::PreScheduleTx, |mut ctx| -> Result<_, Error> {
                                 // First authenticate the transaction to get any nonce related errors.
                                 match R::Modules::authenticate_tx(&mut ctx, &tx) {
-                                    Err(modules::core::Error::FutureNonce) => {
+                                    Err(modules::core::Error::FutureNonce(..)) => {
                                         // Only skip transaction as it may become valid in the future.
                                         return Ok(true);
                                     }
@@ -988,6 +1043,20 @@ impl<R: Runtime + Send + Sync> transaction::dispatcher::Dispatcher for Dispatche
                 .prefetch_prefixes(prefixes.into_iter().collect(), R::PREFETCH_LIMIT);
         }
 
+        // Decompress all secp256k1 signer keys up front so that deriving their addresses doesn't
+        // decompress them one at a time.
+        secp256k1::PublicKey::warm_up_uncompressed_cache(
+            txs.iter()
+                .filter_map(|tx| tx.as_ref().ok())
+                .flat_map(|(_, tx)| tx.auth_info.signer_info.iter())
+                .filter_map(|si| match &si.address_spec {
+                    types::transaction::AddressSpec::Signature(
+                        types::address::SignatureAddressSpec::Secp256k1Eth(pk),
+                    ) => Some(pk),
+                    _ => None,
+                }),
+        );
+
         // Check the batch.
         let mut results = Vec::with_capacity(batch.len());
         let mut idx: usize = 0;
@@ -1225,6 +1294,7 @@ mod test {
     impl Runtime for AlphabetRuntime {
         const VERSION: Version = Version::new(0, 0, 0);
         type Core = Core;
+        type TransferHooks = ();
         type Modules = (Core, AlphabetModule);
 
         fn genesis_state() -> <Self::Modules as module::MigrationHandler>::Genesis {
@@ -1240,8 +1310,10 @@ mod test {
                             auth_signature: 0,
                             auth_multisig_signer: 0,
                             callformat_x25519_deoxysii: 0,
+                            event_byte: 0,
                         },
                         min_gas_price: BTreeMap::from([(token::Denomination::NATIVE, 0)]),
+                        max_tx_events_size: 0,
                     },
                 },
                 (),
@@ -76,6 +76,8 @@ pub struct EventTag {
 }
 
 /// Event tags with values accumulated by key.
+///
+/// Values under each key are kept in the order in which they were emitted.
 pub type EventTags = BTreeMap<Vec<u8>, Vec<cbor::Value>>;
 
 /// Provides method for converting event tags into events.
@@ -12,6 +12,7 @@ pub mod dispatcher;
 pub mod error;
 pub mod event;
 pub mod keymanager;
+pub mod metrics;
 pub mod module;
 pub mod modules;
 pub mod runtime;
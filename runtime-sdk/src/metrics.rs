@@ -0,0 +1,111 @@
+//! Runtime metrics.
+//!
+//! Metrics are kept in the memory of the local runtime instance and are not part of the runtime
+//! state, so they may differ between nodes and must never influence execution. Node operators can
+//! inspect them via the `core.Metrics` query.
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, MutexGuard},
+};
+
+use once_cell::sync::Lazy;
+
+/// Upper bounds (inclusive) of histogram buckets. Values above the last bound are only counted in
+/// the total count and sum.
+pub const HISTOGRAM_BUCKETS: &[u64] = &[
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+];
+
+/// A histogram of observed values.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct Histogram {
+    /// Number of observed values.
+    pub count: u64,
+    /// Sum of observed values.
+    pub sum: u64,
+    /// Number of observed values in each of the [`HISTOGRAM_BUCKETS`] (non-cumulative).
+    pub buckets: Vec<u64>,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: u64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; HISTOGRAM_BUCKETS.len()];
+        }
+        self.count = self.count.saturating_add(1);
+        self.sum = self.sum.saturating_add(value);
+        if let Some(idx) = HISTOGRAM_BUCKETS.iter().position(|bound| value <= *bound) {
+            self.buckets[idx] += 1;
+        }
+    }
+}
+
+/// A snapshot of all metrics.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct Metrics {
+    /// Monotonically increasing counters.
+    pub counters: BTreeMap<String, u64>,
+    /// Histograms of observed values.
+    pub histograms: BTreeMap<String, Histogram>,
+}
+
+static METRICS: Lazy<Mutex<Metrics>> = Lazy::new(Default::default);
+
+fn metrics() -> MutexGuard<'static, Metrics> {
+    // Metrics are best-effort, so a panic while holding the lock should not disable them.
+    METRICS.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Increments the counter with the given name by the given amount.
+pub fn inc_counter(name: &str, by: u64) {
+    let mut metrics = metrics();
+    let counter = metrics.counters.entry(name.to_string()).or_default();
+    *counter = counter.saturating_add(by);
+}
+
+/// Records an observation in the histogram with the given name.
+pub fn observe(name: &str, value: u64) {
+    metrics()
+        .histograms
+        .entry(name.to_string())
+        .or_default()
+        .observe(value);
+}
+
+/// Returns a snapshot of all metrics.
+pub fn snapshot() -> Metrics {
+    metrics().clone()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        inc_counter("test.Counter", 1);
+        inc_counter("test.Counter", 2);
+        for value in [0, 10, 11, 5_000_000_000] {
+            observe("test.Histogram", value);
+        }
+
+        let snapshot = snapshot();
+        assert_eq!(snapshot.counters.get("test.Counter"), Some(&3));
+        let histogram = snapshot.histograms.get("test.Histogram").unwrap();
+        assert_eq!(histogram.count, 4);
+        assert_eq!(histogram.sum, 5_000_000_021);
+        assert_eq!(histogram.buckets[0], 1);
+        assert_eq!(histogram.buckets[1], 1);
+        assert_eq!(histogram.buckets[2], 1);
+        assert_eq!(histogram.buckets.iter().sum::<u64>(), 3);
+    }
+}
@@ -395,6 +395,18 @@ pub trait TransactionHandler {
         Ok(())
     }
 
+    /// Authorize the decoded call method, within the transaction context.
+    ///
+    /// This allows modules to restrict which methods the transaction signers may invoke. If an
+    /// error is returned the call is rejected before it is dispatched.
+    fn authorize_call<C: TxContext>(
+        _ctx: &mut C,
+        _method: &str,
+    ) -> Result<(), modules::core::Error> {
+        // Default implementation allows all methods.
+        Ok(())
+    }
+
     /// Perform any action after call, within the transaction context.
     ///
     /// If an error is returned the transaction call fails and updates are rolled back.
@@ -454,6 +466,11 @@ impl TransactionHandler for Tuple {
         Ok(())
     }
 
+    fn authorize_call<C: TxContext>(ctx: &mut C, method: &str) -> Result<(), modules::core::Error> {
+        for_tuples!( #( Tuple::authorize_call(ctx, method)?; )* );
+        Ok(())
+    }
+
     fn after_handle_call<C: TxContext>(ctx: &mut C) -> Result<(), modules::core::Error> {
         for_tuples!( #( Tuple::after_handle_call(ctx)?; )* );
         Ok(())
@@ -5,6 +5,7 @@ use std::{
     convert::TryInto,
 };
 
+use impl_trait_for_tuples::impl_for_tuples;
 use num_traits::Zero;
 use once_cell::sync::Lazy;
 use thiserror::Error;
@@ -25,7 +26,7 @@ use crate::{
     types::{
         address::{Address, SignatureAddressSpec},
         token,
-        transaction::{AuthInfo, Transaction},
+        transaction::{AuthInfo, Call, Transaction},
         role::{self, Role}, proposal::ProposalState,
         vote::{Action,Vote},
     },
@@ -39,9 +40,53 @@ pub mod types;
 /// Unique module name.
 const MODULE_NAME: &str = "accounts";
 
-/// Maximum delta that the transaction nonce can be in the future from the current nonce to still
-/// be accepted during transaction checks.
-const MAX_CHECK_NONCE_FUTURE_DELTA: u64 = 0; // Increase once supported in Oasis Core.
+/// Maximum number of transfers in a single TransferBatch call.
+const MAX_TRANSFER_BATCH_SIZE: usize = 256;
+
+/// Length (in seconds) of the window over which transfer limits apply.
+const TRANSFER_LIMIT_WINDOW: u64 = 86_400;
+
+/// Maximum number of accounts in a single PruneAccounts call.
+const MAX_PRUNE_ACCOUNTS_BATCH_SIZE: usize = 256;
+
+/// Actions with their own quorum. All other actions share the quorum of one of these, see
+/// `Module::quorum_action`.
+const QUORUM_ACTIONS: &[Action] = &[
+    Action::Mint,
+    Action::Burn,
+    Action::Whitelist,
+    Action::Blacklist,
+    Action::Config,
+];
+
+/// Whether the role may be configured as the proposer or voter role of the action. Roles held by
+/// ordinary accounts can't be granted governance powers.
+fn is_valid_action_role(action: Action, role: Role) -> bool {
+    action != Action::NoAction && !matches!(role, Role::User | Role::BlacklistedUser)
+}
+
+/// Quorum (in percent) of actions for which no quorum has been configured.
+const DEFAULT_QUORUM: u8 = 100;
+
+/// Share (in percent) of abstaining voters that cancels proposals of actions for which no
+/// abstain quorum has been configured.
+const DEFAULT_ABSTAIN_QUORUM: u8 = 50;
+
+/// Maximum number of proposals returned by a single Proposals query.
+const MAX_PROPOSALS_QUERY_LIMIT: u16 = 100;
+
+/// Maximum number of activity entries returned by a single History query.
+const MAX_HISTORY_QUERY_LIMIT: u16 = 100;
+
+/// Maximum number of addresses returned by a single AllBalances query.
+const MAX_ALL_BALANCES_QUERY_LIMIT: u16 = 100;
+
+/// Methods that can be authorized for session keys.
+const SESSION_KEY_METHODS: &[&str] = &[
+    "accounts.Propose",
+    "accounts.VoteST",
+    "accounts.CancelProposal",
+];
 
 /// Errors emitted by the accounts module.
 #[derive(Error, Debug, oasis_runtime_sdk_macros::Error)]
@@ -94,6 +139,30 @@ pub enum Error {
     #[sdk_error(code = 10)]
     VoteDup,
 
+    #[error("insufficient allowance")]
+    #[sdk_error(code = 11)]
+    InsufficientAllowance,
+
+    #[error("account frozen")]
+    #[sdk_error(code = 12)]
+    AccountFrozen,
+
+    #[error("amount below minimum transfer amount")]
+    #[sdk_error(code = 13)]
+    BelowMinimumTransfer,
+
+    #[error("transfer limit exceeded")]
+    #[sdk_error(code = 14)]
+    TransferLimitExceeded,
+
+    #[error("reserved address")]
+    #[sdk_error(code = 15)]
+    ReservedAddress,
+
+    #[error("too many active proposals")]
+    #[sdk_error(code = 16)]
+    TooManyActiveProposals,
+
 }
 
 
@@ -106,6 +175,8 @@ pub enum Event {
         from: Address,
         to: Address,
         amount: token::BaseUnits,
+        #[cbor(optional)]
+        memo: Option<Vec<u8>>,
         // GBTODO: stop here currently.
         // txseq: u128,
         // GBTODO: debug later when necessary.
@@ -123,6 +194,67 @@ pub enum Event {
         owner: Address,
         amount: token::BaseUnits,
     },
+
+    #[sdk_event(code = 4)]
+    FeeAccumulatorViolation { reason: String },
+
+    #[sdk_event(code = 5)]
+    Approve {
+        owner: Address,
+        spender: Address,
+        amount: token::BaseUnits,
+    },
+
+    #[sdk_event(code = 6)]
+    ProposalCancelled { id: u32, canceller: Address },
+
+    #[sdk_event(code = 7)]
+    ProposalSubmitted {
+        id: u32,
+        submitter: Address,
+        action: Action,
+    },
+
+    #[sdk_event(code = 8)]
+    ProposalVoted { id: u32, voter: Address, option: Vote },
+
+    #[sdk_event(code = 9)]
+    ProposalFinalized { id: u32, state: ProposalState },
+
+    #[sdk_event(code = 10)]
+    RoleRevoked { address: Address, role: Role },
+
+    #[sdk_event(code = 11)]
+    MultisigCreated { address: Address, creator: Address },
+
+    #[sdk_event(code = 12)]
+    MultisigExecuted { multisig: Address, id: u64 },
+
+    #[sdk_event(code = 13)]
+    VoteDelegated {
+        delegator: Address,
+        action: Action,
+        #[cbor(optional)]
+        delegate: Option<Address>,
+    },
+
+    #[sdk_event(code = 14)]
+    ProposalExecuted { id: u32, executor: Address },
+
+    #[sdk_event(code = 15)]
+    EscrowCreated { id: u64, creator: Address },
+
+    #[sdk_event(code = 16)]
+    EscrowReleased { id: u64, caller: Address },
+
+    #[sdk_event(code = 17)]
+    EscrowRefunded { id: u64, caller: Address },
+
+    #[sdk_event(code = 18)]
+    StreamCreated { id: u64, sender: Address },
+
+    #[sdk_event(code = 19)]
+    StreamCancelled { id: u64, canceller: Address },
 }
 
 /// Gas costs.
@@ -132,6 +264,10 @@ pub struct GasCosts {
 
     // GB: gas cost for all mint/burn/whitelist/blacklist/editrole etc manage stable coin.
     pub tx_managest: u64,
+
+    /// Gas cost of setting the profile metadata of an account.
+    #[cbor(optional)]
+    pub tx_set_metadata: u64,
 }
 
 /// Parameters for the accounts module.
@@ -150,8 +286,95 @@ pub struct Parameters {
     #[cbor(optional)]
     pub debug_disable_nonce_check: bool,
 
+    /// Maximum delta that the transaction nonce can be in the future from the current nonce to
+    /// still be accepted during transaction checks. A value of `0` requires sequential nonces.
+    ///
+    /// Transactions with future nonces do not advance the nonce during checks, so that senders
+    /// can submit a window of sequential transactions in any order. Future nonces are not tracked,
+    /// so multiple transactions using the same future nonce are all accepted during checks even
+    /// though at most one of them can be executed.
+    #[cbor(optional)]
+    pub max_check_nonce_future_delta: u64,
+
     #[cbor(optional)]
     pub denomination_infos: BTreeMap<token::Denomination, types::DenominationInfo>,
+
+    /// Maximum number of rounds for which a session key can be registered. A value of `0`
+    /// disables session keys.
+    #[cbor(optional)]
+    pub max_session_key_rounds: u64,
+
+    /// Distribution of the fees collected in each block.
+    #[cbor(optional)]
+    pub fee_distribution: types::FeeDistribution,
+
+    /// Minimum non-zero amount that can be transferred, per denomination.
+    #[cbor(optional)]
+    pub min_transfer_amounts: BTreeMap<token::Denomination, u128>,
+
+    /// Maximum amount that accounts holding a role may transfer per day, per denomination.
+    /// Accounts are only limited in case all of their roles are, the most permissive limit
+    /// applies.
+    #[cbor(optional)]
+    pub transfer_limits: BTreeMap<role::Role, BTreeMap<token::Denomination, u128>>,
+
+    /// Quorums (in percent) of proposals with the given action. Actions without a configured
+    /// quorum require all voters to vote.
+    #[cbor(optional)]
+    pub quorums: BTreeMap<Action, u8>,
+
+    /// Share (in percent) of voting weight that needs to abstain for proposals with the given
+    /// action to be cancelled. Actions without a configured abstain quorum are cancelled when
+    /// half of the voters abstain.
+    #[cbor(optional)]
+    pub abstain_quorums: BTreeMap<Action, u8>,
+
+    /// Deposit required to submit a proposal. It is refunded to the submitter when the proposal
+    /// passes or is cancelled and burned when it is rejected.
+    #[cbor(optional)]
+    pub proposal_deposit: Option<token::BaseUnits>,
+
+    /// Methods that accounts holding a role may not call, e.g. `accounts.Transfer`. Patterns
+    /// ending with `*` match all methods with the given prefix, e.g. `evm.*`. Accounts may not
+    /// call a method in case any of their roles restricts it.
+    #[cbor(optional)]
+    pub method_restrictions: BTreeMap<role::Role, Vec<String>>,
+
+    /// Whether invariant checks should also sum up the balances of all accounts instead of only
+    /// relying on the running totals. This requires a scan of all balances.
+    #[cbor(optional)]
+    pub deep_invariant_checks: bool,
+
+    /// Whether the votes of each voter are kept once a proposal is finalized so that they can be
+    /// audited later. Otherwise they are discarded to save space.
+    #[cbor(optional)]
+    pub retain_proposal_votes: bool,
+
+    /// Maximum number of proposals that can be active at the same time. A value of `0` means
+    /// that the number is not limited.
+    #[cbor(optional)]
+    pub max_active_proposals: u32,
+
+    /// Maximum number of active proposals of a single submitter. A value of `0` means that the
+    /// number is not limited.
+    #[cbor(optional)]
+    pub max_active_proposals_per_submitter: u32,
+
+    /// Roles that may submit proposals with the given action. Actions without a configured role
+    /// use the default proposer role of the action.
+    #[cbor(optional)]
+    pub proposer_roles: BTreeMap<Action, role::Role>,
+
+    /// Roles that may vote on proposals with the given action. Actions without a configured role
+    /// use the default voter role of the action.
+    #[cbor(optional)]
+    pub voter_roles: BTreeMap<Action, role::Role>,
+
+    /// Rates at which fees paid in non-native denominations are converted into the native
+    /// denomination. The common pool receives such fees and pays their native equivalent into
+    /// the fee accumulator instead, so that only native fees are disbursed.
+    #[cbor(optional)]
+    pub fee_conversion_rates: BTreeMap<token::Denomination, types::FeeConversionRate>,
 }
 
 /// Errors emitted during rewards parameter validation.
@@ -159,18 +382,65 @@ pub struct Parameters {
 pub enum ParameterValidationError {
     #[error("debug option used: {0}")]
     DebugOptionUsed(String),
+    #[error("fee distribution shares exceed 100%")]
+    InvalidFeeDistribution,
+    #[error("invalid denomination info: {0}")]
+    InvalidDenominationInfo(token::Denomination),
+    #[error("invalid quorum for action {0:?}")]
+    InvalidQuorum(Action),
+    #[error("invalid method restriction: {0}")]
+    InvalidMethodRestriction(String),
+    #[error("invalid role mapping for action {0:?}")]
+    InvalidActionRole(Action),
+    #[error("invalid fee conversion rate: {0}")]
+    InvalidFeeConversionRate(token::Denomination),
 }
 
 impl module::Parameters for Parameters {
     type Error = ParameterValidationError;
 
-    #[cfg(not(feature = "unsafe-allow-debug"))]
     fn validate_basic(&self) -> Result<(), Self::Error> {
+        #[cfg(not(feature = "unsafe-allow-debug"))]
         if self.debug_disable_nonce_check {
             return Err(ParameterValidationError::DebugOptionUsed(
                 "debug_disable_nonce_check".to_string(),
             ));
         }
+        if !self.fee_distribution.is_valid() {
+            return Err(ParameterValidationError::InvalidFeeDistribution);
+        }
+        for (denomination, info) in &self.denomination_infos {
+            if !info.is_valid() {
+                return Err(ParameterValidationError::InvalidDenominationInfo(
+                    denomination.clone(),
+                ));
+            }
+        }
+        for (action, quorum) in self.quorums.iter().chain(&self.abstain_quorums) {
+            if !QUORUM_ACTIONS.contains(action) || *quorum > 100 {
+                return Err(ParameterValidationError::InvalidQuorum(*action));
+            }
+        }
+        for (action, role) in self.proposer_roles.iter().chain(&self.voter_roles) {
+            if !is_valid_action_role(*action, *role) {
+                return Err(ParameterValidationError::InvalidActionRole(*action));
+            }
+        }
+        for (denomination, rate) in &self.fee_conversion_rates {
+            if denomination.is_native() || !rate.is_valid() {
+                return Err(ParameterValidationError::InvalidFeeConversionRate(
+                    denomination.clone(),
+                ));
+            }
+        }
+        for pattern in self.method_restrictions.values().flatten() {
+            let prefix = pattern.strip_suffix('*').unwrap_or(pattern);
+            if pattern.is_empty() || prefix.contains('*') {
+                return Err(ParameterValidationError::InvalidMethodRestriction(
+                    pattern.clone(),
+                ));
+            }
+        }
 
         Ok(())
     }
@@ -190,6 +460,9 @@ pub struct Genesis {
 /// Interface that can be called from other modules.
 pub trait API {
     /// Transfer an amount from one account to the other.
+    ///
+    /// The checks of user transfers apply, so the transfer fails in case either account is
+    /// frozen, a transfer hook rejects it or the source account's transfer limit is exceeded.
     fn transfer<C: Context>(
         ctx: &mut C,
         from: Address,
@@ -197,6 +470,19 @@ pub trait API {
         amount: &token::BaseUnits,
     ) -> Result<(), Error>;
 
+    /// Transfer an amount from one account to the other without applying the frozen, vesting and
+    /// transfer limit checks of user transfers.
+    ///
+    /// This is meant for modules returning funds they hold on behalf of an account, e.g. refunds
+    /// of failed consensus operations, which must not fail due to the state of the recipient.
+    /// Transfer hooks are still invoked, see [`TransferHook`] for what they must not reject.
+    fn transfer_unchecked<C: Context>(
+        ctx: &mut C,
+        from: Address,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error>;
+
     /// Mint new tokens, increasing the total supply.
     fn mint<C: Context>(ctx: &mut C, to: Address, amount: &token::BaseUnits) -> Result<(), Error>;
 
@@ -215,14 +501,35 @@ pub trait API {
     fn get_and_increment_proposal_id<S: storage::Store>(state: S) -> Result<u32, Error>;
     fn insert_proposal<S: storage::Store>(state: S, proposal: types::Proposal) -> Result<(), Error>;
 
-    fn get_voter_with_action(action: Action) -> Option<Role>;
-    fn get_proposer_with_action(action: Action) -> Option<Role>;
+    /// Role that may vote on proposals with the given action.
+    fn get_voter_with_action<S: storage::Store>(state: S, action: Action) -> Option<Role>;
+    /// Role that may submit proposals with the given action.
+    fn get_proposer_with_action<S: storage::Store>(state: S, action: Action) -> Option<Role>;
     //Sifei: added for quorum, role counter
     fn get_quorum<S: storage::Store>(state: S, action:Action) -> Result<u8, Error>;
     fn set_quorum<S: storage::Store>(state: S, action:Action, quorum: u8) -> Result<(), Error>;
+    /// Share (in percent) of voting weight that needs to abstain to cancel a proposal.
+    fn get_abstain_quorum<S: storage::Store>(state: S, action: Action) -> Result<u8, Error>;
+    /// Sets the share (in percent) of voting weight that needs to abstain to cancel a proposal.
+    fn set_abstain_quorum<S: storage::Store>(
+        state: S,
+        action: Action,
+        quorum: u8,
+    ) -> Result<(), Error>;
     fn get_voters_num_with_action<S: storage::Store>(state: S, action: Action) -> Result<u16, Error>;
 
+    /// Voting weight of the address when voting with the given role. Defaults to one.
+    fn get_voter_weight<S: storage::Store>(state: S, address: Address, role: role::Role) -> u16;
+    /// Sets the voting weight of the address when voting with the given role.
+    fn set_voter_weight<S: storage::Store>(state: S, address: Address, role: role::Role, weight: u16);
+    /// Total voting weight of the voters for the given action.
+    fn get_voters_weight_with_action<S: storage::Store>(state: S, action: Action) -> Result<u64, Error>;
+
     fn add_address_to_roles<S: storage::Store>(state: S, address: Address, role: role::Role) -> Result<(), Error>;
+    /// Removes an address from the addresses of a role, decrementing the role's counter.
+    fn remove_address_from_roles<S: storage::Store>(state: S, address: Address, role: role::Role);
+    /// Removes the role from the roles held by the address.
+    fn remove_role_from_address<S: storage::Store>(state: S, address: Address, role: role::Role);
 
     fn add_role_to_address<S: storage::Store>(state: S, address: Address, role: role::Role);
     fn get_addrsno_in_role<S: storage::Store>(state: S, role: role::Role) -> u16;
@@ -231,9 +538,25 @@ pub trait API {
 
     fn set_role<S: storage::Store>(state: S, address: Address, role: role::Role);
     fn get_role<S: storage::Store>(state: S, address: Address) -> Result<role::Role, Error>;
+    /// All roles held by the address. Addresses without any other role hold the User role.
+    fn get_roles<S: storage::Store>(state: S, address: Address) -> BTreeSet<role::Role>;
+    /// Whether the address holds the given role.
+    fn has_role<S: storage::Store>(state: S, address: Address, role: role::Role) -> bool;
     fn set_initstatus<S: storage::Store>(state: S, address: Address, init: bool);
     fn get_initstatus<S: storage::Store>(state: S, address: Address) -> Result<bool, Error>;
 
+    /// Whether the contract at the given address has been paused through a governance proposal.
+    fn is_contract_paused<S: storage::Store>(state: S, address: Address) -> bool;
+
+    /// Whether the account at the given address has been frozen through a governance proposal.
+    fn is_frozen<S: storage::Store>(state: S, address: Address) -> bool;
+
+    /// Removes the account's zero balances and, in case the account holds no other state, its
+    /// metadata. Pruning never changes the observable state of the account.
+    ///
+    /// Returns whether any state has been removed.
+    fn prune_account<S: storage::Store>(state: S, address: Address) -> bool;
+
     /// Sets an account's balance of the given denomination.
     ///
     /// # Warning
@@ -248,6 +571,14 @@ pub trait API {
         denomination: token::Denomination,
     ) -> Result<u128, Error>;
 
+    /// Fetch the amount of the given denomination that is locked in an account by its vesting
+    /// schedule in the current round.
+    fn get_locked_balance<C: Context>(
+        ctx: &mut C,
+        address: Address,
+        denomination: &token::Denomination,
+    ) -> u128;
+
     /// Ensures that the given account has at least the specified balance.
     fn ensure_balance<S: storage::Store>(
         state: S,
@@ -268,6 +599,33 @@ pub trait API {
         address: Address,
     ) -> Result<types::AccountBalances, Error>;
 
+    /// Fetch the amount of the given denomination that `spender` may transfer on behalf of
+    /// `owner`.
+    fn get_allowance<S: storage::Store>(
+        state: S,
+        owner: Address,
+        spender: Address,
+        denomination: &token::Denomination,
+    ) -> u128;
+
+    /// Allow `spender` to transfer up to the given amount on behalf of `owner`, replacing any
+    /// previous allowance of the same denomination.
+    fn approve<C: Context>(
+        ctx: &mut C,
+        owner: Address,
+        spender: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error>;
+
+    /// Transfer an amount on behalf of `from`, spending the allowance given to `spender`.
+    fn transfer_from<C: Context>(
+        ctx: &mut C,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error>;
+
     /// Fetch addresses.
     fn get_addresses<S: storage::Store>(
         state: S,
@@ -279,6 +637,12 @@ pub trait API {
         state: S,
     ) -> Result<BTreeMap<token::Denomination, u128>, Error>;
 
+    /// Fetch the total supply of the given denomination.
+    fn get_total_supply<S: storage::Store>(
+        state: S,
+        denomination: token::Denomination,
+    ) -> Result<u128, Error>;
+
     /// Sets the total supply for the given denomination.
     ///
     /// # Warning
@@ -292,6 +656,13 @@ pub trait API {
         denomination: &token::Denomination,
     ) -> Result<types::DenominationInfo, Error>;
 
+    /// Ensures that the given non-zero amount is not below the minimum transfer amount of its
+    /// denomination.
+    fn ensure_min_transfer_amount<S: storage::Store>(
+        state: S,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error>;
+
     /// Move amount from address into fee accumulator.
     fn move_into_fee_accumulator<C: Context>(
         ctx: &mut C,
@@ -306,6 +677,15 @@ pub trait API {
         amount: &token::BaseUnits,
     ) -> Result<(), modules::core::Error>;
 
+    /// Refund part of the fee pre-paid by the current transaction (e.g. for unused gas) to the
+    /// account that paid it, i.e. the first signer or, in case the fee was paid from a fee grant,
+    /// the granter. Refunds to a granter are credited back to the fee grant and refunds of fees
+    /// paid in a denomination with a conversion rate are exchanged back through the common pool.
+    fn refund_fee<C: TxContext>(
+        ctx: &mut C,
+        amount: &token::BaseUnits,
+    ) -> Result<(), modules::core::Error>;
+
     /// Check transaction signer account nonces.
     /// Return payer address.
     fn check_signer_nonces<C: Context>(
@@ -320,6 +700,136 @@ pub trait API {
     ) -> Result<(), modules::core::Error>;
 }
 
+/// Hooks invoked by the accounts module around every transfer, mint and burn.
+///
+/// Hooks are registered by the runtime via `Runtime::TransferHooks`. Returning an error from any
+/// of the hooks fails the whole operation.
+///
+/// Hooks also run for refunds made through [`API::transfer_unchecked`], which are not allowed to
+/// fail. Hooks must therefore never veto transfers out of module-owned accounts.
+pub trait TransferHook {
+    /// Called before the amount is transferred, allowing the hook to veto the transfer.
+    fn before_transfer<C: Context>(
+        _ctx: &mut C,
+        _from: Address,
+        _to: Address,
+        _amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        // Default implementation allows all transfers.
+        Ok(())
+    }
+
+    /// Called after the amount has been transferred.
+    fn after_transfer<C: Context>(
+        _ctx: &mut C,
+        _from: Address,
+        _to: Address,
+        _amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        // Default implementation doesn't do anything.
+        Ok(())
+    }
+
+    /// Called before the amount is minted, allowing the hook to veto the mint.
+    fn before_mint<C: Context>(
+        _ctx: &mut C,
+        _to: Address,
+        _amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        // Default implementation allows all mints.
+        Ok(())
+    }
+
+    /// Called after the amount has been minted.
+    fn after_mint<C: Context>(
+        _ctx: &mut C,
+        _to: Address,
+        _amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        // Default implementation doesn't do anything.
+        Ok(())
+    }
+
+    /// Called before the amount is burned, allowing the hook to veto the burn.
+    fn before_burn<C: Context>(
+        _ctx: &mut C,
+        _from: Address,
+        _amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        // Default implementation allows all burns.
+        Ok(())
+    }
+
+    /// Called after the amount has been burned.
+    fn after_burn<C: Context>(
+        _ctx: &mut C,
+        _from: Address,
+        _amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        // Default implementation doesn't do anything.
+        Ok(())
+    }
+}
+
+#[impl_for_tuples(30)]
+impl TransferHook for Tuple {
+    fn before_transfer<C: Context>(
+        ctx: &mut C,
+        from: Address,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        for_tuples!( #( Tuple::before_transfer(ctx, from, to, amount)?; )* );
+        Ok(())
+    }
+
+    fn after_transfer<C: Context>(
+        ctx: &mut C,
+        from: Address,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        for_tuples!( #( Tuple::after_transfer(ctx, from, to, amount)?; )* );
+        Ok(())
+    }
+
+    fn before_mint<C: Context>(
+        ctx: &mut C,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        for_tuples!( #( Tuple::before_mint(ctx, to, amount)?; )* );
+        Ok(())
+    }
+
+    fn after_mint<C: Context>(
+        ctx: &mut C,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        for_tuples!( #( Tuple::after_mint(ctx, to, amount)?; )* );
+        Ok(())
+    }
+
+    fn before_burn<C: Context>(
+        ctx: &mut C,
+        from: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        for_tuples!( #( Tuple::before_burn(ctx, from, amount)?; )* );
+        Ok(())
+    }
+
+    fn after_burn<C: Context>(
+        ctx: &mut C,
+        from: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        for_tuples!( #( Tuple::after_burn(ctx, from, amount)?; )* );
+        Ok(())
+    }
+}
+
 /// State schema constants.
 pub mod state {
     /// Map of account addresses to account metadata.
@@ -333,6 +843,46 @@ pub mod state {
     pub const ROLES: &[u8] = &[0x04];
     /// Map of proposal id to addresses.
     pub const PROPOSALS: &[u8] = &[0x05];
+    /// Map of session key addresses to session key information.
+    pub const SESSION_KEYS: &[u8] = &[0x06];
+    /// Set of addresses of EVM contracts paused through governance proposals.
+    pub const PAUSED_CONTRACTS: &[u8] = &[0x07];
+    /// Map of owner addresses to map of spender addresses to map of denominations to allowances.
+    pub const ALLOWANCES: &[u8] = &[0x08];
+    /// Map of granter addresses to map of grantee addresses to fee grants.
+    pub const FEE_GRANTS: &[u8] = &[0x09];
+    /// Set of ids of active proposals.
+    pub const ACTIVE_PROPOSALS: &[u8] = &[0x0a];
+    /// Map of addresses to map of roles to voting weights.
+    pub const VOTER_WEIGHTS: &[u8] = &[0x0b];
+    /// Map of entity addresses to the addresses receiving their fee disbursements.
+    pub const REWARD_ADDRESSES: &[u8] = &[0x0c];
+    /// Map of multisig addresses to multisig accounts.
+    pub const MULTISIGS: &[u8] = &[0x0d];
+    /// Map of multisig addresses to map of ids to multisig transactions.
+    pub const MULTISIG_TRANSACTIONS: &[u8] = &[0x0e];
+    /// Map of addresses to amounts transferred in the current transfer limit window.
+    pub const TRANSFER_VOLUMES: &[u8] = &[0x0f];
+    /// Map of addresses to map of actions to the addresses voting on their behalf.
+    pub const VOTE_DELEGATIONS: &[u8] = &[0x10];
+    /// Map of addresses to the number of activity entries recorded for them.
+    pub const ACTIVITY_COUNTERS: &[u8] = &[0x11];
+    /// Map of addresses to map of activity sequence numbers to activity entries.
+    pub const ACTIVITY: &[u8] = &[0x12];
+    /// Map of denominations to the sum of all account balances.
+    pub const BALANCE_TOTALS: &[u8] = &[0x13];
+    /// Set of time-limited whitelistings, keyed by their expiry round and address.
+    pub const WHITELIST_EXPIRIES: &[u8] = &[0x14];
+    /// Map of escrow ids to escrows.
+    pub const ESCROWS: &[u8] = &[0x15];
+    /// Id of the next escrow.
+    pub const NEXT_ESCROW_ID: &[u8] = &[0x16];
+    /// Map of payment stream ids to payment streams.
+    pub const STREAMS: &[u8] = &[0x17];
+    /// Id of the next payment stream.
+    pub const NEXT_STREAM_ID: &[u8] = &[0x18];
+    /// Map of addresses to the number of active proposals they submitted.
+    pub const ACTIVE_PROPOSAL_COUNTS: &[u8] = &[0x19];
 }
 
 
@@ -344,6 +894,15 @@ pub static ADDRESS_COMMON_POOL: Lazy<Address> =
 /// Module's address that has the fee accumulator.
 pub static ADDRESS_FEE_ACCUMULATOR: Lazy<Address> =
     Lazy::new(|| Address::from_module(MODULE_NAME, "fee-accumulator"));
+/// Module's address that holds the deposits of active proposals.
+pub static ADDRESS_PROPOSAL_DEPOSITS: Lazy<Address> =
+    Lazy::new(|| Address::from_module(MODULE_NAME, "proposal-deposits"));
+/// Module's address that holds the funds of open escrows.
+pub static ADDRESS_ESCROW: Lazy<Address> =
+    Lazy::new(|| Address::from_module(MODULE_NAME, "escrow"));
+/// Module's address that holds the funds of open payment streams.
+pub static ADDRESS_STREAMS: Lazy<Address> =
+    Lazy::new(|| Address::from_module(MODULE_NAME, "streams"));
 
 /// This is needed to properly iterate over the BALANCES map.
 #[derive(Clone, PartialEq, PartialOrd, Eq, Ord)]
@@ -395,735 +954,3318 @@ impl std::convert::TryFrom<&[u8]> for AddressWithRole{
     }
 }
 
-impl Module {
-    /// Add given amount of tokens to the specified account's balance.
-    fn add_amount<S: storage::Store>(
-        state: S,
-        addr: Address,
-        amount: &token::BaseUnits,
-    ) -> Result<(), Error> {
-        let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let balances = storage::PrefixStore::new(store, &state::BALANCES);
-        let mut account = storage::TypedStore::new(storage::PrefixStore::new(balances, &addr));
-        let mut value: u128 = account.get(amount.denomination()).unwrap_or_default();
+/// Key of the active proposal index. Ids are encoded in big-endian so that they iterate in order.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+struct ProposalId(u32);
 
-        value = value
-            .checked_add(amount.amount())
-            .ok_or(Error::InvalidArgument)?;
-        account.insert(amount.denomination(), value);
-        Ok(())
+impl std::convert::TryFrom<&[u8]> for ProposalId {
+    type Error = std::array::TryFromSliceError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Ok(ProposalId(u32::from_be_bytes(bytes.try_into()?)))
     }
+}
 
-    /// Subtract given amount of tokens from the specified account's balance.
-    fn sub_amount<S: storage::Store>(
-        state: S,
-        addr: Address,
-        amount: &token::BaseUnits,
-    ) -> Result<(), Error> {
-        let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let balances = storage::PrefixStore::new(store, &state::BALANCES);
-        let mut account = storage::TypedStore::new(storage::PrefixStore::new(balances, &addr));
-        let mut value: u128 = account.get(amount.denomination()).unwrap_or_default();
+/// Key of the whitelist expiry index. Rounds are encoded in big-endian so that whitelistings
+/// iterate in the order in which they expire.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+struct WhitelistExpiry(u64, Address);
 
-        value = value
-            .checked_sub(amount.amount())
-            .ok_or(Error::InsufficientBalance)?;
-        account.insert(amount.denomination(), value);
-        Ok(())
+impl WhitelistExpiry {
+    fn encode(&self) -> Vec<u8> {
+        [&self.0.to_be_bytes()[..], self.1.as_ref()].concat()
     }
+}
 
-    /// Increment the total supply for the given amount.
-    fn inc_total_supply<S: storage::Store>(
-        state: S,
-        amount: &token::BaseUnits,
-    ) -> Result<(), Error> {
-        let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let mut total_supplies =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::TOTAL_SUPPLY));
-        let mut total_supply: u128 = total_supplies
-            .get(amount.denomination())
-            .unwrap_or_default();
+impl std::convert::TryFrom<&[u8]> for WhitelistExpiry {
+    type Error = crate::types::address::Error;
 
-        total_supply = total_supply
-            .checked_add(amount.amount())
-            .ok_or(Error::InvalidArgument)?;
-        total_supplies.insert(amount.denomination(), total_supply);
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != 8 + Address::SIZE {
+            return Err(crate::types::address::Error::MalformedAddress);
+        }
+        let (round, address) = bytes.split_at(8);
+        let round = u64::from_be_bytes(round.try_into().expect("length checked above"));
+        Ok(WhitelistExpiry(round, Address::from_bytes(address)?))
+    }
+}
+
+impl Module {
+    /// Whether the address is derived from a module and can not spend its balance on its own.
+    ///
+    /// Only the owning module may transfer to such addresses, anything sent there by users would
+    /// be lost.
+    pub fn is_reserved_address(address: Address) -> bool {
+        [
+            *ADDRESS_FEE_ACCUMULATOR,
+            *ADDRESS_PROPOSAL_DEPOSITS,
+            *ADDRESS_ESCROW,
+            *ADDRESS_STREAMS,
+            *modules::consensus_accounts::ADDRESS_PENDING_WITHDRAWAL,
+            *modules::consensus_accounts::ADDRESS_PENDING_DELEGATION,
+        ]
+        .contains(&address)
+    }
+
+    /// Ensure that users are not transferring to a reserved address.
+    fn ensure_not_reserved(to: Address) -> Result<(), Error> {
+        if Self::is_reserved_address(to) {
+            return Err(Error::ReservedAddress);
+        }
         Ok(())
     }
 
-    /// Decrement the total supply for the given amount.
-    fn dec_total_supply<S: storage::Store>(
-        state: S,
-        amount: &token::BaseUnits,
-    ) -> Result<(), Error> {
-        let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let mut total_supplies =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::TOTAL_SUPPLY));
-        let mut total_supply: u128 = total_supplies
-            .get(amount.denomination())
-            .unwrap_or_default();
-
-        total_supply = total_supply
-            .checked_sub(amount.amount())
-            .ok_or(Error::InsufficientBalance)?;
-        total_supplies.insert(amount.denomination(), total_supply);
-        Ok(())
-    }
-
-    /// Get all balances.
-    fn get_all_balances<S: storage::Store>(
-        state: S,
-    ) -> Result<BTreeMap<Address, BTreeMap<token::Denomination, u128>>, Error> {
-        let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let balances = storage::TypedStore::new(storage::PrefixStore::new(store, &state::BALANCES));
-
-        // Unfortunately, we can't just return balances.iter().collect() here,
-        // because the stored format doesn't match -- we need this workaround
-        // instead.
-
-        let balmap: BTreeMap<AddressWithDenomination, u128> = balances.iter().collect();
-
-        let mut b: BTreeMap<Address, BTreeMap<token::Denomination, u128>> = BTreeMap::new();
-
-        for (addrden, amt) in &balmap {
-            let addr = &addrden.0;
-            let den = &addrden.1;
-
-            // Fetch existing account's balances or insert blank ones.
-            let addr_bals = b.entry(*addr).or_insert_with(BTreeMap::new);
-
-            // Add to given denomination's balance or insert it if new.
-            addr_bals
-                .entry(den.clone())
-                .and_modify(|a| *a += amt)
-                .or_insert_with(|| *amt);
-        }
-
-        Ok(b)
-    }
-}
-
-/// A fee accumulator that stores fees from all transactions in a block.
-#[derive(Default)]
-pub struct FeeAccumulator {
-    pub total_fees: BTreeMap<token::Denomination, u128>,
-}
-
-impl FeeAccumulator {
-    /// Add given fee to the accumulator.
-    pub fn add(&mut self, fee: &token::BaseUnits) {
-        let current = self
-            .total_fees
-            .entry(fee.denomination().clone())
-            .or_default();
-
-        *current = current.checked_add(fee.amount()).unwrap(); // Should never overflow.
-    }
-
-    /// Subtract given fee from the accumulator.
-    fn sub(&mut self, fee: &token::BaseUnits) -> Result<(), Error> {
-        let current = self
-            .total_fees
-            .entry(fee.denomination().clone())
-            .or_default();
-
-        *current = current
-            .checked_sub(fee.amount())
-            .ok_or(Error::InsufficientBalance)?;
-        Ok(())
-    }
-}
-
-/// Context key for the fee accumulator.
-pub const CONTEXT_KEY_FEE_ACCUMULATOR: &str = "accounts.FeeAccumulator";
-
-impl API for Module {
-    fn transfer<C: Context>(
+    /// Transfer an amount from one account to the other, attaching the given memo to the emitted
+    /// transfer event.
+    fn transfer_with_memo<C: Context>(
         ctx: &mut C,
         from: Address,
         to: Address,
         amount: &token::BaseUnits,
+        memo: Option<Vec<u8>>,
     ) -> Result<(), Error> {
         if ctx.is_check_only() {
             return Ok(());
         }
 
+        if Self::is_frozen(ctx.runtime_state(), from) || Self::is_frozen(ctx.runtime_state(), to) {
+            return Err(Error::AccountFrozen);
+        }
+        <C::Runtime as Runtime>::TransferHooks::before_transfer(ctx, from, to, amount)?;
+
         // Subtract from source account.
+        Self::ensure_spendable(ctx, from, amount)?;
+        Self::use_transfer_limit(ctx, from, amount)?;
         Self::sub_amount(ctx.runtime_state(), from, amount)?;
         // Add to destination account.
         Self::add_amount(ctx.runtime_state(), to, amount)?;
 
+        Self::record_activity(ctx, from);
+        if to != from {
+            Self::record_activity(ctx, to);
+        }
+
         // Emit a transfer event.
         ctx.emit_event(Event::Transfer {
             from,
             to,
             amount: amount.clone(),
+            memo,
             // GB: insert information for transfer/mint/burn later if necessary.
             // txseq: 1234567890,
             // txinfo: "testinfo".to_string(),
         });
 
-        Ok(())
-    }
-
-    fn mint<C: Context>(ctx: &mut C, to: Address, amount: &token::BaseUnits) -> Result<(), Error> {
-        // Add to destination account.
-        Self::add_amount(ctx.runtime_state(), to, amount)?;
-
-        // Increase total supply.
-        Self::inc_total_supply(ctx.runtime_state(), amount)?;
-
-        // Emit a mint event.
-        ctx.emit_event(Event::Mint {
-            owner: to,
-            amount: amount.clone(),
-        });
-
-        Ok(())
-    }
-
-    fn burn<C: Context>(
-        ctx: &mut C,
-        from: Address,
-        amount: &token::BaseUnits,
-    ) -> Result<(), Error> {
-        // Remove from target account.
-        Self::sub_amount(ctx.runtime_state(), from, amount)?;
-
-        // Decrease total supply.
-        Self::dec_total_supply(ctx.runtime_state(), amount)
-            .expect("target account had enough balance so total supply should not underflow");
-
-        // Emit a burn event.
-        ctx.emit_event(Event::Burn {
-            owner: from,
-            amount: amount.clone(),
-        });
-
-        Ok(())
+        <C::Runtime as Runtime>::TransferHooks::after_transfer(ctx, from, to, amount)
     }
 
-    fn set_nonce<S: storage::Store>(state: S, address: Address, nonce: u64) {
+    /// Fetch the session key registered under the given address, if any.
+    pub fn get_session_key<S: storage::Store>(
+        state: S,
+        address: Address,
+    ) -> Option<types::SessionKeyInfo> {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let mut accounts =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
-        let mut account: types::Account = accounts.get(address).unwrap_or_default();
-        account.nonce = nonce;
-        accounts.insert(&address, account);
+        let session_keys =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::SESSION_KEYS));
+        session_keys.get(address)
     }
 
-    fn get_nonce<S: storage::Store>(state: S, address: Address) -> Result<u64, Error> {
+    fn set_session_key<S: storage::Store>(
+        state: S,
+        address: Address,
+        session_key: Option<types::SessionKeyInfo>,
+    ) {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let accounts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
-        let account: types::Account = accounts.get(address).unwrap_or_default();
-        Ok(account.nonce)
+        let mut session_keys =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::SESSION_KEYS));
+        match session_key {
+            Some(session_key) => session_keys.insert(address, session_key),
+            None => session_keys.remove(address),
+        }
     }
 
-    fn get_proposal_id<S: storage::Store>(state: S) -> Result<u32, Error> {
+    fn set_allowance<S: storage::Store>(
+        state: S,
+        owner: Address,
+        spender: Address,
+        amount: &token::BaseUnits,
+    ) {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let proposals =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
-
-        // sifei: please refer this proposal_id to keep quorums to the state.
-        const PROPOSAL_COUNTER_KEY: &[u8] = b"proposal_id";
-        let counter: u32 = proposals.get(PROPOSAL_COUNTER_KEY).unwrap_or(0);
-        Ok(counter)
+        let allowances = storage::PrefixStore::new(store, &state::ALLOWANCES);
+        let owner_allowances = storage::PrefixStore::new(allowances, &owner);
+        let mut spender_allowances =
+            storage::TypedStore::new(storage::PrefixStore::new(owner_allowances, &spender));
+        if amount.amount() == 0 {
+            spender_allowances.remove(amount.denomination());
+        } else {
+            spender_allowances.insert(amount.denomination(), amount.amount());
+        }
     }
 
-    fn get_proposal<S: storage::Store>(state: S, id: u32) -> Result<types::Proposal, Error> {
+    /// Fetch the fee grant given by the granter to the grantee, if any.
+    pub fn get_fee_grant<S: storage::Store>(
+        state: S,
+        granter: Address,
+        grantee: Address,
+    ) -> Option<types::FeeGrantInfo> {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let proposals =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
-
-        let proposal_id_bytes = id.to_le_bytes();
-        let proposal: types::Proposal = proposals.get(proposal_id_bytes).unwrap_or_default();
-
-        Ok(proposal)
+        let fee_grants = storage::PrefixStore::new(store, &state::FEE_GRANTS);
+        let granter_grants =
+            storage::TypedStore::new(storage::PrefixStore::new(fee_grants, &granter));
+        granter_grants.get(grantee)
     }
 
-
-    fn get_and_increment_proposal_id<S: storage::Store>(state: S) -> Result<u32, Error> {
+    fn set_fee_grant<S: storage::Store>(
+        state: S,
+        granter: Address,
+        grantee: Address,
+        fee_grant: Option<types::FeeGrantInfo>,
+    ) {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let mut proposals =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
+        let fee_grants = storage::PrefixStore::new(store, &state::FEE_GRANTS);
+        let mut granter_grants =
+            storage::TypedStore::new(storage::PrefixStore::new(fee_grants, &granter));
+        match fee_grant {
+            Some(fee_grant) => granter_grants.insert(grantee, fee_grant),
+            None => granter_grants.remove(grantee),
+        }
+    }
 
-        const PROPOSAL_COUNTER_KEY: &[u8] = b"proposal_id";
-        let mut counter: u32 = proposals.get(PROPOSAL_COUNTER_KEY).unwrap_or(0);
-        counter = counter.checked_add(1).ok_or(Error::CounterOverflow)?;
-        proposals.insert(PROPOSAL_COUNTER_KEY, counter);
+    /// Update the nonces of the signers of a transaction that passed checks.
+    ///
+    /// Only signers using their current nonce advance it. Transactions with future nonces
+    /// do not advance it, so that the transactions preceding them are still accepted. Future
+    /// nonces are not recorded, so further transactions reusing them pass checks as well.
+    fn update_checked_signer_nonces<C: Context>(ctx: &mut C, auth_info: &AuthInfo) {
+        let params = Self::params(ctx.runtime_state());
+        let mut store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+        let mut accounts =
+            storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::ACCOUNTS));
+        for si in auth_info.signer_info.iter() {
+            let address = si.address_spec.address();
+            let mut account: types::Account = accounts.get(address).unwrap_or_default();
+            if si.nonce != account.nonce && !params.debug_disable_nonce_check {
+                continue;
+            }
 
-        Ok(counter)
+            account.nonce = account.nonce.saturating_add(1);
+            accounts.insert(&address, account);
+        }
     }
 
+    /// Determine the account that pays the fee of a transaction authenticated by the given payer.
+    ///
+    /// In case the transaction names a fee granter, the granter must have given the payer a grant
+    /// that covers the fee in the current round.
+    fn fee_payer<C: Context>(
+        ctx: &mut C,
+        auth_info: &AuthInfo,
+        payer: Address,
+    ) -> Result<Address, modules::core::Error> {
+        let granter = match auth_info.fee_granter {
+            Some(granter) => granter,
+            None => return Ok(payer),
+        };
 
-    fn insert_proposal<S: storage::Store>(state: S, proposal: types::Proposal) -> Result<(), Error> {
-        let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let mut proposals =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
-
-        let proposal_id_bytes = proposal.id.to_le_bytes();
-        proposals.insert(proposal_id_bytes, proposal);
+        let round = ctx.runtime_header().round;
+        let fee_grant = Self::get_fee_grant(ctx.runtime_state(), granter, payer)
+            .ok_or(modules::core::Error::NotAuthenticated)?;
+        if !fee_grant.covers(&auth_info.fee.amount, round) {
+            return Err(modules::core::Error::InsufficientFeeBalance);
+        }
+        if Self::get_role(ctx.runtime_state(), granter).unwrap_or_default()
+            == Role::BlacklistedUser
+            || Self::is_frozen(ctx.runtime_state(), granter)
+        {
+            return Err(modules::core::Error::NotAuthenticated);
+        }
 
-        Ok(())
+        Ok(granter)
     }
 
+    /// Spend the given fee from the fee grant given by the granter to the grantee.
+    fn use_fee_grant<S: storage::Store>(
+        mut state: S,
+        granter: Address,
+        grantee: Address,
+        fee: &token::BaseUnits,
+    ) {
+        let mut fee_grant = Self::get_fee_grant(&mut state, granter, grantee)
+            .expect("fee grant must exist"); // Already checked.
+        let remaining = fee_grant.spend_limit.amount() - fee.amount(); // Already checked.
+        fee_grant.spend_limit =
+            token::BaseUnits::new(remaining, fee_grant.spend_limit.denomination().clone());
+        Self::set_fee_grant(state, granter, grantee, Some(fee_grant));
+    }
 
-    fn get_voter_with_action(action: Action) -> Option<Role> {
-        match action {
-            Action::NoAction => None,
-            Action::SetRoles => Some(Role::Admin),
-            Action::Mint => Some(Role::MintVoter),
-            Action::Burn => Some(Role::BurnVoter),
-            Action::Whitelist => Some(Role::WhitelistVoter),
-            Action::Blacklist => Some(Role::BlacklistVoter),
-            Action::Config => Some(Role::Admin),
-        }
+    /// Credit a refunded fee back to the fee grant given by the granter to the grantee.
+    fn refund_fee_grant<S: storage::Store>(
+        mut state: S,
+        granter: Address,
+        grantee: Address,
+        fee: &token::BaseUnits,
+    ) {
+        // The grant may have been revoked in the meantime.
+        let mut fee_grant = match Self::get_fee_grant(&mut state, granter, grantee) {
+            Some(fee_grant) => fee_grant,
+            None => return,
+        };
+        let limit = fee_grant.spend_limit.amount().saturating_add(fee.amount());
+        fee_grant.spend_limit =
+            token::BaseUnits::new(limit, fee_grant.spend_limit.denomination().clone());
+        Self::set_fee_grant(state, granter, grantee, Some(fee_grant));
     }
 
-    fn get_proposer_with_action(action: Action) -> Option<Role> {
-        match action {
-            Action::NoAction => None,
-            Action::SetRoles => Some(Role::Admin),
-            Action::Mint => Some(Role::MintProposer),
-            Action::Burn => Some(Role::BurnProposer),
-            Action::Whitelist => Some(Role::WhitelistProposer),
-            Action::Blacklist => Some(Role::BlacklistProposer),
-            Action::Config => Some(Role::Admin),
-        }
+    /// Native equivalent of the fee in case it is paid in a denomination with a conversion rate.
+    fn convert_fee<S: storage::Store>(
+        state: S,
+        fee: &token::BaseUnits,
+    ) -> Option<token::BaseUnits> {
+        let params = Self::params(state);
+        let rate = params.fee_conversion_rates.get(fee.denomination())?;
+        Some(token::BaseUnits::new(
+            rate.convert(fee.amount()),
+            token::Denomination::NATIVE,
+        ))
     }
 
-    //Sifei: get_quorum for Burn/Mint/Whitelist/Blacklist/Config
-    fn get_quorum<S: storage::Store>(state: S, action: Action) -> Result<u8, Error>  {
-        let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let proposals =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
- 
-        const PROPOSAL_MINT_KEY:  &[u8] = b"proposal_mint_quorum";
-        const PROPOSAL_BURN_KEY:  &[u8] = b"proposal_burn_quorum";
-        const PROPOSAL_WHITELIST_KEY:  &[u8] = b"proposal_whitelist_quorum";
-        const PROPOSAL_BLACKLIST_KEY:  &[u8] = b"proposal_blacklist_quorum";
-        const PROPOSAL_CONFIG_KEY:  &[u8] = b"proposal_config_quorum";
-
-        // sifei: get quorum
-        let quorum: u8 = match action {
-            Action::Mint => proposals.get(PROPOSAL_MINT_KEY).unwrap_or(100),
-            Action::Burn => proposals.get(PROPOSAL_BURN_KEY).unwrap_or(100),
-            Action::Whitelist => proposals.get(PROPOSAL_WHITELIST_KEY).unwrap_or(100),
-            Action::Blacklist => proposals.get(PROPOSAL_BLACKLIST_KEY).unwrap_or(100),
-            Action::Config => proposals.get(PROPOSAL_CONFIG_KEY).unwrap_or(100),
-            Action::SetRoles => proposals.get(PROPOSAL_CONFIG_KEY).unwrap_or(100),
-            _ => return Err(Error::NotFound),
+    /// Move the fee paid by the payer into the fee accumulator. Fees in a denomination with a
+    /// conversion rate are exchanged with the common pool first.
+    fn pay_fee<C: Context>(
+        ctx: &mut C,
+        payer: Address,
+        fee: &token::BaseUnits,
+    ) -> Result<(), modules::core::Error> {
+        let native_fee = match Self::convert_fee(ctx.runtime_state(), fee) {
+            Some(native_fee) if !ctx.is_simulation() => native_fee,
+            _ => return Self::move_into_fee_accumulator(ctx, payer, fee),
         };
-        Ok(quorum)
+
+        Self::ensure_balance(ctx.runtime_state(), *ADDRESS_COMMON_POOL, &native_fee)
+            .and_then(|_| Self::ensure_spendable(ctx, payer, fee))
+            .and_then(|_| Self::sub_amount(ctx.runtime_state(), payer, fee))
+            .and_then(|_| Self::add_amount(ctx.runtime_state(), *ADDRESS_COMMON_POOL, fee))
+            .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
+        Self::move_into_fee_accumulator(ctx, *ADDRESS_COMMON_POOL, &native_fee)
     }
 
-    //Sifei: set_quorum for Burn/Mint/Whitelist/Blacklist/Config
-    fn set_quorum<S: storage::Store>(state: S, action: Action, quorum:u8) -> Result<(), Error> {
+    fn set_contract_paused<S: storage::Store>(state: S, address: Address, paused: bool) {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let mut proposals =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
-        const PROPOSAL_MINT_KEY:  &[u8] = b"proposal_mint_quorum";
-        const PROPOSAL_BURN_KEY:  &[u8] = b"proposal_burn_quorum";
-        const PROPOSAL_WHITELIST_KEY:  &[u8] = b"proposal_whitelist_quorum";
-        const PROPOSAL_BLACKLIST_KEY:  &[u8] = b"proposal_blacklist_quorum";
-        const PROPOSAL_CONFIG_KEY:  &[u8] = b"proposal_config_quorum";
-
-        match action {
-            Action::Mint => proposals.insert(PROPOSAL_MINT_KEY, quorum),
-            Action::Burn => proposals.insert(PROPOSAL_BURN_KEY, quorum),
-            Action::Whitelist => proposals.insert(PROPOSAL_WHITELIST_KEY, quorum),
-            Action::Blacklist => proposals.insert(PROPOSAL_BLACKLIST_KEY, quorum),
-            Action::Config => proposals.insert(PROPOSAL_CONFIG_KEY, quorum),
-            _ => return Err(Error::NotFound),
-        };
-        Ok(())
+        let mut paused_contracts =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PAUSED_CONTRACTS));
+        if paused {
+            paused_contracts.insert(address, true);
+        } else {
+            paused_contracts.remove(address);
+        }
     }
 
-    fn set_role<S: storage::Store>(state: S, address: Address, role: role::Role) {
+    fn set_frozen<S: storage::Store>(state: S, address: Address, frozen: bool) {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
         let mut accounts =
             storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
         let mut account: types::Account = accounts.get(address).unwrap_or_default();
-        account.role = role;
+        account.frozen = frozen;
         accounts.insert(&address, account);
     }
 
+    /// Set the round after which the whitelisting of the address expires, keeping the expiry
+    /// index consistent. Whitelistings without an expiry do not expire.
+    fn set_whitelist_expiry<S: storage::Store>(state: S, address: Address, expiry: Option<u64>) {
+        let mut store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut accounts =
+            storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::ACCOUNTS));
+        let mut account: types::Account = accounts.get(address).unwrap_or_default();
+        let previous = std::mem::replace(&mut account.whitelist_expiry, expiry);
+        accounts.insert(&address, account);
 
+        let mut expiries =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::WHITELIST_EXPIRIES));
+        if let Some(round) = previous {
+            expiries.remove(WhitelistExpiry(round, address).encode());
+        }
+        if let Some(round) = expiry {
+            expiries.insert(WhitelistExpiry(round, address).encode(), true);
+        }
+    }
 
-    /// GB: add an address to some role (e.g. MintVoter), so this role holds all addresses of such a role.
-    /// this function should be used together with remove_address_from_roles, to maintain the sequ8 counter.
-    fn add_address_to_roles<S: storage::Store>(state: S, address: Address, role: role::Role) -> Result<(), Error> {
-        // GB: the following to insert the address to the corresponding role vec.
+    /// Role of the address in the given round. Whitelistings that have expired by then count as
+    /// the User role, even before they have been revoked at the end of their expiry round.
+    pub fn get_effective_role<S: storage::Store>(state: S, address: Address, round: u64) -> Role {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let roles_store = storage::PrefixStore::new(store, &state::ROLES);
-        let role_bytes = role.marshal_binary();
-        let mut role_store =
-             storage::TypedStore::new(storage::PrefixStore::new(roles_store, &role_bytes));
-
-        // GB: define a map to save the sequence of specific role's addresses.
-        let role_str = role.to_string();
-        let seq_key = "seq".to_string() + &role_str;
-        let seq_key_bytes = seq_key.as_bytes();
-        let mut counter: u32 = role_store.get(&seq_key_bytes).unwrap_or(0);
-        counter = counter.checked_add(1).ok_or(Error::CounterOverflow)?;
-        role_store.insert(&seq_key_bytes, counter);
-
-        // GBTODO: decrease the counter while removing an address from a role.
-        role_store.insert(&address, counter);
-
-        Ok(())
+        let accounts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
+        let account: types::Account = accounts.get(address).unwrap_or_default();
+        match account.whitelist_expiry {
+            Some(expiry) if account.role == Role::WhitelistedUser && round > expiry => Role::User,
+            _ => account.role,
+        }
     }
 
-    //Sifei: save address with role to store
-    /// GB: map one address to multi roles, but we only allow one role for an address at current stage.
-    /// we remove the address from ROLES storage while new role comes to avoid inconsistency.
-    fn add_role_to_address<S: storage::Store>(state: S, address: Address, role: role::Role) {
-        // GB: the following to insert the address to the corresponding role vec.
-        let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let roles_store = storage::PrefixStore::new(store, &state::ROLES);
-
-        let mut role_account =
-             storage::TypedStore::new(storage::PrefixStore::new(roles_store, &address));
+    /// Revoke all whitelistings expiring in the given round or earlier.
+    fn expire_whitelistings<C: Context>(ctx: &mut C, round: u64) {
+        let expired: Vec<WhitelistExpiry> = {
+            let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+            let expiries = storage::TypedStore::new(storage::PrefixStore::new(
+                store,
+                &state::WHITELIST_EXPIRIES,
+            ));
+            expiries
+                .iter()
+                .map(|(key, _): (WhitelistExpiry, bool)| key)
+                .take_while(|key| key.0 <= round)
+                .collect()
+        };
 
-        // GB: remove this address's storage (all role->bool mappings) first.
-        // this is just a workaround, still need to save a lot of address even after the users are set to User.
-        for role in Role::iter() {
-            let rawu8role = role.marshal_binary();
-            role_account.remove(&rawu8role);
+        for WhitelistExpiry(_, address) in expired {
+            Self::set_whitelist_expiry(ctx.runtime_state(), address, None);
+            // The address may have lost the role in the meantime.
+            if Self::revoke_role(ctx.runtime_state(), address, Role::WhitelistedUser).is_ok() {
+                ctx.emit_event(Event::RoleRevoked {
+                    address,
+                    role: Role::WhitelistedUser,
+                });
+            }
         }
+    }
 
-        if role != Role::User {
-            // Update the map in the store.
-            let rawu8 = role.marshal_binary();
-            role_account.insert(rawu8, true);
+    /// Revoke the role from the address, keeping all role indices consistent.
+    fn revoke_role<S: storage::Store>(
+        mut state: S,
+        address: Address,
+        role: role::Role,
+    ) -> Result<(), Error> {
+        if role == Role::User || !Self::has_role(&mut state, address, role) {
+            return Err(Error::InvalidArgument);
         }
-    }
 
+        Self::remove_role_from_address(&mut state, address, role);
+        Self::remove_address_from_roles(&mut state, address, role);
+        Self::set_voter_weight(&mut state, address, role, 1);
 
-    //Sifei: get no of addresses with requested role
-    fn get_addrsno_in_role<S: storage::Store>(state: S, role: role::Role) -> u16 {
-        //Option variable
-        let rstaddresses = Self::get_addresses_in_role (state, role);
-        let addressno = match rstaddresses {
-            Ok(addresses) => addresses.len(),
-            Err(_) => 0,
-        };
-        addressno as u16
+        // The account's primary role falls back to one of the remaining roles.
+        if Self::get_role(&mut state, address)? == role {
+            Self::set_role(&mut state, address, Role::User);
+            let remaining = Self::get_roles(&mut state, address);
+            let primary = remaining.into_iter().next().unwrap_or_default();
+            Self::set_role(&mut state, address, primary);
+        }
+
+        Ok(())
     }
 
-    //Sifei: get addresses with requested role
-    /*    
-    GB: get all addresses of some role by iterating all the addresses in the ROLES storage, 
-    it would become quite cubersome when a lot of addresses are handled.
-    */
-    fn get_addresses_in_role<S: storage::Store>(state: S, role: role::Role) -> Result<Vec<Address>, Error> {
+    /// Fetch the ids of all active proposals in increasing order.
+    fn get_active_proposal_ids<S: storage::Store>(state: S) -> Vec<u32> {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let role_addresses: BTreeMap<AddressWithRole, bool> =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ROLES))
+        let active_proposals =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACTIVE_PROPOSALS));
+        active_proposals
             .iter()
-            .collect();
-
-        //get addresses 
-        Ok(role_addresses
-            .into_keys()
-            .filter(|ra| ra.1 == role.marshal_binary())
-            .map(|ra| ra.0)
-            .collect())
+            .map(|(id, _): (ProposalId, bool)| id.0)
+            .collect()
     }
 
-    //Sifei: get no of voters for action
-    fn get_voters_num_with_action<S: storage::Store>(state: S, action: Action) -> Result<u16, Error> {
-        let  voters= match action {
-              Action::Mint => Self::get_addrsno_in_role(state, role::Role::MintVoter),
-              Action::Burn => Self::get_addrsno_in_role(state, role::Role::BurnVoter),
-              Action::Whitelist => Self::get_addrsno_in_role(state, role::Role::WhitelistVoter),
-              Action::Blacklist => Self::get_addrsno_in_role(state, role::Role::BlacklistVoter),
-              Action::Config => Self::get_addrsno_in_role(state, role::Role::Admin),
-              Action::SetRoles=> Self::get_addrsno_in_role(state, role::Role::Admin),
-              Action::NoAction=> return Err(Error::NotFound),
-        };
-        Ok(voters as u16)
+    /// Fetch the number of active proposals submitted by the given address.
+    fn get_active_proposal_count<S: storage::Store>(state: S, submitter: Address) -> u32 {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let counts = storage::TypedStore::new(storage::PrefixStore::new(
+            store,
+            &state::ACTIVE_PROPOSAL_COUNTS,
+        ));
+        counts.get(submitter).unwrap_or_default()
     }
 
+    fn set_active_proposal_count<S: storage::Store>(state: S, submitter: Address, count: u32) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut counts = storage::TypedStore::new(storage::PrefixStore::new(
+            store,
+            &state::ACTIVE_PROPOSAL_COUNTS,
+        ));
+        if count == 0 {
+            counts.remove(submitter);
+        } else {
+            counts.insert(submitter, count);
+        }
+    }
 
-    fn get_role<S: storage::Store>(state: S, address: Address) -> Result<role::Role, Error> {
+    /// Fetch the profile metadata of the given account, if any.
+    pub fn get_metadata<S: storage::Store>(
+        state: S,
+        address: Address,
+    ) -> Option<types::AccountMetadata> {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
         let accounts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
         let account: types::Account = accounts.get(address).unwrap_or_default();
-        Ok(account.role)
+        account.metadata
     }
 
-    fn set_initstatus<S: storage::Store>(state: S, address: Address, init: bool) {
+    /// Set the profile metadata of the given account. Empty metadata is removed.
+    fn set_metadata<S: storage::Store>(
+        state: S,
+        address: Address,
+        metadata: Option<types::AccountMetadata>,
+    ) {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
         let mut accounts =
             storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
         let mut account: types::Account = accounts.get(address).unwrap_or_default();
-        account.init = init;
+        account.metadata = metadata.filter(|metadata| metadata != &Default::default());
         accounts.insert(&address, account);
     }
 
-    fn get_initstatus<S: storage::Store>(state: S, address: Address) -> Result<bool, Error> {
+    /// Fetch the vesting schedule of the given account, if any.
+    pub fn get_vesting_schedule<S: storage::Store>(
+        state: S,
+        address: Address,
+    ) -> Option<types::VestingSchedule> {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
         let accounts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
         let account: types::Account = accounts.get(address).unwrap_or_default();
-        Ok(account.init)
+        account.vesting
     }
 
+    /// Fetch the address receiving the fees disbursed to the given entity address. Entities
+    /// without a registered reward address receive the fees themselves.
+    pub fn get_reward_address<S: storage::Store>(state: S, address: Address) -> Address {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let reward_addresses =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::REWARD_ADDRESSES));
+        reward_addresses.get(address).unwrap_or(address)
+    }
 
-    fn set_balance<S: storage::Store>(state: S, address: Address, amount: &token::BaseUnits) {
+    fn set_reward_address<S: storage::Store>(state: S, address: Address, reward: Option<Address>) {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let balances = storage::PrefixStore::new(store, &state::BALANCES);
-        let mut account = storage::TypedStore::new(storage::PrefixStore::new(balances, &address));
-        account.insert(amount.denomination(), amount.amount());
+        let mut reward_addresses =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::REWARD_ADDRESSES));
+        match reward {
+            Some(reward) if reward != address => reward_addresses.insert(address, reward),
+            _ => reward_addresses.remove(address),
+        }
     }
 
-    fn get_balance<S: storage::Store>(
+    /// Method authorizer enforcing the method restrictions of the roles held by the given caller,
+    /// see `Parameters::method_restrictions`.
+    pub fn method_authorizer<S: storage::Store>(
+        mut state: S,
+        caller: Address,
+    ) -> Box<dyn Fn(&str) -> bool> {
+        let params = Self::params(&mut state);
+        let mut restricted: Vec<String> = Vec::new();
+        if !params.method_restrictions.is_empty() {
+            // Session keys act with the roles of their owner.
+            let caller = Self::get_session_key(&mut state, caller)
+                .map(|session_key| session_key.owner)
+                .unwrap_or(caller);
+            for role in Self::get_roles(&mut state, caller) {
+                if let Some(patterns) = params.method_restrictions.get(&role) {
+                    restricted.extend(patterns.iter().cloned());
+                }
+            }
+        }
+
+        Box::new(move |method| {
+            !restricted
+                .iter()
+                .any(|pattern| match pattern.strip_suffix('*') {
+                    Some(prefix) => method.starts_with(prefix),
+                    None => method == pattern.as_str(),
+                })
+        })
+    }
+
+    /// Fetch the addresses voting on behalf of the given address, per action.
+    pub fn get_vote_delegations<S: storage::Store>(
         state: S,
         address: Address,
-        denomination: token::Denomination,
-    ) -> Result<u128, Error> {
+    ) -> BTreeMap<Action, Address> {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let balances = storage::PrefixStore::new(store, &state::BALANCES);
-        let account = storage::TypedStore::new(storage::PrefixStore::new(balances, &address));
-
-        Ok(account.get(&denomination).unwrap_or_default())
+        let delegations =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::VOTE_DELEGATIONS));
+        delegations.get(address).unwrap_or_default()
     }
 
-    fn get_balances<S: storage::Store>(
+    fn set_vote_delegations<S: storage::Store>(
         state: S,
         address: Address,
-    ) -> Result<types::AccountBalances, Error> {
+        delegations: BTreeMap<Action, Address>,
+    ) {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let balances = storage::PrefixStore::new(store, &state::BALANCES);
-        let account = storage::TypedStore::new(storage::PrefixStore::new(balances, &address));
+        let mut store =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::VOTE_DELEGATIONS));
+        if delegations.is_empty() {
+            store.remove(address);
+        } else {
+            store.insert(address, delegations);
+        }
+    }
 
-        Ok(types::AccountBalances {
-            balances: account.iter().collect(),
-        })
+    /// Fetch the number of activity entries recorded for the given address.
+    pub fn get_activity_counter<S: storage::Store>(state: S, address: Address) -> u64 {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let counters =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACTIVITY_COUNTERS));
+        counters.get(address).unwrap_or_default()
     }
 
-    fn get_addresses<S: storage::Store>(
+    /// Fetch the activity entry of the given address with the given sequence number, if any.
+    pub fn get_activity<S: storage::Store>(
         state: S,
-        denomination: token::Denomination,
-    ) -> Result<Vec<Address>, Error> {
+        address: Address,
+        seq: u64,
+    ) -> Option<types::Activity> {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let balances: BTreeMap<AddressWithDenomination, Quantity> =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::BALANCES))
-                .iter()
-                .collect();
+        let activity = storage::PrefixStore::new(store, &state::ACTIVITY);
+        let entries = storage::TypedStore::new(storage::PrefixStore::new(activity, &address));
+        entries.get(seq.to_be_bytes())
+    }
 
-        Ok(balances
-            .into_keys()
-            .filter(|bal| bal.1 == denomination)
-            .map(|bal| bal.0)
-            .collect())
+    /// Append an entry for the current round and transaction to the activity index of the given
+    /// address.
+    fn record_activity<C: Context>(ctx: &mut C, address: Address) {
+        let round = ctx.runtime_header().round;
+        let tx_index = ctx
+            .value::<usize>(CONTEXT_KEY_TX_INDEX)
+            .get()
+            .map(|index| *index as u32);
+        let seq = Self::get_activity_counter(ctx.runtime_state(), address) + 1;
+
+        let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+        let mut counters =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACTIVITY_COUNTERS));
+        counters.insert(address, seq);
+
+        let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+        let activity = storage::PrefixStore::new(store, &state::ACTIVITY);
+        let mut entries = storage::TypedStore::new(storage::PrefixStore::new(activity, &address));
+        entries.insert(
+            seq.to_be_bytes(),
+            types::Activity {
+                seq,
+                round,
+                tx_index,
+            },
+        );
     }
 
-    fn get_total_supplies<S: storage::Store>(
+    /// Fetch the multisig account at the given address, if any.
+    pub fn get_multisig<S: storage::Store>(
         state: S,
-    ) -> Result<BTreeMap<token::Denomination, u128>, Error> {
+        address: Address,
+    ) -> Option<types::MultisigAccount> {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let ts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::TOTAL_SUPPLY));
+        let multisigs =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::MULTISIGS));
+        multisigs.get(address)
+    }
 
-        Ok(ts.iter().collect())
+    fn set_multisig<S: storage::Store>(
+        state: S,
+        address: Address,
+        multisig: types::MultisigAccount,
+    ) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut multisigs =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::MULTISIGS));
+        multisigs.insert(address, multisig);
     }
 
-    fn set_total_supply<S: storage::Store>(state: S, amount: &token::BaseUnits) {
+    /// Fetch the multisig transaction with the given id, if any.
+    pub fn get_multisig_transaction<S: storage::Store>(
+        state: S,
+        multisig: Address,
+        id: u64,
+    ) -> Option<types::MultisigTransaction> {
         let store = storage::PrefixStore::new(state, &MODULE_NAME);
-        let mut total_supplies =
-            storage::TypedStore::new(storage::PrefixStore::new(store, &state::TOTAL_SUPPLY));
-        total_supplies.insert(amount.denomination(), amount.amount());
+        let transactions = storage::PrefixStore::new(store, &state::MULTISIG_TRANSACTIONS);
+        let multisig_transactions =
+            storage::TypedStore::new(storage::PrefixStore::new(transactions, &multisig));
+        multisig_transactions.get(id.to_be_bytes())
     }
 
-    fn get_denomination_info<S: storage::Store>(
+    fn set_multisig_transaction<S: storage::Store>(
         state: S,
-        denomination: &token::Denomination,
-    ) -> Result<types::DenominationInfo, Error> {
-        let params = Self::params(state);
-        params
-            .denomination_infos
-            .get(denomination)
-            .cloned()
-            .ok_or(Error::NotFound)
+        multisig: Address,
+        id: u64,
+        transaction: types::MultisigTransaction,
+    ) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let transactions = storage::PrefixStore::new(store, &state::MULTISIG_TRANSACTIONS);
+        let mut multisig_transactions =
+            storage::TypedStore::new(storage::PrefixStore::new(transactions, &multisig));
+        multisig_transactions.insert(id.to_be_bytes(), transaction);
     }
 
-    fn move_into_fee_accumulator<C: Context>(
+    /// Fetch the open escrow with the given id, if any.
+    pub fn get_escrow<S: storage::Store>(state: S, id: u64) -> Option<types::Escrow> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let escrows = storage::TypedStore::new(storage::PrefixStore::new(store, &state::ESCROWS));
+        escrows.get(id.to_be_bytes())
+    }
+
+    fn set_escrow<S: storage::Store>(state: S, id: u64, escrow: Option<types::Escrow>) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut escrows =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ESCROWS));
+        match escrow {
+            Some(escrow) => escrows.insert(id.to_be_bytes(), escrow),
+            None => escrows.remove(id.to_be_bytes()),
+        }
+    }
+
+    /// Allocate the id of a new escrow.
+    fn next_escrow_id<S: storage::Store>(state: S) -> Result<u64, Error> {
+        let mut store = storage::TypedStore::new(storage::PrefixStore::new(state, &MODULE_NAME));
+        let id: u64 = store.get(state::NEXT_ESCROW_ID).unwrap_or_default();
+        store.insert(
+            state::NEXT_ESCROW_ID,
+            id.checked_add(1).ok_or(Error::CounterOverflow)?,
+        );
+        Ok(id)
+    }
+
+    /// Close the escrow with the given id, paying out the escrowed funds to the given address.
+    fn close_escrow<C: Context>(
+        ctx: &mut C,
+        id: u64,
+        escrow: &types::Escrow,
+        to: Address,
+    ) -> Result<(), Error> {
+        Self::pay_out(ctx, *ADDRESS_ESCROW, to, &escrow.amount)?;
+        Self::set_escrow(ctx.runtime_state(), id, None);
+
+        Ok(())
+    }
+
+    /// Pay out funds held by one of the module's accounts.
+    ///
+    /// Payouts skip the spending checks of the source account as the funds already left the
+    /// original owner's account when they were locked. Frozen recipients are still rejected and
+    /// the transfer hooks run as for any other user-initiated transfer.
+    fn pay_out<C: Context>(
         ctx: &mut C,
         from: Address,
+        to: Address,
         amount: &token::BaseUnits,
-    ) -> Result<(), modules::core::Error> {
-        if ctx.is_simulation() {
+    ) -> Result<(), Error> {
+        if amount.amount() == 0 {
             return Ok(());
         }
+        if Self::is_frozen(ctx.runtime_state(), to) {
+            return Err(Error::AccountFrozen);
+        }
+        <C::Runtime as Runtime>::TransferHooks::before_transfer(ctx, from, to, amount)?;
 
-        Self::sub_amount(ctx.runtime_state(), from, amount)
-            .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
+        Self::sub_amount(ctx.runtime_state(), from, amount)?;
+        Self::add_amount(ctx.runtime_state(), to, amount)?;
+        Self::record_activity(ctx, to);
+        ctx.emit_event(Event::Transfer {
+            from,
+            to,
+            amount: amount.clone(),
+            memo: None,
+        });
 
-        ctx.value::<FeeAccumulator>(CONTEXT_KEY_FEE_ACCUMULATOR)
-            .or_default()
-            .add(amount);
+        <C::Runtime as Runtime>::TransferHooks::after_transfer(ctx, from, to, amount)
+    }
+
+    /// Fetch the payment stream with the given id, if any.
+    pub fn get_stream<S: storage::Store>(state: S, id: u64) -> Option<types::Stream> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let streams = storage::TypedStore::new(storage::PrefixStore::new(store, &state::STREAMS));
+        streams.get(id.to_be_bytes())
+    }
+
+    fn set_stream<S: storage::Store>(state: S, id: u64, stream: Option<types::Stream>) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut streams =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::STREAMS));
+        match stream {
+            Some(stream) => streams.insert(id.to_be_bytes(), stream),
+            None => streams.remove(id.to_be_bytes()),
+        }
+    }
+
+    /// Allocate the id of a new payment stream.
+    fn next_stream_id<S: storage::Store>(state: S) -> Result<u64, Error> {
+        let mut store = storage::TypedStore::new(storage::PrefixStore::new(state, &MODULE_NAME));
+        let id: u64 = store.get(state::NEXT_STREAM_ID).unwrap_or_default();
+        store.insert(
+            state::NEXT_STREAM_ID,
+            id.checked_add(1).ok_or(Error::CounterOverflow)?,
+        );
+        Ok(id)
+    }
+
+    /// Record the approval of a multisig transaction by the given member and execute the
+    /// transaction once it has been approved by enough current members.
+    fn approve_multisig_transaction<C: Context>(
+        ctx: &mut C,
+        multisig_address: Address,
+        id: u64,
+        member: Address,
+    ) -> Result<(), Error> {
+        let multisig =
+            Self::get_multisig(ctx.runtime_state(), multisig_address).ok_or(Error::NotFound)?;
+        if !multisig.is_member(&member) {
+            return Err(Error::InvalidRole);
+        }
+        let mut transaction =
+            Self::get_multisig_transaction(ctx.runtime_state(), multisig_address, id)
+                .ok_or(Error::NotFound)?;
+        if transaction.executed {
+            return Err(Error::InvalidState);
+        }
+        if transaction.approvals.contains(&member) {
+            return Err(Error::VoteDup);
+        }
+        transaction.approvals.push(member);
+
+        Self::maybe_execute_multisig_transaction(
+            ctx,
+            multisig_address,
+            &multisig,
+            id,
+            &mut transaction,
+        )?;
+        Self::set_multisig_transaction(ctx.runtime_state(), multisig_address, id, transaction);
 
         Ok(())
     }
 
-    fn move_from_fee_accumulator<C: Context>(
+    /// Execute the multisig transaction in case it has been approved by enough current members,
+    /// returning whether it has been executed.
+    fn maybe_execute_multisig_transaction<C: Context>(
         ctx: &mut C,
-        to: Address,
-        amount: &token::BaseUnits,
-    ) -> Result<(), modules::core::Error> {
-        if ctx.is_simulation() {
+        multisig_address: Address,
+        multisig: &types::MultisigAccount,
+        id: u64,
+        transaction: &mut types::MultisigTransaction,
+    ) -> Result<bool, Error> {
+        // Approvals of former members no longer count.
+        let approvals = transaction
+            .approvals
+            .iter()
+            .filter(|approver| multisig.is_member(approver))
+            .count();
+        if approvals < multisig.threshold as usize {
+            return Ok(false);
+        }
+
+        let transfer = transaction.transfer.clone();
+        Self::transfer_with_memo(
+            ctx,
+            multisig_address,
+            transfer.to,
+            &transfer.amount,
+            transfer.memo,
+        )?;
+        transaction.executed = true;
+        ctx.emit_event(Event::MultisigExecuted {
+            multisig: multisig_address,
+            id,
+        });
+
+        Ok(true)
+    }
+
+    /// Count the vote of the caller on an active proposal, finalizing the proposal in case
+    /// enough votes have been cast.
+    fn tally_vote<C: TxContext>(
+        ctx: &mut C,
+        mut proposal: types::Proposal,
+        caller_address: Address,
+        option: Vote,
+    ) -> Result<(), Error> {
+        let caller_roles = Self::get_roles(ctx.runtime_state(), caller_address);
+
+        // sifei: get_action  (mint/burn/whitelist/blacklist/config/SetRoles)
+        let action = proposal.content.action;
+
+        // GB: if the caller does not hold the role required by the action, then return error.
+        // GBTODO: the voter can not vote twice.
+        let voter_role = match Self::get_voter_with_action(ctx.runtime_state(), action) {
+            Some(role) if caller_roles.contains(&role) => role,
+            _ => return Err(Error::InvalidRole),
+        };
+
+
+        // sifei: define get_quorum from state with action for the following usage.
+        let quorum = Self::get_quorum(ctx.runtime_state(), action)?;
+        if quorum > 100 {
+            return Err(Error::InvalidQuorum);
+        }
+
+
+        // Votes are weighted by the voting weight of each voter, so the totals are sums of
+        // weights rather than head-counts.
+        let voter_total: u64 = Self::get_voters_weight_with_action(ctx.runtime_state(), action)?;
+        // GB: round up to ensure enough votes.
+        let threshold = |quorum: u8| {
+            voter_total
+                .checked_mul(quorum.into())
+                .and_then(|result| result.checked_add(99)) // +99 is equivalent to + (divisor - 1)
+                .map(|result| result / 100)
+                .ok_or(Error::InvalidQuorum)
+        };
+        proposal.add_vote(option);
+        // sifei: if the vote_count exceed the requirements of specific action (mint), 
+        let mut vote_count: u64 = proposal
+            .voteOption
+            .iter()
+            .flatten()
+            .filter(|(_, vote)| **vote == option)
+            .map(|(voter, _)| {
+                u64::from(Self::get_voter_weight(
+                    ctx.runtime_state(),
+                    *voter,
+                    voter_role,
+                ))
+            })
+            .sum();
+        // Voters that did not vote themselves follow the vote of their delegate, if any.
+        let votes = proposal.voteOption.clone().unwrap_or_default();
+        for delegator in Self::get_addresses_in_role(ctx.runtime_state(), voter_role)? {
+            if votes.contains_key(&delegator) {
+                continue;
+            }
+            let delegations = Self::get_vote_delegations(ctx.runtime_state(), delegator);
+            let delegate_vote = delegations.get(&action).and_then(|delegate| votes.get(delegate));
+            if delegate_vote == Some(&option) {
+                vote_count += u64::from(Self::get_voter_weight(
+                    ctx.runtime_state(),
+                    delegator,
+                    voter_role,
+                ));
+            }
+        }
+        if option == Vote::VoteYes {
+            if vote_count >= threshold(quorum)? {
+                // The action is executed separately, see `accounts.ExecuteProposal`. Change the
+                // proposal state.
+                proposal.state = ProposalState::Passed;
+            }
+
+            //saved proposal late
+        } else if  option == Vote::VoteNo {
+            if vote_count >= threshold(100 - quorum)? {
+                // then change the proposal state.
+                proposal.state = ProposalState::Rejected;
+            }
+        } else {
+            // proposal cancelled if enough voters abstain.
+            let abstain_quorum = Self::get_abstain_quorum(ctx.runtime_state(), action)?;
+            if vote_count >= threshold(abstain_quorum)? {
+                proposal.state = ProposalState::Cancelled;
+            }
+        }
+
+        ctx.emit_event(Event::ProposalVoted {
+            id: proposal.id,
+            voter: caller_address,
+            option,
+        });
+        if proposal.state != ProposalState::Active {
+            Self::release_proposal_votes(ctx.runtime_state(), &mut proposal);
+            Self::release_proposal_deposit(ctx, &proposal)?;
+            ctx.emit_event(Event::ProposalFinalized {
+                id: proposal.id,
+                state: proposal.state.clone(),
+            });
+        }
+
+        // finally, save the updated proposal.
+        Self::insert_proposal(ctx.runtime_state(), proposal)?;
+
+        Ok(())
+    }
+
+    /// Execute the action of a passed proposal.
+    fn execute_proposal_action<C: TxContext>(
+        ctx: &mut C,
+        proposal: &types::Proposal,
+    ) -> Result<(), Error> {
+        let action = proposal.content.action;
+        // this is the interface for invoke action mint/burn/whitelist/blacklist/config function.
+        let proposaldata = proposal.content.data.clone();
+        match action {
+            Action::Mint =>  {
+                //get data from proposalData and invoke mint
+                let mintaddress = match proposaldata.address {
+                    None  =>  return Err(Error::NotFound),
+                    Some(addr) => addr,
+                };
+                let mintamount  = match proposaldata.amount {
+                    None =>  return Err(Error::NotFound),
+                    Some(amt) => amt,
+                };
+                Self::mint(ctx, mintaddress, &mintamount)?;
+            },
+            Action::Burn => {
+                //get data from proposalData and invoke burn
+                let burnaddress = match proposaldata.address {
+                    None  =>  return Err(Error::NotFound),
+                    Some(addr) => addr,
+                };
+                let burnamount  = match proposaldata.amount {
+                    None =>  return Err(Error::NotFound),
+                    Some(amt) => amt,
+                };
+                Self::burn(ctx, burnaddress, &burnamount)?;
+            },
+            Action::Whitelist =>  {
+                //get data from proposalData and invoke Whitelist
+                let whitelistaddress = match proposaldata.address {
+                    None  =>  return Err(Error::NotFound),
+                    Some(addr) => addr,
+                };
+
+                //set current role for account
+                Self::set_role(ctx.runtime_state(), whitelistaddress, Role::WhitelistedUser);
+                // Self::add_address_to_roles(ctx.runtime_state(), whitelistaddress, Role::WhitelistedUser)?;
+                //set whitelist role for account
+                Self::add_role_to_address(ctx.runtime_state(), whitelistaddress, Role::WhitelistedUser);
+                Self::set_whitelist_expiry(
+                    ctx.runtime_state(),
+                    whitelistaddress,
+                    proposaldata.whitelist_expiry,
+                );
+            },
+            Action::Blacklist =>  {
+                //get data from proposalData and invoke Blacklist
+                let blacklistaddress = match proposaldata.address {
+                    None  =>  return Err(Error::NotFound),
+                    Some(addr) => addr,
+                };
+
+                //set role for account
+                Self::set_role(ctx.runtime_state(), blacklistaddress, Role::BlacklistedUser);
+                //set blacklist role for account
+                Self::add_role_to_address(ctx.runtime_state(), blacklistaddress, Role::BlacklistedUser);
+            },
+
+            Action::Config => {
+                //get data from proposalData and invoke config
+                if proposaldata.mint_quorum != None {
+                    Self::set_quorum(ctx.runtime_state(), Action::Mint,proposaldata.mint_quorum.unwrap())?;
+                }
+                if proposaldata.burn_quorum != None {
+                    Self::set_quorum(ctx.runtime_state(), Action::Burn,proposaldata.burn_quorum.unwrap())?;
+                }
+                if proposaldata.whitelist_quorum != None {
+                    Self::set_quorum(ctx.runtime_state(), Action::Whitelist,proposaldata.whitelist_quorum.unwrap())?;
+                }
+                if proposaldata.blacklist_quorum != None {
+                    Self::set_quorum(ctx.runtime_state(), Action::Blacklist,proposaldata.blacklist_quorum.unwrap())?;
+                }
+                if proposaldata.config_quorum != None {
+                    Self::set_quorum(ctx.runtime_state(), Action::Config,proposaldata.config_quorum.unwrap())?;
+                }
+                for (action, quorum) in proposaldata.abstain_quorums.unwrap_or_default() {
+                    Self::set_abstain_quorum(ctx.runtime_state(), action, quorum)?;
+                }
+                if let Some(fee_distribution) = proposaldata.fee_distribution {
+                    let mut params = Self::params(ctx.runtime_state());
+                    params.fee_distribution = fee_distribution;
+                    Self::set_params(ctx.runtime_state(), params);
+                }
+                if let Some(denomination_infos) = proposaldata.denomination_infos {
+                    let mut params = Self::params(ctx.runtime_state());
+                    params.denomination_infos.extend(denomination_infos);
+                    Self::set_params(ctx.runtime_state(), params);
+                }
+                if let Some(transfer_limits) = proposaldata.transfer_limits {
+                    let mut params = Self::params(ctx.runtime_state());
+                    for (role, limits) in transfer_limits {
+                        if limits.is_empty() {
+                            params.transfer_limits.remove(&role);
+                        } else {
+                            params.transfer_limits.insert(role, limits);
+                        }
+                    }
+                    Self::set_params(ctx.runtime_state(), params);
+                }
+                if let Some(rates) = proposaldata.fee_conversion_rates {
+                    let mut params = Self::params(ctx.runtime_state());
+                    for (denomination, rate) in rates {
+                        if rate.native_amount == 0 {
+                            params.fee_conversion_rates.remove(&denomination);
+                        } else {
+                            params.fee_conversion_rates.insert(denomination, rate);
+                        }
+                    }
+                    Self::set_params(ctx.runtime_state(), params);
+                }
+                if let Some(proposer_roles) = proposaldata.proposer_roles {
+                    let mut params = Self::params(ctx.runtime_state());
+                    params.proposer_roles.extend(proposer_roles);
+                    Self::set_params(ctx.runtime_state(), params);
+                }
+                if let Some(voter_roles) = proposaldata.voter_roles {
+                    let mut params = Self::params(ctx.runtime_state());
+                    params.voter_roles.extend(voter_roles);
+                    Self::set_params(ctx.runtime_state(), params);
+                }
+                if let Some(params) = proposaldata.core_parameters {
+                    // Parameter changes take effect at a block boundary so that all
+                    // transactions in a block run with the same parameters.
+                    let next_round = ctx.runtime_header().round + 1;
+                    let round = proposaldata.activation_round.unwrap_or_default().max(next_round);
+                    <C::Runtime as Runtime>::Core::schedule_params(ctx, round, params)?;
+                }
+
+            },
+            Action::PauseContract | Action::UnpauseContract => {
+                let contract_address = match proposaldata.address {
+                    None  =>  return Err(Error::NotFound),
+                    Some(addr) => addr,
+                };
+                Self::set_contract_paused(
+                    ctx.runtime_state(),
+                    contract_address,
+                    action == Action::PauseContract,
+                );
+            },
+            Action::SetMultisigMembers => {
+                let multisig_address = proposaldata.address.ok_or(Error::NotFound)?;
+                let mut multisig =
+                    Self::get_multisig(ctx.runtime_state(), multisig_address)
+                        .ok_or(Error::NotFound)?;
+                multisig.members =
+                    proposaldata.multisig_members.ok_or(Error::NotFound)?;
+                multisig.threshold =
+                    proposaldata.multisig_threshold.ok_or(Error::NotFound)?;
+                Self::set_multisig(ctx.runtime_state(), multisig_address, multisig);
+            },
+            Action::SpendCommonPool => {
+                let to = proposaldata.address.ok_or(Error::NotFound)?;
+                let amount = proposaldata.amount.ok_or(Error::NotFound)?;
+                Self::transfer(ctx, *ADDRESS_COMMON_POOL, to, &amount)?;
+            },
+            Action::Freeze | Action::Unfreeze => {
+                let frozen_address = match proposaldata.address {
+                    None  =>  return Err(Error::NotFound),
+                    Some(addr) => addr,
+                };
+                Self::set_frozen(
+                    ctx.runtime_state(),
+                    frozen_address,
+                    action == Action::Freeze,
+                );
+            },
+            Action::NoAction => {
+                // no actions
+            },
+            Action::SetRoles => {
+                //get data from proposalData and SetRoles
+                let editroleaddress = match proposaldata.address {
+                    None  =>  return Err(Error::NotFound),
+                    Some(addr) => addr,
+                };
+                let editrolerole  = match proposaldata.role {
+                    None =>  return Err(Error::NotFound),
+                    Some(rl) => rl,
+                };
+                //set current role for account
+                Self::set_role(ctx.runtime_state(), editroleaddress, editrolerole);
+                //set editrole role for account
+                Self::add_role_to_address(ctx.runtime_state(), editroleaddress, editrolerole);
+                if let Some(weight) = proposaldata.voter_weight {
+                    Self::set_voter_weight(ctx.runtime_state(), editroleaddress, editrolerole, weight);
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Ensure that the active proposal limits allow the submitter to submit another proposal.
+    fn ensure_can_submit_proposal<S: storage::Store>(
+        mut state: S,
+        params: &Parameters,
+        submitter: Address,
+    ) -> Result<(), Error> {
+        if params.max_active_proposals > 0
+            && Self::get_active_proposal_ids(&mut state).len()
+                >= params.max_active_proposals as usize
+        {
+            return Err(Error::TooManyActiveProposals);
+        }
+        if params.max_active_proposals_per_submitter > 0
+            && Self::get_active_proposal_count(&mut state, submitter)
+                >= params.max_active_proposals_per_submitter
+        {
+            return Err(Error::TooManyActiveProposals);
+        }
+        Ok(())
+    }
+
+    /// Discard the votes of a finalized proposal to save space, unless they are retained for
+    /// audits.
+    fn release_proposal_votes<S: storage::Store>(state: S, proposal: &mut types::Proposal) {
+        if !Self::params(state).retain_proposal_votes {
+            proposal.voteOption = None;
+        }
+    }
+
+    /// Release the deposit of a finalized proposal. The deposit is burned in case the proposal
+    /// has been rejected and refunded to the submitter otherwise.
+    fn release_proposal_deposit<C: Context>(
+        ctx: &mut C,
+        proposal: &types::Proposal,
+    ) -> Result<(), Error> {
+        let deposit = match &proposal.deposit {
+            Some(deposit) => deposit,
+            None => return Ok(()),
+        };
+        if proposal.state == ProposalState::Rejected {
+            return Self::burn(ctx, *ADDRESS_PROPOSAL_DEPOSITS, deposit);
+        }
+
+        // Refunds bypass the transfer checks so that a frozen or limited submitter can not
+        // prevent the proposal from being finalized.
+        Self::sub_amount(ctx.runtime_state(), *ADDRESS_PROPOSAL_DEPOSITS, deposit)?;
+        Self::add_amount(ctx.runtime_state(), proposal.submitter, deposit)?;
+        ctx.emit_event(Event::Transfer {
+            from: *ADDRESS_PROPOSAL_DEPOSITS,
+            to: proposal.submitter,
+            amount: deposit.clone(),
+            memo: None,
+        });
+
+        Ok(())
+    }
+
+    /// Ensure that spending the given amount would not touch the account's locked balance.
+    fn ensure_spendable<C: Context>(
+        ctx: &mut C,
+        address: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        let locked = Self::get_locked_balance(ctx, address, amount.denomination());
+        if locked == 0 {
+            return Ok(());
+        }
+        let balance =
+            Self::get_balance(ctx.runtime_state(), address, amount.denomination().clone())?;
+        if balance.saturating_sub(amount.amount()) < locked {
+            return Err(Error::InsufficientBalance);
+        }
+        Ok(())
+    }
+
+    /// Action whose quorum applies to proposals with the given action.
+    fn quorum_action(action: Action) -> Option<Action> {
+        match action {
+            Action::Mint
+            | Action::Burn
+            | Action::Whitelist
+            | Action::Blacklist
+            | Action::Config => Some(action),
+            Action::Freeze | Action::Unfreeze => Some(Action::Blacklist),
+            Action::SetRoles
+            | Action::PauseContract
+            | Action::UnpauseContract
+            | Action::SetMultisigMembers
+            | Action::SpendCommonPool => Some(Action::Config),
+            Action::NoAction => None,
+        }
+    }
+
+    /// Role that votes on proposals with the given action unless configured otherwise.
+    fn default_voter_role(action: Action) -> Option<Role> {
+        match action {
+            Action::NoAction => None,
+            Action::SetRoles => Some(Role::Admin),
+            Action::Mint => Some(Role::MintVoter),
+            Action::Burn => Some(Role::BurnVoter),
+            Action::Whitelist => Some(Role::WhitelistVoter),
+            Action::Blacklist | Action::Freeze | Action::Unfreeze => Some(Role::BlacklistVoter),
+            Action::Config => Some(Role::Admin),
+            Action::PauseContract | Action::UnpauseContract => Some(Role::Admin),
+            Action::SetMultisigMembers | Action::SpendCommonPool => Some(Role::Admin),
+        }
+    }
+
+    /// Role that submits proposals with the given action unless configured otherwise.
+    fn default_proposer_role(action: Action) -> Option<Role> {
+        match action {
+            Action::NoAction => None,
+            Action::SetRoles => Some(Role::Admin),
+            Action::Mint => Some(Role::MintProposer),
+            Action::Burn => Some(Role::BurnProposer),
+            Action::Whitelist => Some(Role::WhitelistProposer),
+            Action::Blacklist | Action::Freeze | Action::Unfreeze => {
+                Some(Role::BlacklistProposer)
+            }
+            Action::Config => Some(Role::Admin),
+            Action::PauseContract | Action::UnpauseContract => Some(Role::Admin),
+            Action::SetMultisigMembers | Action::SpendCommonPool => Some(Role::Admin),
+        }
+    }
+
+    /// Daily transfer limit of the address for the given denomination, if any.
+    ///
+    /// In case the address holds multiple roles the most permissive one wins, so there is no limit
+    /// as soon as any of its roles is not limited for the denomination.
+    fn get_transfer_limit<S: storage::Store>(
+        state: S,
+        params: &Parameters,
+        address: Address,
+        denomination: &token::Denomination,
+    ) -> Option<u128> {
+        let mut limit = None;
+        for role in Self::get_roles(state, address) {
+            match params
+                .transfer_limits
+                .get(&role)
+                .and_then(|limits| limits.get(denomination))
+            {
+                Some(role_limit) => limit = limit.max(Some(*role_limit)),
+                None => return None,
+            }
+        }
+        limit
+    }
+
+    /// Record the transfer of the given amount from the address, making sure that it stays
+    /// within the address' daily transfer limit.
+    fn use_transfer_limit<C: Context>(
+        ctx: &mut C,
+        address: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        if params.transfer_limits.is_empty() {
+            return Ok(());
+        }
+        // Module-owned accounts hold the funds of many accounts, so they are not limited.
+        if Self::is_reserved_address(address) || address == *ADDRESS_COMMON_POOL {
+            return Ok(());
+        }
+        let limit = match Self::get_transfer_limit(
+            ctx.runtime_state(),
+            &params,
+            address,
+            amount.denomination(),
+        ) {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+
+        let window = ctx.runtime_header().timestamp / TRANSFER_LIMIT_WINDOW;
+        let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+        let mut volumes =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::TRANSFER_VOLUMES));
+        let mut volume: types::TransferVolume = volumes.get(address).unwrap_or_default();
+        if volume.window != window {
+            volume = types::TransferVolume {
+                window,
+                amounts: BTreeMap::new(),
+            };
+        }
+
+        let transferred = volume
+            .amounts
+            .entry(amount.denomination().clone())
+            .or_default();
+        *transferred = transferred
+            .checked_add(amount.amount())
+            .filter(|transferred| *transferred <= limit)
+            .ok_or(Error::TransferLimitExceeded)?;
+        volumes.insert(address, volume);
+
+        Ok(())
+    }
+
+    /// Address on whose behalf the transaction is made. This is the session key owner in case the
+    /// transaction is signed by a session key, otherwise it is the caller.
+    fn tx_role_caller<C: TxContext>(ctx: &mut C) -> Address {
+        let caller = ctx.tx_caller_address();
+        Self::get_session_key(ctx.runtime_state(), caller)
+            .map(|session_key| session_key.owner)
+            .unwrap_or(caller)
+    }
+
+    /// Add given amount of tokens to the specified account's balance.
+    fn add_amount<S: storage::Store>(
+        mut state: S,
+        addr: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        let store = storage::PrefixStore::new(&mut state, &MODULE_NAME);
+        let balances = storage::PrefixStore::new(store, &state::BALANCES);
+        let mut account = storage::TypedStore::new(storage::PrefixStore::new(balances, &addr));
+        let value: u128 = account.get(amount.denomination()).unwrap_or_default();
+
+        let new_value = value
+            .checked_add(amount.amount())
+            .ok_or(Error::InvalidArgument)?;
+        account.insert(amount.denomination(), new_value);
+
+        Self::update_balance_total(state, amount.denomination(), value, new_value);
+        Ok(())
+    }
+
+    /// Subtract given amount of tokens from the specified account's balance.
+    fn sub_amount<S: storage::Store>(
+        mut state: S,
+        addr: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        let store = storage::PrefixStore::new(&mut state, &MODULE_NAME);
+        let balances = storage::PrefixStore::new(store, &state::BALANCES);
+        let mut account = storage::TypedStore::new(storage::PrefixStore::new(balances, &addr));
+        let value: u128 = account.get(amount.denomination()).unwrap_or_default();
+
+        let new_value = value
+            .checked_sub(amount.amount())
+            .ok_or(Error::InsufficientBalance)?;
+        account.insert(amount.denomination(), new_value);
+
+        Self::update_balance_total(state, amount.denomination(), value, new_value);
+        Ok(())
+    }
+
+    /// Update the running sum of all account balances of the given denomination after a balance
+    /// changed from `old` to `new`.
+    fn update_balance_total<S: storage::Store>(
+        state: S,
+        denomination: &token::Denomination,
+        old: u128,
+        new: u128,
+    ) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut totals =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::BALANCE_TOTALS));
+        let total: u128 = totals.get(denomination).unwrap_or_default();
+        // The sum can only leave the valid range in case the state is already inconsistent, which
+        // is reported by the invariant checks.
+        let total = if new >= old {
+            total.saturating_add(new - old)
+        } else {
+            total.saturating_sub(old - new)
+        };
+        totals.insert(denomination, total);
+    }
+
+    /// Fetch the running sums of all account balances, per denomination.
+    fn get_balance_totals<S: storage::Store>(state: S) -> BTreeMap<token::Denomination, u128> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let totals =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::BALANCE_TOTALS));
+        totals.iter().collect()
+    }
+
+    /// Compute the sums of all account balances, per denomination, by scanning all balances.
+    fn compute_balance_totals<S: storage::Store>(
+        state: S,
+    ) -> Result<BTreeMap<token::Denomination, u128>, Error> {
+        let mut totals: BTreeMap<token::Denomination, u128> = BTreeMap::new();
+        for bals in Self::get_all_balances(state)?.values() {
+            for (den, amt) in bals {
+                totals
+                    .entry(den.clone())
+                    .and_modify(|a| *a += amt)
+                    .or_insert_with(|| *amt);
+            }
+        }
+        Ok(totals)
+    }
+
+    /// Check that the given sums of account balances match the total supplies.
+    fn check_balance_totals(
+        mut computed_ts: BTreeMap<token::Denomination, u128>,
+        total_supplies: &BTreeMap<token::Denomination, u128>,
+    ) -> Result<(), CoreError> {
+        for (den, ts) in total_supplies {
+            // Return error if total supplies have a denomination that we
+            // didn't encounter when computing total supplies based on account
+            // balances.
+            #[allow(clippy::or_fun_call)]
+            let computed = computed_ts
+                .remove(den)
+                .ok_or(CoreError::InvariantViolation(
+                    "unexpected denomination".to_string(),
+                ))?;
+
+            if &computed != ts {
+                // Computed and actual total supplies don't match.
+                return Err(CoreError::InvariantViolation(format!(
+                    "computed and actual total supplies don't match (computed={computed}, actual={ts})",
+                )));
+            }
+        }
+
+        // There should be no remaining denominations in the computed supplies,
+        // because that would mean that accounts have denominations that don't
+        // appear in the total supplies table, which would obviously be wrong.
+        if computed_ts.is_empty() {
+            Ok(())
+        } else {
+            Err(CoreError::InvariantViolation(
+                "encountered denomination that isn't present in total supplies table".to_string(),
+            ))
+        }
+    }
+
+    /// Increment the total supply for the given amount.
+    fn inc_total_supply<S: storage::Store>(
+        state: S,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut total_supplies =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::TOTAL_SUPPLY));
+        let mut total_supply: u128 = total_supplies
+            .get(amount.denomination())
+            .unwrap_or_default();
+
+        total_supply = total_supply
+            .checked_add(amount.amount())
+            .ok_or(Error::InvalidArgument)?;
+        total_supplies.insert(amount.denomination(), total_supply);
+        Ok(())
+    }
+
+    /// Decrement the total supply for the given amount.
+    fn dec_total_supply<S: storage::Store>(
+        state: S,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut total_supplies =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::TOTAL_SUPPLY));
+        let mut total_supply: u128 = total_supplies
+            .get(amount.denomination())
+            .unwrap_or_default();
+
+        total_supply = total_supply
+            .checked_sub(amount.amount())
+            .ok_or(Error::InsufficientBalance)?;
+        total_supplies.insert(amount.denomination(), total_supply);
+        Ok(())
+    }
+
+    /// Get all balances.
+    fn get_all_balances<S: storage::Store>(
+        state: S,
+    ) -> Result<BTreeMap<Address, BTreeMap<token::Denomination, u128>>, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let balances = storage::TypedStore::new(storage::PrefixStore::new(store, &state::BALANCES));
+
+        // Unfortunately, we can't just return balances.iter().collect() here,
+        // because the stored format doesn't match -- we need this workaround
+        // instead.
+
+        let balmap: BTreeMap<AddressWithDenomination, u128> = balances.iter().collect();
+
+        let mut b: BTreeMap<Address, BTreeMap<token::Denomination, u128>> = BTreeMap::new();
+
+        for (addrden, amt) in &balmap {
+            let addr = &addrden.0;
+            let den = &addrden.1;
+
+            // Fetch existing account's balances or insert blank ones.
+            let addr_bals = b.entry(*addr).or_insert_with(BTreeMap::new);
+
+            // Add to given denomination's balance or insert it if new.
+            addr_bals
+                .entry(den.clone())
+                .and_modify(|a| *a += amt)
+                .or_insert_with(|| *amt);
+        }
+
+        Ok(b)
+    }
+
+    /// Get the balances of at most `limit` addresses, starting after the given address.
+    fn get_balances_page<S: storage::Store>(
+        state: S,
+        start_after: Option<Address>,
+        limit: usize,
+    ) -> Vec<types::AddressBalances> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let balances = storage::PrefixStore::new(store, &state::BALANCES);
+
+        let mut it = storage::Store::iter(&balances);
+        if let Some(start_after) = start_after {
+            it.seek(start_after.as_ref());
+        }
+
+        let mut page: Vec<types::AddressBalances> = Vec::new();
+        for (key, value) in it {
+            let AddressWithDenomination(address, denomination) =
+                AddressWithDenomination::try_from(key.as_slice())
+                    .unwrap_or_else(|e| panic!("corrupted storage key: {e}"));
+            if Some(address) == start_after {
+                continue;
+            }
+            if page.last().map(|last| last.address) != Some(address) {
+                if page.len() >= limit {
+                    break;
+                }
+                page.push(types::AddressBalances {
+                    address,
+                    balances: BTreeMap::new(),
+                });
+            }
+            let amount: u128 = cbor::from_slice(&value).unwrap();
+            page.last_mut().unwrap().balances.insert(denomination, amount);
+        }
+
+        page
+    }
+}
+
+/// A fee accumulator that stores fees from all transactions in a block.
+#[derive(Default)]
+pub struct FeeAccumulator {
+    pub total_fees: BTreeMap<token::Denomination, u128>,
+}
+
+impl FeeAccumulator {
+    /// Add given fee to the accumulator.
+    pub fn add(&mut self, fee: &token::BaseUnits) {
+        let current = self
+            .total_fees
+            .entry(fee.denomination().clone())
+            .or_default();
+
+        *current = current.checked_add(fee.amount()).unwrap(); // Should never overflow.
+    }
+
+    /// Subtract given fee from the accumulator.
+    fn sub(&mut self, fee: &token::BaseUnits) -> Result<(), Error> {
+        let current = self
+            .total_fees
+            .entry(fee.denomination().clone())
+            .or_default();
+
+        *current = current
+            .checked_sub(fee.amount())
+            .ok_or(Error::InsufficientBalance)?;
+        Ok(())
+    }
+}
+
+/// Context key for the fee accumulator.
+pub const CONTEXT_KEY_FEE_ACCUMULATOR: &str = "accounts.FeeAccumulator";
+/// Context key for the index of the transaction that is currently being executed.
+pub const CONTEXT_KEY_TX_INDEX: &str = "accounts.TxIndex";
+
+impl API for Module {
+    fn transfer<C: Context>(
+        ctx: &mut C,
+        from: Address,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        Self::transfer_with_memo(ctx, from, to, amount, None)
+    }
+
+    fn transfer_unchecked<C: Context>(
+        ctx: &mut C,
+        from: Address,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        <C::Runtime as Runtime>::TransferHooks::before_transfer(ctx, from, to, amount)?;
+
+        Self::sub_amount(ctx.runtime_state(), from, amount)?;
+        Self::add_amount(ctx.runtime_state(), to, amount)?;
+
+        Self::record_activity(ctx, from);
+        if to != from {
+            Self::record_activity(ctx, to);
+        }
+
+        ctx.emit_event(Event::Transfer {
+            from,
+            to,
+            amount: amount.clone(),
+            memo: None,
+        });
+
+        <C::Runtime as Runtime>::TransferHooks::after_transfer(ctx, from, to, amount)
+    }
+
+    fn mint<C: Context>(ctx: &mut C, to: Address, amount: &token::BaseUnits) -> Result<(), Error> {
+        <C::Runtime as Runtime>::TransferHooks::before_mint(ctx, to, amount)?;
+
+        // Add to destination account.
+        Self::add_amount(ctx.runtime_state(), to, amount)?;
+
+        // Increase total supply.
+        Self::inc_total_supply(ctx.runtime_state(), amount)?;
+
+        Self::record_activity(ctx, to);
+
+        // Emit a mint event.
+        ctx.emit_event(Event::Mint {
+            owner: to,
+            amount: amount.clone(),
+        });
+
+        <C::Runtime as Runtime>::TransferHooks::after_mint(ctx, to, amount)
+    }
+
+    fn burn<C: Context>(
+        ctx: &mut C,
+        from: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        <C::Runtime as Runtime>::TransferHooks::before_burn(ctx, from, amount)?;
+
+        // Remove from target account.
+        Self::ensure_spendable(ctx, from, amount)?;
+        Self::sub_amount(ctx.runtime_state(), from, amount)?;
+
+        // Decrease total supply.
+        Self::dec_total_supply(ctx.runtime_state(), amount)
+            .expect("target account had enough balance so total supply should not underflow");
+
+        Self::record_activity(ctx, from);
+
+        // Emit a burn event.
+        ctx.emit_event(Event::Burn {
+            owner: from,
+            amount: amount.clone(),
+        });
+
+        <C::Runtime as Runtime>::TransferHooks::after_burn(ctx, from, amount)
+    }
+
+    fn set_nonce<S: storage::Store>(state: S, address: Address, nonce: u64) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut accounts =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
+        let mut account: types::Account = accounts.get(address).unwrap_or_default();
+        account.nonce = nonce;
+        accounts.insert(&address, account);
+    }
+
+    fn get_nonce<S: storage::Store>(state: S, address: Address) -> Result<u64, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let accounts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
+        let account: types::Account = accounts.get(address).unwrap_or_default();
+        Ok(account.nonce)
+    }
+
+    fn get_proposal_id<S: storage::Store>(state: S) -> Result<u32, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let proposals =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
+
+        // sifei: please refer this proposal_id to keep quorums to the state.
+        const PROPOSAL_COUNTER_KEY: &[u8] = b"proposal_id";
+        let counter: u32 = proposals.get(PROPOSAL_COUNTER_KEY).unwrap_or(0);
+        Ok(counter)
+    }
+
+    fn get_proposal<S: storage::Store>(state: S, id: u32) -> Result<types::Proposal, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let proposals =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
+
+        let proposal_id_bytes = id.to_le_bytes();
+        let proposal: types::Proposal = proposals.get(proposal_id_bytes).unwrap_or_default();
+
+        Ok(proposal)
+    }
+
+
+    fn get_and_increment_proposal_id<S: storage::Store>(state: S) -> Result<u32, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut proposals =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
+
+        const PROPOSAL_COUNTER_KEY: &[u8] = b"proposal_id";
+        let mut counter: u32 = proposals.get(PROPOSAL_COUNTER_KEY).unwrap_or(0);
+        counter = counter.checked_add(1).ok_or(Error::CounterOverflow)?;
+        proposals.insert(PROPOSAL_COUNTER_KEY, counter);
+
+        Ok(counter)
+    }
+
+
+    fn insert_proposal<S: storage::Store>(
+        mut state: S,
+        proposal: types::Proposal,
+    ) -> Result<(), Error> {
+        let mut store = storage::PrefixStore::new(&mut state, &MODULE_NAME);
+        let mut proposals =
+            storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::PROPOSALS));
+
+        let proposal_id_bytes = proposal.id.to_le_bytes();
+        let id = proposal.id;
+        let submitter = proposal.submitter;
+        let active = proposal.state == ProposalState::Active;
+        proposals.insert(proposal_id_bytes, proposal);
+
+        // Keep the active proposal index in sync.
+        let mut active_proposals = storage::TypedStore::new(storage::PrefixStore::new(
+            store,
+            &state::ACTIVE_PROPOSALS,
+        ));
+        let was_active = active_proposals.get::<_, bool>(id.to_be_bytes()).is_some();
+        if active {
+            active_proposals.insert(id.to_be_bytes(), true);
+        } else {
+            active_proposals.remove(id.to_be_bytes());
+        }
+
+        // Keep the number of active proposals of the submitter in sync.
+        if active != was_active {
+            let count = Self::get_active_proposal_count(&mut state, submitter);
+            let count = if active {
+                count.saturating_add(1)
+            } else {
+                count.saturating_sub(1)
+            };
+            Self::set_active_proposal_count(&mut state, submitter, count);
+        }
+
+        Ok(())
+    }
+
+
+    fn get_voter_with_action<S: storage::Store>(state: S, action: Action) -> Option<Role> {
+        Self::params(state)
+            .voter_roles
+            .get(&action)
+            .copied()
+            .or_else(|| Self::default_voter_role(action))
+    }
+
+    fn get_proposer_with_action<S: storage::Store>(state: S, action: Action) -> Option<Role> {
+        Self::params(state)
+            .proposer_roles
+            .get(&action)
+            .copied()
+            .or_else(|| Self::default_proposer_role(action))
+    }
+
+    //Sifei: get_quorum for Burn/Mint/Whitelist/Blacklist/Config
+    fn get_quorum<S: storage::Store>(state: S, action: Action) -> Result<u8, Error> {
+        let action = Self::quorum_action(action).ok_or(Error::NotFound)?;
+        let params = Self::params(state);
+        Ok(params
+            .quorums
+            .get(&action)
+            .copied()
+            .unwrap_or(DEFAULT_QUORUM))
+    }
+
+    //Sifei: set_quorum for Burn/Mint/Whitelist/Blacklist/Config
+    fn set_quorum<S: storage::Store>(
+        mut state: S,
+        action: Action,
+        quorum: u8,
+    ) -> Result<(), Error> {
+        if !QUORUM_ACTIONS.contains(&action) {
+            return Err(Error::NotFound);
+        }
+        let mut params = Self::params(&mut state);
+        params.quorums.insert(action, quorum);
+        Self::set_params(state, params);
+        Ok(())
+    }
+
+    fn get_abstain_quorum<S: storage::Store>(state: S, action: Action) -> Result<u8, Error> {
+        let action = Self::quorum_action(action).ok_or(Error::NotFound)?;
+        let params = Self::params(state);
+        Ok(params
+            .abstain_quorums
+            .get(&action)
+            .copied()
+            .unwrap_or(DEFAULT_ABSTAIN_QUORUM))
+    }
+
+    fn set_abstain_quorum<S: storage::Store>(
+        mut state: S,
+        action: Action,
+        quorum: u8,
+    ) -> Result<(), Error> {
+        if !QUORUM_ACTIONS.contains(&action) {
+            return Err(Error::NotFound);
+        }
+        let mut params = Self::params(&mut state);
+        params.abstain_quorums.insert(action, quorum);
+        Self::set_params(state, params);
+        Ok(())
+    }
+
+    fn set_role<S: storage::Store>(state: S, address: Address, role: role::Role) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut accounts =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
+        let mut account: types::Account = accounts.get(address).unwrap_or_default();
+        account.role = role;
+        accounts.insert(&address, account);
+    }
+
+
+
+    /// GB: add an address to some role (e.g. MintVoter), so this role holds all addresses of such a role.
+    /// this function should be used together with remove_address_from_roles, to maintain the sequ8 counter.
+    fn add_address_to_roles<S: storage::Store>(state: S, address: Address, role: role::Role) -> Result<(), Error> {
+        // GB: the following to insert the address to the corresponding role vec.
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let roles_store = storage::PrefixStore::new(store, &state::ROLES);
+        let role_bytes = role.marshal_binary();
+        let mut role_store =
+             storage::TypedStore::new(storage::PrefixStore::new(roles_store, &role_bytes));
+
+        // GB: define a map to save the sequence of specific role's addresses.
+        let role_str = role.to_string();
+        let seq_key = "seq".to_string() + &role_str;
+        let seq_key_bytes = seq_key.as_bytes();
+        let mut counter: u32 = role_store.get(&seq_key_bytes).unwrap_or(0);
+        counter = counter.checked_add(1).ok_or(Error::CounterOverflow)?;
+        role_store.insert(&seq_key_bytes, counter);
+
+        role_store.insert(&address, counter);
+
+        Ok(())
+    }
+
+    fn remove_address_from_roles<S: storage::Store>(state: S, address: Address, role: role::Role) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let roles_store = storage::PrefixStore::new(store, &state::ROLES);
+        let role_bytes = role.marshal_binary();
+        let mut role_store =
+             storage::TypedStore::new(storage::PrefixStore::new(roles_store, &role_bytes));
+
+        if role_store.get::<_, u32>(&address).is_none() {
+            return;
+        }
+        role_store.remove(&address);
+
+        let role_str = role.to_string();
+        let seq_key = "seq".to_string() + &role_str;
+        let seq_key_bytes = seq_key.as_bytes();
+        let counter: u32 = role_store.get(&seq_key_bytes).unwrap_or(0);
+        role_store.insert(&seq_key_bytes, counter.saturating_sub(1));
+    }
+
+    fn remove_role_from_address<S: storage::Store>(state: S, address: Address, role: role::Role) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let roles_store = storage::PrefixStore::new(store, &state::ROLES);
+        let mut role_account =
+             storage::TypedStore::new(storage::PrefixStore::new(roles_store, &address));
+        role_account.remove(role.marshal_binary());
+    }
+
+    //Sifei: save address with role to store
+    /// Adds the role to the roles held by the address. Becoming a User or BlacklistedUser drops
+    /// all other roles.
+    fn add_role_to_address<S: storage::Store>(state: S, address: Address, role: role::Role) {
+        // GB: the following to insert the address to the corresponding role vec.
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let roles_store = storage::PrefixStore::new(store, &state::ROLES);
+
+        let mut role_account =
+             storage::TypedStore::new(storage::PrefixStore::new(roles_store, &address));
+
+        if matches!(role, Role::User | Role::BlacklistedUser) {
+            for role in Role::iter() {
+                let rawu8role = role.marshal_binary();
+                role_account.remove(&rawu8role);
+            }
+        }
+
+        if role != Role::User {
+            // Update the map in the store.
+            let rawu8 = role.marshal_binary();
+            role_account.insert(rawu8, true);
+        }
+    }
+
+
+    //Sifei: get no of addresses with requested role
+    fn get_addrsno_in_role<S: storage::Store>(state: S, role: role::Role) -> u16 {
+        //Option variable
+        let rstaddresses = Self::get_addresses_in_role (state, role);
+        let addressno = match rstaddresses {
+            Ok(addresses) => addresses.len(),
+            Err(_) => 0,
+        };
+        addressno as u16
+    }
+
+    //Sifei: get addresses with requested role
+    /*    
+    GB: get all addresses of some role by iterating all the addresses in the ROLES storage, 
+    it would become quite cubersome when a lot of addresses are handled.
+    */
+    fn get_addresses_in_role<S: storage::Store>(state: S, role: role::Role) -> Result<Vec<Address>, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let role_addresses: BTreeMap<AddressWithRole, bool> =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ROLES))
+            .iter()
+            .collect();
+
+        //get addresses 
+        Ok(role_addresses
+            .into_keys()
+            .filter(|ra| ra.1 == role.marshal_binary())
+            .map(|ra| ra.0)
+            .collect())
+    }
+
+    //Sifei: get no of voters for action
+    fn get_voters_num_with_action<S: storage::Store>(
+        mut state: S,
+        action: Action,
+    ) -> Result<u16, Error> {
+        let role = Self::get_voter_with_action(&mut state, action).ok_or(Error::NotFound)?;
+        Ok(Self::get_addrsno_in_role(state, role))
+    }
+
+
+    fn get_voter_weight<S: storage::Store>(state: S, address: Address, role: role::Role) -> u16 {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let weights = storage::PrefixStore::new(store, &state::VOTER_WEIGHTS);
+        let address_weights =
+            storage::TypedStore::new(storage::PrefixStore::new(weights, &address));
+        address_weights.get(role.marshal_binary()).unwrap_or(1)
+    }
+
+    fn set_voter_weight<S: storage::Store>(state: S, address: Address, role: role::Role, weight: u16) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let weights = storage::PrefixStore::new(store, &state::VOTER_WEIGHTS);
+        let mut address_weights =
+            storage::TypedStore::new(storage::PrefixStore::new(weights, &address));
+        // Only non-default weights are stored.
+        if weight == 1 {
+            address_weights.remove(role.marshal_binary());
+        } else {
+            address_weights.insert(role.marshal_binary(), weight);
+        }
+    }
+
+    fn get_voters_weight_with_action<S: storage::Store>(mut state: S, action: Action) -> Result<u64, Error> {
+        let role = Self::get_voter_with_action(&mut state, action).ok_or(Error::NotFound)?;
+        let addresses = Self::get_addresses_in_role(&mut state, role)?;
+        Ok(addresses
+            .into_iter()
+            .map(|address| u64::from(Self::get_voter_weight(&mut state, address, role)))
+            .sum())
+    }
+
+    fn get_roles<S: storage::Store>(mut state: S, address: Address) -> BTreeSet<role::Role> {
+        let primary = Self::get_role(&mut state, address).unwrap_or_default();
+
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let roles_store = storage::PrefixStore::new(store, &state::ROLES);
+        let role_account =
+            storage::TypedStore::new(storage::PrefixStore::new(roles_store, &address));
+        let mut roles: BTreeSet<role::Role> = role_account
+            .iter()
+            .map(|(role, _): (Role, bool)| role)
+            .collect();
+
+        if primary != Role::User || roles.is_empty() {
+            roles.insert(primary);
+        }
+        roles
+    }
+
+    fn has_role<S: storage::Store>(state: S, address: Address, role: role::Role) -> bool {
+        Self::get_roles(state, address).contains(&role)
+    }
+
+    fn get_role<S: storage::Store>(state: S, address: Address) -> Result<role::Role, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let accounts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
+        let account: types::Account = accounts.get(address).unwrap_or_default();
+        Ok(account.role)
+    }
+
+    fn set_initstatus<S: storage::Store>(state: S, address: Address, init: bool) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut accounts =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
+        let mut account: types::Account = accounts.get(address).unwrap_or_default();
+        account.init = init;
+        accounts.insert(&address, account);
+    }
+
+    fn get_initstatus<S: storage::Store>(state: S, address: Address) -> Result<bool, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let accounts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
+        let account: types::Account = accounts.get(address).unwrap_or_default();
+        Ok(account.init)
+    }
+
+    fn is_contract_paused<S: storage::Store>(state: S, address: Address) -> bool {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let paused_contracts =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::PAUSED_CONTRACTS));
+        paused_contracts.get(address).unwrap_or(false)
+    }
+
+    fn is_frozen<S: storage::Store>(state: S, address: Address) -> bool {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let accounts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
+        let account: types::Account = accounts.get(address).unwrap_or_default();
+        account.frozen
+    }
+
+    fn prune_account<S: storage::Store>(state: S, address: Address) -> bool {
+        let mut store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut pruned = false;
+
+        let is_empty = {
+            let balances = storage::PrefixStore::new(&mut store, &state::BALANCES);
+            let mut account =
+                storage::TypedStore::new(storage::PrefixStore::new(balances, &address));
+            let balances: BTreeMap<token::Denomination, u128> = account.iter().collect();
+            for (denomination, _) in balances.iter().filter(|(_, value)| **value == 0) {
+                account.remove(denomination);
+                pruned = true;
+            }
+            balances.values().all(|value| *value == 0)
+        };
+
+        // Accounts holding a nonce must be kept, otherwise old transactions could be replayed.
+        let mut accounts =
+            storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::ACCOUNTS));
+        if is_empty && accounts.get::<_, types::Account>(address) == Some(Default::default()) {
+            accounts.remove(address);
+            pruned = true;
+        }
+
+        pruned
+    }
+
+
+    fn set_balance<S: storage::Store>(mut state: S, address: Address, amount: &token::BaseUnits) {
+        let store = storage::PrefixStore::new(&mut state, &MODULE_NAME);
+        let balances = storage::PrefixStore::new(store, &state::BALANCES);
+        let mut account = storage::TypedStore::new(storage::PrefixStore::new(balances, &address));
+        let value: u128 = account.get(amount.denomination()).unwrap_or_default();
+        account.insert(amount.denomination(), amount.amount());
+
+        Self::update_balance_total(state, amount.denomination(), value, amount.amount());
+    }
+
+    fn get_locked_balance<C: Context>(
+        ctx: &mut C,
+        address: Address,
+        denomination: &token::Denomination,
+    ) -> u128 {
+        match Self::get_vesting_schedule(ctx.runtime_state(), address) {
+            Some(schedule) if schedule.amount.denomination() == denomination => {
+                schedule.locked(ctx.runtime_header().round)
+            }
+            _ => 0,
+        }
+    }
+
+    fn get_balance<S: storage::Store>(
+        state: S,
+        address: Address,
+        denomination: token::Denomination,
+    ) -> Result<u128, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let balances = storage::PrefixStore::new(store, &state::BALANCES);
+        let account = storage::TypedStore::new(storage::PrefixStore::new(balances, &address));
+
+        Ok(account.get(&denomination).unwrap_or_default())
+    }
+
+    fn get_balances<S: storage::Store>(
+        state: S,
+        address: Address,
+    ) -> Result<types::AccountBalances, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let balances = storage::PrefixStore::new(store, &state::BALANCES);
+        let account = storage::TypedStore::new(storage::PrefixStore::new(balances, &address));
+
+        Ok(types::AccountBalances {
+            balances: account.iter().collect(),
+        })
+    }
+
+    fn get_allowance<S: storage::Store>(
+        state: S,
+        owner: Address,
+        spender: Address,
+        denomination: &token::Denomination,
+    ) -> u128 {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let allowances = storage::PrefixStore::new(store, &state::ALLOWANCES);
+        let owner_allowances = storage::PrefixStore::new(allowances, &owner);
+        let spender_allowances =
+            storage::TypedStore::new(storage::PrefixStore::new(owner_allowances, &spender));
+        spender_allowances.get(denomination).unwrap_or_default()
+    }
+
+    fn approve<C: Context>(
+        ctx: &mut C,
+        owner: Address,
+        spender: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        if owner == spender {
+            return Err(Error::InvalidArgument);
+        }
+
+        Self::set_allowance(ctx.runtime_state(), owner, spender, amount);
+
+        ctx.emit_event(Event::Approve {
+            owner,
+            spender,
+            amount: amount.clone(),
+        });
+
+        Ok(())
+    }
+
+    fn transfer_from<C: Context>(
+        ctx: &mut C,
+        spender: Address,
+        from: Address,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        // Blacklisted accounts may neither spend nor have their allowances spent.
+        if Self::has_role(ctx.runtime_state(), from, Role::BlacklistedUser)
+            || Self::has_role(ctx.runtime_state(), spender, Role::BlacklistedUser)
+        {
+            return Err(Error::Forbidden);
+        }
+
+        let allowance =
+            Self::get_allowance(ctx.runtime_state(), from, spender, amount.denomination());
+        let remaining = allowance
+            .checked_sub(amount.amount())
+            .ok_or(Error::InsufficientAllowance)?;
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        Self::set_allowance(
+            ctx.runtime_state(),
+            from,
+            spender,
+            &token::BaseUnits::new(remaining, amount.denomination().clone()),
+        );
+        Self::transfer(ctx, from, to, amount)
+    }
+
+    fn get_addresses<S: storage::Store>(
+        state: S,
+        denomination: token::Denomination,
+    ) -> Result<Vec<Address>, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let balances: BTreeMap<AddressWithDenomination, Quantity> =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::BALANCES))
+                .iter()
+                .collect();
+
+        Ok(balances
+            .into_keys()
+            .filter(|bal| bal.1 == denomination)
+            .map(|bal| bal.0)
+            .collect())
+    }
+
+    fn get_total_supplies<S: storage::Store>(
+        state: S,
+    ) -> Result<BTreeMap<token::Denomination, u128>, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let ts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::TOTAL_SUPPLY));
+
+        Ok(ts.iter().collect())
+    }
+
+    fn get_total_supply<S: storage::Store>(
+        state: S,
+        denomination: token::Denomination,
+    ) -> Result<u128, Error> {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let ts = storage::TypedStore::new(storage::PrefixStore::new(store, &state::TOTAL_SUPPLY));
+
+        Ok(ts.get(denomination).unwrap_or_default())
+    }
+
+    fn set_total_supply<S: storage::Store>(state: S, amount: &token::BaseUnits) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut total_supplies =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::TOTAL_SUPPLY));
+        total_supplies.insert(amount.denomination(), amount.amount());
+    }
+
+    fn get_denomination_info<S: storage::Store>(
+        state: S,
+        denomination: &token::Denomination,
+    ) -> Result<types::DenominationInfo, Error> {
+        let params = Self::params(state);
+        params
+            .denomination_infos
+            .get(denomination)
+            .cloned()
+            .ok_or(Error::NotFound)
+    }
+
+    fn ensure_min_transfer_amount<S: storage::Store>(
+        state: S,
+        amount: &token::BaseUnits,
+    ) -> Result<(), Error> {
+        let params = Self::params(state);
+        let min_amount = params
+            .min_transfer_amounts
+            .get(amount.denomination())
+            .copied()
+            .unwrap_or_default();
+        if amount.amount() != 0 && amount.amount() < min_amount {
+            return Err(Error::BelowMinimumTransfer);
+        }
+        Ok(())
+    }
+
+    fn move_into_fee_accumulator<C: Context>(
+        ctx: &mut C,
+        from: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), modules::core::Error> {
+        if ctx.is_simulation() {
+            return Ok(());
+        }
+
+        Self::ensure_spendable(ctx, from, amount)
+            .and_then(|_| Self::sub_amount(ctx.runtime_state(), from, amount))
+            .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
+
+        ctx.value::<FeeAccumulator>(CONTEXT_KEY_FEE_ACCUMULATOR)
+            .or_default()
+            .add(amount);
+
+        Ok(())
+    }
+
+    fn move_from_fee_accumulator<C: Context>(
+        ctx: &mut C,
+        to: Address,
+        amount: &token::BaseUnits,
+    ) -> Result<(), modules::core::Error> {
+        if ctx.is_simulation() {
+            return Ok(());
+        }
+
+        ctx.value::<FeeAccumulator>(CONTEXT_KEY_FEE_ACCUMULATOR)
+            .or_default()
+            .sub(amount)
+            .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
+
+        Self::add_amount(ctx.runtime_state(), to, amount)
+            .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
+
+        Ok(())
+    }
+
+    fn refund_fee<C: TxContext>(
+        ctx: &mut C,
+        amount: &token::BaseUnits,
+    ) -> Result<(), modules::core::Error> {
+        let auth_info = ctx.tx_auth_info().clone();
+        let payer = match auth_info.signer_info.first() {
+            Some(si) => si.address_spec.address(),
+            // Transactions without signers (e.g. simulated calls) did not pre-pay any fee.
+            None => return Ok(()),
+        };
+        let fee_payer = auth_info.fee_granter.unwrap_or(payer);
+
+        match Self::convert_fee(ctx.runtime_state(), amount) {
+            // The accumulator holds the native equivalent of converted fees, see `pay_fee`.
+            Some(native_refund) if !ctx.is_simulation() => {
+                Self::move_from_fee_accumulator(ctx, *ADDRESS_COMMON_POOL, &native_refund)?;
+                Self::sub_amount(ctx.runtime_state(), *ADDRESS_COMMON_POOL, amount)
+                    .and_then(|_| Self::add_amount(ctx.runtime_state(), fee_payer, amount))
+                    .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
+            }
+            _ => Self::move_from_fee_accumulator(ctx, fee_payer, amount)?,
+        }
+        if fee_payer != payer {
+            Self::refund_fee_grant(ctx.runtime_state(), fee_payer, payer, amount);
+        }
+
+        Ok(())
+    }
+
+    fn check_signer_nonces<C: Context>(
+        ctx: &mut C,
+        auth_info: &AuthInfo,
+    ) -> Result<Address, modules::core::Error> {
+        let is_pre_schedule = ctx.is_pre_schedule();
+        let is_check_only = ctx.is_check_only();
+
+        // TODO: Optimize the check/update pair so that the accounts are
+        // fetched only once.
+        let params = Self::params(ctx.runtime_state());
+        // Fetch information about each signer.
+        let mut store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+        let accounts =
+            storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::ACCOUNTS));
+        let mut sender = None;
+        for si in auth_info.signer_info.iter() {
+            let address = si.address_spec.address();
+            let account: types::Account = accounts.get(address).unwrap_or_default();
+
+            // First signer pays for the fees and is considered the sender.
+            if sender.is_none() {
+                sender = Some(SenderMeta {
+                    address,
+                    tx_nonce: si.nonce,
+                    state_nonce: account.nonce,
+                });
+            }
+
+            // When nonce checking is disabled, skip the rest of the checks.
+            if params.debug_disable_nonce_check {
+                continue;
+            }
+
+            // Check signer nonce against the corresponding account nonce.
+            match si.nonce.cmp(&account.nonce) {
+                Ordering::Less => {
+                    // In the past and will never become valid, reject.
+                    return Err(modules::core::Error::InvalidNonce(account.nonce, si.nonce));
+                }
+                Ordering::Equal => {} // Ok.
+                Ordering::Greater => {
+                    // If too much in the future, reject.
+                    if si.nonce - account.nonce > params.max_check_nonce_future_delta {
+                        return Err(modules::core::Error::InvalidNonce(account.nonce, si.nonce));
+                    }
+
+                    // If in the future and this is before scheduling, reject with separate error
+                    // that will make the scheduler skip the transaction.
+                    if is_pre_schedule {
+                        return Err(modules::core::Error::FutureNonce(account.nonce, si.nonce));
+                    }
+
+                    // If in the future and this is during execution, reject.
+                    if !is_check_only {
+                        return Err(modules::core::Error::InvalidNonce(account.nonce, si.nonce));
+                    }
+
+                    // If in the future and this is during checks, accept.
+                }
+            }
+        }
+
+        // Configure the sender.
+        let sender = sender.expect("at least one signer is always present");
+        let sender_address = sender.address;
+        if is_check_only {
+            <C::Runtime as Runtime>::Core::set_sender_meta(ctx, sender);
+        }
+
+        Ok(sender_address)
+    }
+
+    fn update_signer_nonces<C: Context>(
+        ctx: &mut C,
+        auth_info: &AuthInfo,
+    ) -> Result<(), modules::core::Error> {
+        // Fetch information about each signer.
+        let mut store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+        let mut accounts =
+            storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::ACCOUNTS));
+        for si in auth_info.signer_info.iter() {
+            let address = si.address_spec.address();
+            let mut account: types::Account = accounts.get(address).unwrap_or_default();
+
+            // Update nonce. Should never overflow.
+            account.nonce = account
+                .nonce
+                .checked_add(1)
+                .ok_or(modules::core::Error::InvalidNonce(account.nonce, si.nonce))?;
+            accounts.insert(&address, account);
+        }
+        Ok(())
+    }
+}
+
+#[sdk_derive(MethodHandler)]
+impl Module {
+    #[handler(prefetch = "accounts.Transfer")]
+    fn prefetch_transfer(
+        add_prefix: &mut dyn FnMut(Prefix),
+        body: cbor::Value,
+        auth_info: &AuthInfo,
+    ) -> Result<(), crate::error::RuntimeError> {
+        let args: types::Transfer = cbor::from_value(body).map_err(|_| Error::InvalidArgument)?;
+        let from = auth_info.signer_info[0].address_spec.address();
+
+        // Prefetch accounts 'to'.
+        add_prefix(Prefix::from(
+            [MODULE_NAME.as_bytes(), state::ACCOUNTS, args.to.as_ref()].concat(),
+        ));
+        add_prefix(Prefix::from(
+            [MODULE_NAME.as_bytes(), state::BALANCES, args.to.as_ref()].concat(),
+        ));
+        // Prefetch accounts 'from'.
+        add_prefix(Prefix::from(
+            [MODULE_NAME.as_bytes(), state::ACCOUNTS, from.as_ref()].concat(),
+        ));
+        add_prefix(Prefix::from(
+            [MODULE_NAME.as_bytes(), state::BALANCES, from.as_ref()].concat(),
+        ));
+
+        Ok(())
+    }
+
+    #[handler(call = "accounts.Transfer")]
+    fn tx_transfer<C: TxContext>(ctx: &mut C, body: types::Transfer) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+
+        // Reject transfers when they are disabled.
+        if params.transfers_disabled {
+            return Err(Error::Forbidden);
+        }
+        if !body.has_valid_memo() {
+            return Err(Error::InvalidArgument);
+        }
+        Self::ensure_not_reserved(body.to)?;
+        Self::ensure_min_transfer_amount(ctx.runtime_state(), &body.amount)?;
+
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_transfer)?;
+
+        Self::transfer_with_memo(
+            ctx,
+            ctx.tx_caller_address(),
+            body.to,
+            &body.amount,
+            body.memo,
+        )?;
+
+        Ok(())
+    }
+
+    #[handler(prefetch = "accounts.TransferBatch")]
+    fn prefetch_transfer_batch(
+        add_prefix: &mut dyn FnMut(Prefix),
+        body: cbor::Value,
+        auth_info: &AuthInfo,
+    ) -> Result<(), crate::error::RuntimeError> {
+        let args: types::TransferBatch =
+            cbor::from_value(body).map_err(|_| Error::InvalidArgument)?;
+        let from = auth_info.signer_info[0].address_spec.address();
+
+        for address in args
+            .transfers
+            .iter()
+            .map(|transfer| transfer.to)
+            .chain([from])
+        {
+            add_prefix(Prefix::from(
+                [MODULE_NAME.as_bytes(), state::ACCOUNTS, address.as_ref()].concat(),
+            ));
+            add_prefix(Prefix::from(
+                [MODULE_NAME.as_bytes(), state::BALANCES, address.as_ref()].concat(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[handler(call = "accounts.TransferBatch")]
+    fn tx_transfer_batch<C: TxContext>(
+        ctx: &mut C,
+        body: types::TransferBatch,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+
+        // Reject transfers when they are disabled.
+        if params.transfers_disabled {
+            return Err(Error::Forbidden);
+        }
+        if body.transfers.is_empty() || body.transfers.len() > MAX_TRANSFER_BATCH_SIZE {
+            return Err(Error::InvalidArgument);
+        }
+        if !body
+            .transfers
+            .iter()
+            .all(types::Transfer::has_valid_memo)
+        {
+            return Err(Error::InvalidArgument);
+        }
+        for transfer in &body.transfers {
+            Self::ensure_not_reserved(transfer.to)?;
+            Self::ensure_min_transfer_amount(ctx.runtime_state(), &transfer.amount)?;
+        }
+
+        let gas = params
+            .gas_costs
+            .tx_transfer
+            .checked_mul(body.transfers.len() as u64)
+            .ok_or(Error::InvalidArgument)?;
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas)?;
+
+        // Any failing transfer fails the whole call, which reverts all previous transfers.
+        let from = ctx.tx_caller_address();
+        for transfer in body.transfers {
+            Self::transfer_with_memo(ctx, from, transfer.to, &transfer.amount, transfer.memo)?;
+        }
+
+        Ok(())
+    }
+
+    #[handler(call = "accounts.PruneAccounts")]
+    fn tx_prune_accounts<C: TxContext>(
+        ctx: &mut C,
+        body: types::PruneAccounts,
+    ) -> Result<u64, Error> {
+        let params = Self::params(ctx.runtime_state());
+        if body.addresses.is_empty() || body.addresses.len() > MAX_PRUNE_ACCOUNTS_BATCH_SIZE {
+            return Err(Error::InvalidArgument);
+        }
+        let gas = params
+            .gas_costs
+            .tx_managest
+            .checked_mul(body.addresses.len() as u64)
+            .ok_or(Error::InvalidArgument)?;
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, gas)?;
+
+        // Only admins may prune accounts.
+        let caller_address = Self::tx_role_caller(ctx);
+        if !Self::has_role(ctx.runtime_state(), caller_address, Role::Admin) {
+            return Err(Error::InvalidRole);
+        }
+        if ctx.is_check_only() {
+            return Ok(0);
+        }
+
+        let mut pruned = 0;
+        for address in body.addresses {
+            if Self::prune_account(ctx.runtime_state(), address) {
+                pruned += 1;
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    #[handler(call = "accounts.Approve")]
+    fn tx_approve<C: TxContext>(ctx: &mut C, body: types::Approve) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+
+        // Reject approvals when transfers are disabled.
+        if params.transfers_disabled {
+            return Err(Error::Forbidden);
+        }
+
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_transfer)?;
+
+        Self::approve(ctx, ctx.tx_caller_address(), body.spender, &body.amount)
+    }
+
+    #[handler(prefetch = "accounts.TransferFrom")]
+    fn prefetch_transfer_from(
+        add_prefix: &mut dyn FnMut(Prefix),
+        body: cbor::Value,
+        auth_info: &AuthInfo,
+    ) -> Result<(), crate::error::RuntimeError> {
+        let args: types::TransferFrom =
+            cbor::from_value(body).map_err(|_| Error::InvalidArgument)?;
+        let spender = auth_info.signer_info[0].address_spec.address();
+
+        for address in [args.from, args.to, spender] {
+            add_prefix(Prefix::from(
+                [MODULE_NAME.as_bytes(), state::ACCOUNTS, address.as_ref()].concat(),
+            ));
+            add_prefix(Prefix::from(
+                [MODULE_NAME.as_bytes(), state::BALANCES, address.as_ref()].concat(),
+            ));
+        }
+        // Prefetch the allowance given to the spender.
+        add_prefix(Prefix::from(
+            [
+                MODULE_NAME.as_bytes(),
+                state::ALLOWANCES,
+                args.from.as_ref(),
+                spender.as_ref(),
+            ]
+            .concat(),
+        ));
+
+        Ok(())
+    }
+
+    #[handler(call = "accounts.TransferFrom")]
+    fn tx_transfer_from<C: TxContext>(
+        ctx: &mut C,
+        body: types::TransferFrom,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+
+        // Reject transfers when they are disabled.
+        if params.transfers_disabled {
+            return Err(Error::Forbidden);
+        }
+        Self::ensure_not_reserved(body.to)?;
+        Self::ensure_min_transfer_amount(ctx.runtime_state(), &body.amount)?;
+
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_transfer)?;
+
+        Self::transfer_from(
+            ctx,
+            ctx.tx_caller_address(),
+            body.from,
+            body.to,
+            &body.amount,
+        )
+    }
+
+    #[handler(query = "accounts.Allowance")]
+    fn query_allowance<C: Context>(
+        ctx: &mut C,
+        args: types::AllowanceQuery,
+    ) -> Result<u128, Error> {
+        Ok(Self::get_allowance(
+            ctx.runtime_state(),
+            args.owner,
+            args.spender,
+            &args.denomination,
+        ))
+    }
+
+    #[handler(call = "accounts.GrantFee")]
+    fn tx_grant_fee<C: TxContext>(ctx: &mut C, body: types::GrantFee) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_transfer)?;
+
+        let granter = ctx.tx_caller_address();
+        if body.grantee == granter || body.expiration < ctx.runtime_header().round {
+            return Err(Error::InvalidArgument);
+        }
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        Self::set_fee_grant(
+            ctx.runtime_state(),
+            granter,
+            body.grantee,
+            Some(types::FeeGrantInfo {
+                spend_limit: body.spend_limit,
+                expiration: body.expiration,
+            }),
+        );
+
+        Ok(())
+    }
+
+    #[handler(call = "accounts.RevokeFeeGrant")]
+    fn tx_revoke_fee_grant<C: TxContext>(
+        ctx: &mut C,
+        body: types::RevokeFeeGrant,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_transfer)?;
+
+        let granter = ctx.tx_caller_address();
+        Self::get_fee_grant(ctx.runtime_state(), granter, body.grantee).ok_or(Error::NotFound)?;
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        Self::set_fee_grant(ctx.runtime_state(), granter, body.grantee, None);
+
+        Ok(())
+    }
+
+    #[handler(call = "accounts.SetRewardAddress")]
+    fn tx_set_reward_address<C: TxContext>(
+        ctx: &mut C,
+        body: types::SetRewardAddress,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        // The caller is the entity, as entity addresses are derived from the entity signing key.
+        let entity = ctx.tx_caller_address();
+        Self::set_reward_address(ctx.runtime_state(), entity, body.address);
+
+        Ok(())
+    }
+
+    #[handler(query = "accounts.RewardAddress")]
+    fn query_reward_address<C: Context>(
+        ctx: &mut C,
+        args: types::RewardAddressQuery,
+    ) -> Result<Address, Error> {
+        Ok(Self::get_reward_address(ctx.runtime_state(), args.address))
+    }
+
+    #[handler(call = "accounts.SetMetadata")]
+    fn tx_set_metadata<C: TxContext>(ctx: &mut C, body: types::SetMetadata) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_set_metadata)?;
+
+        if let Some(metadata) = &body.metadata {
+            if !metadata.is_valid() {
+                return Err(Error::InvalidArgument);
+            }
+        }
+
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        let owner = ctx.tx_caller_address();
+        Self::set_metadata(ctx.runtime_state(), owner, body.metadata);
+
+        Ok(())
+    }
+
+    #[handler(query = "accounts.Metadata")]
+    fn query_metadata<C: Context>(
+        ctx: &mut C,
+        args: types::MetadataQuery,
+    ) -> Result<Option<types::AccountMetadata>, Error> {
+        Ok(Self::get_metadata(ctx.runtime_state(), args.address))
+    }
+
+    #[handler(call = "accounts.DelegateVote")]
+    fn tx_delegate_vote<C: TxContext>(ctx: &mut C, body: types::DelegateVote) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        // Both the delegator and the delegate must be able to vote on the action.
+        let caller_address = Self::tx_role_caller(ctx);
+        let voter_role = Self::get_voter_with_action(ctx.runtime_state(), body.action)
+            .ok_or(Error::InvalidArgument)?;
+        if !Self::has_role(ctx.runtime_state(), caller_address, voter_role) {
+            return Err(Error::InvalidRole);
+        }
+        if let Some(delegate) = body.delegate {
+            if delegate == caller_address {
+                return Err(Error::InvalidArgument);
+            }
+            if !Self::has_role(ctx.runtime_state(), delegate, voter_role) {
+                return Err(Error::InvalidRole);
+            }
+        }
+
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        let mut delegations = Self::get_vote_delegations(ctx.runtime_state(), caller_address);
+        match body.delegate {
+            Some(delegate) => delegations.insert(body.action, delegate),
+            None => delegations.remove(&body.action),
+        };
+        Self::set_vote_delegations(ctx.runtime_state(), caller_address, delegations);
+
+        ctx.emit_event(Event::VoteDelegated {
+            delegator: caller_address,
+            action: body.action,
+            delegate: body.delegate,
+        });
+
+        Ok(())
+    }
+
+    #[handler(query = "accounts.VoteDelegations")]
+    fn query_vote_delegations<C: Context>(
+        ctx: &mut C,
+        args: types::VoteDelegationsQuery,
+    ) -> Result<BTreeMap<Action, Address>, Error> {
+        Ok(Self::get_vote_delegations(ctx.runtime_state(), args.address))
+    }
+
+    #[handler(query = "accounts.History", expensive)]
+    fn query_history<C: Context>(
+        ctx: &mut C,
+        args: types::HistoryQuery,
+    ) -> Result<Vec<types::Activity>, Error> {
+        let limit = match args.limit {
+            0 => MAX_HISTORY_QUERY_LIMIT,
+            limit => limit.min(MAX_HISTORY_QUERY_LIMIT),
+        } as u64;
+
+        // Sequence numbers are assigned consecutively, starting at one.
+        let last_seq = Self::get_activity_counter(ctx.runtime_state(), args.address);
+        let first_seq = args.start_after.saturating_add(1);
+        let last_seq = last_seq.min(args.start_after.saturating_add(limit));
+
+        Ok((first_seq..=last_seq)
+            .filter_map(|seq| Self::get_activity(ctx.runtime_state(), args.address, seq))
+            .collect())
+    }
+
+    #[handler(call = "accounts.CreateMultisig")]
+    fn tx_create_multisig<C: TxContext>(
+        ctx: &mut C,
+        body: types::CreateMultisig,
+    ) -> Result<Address, Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        if !types::MultisigAccount::is_valid(&body.members, body.threshold) {
+            return Err(Error::InvalidArgument);
+        }
+
+        // The address is derived from the creator and its nonce so that it is unique.
+        let creator = ctx.tx_caller_address();
+        let nonce = ctx.tx_auth_info().signer_info[0].nonce;
+        let address = Address::from_module_raw(
+            MODULE_NAME,
+            &[
+                b"multisig".as_slice(),
+                creator.as_ref(),
+                &nonce.to_be_bytes(),
+            ]
+            .concat(),
+        );
+        if Self::get_multisig(ctx.runtime_state(), address).is_some() {
+            return Err(Error::Forbidden);
+        }
+        if ctx.is_check_only() {
+            return Ok(address);
+        }
+
+        Self::set_multisig(
+            ctx.runtime_state(),
+            address,
+            types::MultisigAccount {
+                members: body.members,
+                threshold: body.threshold,
+                next_id: 0,
+            },
+        );
+        ctx.emit_event(Event::MultisigCreated { address, creator });
+
+        Ok(address)
+    }
+
+    #[handler(call = "accounts.MultisigSubmit")]
+    fn tx_multisig_submit<C: TxContext>(
+        ctx: &mut C,
+        body: types::MultisigSubmit,
+    ) -> Result<u64, Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        if params.transfers_disabled {
+            return Err(Error::Forbidden);
+        }
+        if !body.transfer.has_valid_memo() {
+            return Err(Error::InvalidArgument);
+        }
+        Self::ensure_not_reserved(body.transfer.to)?;
+        Self::ensure_min_transfer_amount(ctx.runtime_state(), &body.transfer.amount)?;
+
+        let member = ctx.tx_caller_address();
+        let mut multisig =
+            Self::get_multisig(ctx.runtime_state(), body.multisig).ok_or(Error::NotFound)?;
+        if !multisig.is_member(&member) {
+            return Err(Error::InvalidRole);
+        }
+        if ctx.is_check_only() {
+            return Ok(multisig.next_id);
+        }
+
+        let id = multisig.next_id;
+        multisig.next_id = multisig
+            .next_id
+            .checked_add(1)
+            .ok_or(Error::CounterOverflow)?;
+        Self::set_multisig(ctx.runtime_state(), body.multisig, multisig);
+        Self::set_multisig_transaction(
+            ctx.runtime_state(),
+            body.multisig,
+            id,
+            types::MultisigTransaction {
+                transfer: body.transfer,
+                approvals: vec![],
+                executed: false,
+            },
+        );
+        Self::approve_multisig_transaction(ctx, body.multisig, id, member)?;
+
+        Ok(id)
+    }
+
+    #[handler(call = "accounts.MultisigApprove")]
+    fn tx_multisig_approve<C: TxContext>(
+        ctx: &mut C,
+        body: types::MultisigApprove,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        if params.transfers_disabled {
+            return Err(Error::Forbidden);
+        }
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        let member = ctx.tx_caller_address();
+        Self::approve_multisig_transaction(ctx, body.multisig, body.id, member)
+    }
+
+    #[handler(call = "accounts.MultisigExecute")]
+    fn tx_multisig_execute<C: TxContext>(
+        ctx: &mut C,
+        body: types::MultisigExecute,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        if params.transfers_disabled {
+            return Err(Error::Forbidden);
+        }
+
+        let member = ctx.tx_caller_address();
+        let multisig =
+            Self::get_multisig(ctx.runtime_state(), body.multisig).ok_or(Error::NotFound)?;
+        if !multisig.is_member(&member) {
+            return Err(Error::InvalidRole);
+        }
+        let mut transaction =
+            Self::get_multisig_transaction(ctx.runtime_state(), body.multisig, body.id)
+                .ok_or(Error::NotFound)?;
+        if transaction.executed {
+            return Err(Error::InvalidState);
+        }
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        // The approvals are re-evaluated against the current members and threshold, which may
+        // have been changed by governance since the last approval.
+        if !Self::maybe_execute_multisig_transaction(
+            ctx,
+            body.multisig,
+            &multisig,
+            body.id,
+            &mut transaction,
+        )? {
+            return Err(Error::InvalidState);
+        }
+        Self::set_multisig_transaction(ctx.runtime_state(), body.multisig, body.id, transaction);
+
+        Ok(())
+    }
+
+    #[handler(query = "accounts.Multisig")]
+    fn query_multisig<C: Context>(
+        ctx: &mut C,
+        args: types::MultisigQuery,
+    ) -> Result<types::MultisigAccount, Error> {
+        Self::get_multisig(ctx.runtime_state(), args.address).ok_or(Error::NotFound)
+    }
+
+    #[handler(query = "accounts.MultisigTransaction")]
+    fn query_multisig_transaction<C: Context>(
+        ctx: &mut C,
+        args: types::MultisigTransactionQuery,
+    ) -> Result<types::MultisigTransaction, Error> {
+        Self::get_multisig_transaction(ctx.runtime_state(), args.multisig, args.id)
+            .ok_or(Error::NotFound)
+    }
+
+    #[handler(call = "accounts.EscrowCreate")]
+    fn tx_escrow_create<C: TxContext>(
+        ctx: &mut C,
+        body: types::EscrowCreate,
+    ) -> Result<u64, Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        if params.transfers_disabled {
+            return Err(Error::Forbidden);
+        }
+        // Without a release condition the funds could only ever be released to the recipient.
+        if body.arbiter.is_none() && body.timeout.is_none() {
+            return Err(Error::InvalidArgument);
+        }
+        if matches!(body.timeout, Some(timeout) if timeout < ctx.runtime_header().round) {
+            return Err(Error::InvalidArgument);
+        }
+        Self::ensure_not_reserved(body.to)?;
+        Self::ensure_min_transfer_amount(ctx.runtime_state(), &body.amount)?;
+        if ctx.is_check_only() {
+            return Ok(0);
+        }
+
+        let creator = ctx.tx_caller_address();
+        Self::transfer(ctx, creator, *ADDRESS_ESCROW, &body.amount)?;
+
+        let id = Self::next_escrow_id(ctx.runtime_state())?;
+        Self::set_escrow(
+            ctx.runtime_state(),
+            id,
+            Some(types::Escrow {
+                creator,
+                to: body.to,
+                amount: body.amount,
+                arbiter: body.arbiter,
+                timeout: body.timeout,
+            }),
+        );
+        ctx.emit_event(Event::EscrowCreated { id, creator });
+
+        Ok(id)
+    }
+
+    #[handler(call = "accounts.EscrowRelease")]
+    fn tx_escrow_release<C: TxContext>(
+        ctx: &mut C,
+        body: types::EscrowRelease,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        let caller = ctx.tx_caller_address();
+        let escrow = Self::get_escrow(ctx.runtime_state(), body.id).ok_or(Error::NotFound)?;
+        if !escrow.can_release(&caller) {
+            return Err(Error::InvalidRole);
+        }
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        Self::close_escrow(ctx, body.id, &escrow, escrow.to)?;
+        ctx.emit_event(Event::EscrowReleased {
+            id: body.id,
+            caller,
+        });
+
+        Ok(())
+    }
+
+    #[handler(call = "accounts.EscrowRefund")]
+    fn tx_escrow_refund<C: TxContext>(ctx: &mut C, body: types::EscrowRefund) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        let caller = ctx.tx_caller_address();
+        let escrow = Self::get_escrow(ctx.runtime_state(), body.id).ok_or(Error::NotFound)?;
+        if !escrow.can_refund(&caller, ctx.runtime_header().round) {
+            return Err(Error::InvalidRole);
+        }
+        if ctx.is_check_only() {
             return Ok(());
         }
 
-        ctx.value::<FeeAccumulator>(CONTEXT_KEY_FEE_ACCUMULATOR)
-            .or_default()
-            .sub(amount)
-            .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
-
-        Self::add_amount(ctx.runtime_state(), to, amount)
-            .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
+        Self::close_escrow(ctx, body.id, &escrow, escrow.creator)?;
+        ctx.emit_event(Event::EscrowRefunded {
+            id: body.id,
+            caller,
+        });
 
         Ok(())
     }
 
-    fn check_signer_nonces<C: Context>(
+    #[handler(query = "accounts.Escrow")]
+    fn query_escrow<C: Context>(
         ctx: &mut C,
-        auth_info: &AuthInfo,
-    ) -> Result<Address, modules::core::Error> {
-        let is_pre_schedule = ctx.is_pre_schedule();
-        let is_check_only = ctx.is_check_only();
+        args: types::EscrowQuery,
+    ) -> Result<types::Escrow, Error> {
+        Self::get_escrow(ctx.runtime_state(), args.id).ok_or(Error::NotFound)
+    }
 
-        // TODO: Optimize the check/update pair so that the accounts are
-        // fetched only once.
+    #[handler(call = "accounts.StreamCreate")]
+    fn tx_stream_create<C: TxContext>(
+        ctx: &mut C,
+        body: types::StreamCreate,
+    ) -> Result<u64, Error> {
         let params = Self::params(ctx.runtime_state());
-        // Fetch information about each signer.
-        let mut store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
-        let accounts =
-            storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::ACCOUNTS));
-        let mut sender = None;
-        for si in auth_info.signer_info.iter() {
-            let address = si.address_spec.address();
-            let account: types::Account = accounts.get(address).unwrap_or_default();
-
-            // First signer pays for the fees and is considered the sender.
-            if sender.is_none() {
-                sender = Some(SenderMeta {
-                    address,
-                    tx_nonce: si.nonce,
-                    state_nonce: account.nonce,
-                });
-            }
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
 
-            // When nonce checking is disabled, skip the rest of the checks.
-            if params.debug_disable_nonce_check {
-                continue;
-            }
+        if params.transfers_disabled {
+            return Err(Error::Forbidden);
+        }
+        let round = ctx.runtime_header().round;
+        let start = body.start.unwrap_or(round);
+        if start < round || start >= body.end {
+            return Err(Error::InvalidArgument);
+        }
+        Self::ensure_not_reserved(body.to)?;
+        Self::ensure_min_transfer_amount(ctx.runtime_state(), &body.amount)?;
+        if ctx.is_check_only() {
+            return Ok(0);
+        }
 
-            // Check signer nonce against the corresponding account nonce.
-            match si.nonce.cmp(&account.nonce) {
-                Ordering::Less => {
-                    // In the past and will never become valid, reject.
-                    return Err(modules::core::Error::InvalidNonce);
-                }
-                Ordering::Equal => {} // Ok.
-                Ordering::Greater => {
-                    // If too much in the future, reject.
-                    if si.nonce - account.nonce > MAX_CHECK_NONCE_FUTURE_DELTA {
-                        return Err(modules::core::Error::InvalidNonce);
-                    }
+        let sender = ctx.tx_caller_address();
+        Self::transfer(ctx, sender, *ADDRESS_STREAMS, &body.amount)?;
 
-                    // If in the future and this is before scheduling, reject with separate error
-                    // that will make the scheduler skip the transaction.
-                    if is_pre_schedule {
-                        return Err(modules::core::Error::FutureNonce);
-                    }
+        let id = Self::next_stream_id(ctx.runtime_state())?;
+        Self::set_stream(
+            ctx.runtime_state(),
+            id,
+            Some(types::Stream {
+                sender,
+                to: body.to,
+                amount: body.amount,
+                start,
+                end: body.end,
+                withdrawn: 0,
+            }),
+        );
+        ctx.emit_event(Event::StreamCreated { id, sender });
+
+        Ok(id)
+    }
 
-                    // If in the future and this is during execution, reject.
-                    if !is_check_only {
-                        return Err(modules::core::Error::InvalidNonce);
-                    }
+    #[handler(call = "accounts.StreamWithdraw")]
+    fn tx_stream_withdraw<C: TxContext>(
+        ctx: &mut C,
+        body: types::StreamWithdraw,
+    ) -> Result<token::BaseUnits, Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
 
-                    // If in the future and this is during checks, accept.
-                }
-            }
+        let mut stream = Self::get_stream(ctx.runtime_state(), body.id).ok_or(Error::NotFound)?;
+        if ctx.tx_caller_address() != stream.to {
+            return Err(Error::InvalidRole);
         }
-
-        // Configure the sender.
-        let sender = sender.expect("at least one signer is always present");
-        let sender_address = sender.address;
-        if is_check_only {
-            <C::Runtime as Runtime>::Core::set_sender_meta(ctx, sender);
+        let amount = token::BaseUnits::new(
+            stream.withdrawable(ctx.runtime_header().round),
+            stream.amount.denomination().clone(),
+        );
+        if ctx.is_check_only() {
+            return Ok(amount);
         }
 
-        Ok(sender_address)
+        Self::pay_out(ctx, *ADDRESS_STREAMS, stream.to, &amount)?;
+        stream.withdrawn += amount.amount();
+        // Fully withdrawn streams are removed.
+        let stream = Some(stream).filter(|stream| stream.withdrawn < stream.amount.amount());
+        Self::set_stream(ctx.runtime_state(), body.id, stream);
+
+        Ok(amount)
     }
 
-    fn update_signer_nonces<C: Context>(
-        ctx: &mut C,
-        auth_info: &AuthInfo,
-    ) -> Result<(), modules::core::Error> {
-        // Fetch information about each signer.
-        let mut store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
-        let mut accounts =
-            storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::ACCOUNTS));
-        for si in auth_info.signer_info.iter() {
-            let address = si.address_spec.address();
-            let mut account: types::Account = accounts.get(address).unwrap_or_default();
+    #[handler(call = "accounts.StreamCancel")]
+    fn tx_stream_cancel<C: TxContext>(ctx: &mut C, body: types::StreamCancel) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
 
-            // Update nonce.
-            account.nonce = account
-                .nonce
-                .checked_add(1)
-                .ok_or(modules::core::Error::InvalidNonce)?; // Should never overflow.
-            accounts.insert(&address, account);
+        let canceller = ctx.tx_caller_address();
+        let mut stream = Self::get_stream(ctx.runtime_state(), body.id).ok_or(Error::NotFound)?;
+        if canceller != stream.sender {
+            return Err(Error::InvalidRole);
+        }
+        if ctx.is_check_only() {
+            return Ok(());
         }
-        Ok(())
-    }
-}
-
-#[sdk_derive(MethodHandler)]
-impl Module {
-    #[handler(prefetch = "accounts.Transfer")]
-    fn prefetch_transfer(
-        add_prefix: &mut dyn FnMut(Prefix),
-        body: cbor::Value,
-        auth_info: &AuthInfo,
-    ) -> Result<(), crate::error::RuntimeError> {
-        let args: types::Transfer = cbor::from_value(body).map_err(|_| Error::InvalidArgument)?;
-        let from = auth_info.signer_info[0].address_spec.address();
 
-        // Prefetch accounts 'to'.
-        add_prefix(Prefix::from(
-            [MODULE_NAME.as_bytes(), state::ACCOUNTS, args.to.as_ref()].concat(),
-        ));
-        add_prefix(Prefix::from(
-            [MODULE_NAME.as_bytes(), state::BALANCES, args.to.as_ref()].concat(),
-        ));
-        // Prefetch accounts 'from'.
-        add_prefix(Prefix::from(
-            [MODULE_NAME.as_bytes(), state::ACCOUNTS, from.as_ref()].concat(),
-        ));
-        add_prefix(Prefix::from(
-            [MODULE_NAME.as_bytes(), state::BALANCES, from.as_ref()].concat(),
-        ));
+        let denomination = stream.amount.denomination().clone();
+        let round = ctx.runtime_header().round;
+        let streamed = stream.streamed(round);
+
+        // The sender's share is refunded independently of the recipient's payout.
+        Self::pay_out(
+            ctx,
+            *ADDRESS_STREAMS,
+            stream.sender,
+            &token::BaseUnits::new(stream.amount.amount() - streamed, denomination.clone()),
+        )?;
+        let remaining = token::BaseUnits::new(streamed - stream.withdrawn, denomination);
+        if remaining.amount() > 0 && Self::is_frozen(ctx.runtime_state(), stream.to) {
+            // Keep the stream, truncated to the amount streamed so far, so that the recipient can
+            // still withdraw its share once the account is unfrozen.
+            stream.amount = token::BaseUnits::new(streamed, remaining.denomination().clone());
+            stream.end = round;
+            Self::set_stream(ctx.runtime_state(), body.id, Some(stream));
+        } else {
+            Self::pay_out(ctx, *ADDRESS_STREAMS, stream.to, &remaining)?;
+            Self::set_stream(ctx.runtime_state(), body.id, None);
+        }
+        ctx.emit_event(Event::StreamCancelled {
+            id: body.id,
+            canceller,
+        });
 
         Ok(())
     }
 
-    #[handler(call = "accounts.Transfer")]
-    fn tx_transfer<C: TxContext>(ctx: &mut C, body: types::Transfer) -> Result<(), Error> {
-        let params = Self::params(ctx.runtime_state());
+    #[handler(query = "accounts.Stream")]
+    fn query_stream<C: Context>(
+        ctx: &mut C,
+        args: types::StreamQuery,
+    ) -> Result<types::Stream, Error> {
+        Self::get_stream(ctx.runtime_state(), args.id).ok_or(Error::NotFound)
+    }
 
-        // Reject transfers when they are disabled.
-        if params.transfers_disabled {
-            return Err(Error::Forbidden);
+    #[handler(query = "accounts.Proposals")]
+    fn query_proposals<C: Context>(
+        ctx: &mut C,
+        args: types::ProposalsQuery,
+    ) -> Result<Vec<types::Proposal>, Error> {
+        let limit = match args.limit {
+            0 => MAX_PROPOSALS_QUERY_LIMIT,
+            limit => limit.min(MAX_PROPOSALS_QUERY_LIMIT),
+        } as usize;
+
+        // Active proposals are served from the index, others require scanning all proposals.
+        let ids: Vec<u32> = if args.state == Some(ProposalState::Active) {
+            Self::get_active_proposal_ids(ctx.runtime_state())
+                .into_iter()
+                .filter(|id| *id > args.start_after)
+                .collect()
+        } else {
+            let last_id = Self::get_proposal_id(ctx.runtime_state())?;
+            (args.start_after.saturating_add(1)..=last_id).collect()
+        };
+
+        let mut proposals = Vec::new();
+        for id in ids {
+            if proposals.len() >= limit {
+                break;
+            }
+            let proposal = Self::get_proposal(ctx.runtime_state(), id)?;
+            if args.state.as_ref().map_or(false, |state| *state != proposal.state)
+                || args.action.map_or(false, |action| action != proposal.content.action)
+                || args.submitter.map_or(false, |submitter| submitter != proposal.submitter)
+            {
+                continue;
+            }
+            proposals.push(proposal);
         }
 
-        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_transfer)?;
+        Ok(proposals)
+    }
 
-        Self::transfer(ctx, ctx.tx_caller_address(), body.to, &body.amount)?;
+    #[handler(query = "accounts.Vesting")]
+    fn query_vesting<C: Context>(
+        ctx: &mut C,
+        args: types::VestingQuery,
+    ) -> Result<types::VestingStatus, Error> {
+        let schedule =
+            Self::get_vesting_schedule(ctx.runtime_state(), args.address).ok_or(Error::NotFound)?;
+        let round = ctx.runtime_header().round;
+        let denomination = schedule.amount.denomination().clone();
+        Ok(types::VestingStatus {
+            vested: token::BaseUnits::new(schedule.vested(round), denomination.clone()),
+            locked: token::BaseUnits::new(schedule.locked(round), denomination),
+        })
+    }
 
-        Ok(())
+    #[handler(query = "accounts.FeeGrant")]
+    fn query_fee_grant<C: Context>(
+        ctx: &mut C,
+        args: types::FeeGrantQuery,
+    ) -> Result<types::FeeGrantInfo, Error> {
+        Self::get_fee_grant(ctx.runtime_state(), args.granter, args.grantee)
+            .ok_or(Error::NotFound)
     }
 
 
@@ -1157,8 +4299,8 @@ impl Module {
         let params = Self::params(ctx.runtime_state());
         <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
 
-        let caller_address = ctx.tx_caller_address();
-        let caller_role = Self::get_role(ctx.runtime_state(), caller_address).unwrap_or_default();
+        let caller_address = Self::tx_role_caller(ctx);
+        let caller_roles = Self::get_roles(ctx.runtime_state(), caller_address);
 
         // println!("gbtest: caller_address is {:?}: ", caller_address);
         // GBTODO: add more guards, like the previous proposal must finish before a new proposal;
@@ -1170,14 +4312,15 @@ impl Module {
 
         // GB: only the correct Proposers and Admin can propose something.
         // GBTODO: the correct voters can also propose.
-        let proposer_role = Self::get_proposer_with_action(proposalcontent.action);
-        let voter_role = Self::get_voter_with_action(proposalcontent.action);
+        let proposer_role =
+            Self::get_proposer_with_action(ctx.runtime_state(), proposalcontent.action);
+        let voter_role = Self::get_voter_with_action(ctx.runtime_state(), proposalcontent.action);
 
         let mut is_proposer = false;
         let mut is_voter = false;
 
         if let Some(role) = proposer_role {
-            if caller_role == role {
+            if caller_roles.contains(&role) {
                 is_proposer = true;
             }
         } else {
@@ -1185,7 +4328,7 @@ impl Module {
         }
 
         if let Some(role) = voter_role {
-            if caller_role == role {
+            if caller_roles.contains(&role) {
                 is_voter = true;
             }
         } else {
@@ -1196,6 +4339,7 @@ impl Module {
             return Err(Error::InvalidRole);
         }
 
+        Self::ensure_can_submit_proposal(ctx.runtime_state(), &params, caller_address)?;
 
         match proposalcontent.action {
             // GB: both Mint/Burn action must operate on the WhitelistedUser.
@@ -1205,14 +4349,19 @@ impl Module {
                     Some(addr) => addr,
                 };
 
-                let addr_role = Self::get_role(ctx.runtime_state(), address).unwrap_or_default();
+                let round = ctx.runtime_header().round;
+                let addr_role = Self::get_effective_role(ctx.runtime_state(), address, round);
                 if addr_role != Role::WhitelistedUser {
                     return Err(Error::InvalidArgument);
                 }
             },
 
-            // GB: no constraints for SetRoles, admin can change any roles.
-            Action::SetRoles => {},
+            // GB: admin can change any roles, but voters must keep a non-zero weight.
+            Action::SetRoles => {
+                if proposalcontent.data.voter_weight == Some(0) {
+                    return Err(Error::InvalidArgument);
+                }
+            },
 
             // GB: quorum for config should be [0, 100], and there is a least one quorum in this proposal.
             Action::Config => {
@@ -1224,13 +4373,40 @@ impl Module {
                 is_valid(&data.burn_quorum) &&
                 is_valid(&data.whitelist_quorum) &&
                 is_valid(&data.blacklist_quorum) &&
-                is_valid(&data.config_quorum);
+                is_valid(&data.config_quorum) &&
+                data.abstain_quorums.as_ref().map_or(true, |quorums| {
+                    quorums
+                        .iter()
+                        .all(|(action, quorum)| QUORUM_ACTIONS.contains(action) && *quorum <= 100)
+                }) &&
+                data.fee_distribution.as_ref().map_or(true, |fd| fd.is_valid()) &&
+                data.denomination_infos
+                    .as_ref()
+                    .map_or(true, |infos| infos.values().all(|info| info.is_valid())) &&
+                data.fee_conversion_rates.as_ref().map_or(true, |rates| {
+                    rates
+                        .iter()
+                        .all(|(denomination, rate)| !denomination.is_native() && rate.amount > 0)
+                }) &&
+                data.proposer_roles
+                    .iter()
+                    .chain(&data.voter_roles)
+                    .flatten()
+                    .all(|(action, role)| is_valid_action_role(*action, *role));
 
                 let at_least_one_some = is_some(&data.mint_quorum) ||
                 is_some(&data.burn_quorum) ||
                 is_some(&data.whitelist_quorum) ||
                 is_some(&data.blacklist_quorum) ||
-                is_some(&data.config_quorum);
+                is_some(&data.config_quorum) ||
+                data.abstain_quorums.is_some() ||
+                data.core_parameters.is_some() ||
+                data.fee_distribution.is_some() ||
+                data.denomination_infos.is_some() ||
+                data.transfer_limits.is_some() ||
+                data.fee_conversion_rates.is_some() ||
+                data.proposer_roles.is_some() ||
+                data.voter_roles.is_some();
 
                 if !(valid_values && at_least_one_some){
                     return Err(Error::InvalidArgument);
@@ -1251,6 +4427,12 @@ impl Module {
                 if addr_role == Role::BlacklistedUser {
                     return Err(Error::InvalidArgument);
                 }
+                // Time-limited whitelistings must not have expired already.
+                if let Some(expiry) = proposalcontent.data.whitelist_expiry {
+                    if expiry < ctx.runtime_header().round {
+                        return Err(Error::InvalidArgument);
+                    }
+                }
             },  
 
             /*                
@@ -1269,9 +4451,62 @@ impl Module {
                 }
             },
 
+            // Pausing and resuming only need the address of the contract.
+            Action::PauseContract | Action::UnpauseContract => {
+                if proposalcontent.data.address.is_none() {
+                    return Err(Error::NotFound);
+                }
+            },
+
+            // The new members and threshold must form a valid multisig account.
+            Action::SetMultisigMembers => {
+                let data = &proposalcontent.data;
+                let address = data.address.ok_or(Error::NotFound)?;
+                Self::get_multisig(ctx.runtime_state(), address).ok_or(Error::NotFound)?;
+                let members = data.multisig_members.as_ref().ok_or(Error::NotFound)?;
+                let threshold = data.multisig_threshold.ok_or(Error::NotFound)?;
+                if !types::MultisigAccount::is_valid(members, threshold) {
+                    return Err(Error::InvalidArgument);
+                }
+            },
+
+            // Spending only needs the recipient and a non-zero amount. The common pool balance is
+            // only checked when the proposal is executed as it may change in the meantime.
+            Action::SpendCommonPool => {
+                let data = &proposalcontent.data;
+                Self::ensure_not_reserved(data.address.ok_or(Error::NotFound)?)?;
+                let amount = data.amount.as_ref().ok_or(Error::NotFound)?;
+                if amount.amount() == 0 {
+                    return Err(Error::InvalidArgument);
+                }
+            },
+
+            // Freezing is independent of the roles held by the account, but it must change the
+            // account's frozen state.
+            Action::Freeze | Action::Unfreeze => {
+                let address = match proposalcontent.data.address {
+                    None  =>  return Err(Error::NotFound),
+                    Some(addr) => addr,
+                };
+
+                let frozen = Self::is_frozen(ctx.runtime_state(), address);
+                if frozen == (proposalcontent.action == Action::Freeze) {
+                    return Err(Error::InvalidArgument);
+                }
+            },
+
             _ => { return Err(Error::InvalidArgument); },
         }
 
+        // Hold the deposit until the proposal is finalized.
+        let deposit = params
+            .proposal_deposit
+            .filter(|deposit| deposit.amount() > 0);
+        if let Some(deposit) = &deposit {
+            Self::transfer(ctx, caller_address, *ADDRESS_PROPOSAL_DEPOSITS, deposit)?;
+        }
+
+        let action = body.action;
         let proposal = types::Proposal {
             id: next_id,
             submitter: caller_address, // Use the submitter's address.
@@ -1279,10 +4514,18 @@ impl Module {
             content: body,   
             results: None,
             voteOption: None,
+            deposit,
+            executed: false,
         };
 
         Self::insert_proposal(ctx.runtime_state(), proposal)?;
 
+        ctx.emit_event(Event::ProposalSubmitted {
+            id: next_id,
+            submitter: caller_address,
+            action,
+        });
+
 
         // println!("gbtest: insert_proposal.");
         Ok(())
@@ -1312,192 +4555,165 @@ impl Module {
         Ok(())
     }
 
-    #[handler(call = "accounts.VoteST")]
-    fn tx_votest<C: TxContext>(ctx: &mut C, body: types::VoteProposal) -> Result<(), Error> {
+    #[handler(call = "accounts.VoteST")]
+    fn tx_votest<C: TxContext>(ctx: &mut C, body: types::VoteProposal) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        let caller_address = Self::tx_role_caller(ctx);
+
+
+        // println!("gbtest: caller_address is {:?}: ", caller_address);
+        let mut proposal = Self::get_proposal(ctx.runtime_state(), body.id)?;
+        // println!("gbtest file: {}, line: {}", file!(), line!());
+
+        // check whether the caller has voted or not.
+        let mut vote_option = proposal.voteOption;
+        if let Some(map) = vote_option.as_mut() {
+            if map.contains_key(&caller_address) {
+                // println!("gbtest: The address '{}' is present in the map.", caller_address);
+                return Err(Error::VoteDup);
+            } else {
+                // println!("gbtest: The address '{}' is not found in the map.", caller_address);
+                map.insert(caller_address, body.option);
+                proposal.voteOption = Some(map.clone());
+            }
+        } else {
+            // println!("gbtest: The map is None.");
+            let mut map = HashMap::new();
+            map.insert(caller_address, body.option);
+            proposal.voteOption = Some(map);
+        }
+
+
+        if proposal.state != ProposalState::Active {
+            return Err(Error::InvalidState);
+        }
+        Self::tally_vote(ctx, proposal, caller_address, body.option)
+    }
+
+    #[handler(call = "accounts.ChangeVote")]
+    fn tx_change_vote<C: TxContext>(ctx: &mut C, body: types::VoteProposal) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        let caller_address = Self::tx_role_caller(ctx);
+        let mut proposal = Self::get_proposal(ctx.runtime_state(), body.id)?;
+        if proposal.id != body.id {
+            return Err(Error::NotFound);
+        }
+        if proposal.state != ProposalState::Active {
+            return Err(Error::InvalidState);
+        }
+
+        // Only votes that have already been cast can be changed.
+        let votes = proposal.voteOption.get_or_insert_with(HashMap::new);
+        let previous = *votes.get(&caller_address).ok_or(Error::NotFound)?;
+        if previous == body.option {
+            return Err(Error::VoteDup);
+        }
+        votes.insert(caller_address, body.option);
+        proposal.remove_vote(previous);
+
+        Self::tally_vote(ctx, proposal, caller_address, body.option)
+    }
+
+
+    #[handler(call = "accounts.CancelProposal")]
+    fn tx_cancel_proposal<C: TxContext>(
+        ctx: &mut C,
+        body: types::CancelProposal,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        let caller_address = Self::tx_role_caller(ctx);
+        let is_admin = Self::has_role(ctx.runtime_state(), caller_address, Role::Admin);
+
+        let mut proposal = Self::get_proposal(ctx.runtime_state(), body.id)?;
+        if proposal.id != body.id {
+            return Err(Error::NotFound);
+        }
+        if proposal.state != ProposalState::Active {
+            return Err(Error::InvalidState);
+        }
+        // Only the submitter or an admin may cancel a proposal.
+        if proposal.submitter != caller_address && !is_admin {
+            return Err(Error::InvalidRole);
+        }
+
+        proposal.state = ProposalState::Cancelled;
+        Self::release_proposal_votes(ctx.runtime_state(), &mut proposal);
+        Self::release_proposal_deposit(ctx, &proposal)?;
+        Self::insert_proposal(ctx.runtime_state(), proposal)?;
+
+        ctx.emit_event(Event::ProposalCancelled {
+            id: body.id,
+            canceller: caller_address,
+        });
+
+        Ok(())
+    }
+
+    #[handler(call = "accounts.ExecuteProposal")]
+    fn tx_execute_proposal<C: TxContext>(
+        ctx: &mut C,
+        body: types::ExecuteProposal,
+    ) -> Result<(), Error> {
         let params = Self::params(ctx.runtime_state());
         <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
 
-        let caller_address = ctx.tx_caller_address();
-        let caller_role = Self::get_role(ctx.runtime_state(), caller_address).unwrap_or_default();
-
-
-        // println!("gbtest: caller_address is {:?}: ", caller_address);
+        // Anyone may pay for executing a passed proposal. In case execution fails, the proposal
+        // remains passed and execution can be retried.
+        let caller_address = Self::tx_role_caller(ctx);
         let mut proposal = Self::get_proposal(ctx.runtime_state(), body.id)?;
-        // println!("gbtest file: {}, line: {}", file!(), line!());
-
-        // check whether the caller has voted or not.
-        let mut vote_option = proposal.voteOption;
-        if let Some(map) = vote_option.as_mut() {
-            if map.contains_key(&caller_address) {
-                // println!("gbtest: The address '{}' is present in the map.", caller_address);
-                return Err(Error::VoteDup);
-            } else {
-                // println!("gbtest: The address '{}' is not found in the map.", caller_address);
-                map.insert(caller_address, body.option);
-                proposal.voteOption = Some(map.clone());
-            }
-        } else {
-            // println!("gbtest: The map is None.");
-            let mut map = HashMap::new();
-            map.insert(caller_address, body.option);
-            proposal.voteOption = Some(map);
+        if proposal.id != body.id {
+            return Err(Error::NotFound);
+        }
+        if proposal.state != ProposalState::Passed || proposal.executed {
+            return Err(Error::InvalidState);
         }
-        
 
-        if proposal.state == ProposalState::Active {
-            // sifei: get_action  (mint/burn/whitelist/blacklist/config/SetRoles)
-            let action = proposal.content.action;
+        if ctx.is_check_only() {
+            return Ok(());
+        }
 
-            // GB: if the caller_role does not match the role required by the action, then return error.
-            // GBTODO: the voter can not vote twice.
-            if let Some(role) = Self::get_voter_with_action(action) {
-                if caller_role != role {
-                    return Err(Error::InvalidRole);
-                }
-            } else {
-                return Err(Error::InvalidRole);
-            }
+        Self::execute_proposal_action(ctx, &proposal)?;
+        proposal.executed = true;
+        Self::insert_proposal(ctx.runtime_state(), proposal)?;
 
+        ctx.emit_event(Event::ProposalExecuted {
+            id: body.id,
+            executor: caller_address,
+        });
 
-            // sifei: define get_quorum from state with action for the following usage.
-            let quorum = Self::get_quorum(ctx.runtime_state(), action)?;
-            if quorum > 100 {
-                return Err(Error::InvalidQuorum);
-            }
+        Ok(())
+    }
 
 
-            // Sifei: get total no of voters from role based on action
-            let voter_total:u16 = Self::get_voters_num_with_action(ctx.runtime_state(), action)?;
-            // sifei: if the vote_count exceed the requirements of specific action (mint), 
-            let vote_count = proposal.add_vote(body.option);
-            if body.option == Vote::VoteYes {
-                // GB: round up to ensure enough votes.
-                let result = voter_total as u32 * quorum as u32;
-                let threshold = (result + 99) / 100; // +99 is equivalent to + (divisor - 1)
-
-                if  vote_count  >= (threshold as u16)  {
-                    // this is the interface for invoke action mint/burn/whitelist/blacklist/config function.
-                    let proposaldata = proposal.content.data.clone();
-                    match action {
-                        Action::Mint =>  {
-                            //get data from proposalData and invoke mint
-                            let mintaddress = match proposaldata.address {
-                                None  =>  return Err(Error::NotFound),
-                                Some(addr) => addr,
-                            };
-                            let mintamount  = match proposaldata.amount {
-                                None =>  return Err(Error::NotFound),
-                                Some(amt) => amt,
-                            };
-                            Self::mint(ctx, mintaddress, &mintamount)?;
-                        },
-                        Action::Burn => {
-                            //get data from proposalData and invoke burn
-                            let burnaddress = match proposaldata.address {
-                                None  =>  return Err(Error::NotFound),
-                                Some(addr) => addr,
-                            };
-                            let burnamount  = match proposaldata.amount {
-                                None =>  return Err(Error::NotFound),
-                                Some(amt) => amt,
-                            };
-                            Self::burn(ctx, burnaddress, &burnamount)?;
-                        },
-                        Action::Whitelist =>  {
-                            //get data from proposalData and invoke Whitelist
-                            let whitelistaddress = match proposaldata.address {
-                                None  =>  return Err(Error::NotFound),
-                                Some(addr) => addr,
-                            };
-
-                            //set current role for account
-                            Self::set_role(ctx.runtime_state(), whitelistaddress, Role::WhitelistedUser);
-                            // Self::add_address_to_roles(ctx.runtime_state(), whitelistaddress, Role::WhitelistedUser)?;
-                            //set whitelist role for account
-                            Self::add_role_to_address(ctx.runtime_state(), whitelistaddress, Role::WhitelistedUser);
-
-                        },
-                        Action::Blacklist =>  {
-                            //get data from proposalData and invoke Blacklist
-                            let blacklistaddress = match proposaldata.address {
-                                None  =>  return Err(Error::NotFound),
-                                Some(addr) => addr,
-                            };
-
-                            //set role for account
-                            Self::set_role(ctx.runtime_state(), blacklistaddress, Role::BlacklistedUser);
-                            //set blacklist role for account
-                            Self::add_role_to_address(ctx.runtime_state(), blacklistaddress, Role::BlacklistedUser);
-                        },
-
-                        Action::Config => {
-                            //get data from proposalData and invoke config
-                            if proposaldata.mint_quorum != None {
-                                Self::set_quorum(ctx.runtime_state(), Action::Mint,proposaldata.mint_quorum.unwrap())?;
-                            }
-                            if proposaldata.burn_quorum != None {
-                                Self::set_quorum(ctx.runtime_state(), Action::Burn,proposaldata.burn_quorum.unwrap())?;
-                            }
-                            if proposaldata.whitelist_quorum != None {
-                                Self::set_quorum(ctx.runtime_state(), Action::Whitelist,proposaldata.whitelist_quorum.unwrap())?;
-                            }
-                            if proposaldata.blacklist_quorum != None {
-                                Self::set_quorum(ctx.runtime_state(), Action::Blacklist,proposaldata.blacklist_quorum.unwrap())?;
-                            }
-                            if proposaldata.config_quorum != None {
-                                Self::set_quorum(ctx.runtime_state(), Action::Config,proposaldata.config_quorum.unwrap())?;
-                            }
-
-                        },
-                        Action::NoAction => {
-                            // no actions
-                        },
-                        Action::SetRoles => {
-                            //get data from proposalData and SetRoles
-                            let editroleaddress = match proposaldata.address {
-                                None  =>  return Err(Error::NotFound),
-                                Some(addr) => addr,
-                            };
-                            let editrolerole  = match proposaldata.role {
-                                None =>  return Err(Error::NotFound),
-                                Some(rl) => rl,
-                            };
-                            //set current role for account
-                            Self::set_role(ctx.runtime_state(), editroleaddress, editrolerole);
-                            //set editrole role for account
-                            Self::add_role_to_address(ctx.runtime_state(), editroleaddress, editrolerole);
-                        },
-                    }
-                    // then change the proposal state and clear the voteOption to save space.
-                    proposal.state = ProposalState::Passed;
-                    proposal.voteOption = None;
-                }
+    #[handler(call = "accounts.RevokeRole")]
+    fn tx_revoke_role<C: TxContext>(ctx: &mut C, body: types::RoleAddress) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
 
-                //saved proposal late
-            } else if  body.option == Vote::VoteNo {
-                // GB: round up to ensure enough votes.
-                let result = voter_total as u32 * (100 - quorum) as u32;
-                let threshold = (result + 99) / 100; // +99 is equivalent to + (divisor - 1)
+        // Only admins may revoke roles.
+        let caller_address = Self::tx_role_caller(ctx);
+        if !Self::has_role(ctx.runtime_state(), caller_address, Role::Admin) {
+            return Err(Error::InvalidRole);
+        }
+        // Governance must not be left without an admin.
+        if body.role == Role::Admin
+            && Self::get_addrsno_in_role(ctx.runtime_state(), Role::Admin) <= 1
+        {
+            return Err(Error::Forbidden);
+        }
 
-                if  vote_count  >= (threshold as u16)  {
-                    // then change the proposal state.
-                    proposal.state = ProposalState::Rejected;
-                    proposal.voteOption = None;
-                }
-            } else {
-                // proposal cancelled if half of voters abstain.
-                // GBTODO: further verify and refine later.
-                if vote_count as f32 >= voter_total as f32 * 0.5 {                    
-                    proposal.state = ProposalState::Cancelled;
-                    proposal.voteOption = None;
-                }
-            }
+        Self::revoke_role(ctx.runtime_state(), body.address, body.role)?;
 
-            // finally, save the updated proposal.
-            Self::insert_proposal(ctx.runtime_state(), proposal)?;
-        }else{
-            return Err(Error::InvalidState);
-        }
+        ctx.emit_event(Event::RoleRevoked {
+            address: body.address,
+            role: body.role,
+        });
 
         Ok(())
     }
@@ -1593,6 +4809,7 @@ impl Module {
         if params.mintst_disabled {
             return Err(Error::Forbidden);
         }
+        Self::ensure_not_reserved(body.to)?;
 
         <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
 
@@ -1652,12 +4869,110 @@ impl Module {
 
 
 
+    #[handler(call = "accounts.AddSessionKey")]
+    fn tx_add_session_key<C: TxContext>(ctx: &mut C, body: types::SessionKey) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        if params.max_session_key_rounds == 0 {
+            return Err(Error::Forbidden);
+        }
+
+        // Only role holders may register session keys.
+        let owner = ctx.tx_caller_address();
+        let roles = Self::get_roles(ctx.runtime_state(), owner);
+        if roles.contains(&Role::BlacklistedUser)
+            || roles
+                .iter()
+                .all(|role| matches!(role, Role::User | Role::WhitelistedUser))
+        {
+            return Err(Error::InvalidRole);
+        }
+
+        let round = ctx.runtime_header().round;
+        if body.address == owner
+            || body.expiry < round
+            || body.expiry - round > params.max_session_key_rounds
+            || body.methods.is_empty()
+            || !body
+                .methods
+                .iter()
+                .all(|method| SESSION_KEY_METHODS.contains(&method.as_str()))
+        {
+            return Err(Error::InvalidArgument);
+        }
+
+        // The session key must co-sign its registration to prove that it is controlled by the
+        // owner and not someone else's account.
+        if !ctx
+            .tx_auth_info()
+            .signer_info
+            .iter()
+            .any(|si| si.address_spec.address() == body.address)
+        {
+            return Err(Error::Forbidden);
+        }
+
+        // Session keys must be fresh keys that are not used by anyone else.
+        if Self::get_session_key(ctx.runtime_state(), body.address).is_some()
+            || Self::get_role(ctx.runtime_state(), body.address).unwrap_or_default() != Role::User
+        {
+            return Err(Error::Forbidden);
+        }
+
+        Self::set_session_key(
+            ctx.runtime_state(),
+            body.address,
+            Some(types::SessionKeyInfo {
+                owner,
+                expiry: body.expiry,
+                methods: body.methods,
+            }),
+        );
+
+        Ok(())
+    }
+
+    #[handler(call = "accounts.RevokeSessionKey")]
+    fn tx_revoke_session_key<C: TxContext>(
+        ctx: &mut C,
+        body: types::RevokeSessionKey,
+    ) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_managest)?;
+
+        let session_key =
+            Self::get_session_key(ctx.runtime_state(), body.address).ok_or(Error::NotFound)?;
+        if session_key.owner != ctx.tx_caller_address() {
+            return Err(Error::Forbidden);
+        }
+        Self::set_session_key(ctx.runtime_state(), body.address, None);
+
+        Ok(())
+    }
+
+    #[handler(query = "accounts.SessionKey")]
+    fn query_session_key<C: Context>(
+        ctx: &mut C,
+        args: types::SessionKeyQuery,
+    ) -> Result<types::SessionKeyInfo, Error> {
+        Self::get_session_key(ctx.runtime_state(), args.address).ok_or(Error::NotFound)
+    }
+
     // GB: insert for info query.
     #[handler(query = "accounts.Role")]
     fn query_role<C: Context>(ctx: &mut C, args: types::RoleQuery) -> Result<role::Role, Error> {
         Self::get_role(ctx.runtime_state(), args.address)
     }
 
+    #[handler(query = "accounts.Roles")]
+    fn query_roles<C: Context>(
+        ctx: &mut C,
+        args: types::RoleQuery,
+    ) -> Result<BTreeSet<role::Role>, Error> {
+        Ok(Self::get_roles(ctx.runtime_state(), args.address))
+    }
+
     #[handler(query = "accounts.Init")]
     fn query_init<C: Context>(ctx: &mut C, args: types::InitInfoQuery) -> Result<bool, Error> {
         Self::get_initstatus(ctx.runtime_state(), args.address)
@@ -1669,6 +4984,30 @@ impl Module {
         Self::get_quorum(ctx.runtime_state(), args.action)
     }
 
+    #[handler(query = "accounts.Quorums")]
+    fn query_quorums<C: Context>(ctx: &mut C, _dummy: ()) -> Result<BTreeMap<Action, u8>, Error> {
+        QUORUM_ACTIONS
+            .iter()
+            .map(|action| Ok((*action, Self::get_quorum(ctx.runtime_state(), *action)?)))
+            .collect()
+    }
+
+    #[handler(query = "accounts.AbstainQuorums")]
+    fn query_abstain_quorums<C: Context>(
+        ctx: &mut C,
+        _dummy: (),
+    ) -> Result<BTreeMap<Action, u8>, Error> {
+        QUORUM_ACTIONS
+            .iter()
+            .map(|action| {
+                Ok((
+                    *action,
+                    Self::get_abstain_quorum(ctx.runtime_state(), *action)?,
+                ))
+            })
+            .collect()
+    }
+
 
     #[handler(query = "accounts.RoleAddresses", expensive)]
     fn query_roleaddresses<C: Context>(
@@ -1689,6 +5028,22 @@ impl Module {
         Self::get_proposal(ctx.runtime_state(), id)
     }
 
+    #[handler(query = "accounts.ProposalVotes")]
+    fn query_proposal_votes<C: Context>(
+        ctx: &mut C,
+        args: types::ProposalVotesQuery,
+    ) -> Result<BTreeMap<Address, Vote>, Error> {
+        let proposal = Self::get_proposal(ctx.runtime_state(), args.id)?;
+        if proposal.id != args.id {
+            return Err(Error::NotFound);
+        }
+        Ok(proposal
+            .voteOption
+            .unwrap_or_default()
+            .into_iter()
+            .collect())
+    }
+
 /*####################################################################################################*/
 
 
@@ -1714,6 +5069,35 @@ impl Module {
         Self::get_balances(ctx.runtime_state(), args.address)
     }
 
+    #[handler(query = "accounts.AllBalances", expensive)]
+    fn query_all_balances<C: Context>(
+        ctx: &mut C,
+        args: types::AllBalancesQuery,
+    ) -> Result<Vec<types::AddressBalances>, Error> {
+        let limit = match args.limit {
+            0 => MAX_ALL_BALANCES_QUERY_LIMIT,
+            limit => limit.min(MAX_ALL_BALANCES_QUERY_LIMIT),
+        } as usize;
+
+        Ok(Self::get_balances_page(ctx.runtime_state(), args.start_after, limit))
+    }
+
+    #[handler(query = "accounts.TotalSupply")]
+    fn query_total_supply<C: Context>(
+        ctx: &mut C,
+        args: types::TotalSupplyQuery,
+    ) -> Result<u128, Error> {
+        Self::get_total_supply(ctx.runtime_state(), args.denomination)
+    }
+
+    #[handler(query = "accounts.TotalSupplies")]
+    fn query_total_supplies<C: Context>(
+        ctx: &mut C,
+        _dummy: (),
+    ) -> Result<BTreeMap<token::Denomination, u128>, Error> {
+        Self::get_total_supplies(ctx.runtime_state())
+    }
+
     #[handler(query = "accounts.DenominationInfo")]
     fn query_denomination_info<C: Context>(
         ctx: &mut C,
@@ -1725,6 +5109,7 @@ impl Module {
 
 impl module::Module for Module {
     const NAME: &'static str = MODULE_NAME;
+    const VERSION: u32 = 6;
     type Error = Error;
     type Event = Event;
     type Parameters = Parameters;
@@ -1738,6 +5123,11 @@ impl Module {
         let mut accounts =
             storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::ACCOUNTS));
         for (address, account) in genesis.accounts {
+            if let Some(schedule) = &account.vesting {
+                if !schedule.is_valid() {
+                    panic!("invalid vesting schedule for account {address:?}");
+                }
+            }
             accounts.insert(address, account);
         }
 
@@ -1758,6 +5148,13 @@ impl Module {
             }
         }
 
+        // Initialize the running sums of the balances.
+        let mut balance_totals =
+            storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::BALANCE_TOTALS));
+        for (denomination, total) in computed_total_supply.iter() {
+            balance_totals.insert(denomination, total);
+        }
+
         // Validate and set total supply.
         let mut total_supplies =
             storage::TypedStore::new(storage::PrefixStore::new(&mut store, &state::TOTAL_SUPPLY));
@@ -1787,9 +5184,88 @@ impl Module {
     }
 
     /// Migrate state from a previous version.
-    fn migrate<C: Context>(_ctx: &mut C, _from: u32) -> bool {
-        // No migrations currently supported.
-        false
+    fn migrate<C: Context>(ctx: &mut C, from: u32) -> bool {
+        if from >= Self::VERSION {
+            return false;
+        }
+
+        // Version 2 introduced the active proposal index.
+        if from < 2 {
+            let last_id = Self::get_proposal_id(ctx.runtime_state()).unwrap_or_default();
+            for id in 1..=last_id {
+                let proposal = Self::get_proposal(ctx.runtime_state(), id).unwrap_or_default();
+                if proposal.id == id {
+                    Self::insert_proposal(ctx.runtime_state(), proposal)
+                        .expect("insert_proposal must succeed");
+                }
+            }
+        }
+
+        // Version 3 made addresses multi-role, so the role of each account must be part of the
+        // account's role set.
+        if from < 3 {
+            let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+            let accounts =
+                storage::TypedStore::new(storage::PrefixStore::new(store, &state::ACCOUNTS));
+            let roles: Vec<(Address, Role)> = accounts
+                .iter()
+                .map(|(address, account): (Address, types::Account)| (address, account.role))
+                .filter(|(_, role)| *role != Role::User)
+                .collect();
+            for (address, role) in roles {
+                Self::add_role_to_address(ctx.runtime_state(), address, role);
+            }
+        }
+
+        // Version 4 moved the quorums from the proposals state into the module parameters.
+        if from < 4 {
+            const LEGACY_QUORUM_KEYS: &[(&[u8], Action)] = &[
+                (b"proposal_mint_quorum", Action::Mint),
+                (b"proposal_burn_quorum", Action::Burn),
+                (b"proposal_whitelist_quorum", Action::Whitelist),
+                (b"proposal_blacklist_quorum", Action::Blacklist),
+                (b"proposal_config_quorum", Action::Config),
+            ];
+
+            let mut params = Self::params(ctx.runtime_state());
+            let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+            let mut proposals =
+                storage::TypedStore::new(storage::PrefixStore::new(store, &state::PROPOSALS));
+            for (key, action) in LEGACY_QUORUM_KEYS {
+                if let Some(quorum) = proposals.get::<_, u8>(key) {
+                    params.quorums.insert(*action, quorum);
+                    proposals.remove(key);
+                }
+            }
+            Self::set_params(ctx.runtime_state(), params);
+        }
+
+        // Version 5 introduced the running sums of the balances.
+        if from < 5 {
+            let totals = Self::compute_balance_totals(ctx.runtime_state())
+                .expect("compute_balance_totals must succeed");
+            let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+            let mut balance_totals =
+                storage::TypedStore::new(storage::PrefixStore::new(store, &state::BALANCE_TOTALS));
+            for (denomination, total) in totals {
+                balance_totals.insert(denomination, total);
+            }
+        }
+
+        // Version 6 introduced the number of active proposals of each submitter.
+        if from < 6 {
+            let mut counts: BTreeMap<Address, u32> = BTreeMap::new();
+            for id in Self::get_active_proposal_ids(ctx.runtime_state()) {
+                let proposal = Self::get_proposal(ctx.runtime_state(), id)
+                    .expect("active proposals must exist");
+                *counts.entry(proposal.submitter).or_default() += 1;
+            }
+            for (submitter, count) in counts {
+                Self::set_active_proposal_count(ctx.runtime_state(), submitter, count);
+            }
+        }
+
+        true
     }
 }
 
@@ -1810,7 +5286,11 @@ impl module::MigrationHandler for Module {
         }
 
         // Perform migration.
-        Self::migrate(ctx, version)
+        if !Self::migrate(ctx, version) {
+            return false;
+        }
+        meta.versions.insert(Self::NAME.to_owned(), Self::VERSION);
+        true
     }
 }
 
@@ -1836,6 +5316,17 @@ impl module::TransactionHandler for Module {
         }
 
 
+        // Session keys may only be used for the methods they were authorized for and only until
+        // they expire.
+        for si in &tx.auth_info.signer_info {
+            let address = si.address_spec.address();
+            if let Some(session_key) = Self::get_session_key(ctx.runtime_state(), address) {
+                if !session_key.allows(&tx.call.method, round) {
+                    return Err(modules::core::Error::NotAuthenticated);
+                }
+            }
+        }
+
         // Check nonces.
         let payer = Self::check_signer_nonces(ctx, &tx.auth_info)?;
 
@@ -1845,6 +5336,13 @@ impl module::TransactionHandler for Module {
             return Err(modules::core::Error::NotAuthenticated);
         }
 
+        // Frozen accounts may not submit any transactions.
+        if Self::is_frozen(ctx.runtime_state(), payer) {
+            return Err(modules::core::Error::NotAuthenticated);
+        }
+
+        // Fees are paid by the granter in case the payer has been given a fee grant.
+        let fee_payer = Self::fee_payer(ctx, &tx.auth_info, payer)?;
 
         // Charge the specified amount of fees.
         if !tx.auth_info.fee.amount.amount().is_zero() {
@@ -1852,11 +5350,28 @@ impl module::TransactionHandler for Module {
                 // Do not update balances during transaction checks. In case of checks, only do it
                 // after all the other checks have already passed as otherwise retrying the
                 // transaction will not be possible.
-                Self::ensure_balance(ctx.runtime_state(), payer, &tx.auth_info.fee.amount)
+                Self::ensure_balance(ctx.runtime_state(), fee_payer, &tx.auth_info.fee.amount)
+                    .and_then(|_| {
+                        Self::ensure_spendable(ctx, fee_payer, &tx.auth_info.fee.amount)
+                    })
                     .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
+                // The common pool must cover the native equivalent of converted fees.
+                let native_fee = Self::convert_fee(ctx.runtime_state(), &tx.auth_info.fee.amount);
+                if let Some(fee) = native_fee {
+                    Self::ensure_balance(ctx.runtime_state(), *ADDRESS_COMMON_POOL, &fee)
+                        .map_err(|_| modules::core::Error::InsufficientFeeBalance)?;
+                }
             } else {
                 // Actually perform the move.
-                Self::move_into_fee_accumulator(ctx, payer, &tx.auth_info.fee.amount)?;
+                Self::pay_fee(ctx, fee_payer, &tx.auth_info.fee.amount)?;
+                if fee_payer != payer {
+                    Self::use_fee_grant(
+                        ctx.runtime_state(),
+                        fee_payer,
+                        payer,
+                        &tx.auth_info.fee.amount,
+                    );
+                }
             }
 
             // TODO: Emit event that fee has been paid.
@@ -1879,11 +5394,40 @@ impl module::TransactionHandler for Module {
         Ok(())
     }
 
+    fn authorize_call<C: TxContext>(ctx: &mut C, method: &str) -> Result<(), modules::core::Error> {
+        // Every signer must be allowed to call the method based on its roles.
+        let signers: Vec<Address> = ctx
+            .tx_auth_info()
+            .signer_info
+            .iter()
+            .map(|si| si.address_spec.address())
+            .collect();
+        for signer in signers {
+            if !Self::method_authorizer(ctx.runtime_state(), signer)(method) {
+                return Err(modules::core::Error::Forbidden);
+            }
+        }
+        Ok(())
+    }
+
+    fn before_handle_call<C: TxContext>(
+        ctx: &mut C,
+        _call: &Call,
+    ) -> Result<(), modules::core::Error> {
+        // Remember the transaction index so that it can be recorded in the activity index.
+        let tx_index = ctx.tx_index();
+        ctx.value::<usize>(CONTEXT_KEY_TX_INDEX).set(tx_index);
+        Ok(())
+    }
+
     fn after_dispatch_tx<C: Context>(
         ctx: &mut C,
         tx_auth_info: &AuthInfo,
         result: &module::CallResult,
     ) {
+        // Activity outside of transactions (e.g. in block handlers) has no transaction index.
+        ctx.value::<usize>(CONTEXT_KEY_TX_INDEX).take();
+
         if !ctx.is_check_only() {
             // Do nothing outside transaction checks.
             return;
@@ -1895,33 +5439,30 @@ impl module::TransactionHandler for Module {
 
         // Update payer balance.
         let payer = Self::check_signer_nonces(ctx, tx_auth_info).unwrap(); // Already checked.
+        let fee_payer = Self::fee_payer(ctx, tx_auth_info, payer).unwrap(); // Already checked.
         let amount = &tx_auth_info.fee.amount;
-        Self::sub_amount(ctx.runtime_state(), payer, amount).unwrap(); // Already checked.
+        Self::sub_amount(ctx.runtime_state(), fee_payer, amount).unwrap(); // Already checked.
+        if fee_payer != payer {
+            Self::use_fee_grant(ctx.runtime_state(), fee_payer, payer, amount);
+        }
 
         // Update nonces.
-        Self::update_signer_nonces(ctx, tx_auth_info).unwrap();
+        Self::update_checked_signer_nonces(ctx, tx_auth_info);
     }
 }
 
 impl module::BlockHandler for Module {
     fn end_block<C: Context>(ctx: &mut C) {
         // Determine the fees that are available for disbursement from the last block.
-        // MZ, this takes long time
-        /*
         let previous_fees = Self::get_balances(ctx.runtime_state(), *ADDRESS_FEE_ACCUMULATOR)
             .expect("get_balances must succeed")
             .balances;
-        */
-
-        let previous_fee = Self::get_balance(
-            ctx.runtime_state(),
-            *ADDRESS_FEE_ACCUMULATOR,
-            token::Denomination::NATIVE,
-        )
-        .expect("get_balance must succeed");
+        let previous_fee = previous_fees
+            .get(&token::Denomination::NATIVE)
+            .copied()
+            .unwrap_or_default();
 
         // Drain previous fees from the fee accumulator.
-        /*
         for (denom, remainder) in &previous_fees {
             Self::sub_amount(
                 ctx.runtime_state(),
@@ -1930,23 +5471,23 @@ impl module::BlockHandler for Module {
             )
             .expect("sub_amount must succeed");
         }
-        */
-
-        Self::sub_amount(
-            ctx.runtime_state(),
-            *ADDRESS_FEE_ACCUMULATOR,
-            &token::BaseUnits::new(previous_fee, token::Denomination::NATIVE),
-        )
-        .expect("sub_amount must succeed");
 
         // Disburse transaction fees to entities controlling all the good nodes in the committee.
         let addrs: Vec<Address> = ctx
             .runtime_round_results()
             .good_compute_entities
             .iter()
-            .map(|pk| Address::from_sigspec(&SignatureAddressSpec::Ed25519(pk.into())))
+            .filter_map(|pk| {
+                Address::from_sigspec(&SignatureAddressSpec::Ed25519(pk.into())).ok()
+            })
+            .collect();
+        // Entities may redirect their fees to a registered reward address.
+        let addrs: Vec<Address> = addrs
+            .into_iter()
+            .map(|address| Self::get_reward_address(ctx.runtime_state(), address))
             .collect();
 
+        let mut disbursed_fees: u128 = 0;
         if !addrs.is_empty() {
             // 1. Get the total amount of fees.
             // NOTE: demonination is not used here, as we assume that all fees are in the same denomination.
@@ -1957,42 +5498,108 @@ impl module::BlockHandler for Module {
             */
             let total_fees = previous_fee;
 
-            // 2. Tax (10% of the total fees) is transferred to the common pool.
-            let tax: u128 = total_fees
-                .checked_div(10)
-                .expect("10% of the total fees should be non-zero");
+            // 2. Split the total fees into the tax, the burned share and the remaining fees as
+            // configured in the parameters.
+            let distribution = Self::params(ctx.runtime_state()).fee_distribution;
+            let (tax, burn, remaining_fees) = distribution.split(total_fees);
+
+            if burn > 0 {
+                let amount = token::BaseUnits::new(burn, token::Denomination::NATIVE);
+                Self::dec_total_supply(ctx.runtime_state(), &amount)
+                    .expect("dec_total_supply must succeed for burning fees");
+                ctx.emit_event(Event::Burn {
+                    owner: *ADDRESS_FEE_ACCUMULATOR,
+                    amount,
+                });
+            }
+
+            // 3. The remaining fees are distributed among the good nodes according to the policy.
+            let mut shares: BTreeMap<Address, u128> = BTreeMap::new();
+            for address in addrs {
+                let share = shares.entry(address).or_default();
+                match distribution.policy {
+                    types::FeeDistributionPolicy::Equal => *share = 1,
+                    types::FeeDistributionPolicy::Proportional => *share += 1,
+                }
+            }
+            let total_shares: u128 = shares.values().sum();
+            let fee_per_share = remaining_fees
+                .checked_div(total_shares)
+                .expect("addrs is non-empty");
+
+            let mut distributed_fees: u128 = 0;
+            for (address, share) in shares {
+                let node_fee = fee_per_share * share;
+                Self::add_amount(
+                    ctx.runtime_state(),
+                    address,
+                    &token::BaseUnits::new(node_fee, token::Denomination::NATIVE),
+                )
+                .expect("add_amount must succeed for fee disbursement");
+                distributed_fees += node_fee;
+            }
+
+            // 4. The tax and any remainder that could not be split evenly go to the common pool.
+            let common_pool_fees = tax + (remaining_fees - distributed_fees);
             Self::add_amount(
                 ctx.runtime_state(),
                 *ADDRESS_COMMON_POOL,
-                &token::BaseUnits::new(
-                    tax, token::Denomination::NATIVE),
+                &token::BaseUnits::new(common_pool_fees, token::Denomination::NATIVE),
             )
             .expect("add_amount must succeed for transfer to the common pool (taxation)");
 
-            // 3. The remaining fees are distributed among the good nodes.
-            let remaining_fees = total_fees
-                .checked_sub(tax)
-                .expect("remaining fees should be non-zero");
-            // Divide the remaining fees equally among the good nodes
-            let each_node_fee = remaining_fees
-                .checked_div(addrs.len() as u128)
-                .expect("addrs is non-empty");
+            disbursed_fees = common_pool_fees + burn + distributed_fees;
+        }
 
-            for address in addrs {
-                Self::add_amount(
-                    ctx.runtime_state(), 
-                    address, 
-                    &token::BaseUnits::new(
-                        each_node_fee, token::Denomination::NATIVE))
-                .expect("add_amount must succeed for fee disbursement");
+        // Fees that have not been disbursed go to the common pool. These are native fees in case
+        // there are no good compute nodes and fees in other denominations, which are only ever
+        // disbursed after having been converted.
+        let mut undisbursed_fees = previous_fees;
+        undisbursed_fees.insert(
+            token::Denomination::NATIVE,
+            previous_fee.saturating_sub(disbursed_fees),
+        );
+        for (denom, amount) in undisbursed_fees {
+            if amount == 0 {
+                continue;
+            }
+            Self::add_amount(
+                ctx.runtime_state(),
+                *ADDRESS_COMMON_POOL,
+                &token::BaseUnits::new(amount, denom.clone()),
+            )
+            .expect("add_amount must succeed for transfer of undisbursed fees");
+            if denom.is_native() {
+                disbursed_fees += amount;
             }
         }
 
+        // Watchdog: the fee accumulator must be fully drained and all drained fees disbursed.
+        if disbursed_fees != previous_fee {
+            ctx.emit_event(Event::FeeAccumulatorViolation {
+                reason: format!(
+                    "disbursed fees don't match drained fees (disbursed={disbursed_fees}, drained={previous_fee})",
+                ),
+            });
+        }
+
         // Fees for the active block should be transferred to the fee accumulator address.
         let acc = ctx
             .value::<FeeAccumulator>(CONTEXT_KEY_FEE_ACCUMULATOR)
             .take()
             .unwrap_or_default();
+        let remaining_fees = Self::get_balances(ctx.runtime_state(), *ADDRESS_FEE_ACCUMULATOR)
+            .expect("get_balances must succeed")
+            .balances;
+        for (denom, remaining) in remaining_fees {
+            if remaining != 0 {
+                ctx.emit_event(Event::FeeAccumulatorViolation {
+                    reason: format!(
+                        "fee accumulator not drained (denomination={denom}, remaining={remaining})",
+                    ),
+                });
+            }
+        }
         for (denom, amount) in acc.total_fees.into_iter() {
             Self::add_amount(
                 ctx.runtime_state(),
@@ -2001,6 +5608,10 @@ impl module::BlockHandler for Module {
             )
             .expect("add_amount must succeed for transfer to fee accumulator")
         }
+
+        // Time-limited whitelistings are revoked at the end of their expiry round.
+        let round = ctx.runtime_header().round;
+        Self::expire_whitelistings(ctx, round);
     }
 }
 
@@ -2008,58 +5619,25 @@ impl module::InvariantHandler for Module {
     /// Check invariants.
     fn check_invariants<C: Context>(ctx: &mut C) -> Result<(), CoreError> {
         // All account balances should sum up to the total supply for their
-        // corresponding denominations.
+        // corresponding denominations. The sums are maintained as balances
+        // change, so only the aggregates need to be compared.
 
-        #[allow(clippy::or_fun_call)]
-        let balances = Self::get_all_balances(ctx.runtime_state()).or(Err(
-            CoreError::InvariantViolation("unable to get balances of all accounts".to_string()),
-        ))?;
         #[allow(clippy::or_fun_call)]
         let total_supplies = Self::get_total_supplies(ctx.runtime_state()).or(Err(
             CoreError::InvariantViolation("unable to get total supplies".to_string()),
         ))?;
+        let balance_totals = Self::get_balance_totals(ctx.runtime_state());
+        Self::check_balance_totals(balance_totals, &total_supplies)?;
 
-        // First, compute total supplies based on account balances.
-        let mut computed_ts: BTreeMap<token::Denomination, u128> = BTreeMap::new();
-
-        for bals in balances.values() {
-            for (den, amt) in bals {
-                computed_ts
-                    .entry(den.clone())
-                    .and_modify(|a| *a += amt)
-                    .or_insert_with(|| *amt);
-            }
-        }
-
-        // Now check if the computed and given total supplies match.
-        for (den, ts) in &total_supplies {
-            // Return error if total supplies have a denomination that we
-            // didn't encounter when computing total supplies based on account
-            // balances.
+        // Optionally make sure that the running sums did not drift from the balances.
+        if Self::params(ctx.runtime_state()).deep_invariant_checks {
             #[allow(clippy::or_fun_call)]
-            let computed = computed_ts
-                .remove(den)
-                .ok_or(CoreError::InvariantViolation(
-                    "unexpected denomination".to_string(),
-                ))?;
-
-            if &computed != ts {
-                // Computed and actual total supplies don't match.
-                return Err(CoreError::InvariantViolation(format!(
-                    "computed and actual total supplies don't match (computed={computed}, actual={ts})",
-                )));
-            }
+            let computed_ts = Self::compute_balance_totals(ctx.runtime_state()).or(Err(
+                CoreError::InvariantViolation("unable to get balances of all accounts".to_string()),
+            ))?;
+            Self::check_balance_totals(computed_ts, &total_supplies)?;
         }
 
-        // There should be no remaining denominations in the computed supplies,
-        // because that would mean that accounts have denominations that don't
-        // appear in the total supplies table, which would obviously be wrong.
-        if computed_ts.is_empty() {
-            Ok(())
-        } else {
-            Err(CoreError::InvariantViolation(
-                "encountered denomination that isn't present in total supplies table".to_string(),
-            ))
-        }
+        Ok(())
     }
 }
@@ -6,21 +6,30 @@ use std::{
 
 use anyhow::anyhow;
 
+use oasis_core_runtime::common::version::Version;
+
 use crate::{
-    context::{BatchContext, Context},
-    module::{BlockHandler, InvariantHandler, MethodHandler, TransactionHandler},
+    context::{BatchContext, Context, Mode},
+    module::{
+        BlockHandler, InvariantHandler, MethodHandler, MigrationHandler, Module as _,
+        TransactionHandler,
+    },
     modules::{core, core::API as _},
+    runtime::Runtime,
     testing::{keys, mock},
     types::{
+        address::{Address, SignatureAddressSpec},
         token::{BaseUnits, Denomination},
         transaction,
         role::Role,
+        proposal::ProposalState,
+        vote::{Action, Vote},
     },
 };
 
 use super::{
-    types::*, Error, Genesis, Module as Accounts, Parameters, ADDRESS_COMMON_POOL,
-    ADDRESS_FEE_ACCUMULATOR, API as _,
+    types::*, Error, Genesis, Module as Accounts, Parameters, TransferHook, ADDRESS_COMMON_POOL,
+    ADDRESS_ESCROW, ADDRESS_FEE_ACCUMULATOR, ADDRESS_PROPOSAL_DEPOSITS, ADDRESS_STREAMS, API as _,
 };
 
 #[test]
@@ -182,6 +191,31 @@ fn test_init_2() {
     );
 }
 
+#[test]
+fn test_query_parameters() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.transfers_disabled = true;
+    params.chain_initiator = keys::dave::address();
+    params.gas_costs.tx_transfer = 1_000;
+    Accounts::set_params(ctx.runtime_state(), params);
+
+    // The query reflects the live parameters rather than the genesis ones.
+    let queried_params = Accounts::query_parameters(&mut ctx, ()).unwrap();
+    assert!(queried_params.transfers_disabled);
+    assert!(!queried_params.mintst_disabled);
+    assert_eq!(queried_params.chain_initiator, keys::dave::address());
+    assert_eq!(queried_params.gas_costs.tx_transfer, 1_000);
+    assert_eq!(
+        queried_params.denomination_infos[&Denomination::NATIVE].decimals,
+        9
+    );
+}
+
 #[test]
 fn test_api_tx_transfer_disabled() {
     let mut mock = mock::Mock::default();
@@ -222,6 +256,7 @@ fn test_api_tx_transfer_disabled() {
             body: cbor::to_value(Transfer {
                 to: keys::bob::address(),
                 amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                memo: None,
             }),
             ..Default::default()
         },
@@ -277,6 +312,7 @@ fn test_prefetch() {
             body: cbor::to_value(Transfer {
                 to: keys::bob::address(),
                 amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                memo: None,
             }),
             ..Default::default()
         },
@@ -296,6 +332,29 @@ fn test_prefetch() {
             "there should be 4 prefixes to be fetched"
         );
     });
+
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.TransferFrom",
+        cbor::to_value(TransferFrom {
+            from: keys::bob::address(),
+            to: keys::charlie::address(),
+            amount: BaseUnits::new(1_000, Denomination::NATIVE),
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut _tx_ctx, call| {
+        let mut prefixes = BTreeSet::new();
+        let result = Accounts::prefetch(&mut prefixes, &call.method, call.body, &auth_info)
+            .ok_or(anyhow!("dispatch failure"))
+            .expect("prefetch should succeed");
+
+        assert!(matches!(result, Ok(())));
+        assert_eq!(
+            prefixes.len(),
+            7,
+            "there should be 7 prefixes to be fetched"
+        );
+    });
 }
 
 pub(crate) fn init_accounts<C: Context>(ctx: &mut C) {
@@ -321,7 +380,10 @@ pub(crate) fn init_accounts<C: Context>(ctx: &mut C) {
                 denomination_infos: {
                     let mut denomination_infos = BTreeMap::new();
                     denomination_infos
-                        .insert(Denomination::NATIVE, DenominationInfo { decimals: 9 });
+                        .insert(Denomination::NATIVE, DenominationInfo {
+                            decimals: 9,
+                            ..Default::default()
+                        });
                     denomination_infos
                 },
                 ..Default::default()
@@ -331,6 +393,32 @@ pub(crate) fn init_accounts<C: Context>(ctx: &mut C) {
     );
 }
 
+/// Builds a plain single-signer transaction calling `method` with `body`.
+pub(crate) fn tx_for(
+    signer: &SignatureAddressSpec,
+    method: &str,
+    body: cbor::Value,
+) -> transaction::Transaction {
+    transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: method.to_owned(),
+            body,
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(signer.clone(), 0)],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    }
+}
+
 #[test]
 fn test_api_transfer() {
     let mut mock = mock::Mock::default();
@@ -413,6 +501,7 @@ fn test_authenticate_tx() {
             body: cbor::to_value(Transfer {
                 to: keys::bob::address(),
                 amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                memo: None,
             }),
             ..Default::default()
         },
@@ -455,7 +544,7 @@ fn test_authenticate_tx() {
 
     // Should fail with an invalid nonce.
     let result = Accounts::authenticate_tx(&mut ctx, &tx);
-    assert!(matches!(result, Err(core::Error::InvalidNonce)));
+    assert!(matches!(result, Err(core::Error::InvalidNonce(..))));
 
     // Should fail when there's not enough balance to pay fees.
     tx.auth_info.signer_info[0].nonce = nonce;
@@ -464,6 +553,285 @@ fn test_authenticate_tx() {
     assert!(matches!(result, Err(core::Error::InsufficientFeeBalance)));
 }
 
+#[test]
+fn test_fee_grants() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let mut tx = transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "accounts.GrantFee".to_owned(),
+            body: cbor::to_value(GrantFee {
+                grantee: keys::bob::address(),
+                spend_limit: BaseUnits::new(1_500, Denomination::NATIVE),
+                expiration: 10,
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+
+    // Alice grants Bob up to 1500 native units of fees.
+    ctx.with_tx(0, 0, tx.clone(), |mut tx_ctx, call| {
+        Accounts::tx_grant_fee(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("granting fees should succeed");
+        tx_ctx.commit();
+    });
+    let fee_grant = Accounts::query_fee_grant(
+        &mut ctx,
+        FeeGrantQuery {
+            granter: keys::alice::address(),
+            grantee: keys::bob::address(),
+        },
+    )
+    .expect("fee grant should exist");
+    assert_eq!(fee_grant.spend_limit.amount(), 1_500);
+    assert_eq!(fee_grant.expiration, 10);
+
+    // Bob's fees are paid by Alice.
+    tx.call.method = "accounts.Transfer".to_owned();
+    tx.call.body = cbor::to_value(Transfer::default());
+    tx.auth_info.signer_info = vec![transaction::SignerInfo::new_sigspec(
+        keys::bob::sigspec(),
+        0,
+    )];
+    tx.auth_info.fee.amount = BaseUnits::new(1_000, Denomination::NATIVE);
+    tx.auth_info.fee_granter = Some(keys::alice::address());
+    Accounts::authenticate_tx(&mut ctx, &tx).expect("transaction authentication should succeed");
+    assert_eq!(
+        Accounts::get_balance(ctx.runtime_state(), keys::alice::address(), Denomination::NATIVE)
+            .unwrap(),
+        999_000,
+        "fees should be paid by the granter"
+    );
+    let fee_grant =
+        Accounts::get_fee_grant(ctx.runtime_state(), keys::alice::address(), keys::bob::address())
+            .unwrap();
+    assert_eq!(
+        fee_grant.spend_limit.amount(),
+        500,
+        "fees should be spent from the grant"
+    );
+
+    // Refunds of unused fees go back to the granter and are credited back to the grant.
+    ctx.with_tx(0, 0, tx.clone(), |mut tx_ctx, _call| {
+        Accounts::refund_fee(&mut tx_ctx, &BaseUnits::new(400, Denomination::NATIVE))
+            .expect("refunding fees should succeed");
+        tx_ctx.commit();
+    });
+    assert_eq!(
+        Accounts::get_balance(ctx.runtime_state(), keys::alice::address(), Denomination::NATIVE)
+            .unwrap(),
+        999_400,
+        "refunds should go to the granter"
+    );
+    assert_eq!(
+        Accounts::get_balance(ctx.runtime_state(), keys::bob::address(), Denomination::NATIVE)
+            .unwrap(),
+        0
+    );
+    let fee_grant =
+        Accounts::get_fee_grant(ctx.runtime_state(), keys::alice::address(), keys::bob::address())
+            .unwrap();
+    assert_eq!(
+        fee_grant.spend_limit.amount(),
+        900,
+        "refunds should be credited back to the grant"
+    );
+
+    // Fees above the spend limit are rejected.
+    tx.auth_info.signer_info[0].nonce = 1;
+    let result = Accounts::authenticate_tx(&mut ctx, &tx);
+    assert!(matches!(result, Err(core::Error::InsufficientFeeBalance)));
+
+    // Fees can't be paid by accounts that have not given a grant.
+    tx.auth_info.fee.amount = BaseUnits::new(100, Denomination::NATIVE);
+    tx.auth_info.fee_granter = Some(keys::charlie::address());
+    let result = Accounts::authenticate_tx(&mut ctx, &tx);
+    assert!(matches!(result, Err(core::Error::NotAuthenticated)));
+
+    // Revoked grants can no longer be used.
+    tx.call.method = "accounts.RevokeFeeGrant".to_owned();
+    tx.call.body = cbor::to_value(RevokeFeeGrant {
+        grantee: keys::bob::address(),
+    });
+    tx.auth_info.signer_info = vec![transaction::SignerInfo::new_sigspec(
+        keys::alice::sigspec(),
+        0,
+    )];
+    tx.auth_info.fee_granter = None;
+    ctx.with_tx(0, 0, tx.clone(), |mut tx_ctx, call| {
+        Accounts::tx_revoke_fee_grant(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("revoking a fee grant should succeed");
+        tx_ctx.commit();
+    });
+    assert!(
+        Accounts::get_fee_grant(ctx.runtime_state(), keys::alice::address(), keys::bob::address())
+            .is_none()
+    );
+}
+
+#[test]
+fn test_vesting() {
+    let mut mock = mock::Mock::default();
+    let alice = keys::alice::address();
+    let bob = keys::bob::address();
+    let schedule = VestingSchedule {
+        amount: BaseUnits::new(800_000, Denomination::NATIVE),
+        start: 0,
+        cliff: 10,
+        end: 100,
+    };
+
+    mock.runtime_header.round = 5;
+    {
+        let mut ctx = mock.create_ctx();
+        Accounts::init(
+            &mut ctx,
+            Genesis {
+                accounts: {
+                    let mut accounts = BTreeMap::new();
+                    accounts.insert(
+                        alice,
+                        Account {
+                            vesting: Some(schedule.clone()),
+                            ..Default::default()
+                        },
+                    );
+                    accounts
+                },
+                balances: {
+                    let mut balances = BTreeMap::new();
+                    balances.insert(alice, {
+                        let mut denominations = BTreeMap::new();
+                        denominations.insert(Denomination::NATIVE, 1_000_000);
+                        denominations
+                    });
+                    balances
+                },
+                total_supplies: {
+                    let mut total_supplies = BTreeMap::new();
+                    total_supplies.insert(Denomination::NATIVE, 1_000_000);
+                    total_supplies
+                },
+                ..Default::default()
+            },
+        );
+
+        // Nothing is released before the cliff.
+        let status = Accounts::query_vesting(&mut ctx, VestingQuery { address: alice }).unwrap();
+        assert_eq!(status.vested.amount(), 0);
+        assert_eq!(status.locked.amount(), 800_000);
+
+        let result = Accounts::transfer(
+            &mut ctx,
+            alice,
+            bob,
+            &BaseUnits::new(300_000, Denomination::NATIVE),
+        );
+        assert!(
+            matches!(result, Err(Error::InsufficientBalance)),
+            "locked balance should not be spendable"
+        );
+        Accounts::transfer(
+            &mut ctx,
+            alice,
+            bob,
+            &BaseUnits::new(200_000, Denomination::NATIVE),
+        )
+        .expect("unlocked balance should be spendable");
+    }
+
+    // Half of the amount is released halfway through the schedule.
+    mock.runtime_header.round = 50;
+    {
+        let mut ctx = mock.create_ctx();
+        let status = Accounts::query_vesting(&mut ctx, VestingQuery { address: alice }).unwrap();
+        assert_eq!(status.vested.amount(), 400_000);
+        assert_eq!(status.locked.amount(), 400_000);
+
+        Accounts::transfer(
+            &mut ctx,
+            alice,
+            bob,
+            &BaseUnits::new(400_000, Denomination::NATIVE),
+        )
+        .expect("vested balance should be spendable");
+        let result = Accounts::burn(&mut ctx, alice, &BaseUnits::new(1, Denomination::NATIVE));
+        assert!(matches!(result, Err(Error::InsufficientBalance)));
+
+        // Accounts without a schedule are not affected.
+        assert!(matches!(
+            Accounts::query_vesting(&mut ctx, VestingQuery { address: bob }),
+            Err(Error::NotFound)
+        ));
+    }
+
+    assert_eq!(schedule.locked(99), 8_000);
+    assert_eq!(schedule.locked(100), 0);
+}
+
+#[test]
+fn test_transfer_limits() {
+    let mut mock = mock::Mock::default();
+    let alice = keys::alice::address();
+    let bob = keys::bob::address();
+    let amount = |amount| BaseUnits::new(amount, Denomination::NATIVE);
+
+    mock.runtime_header.timestamp = 86_400;
+    {
+        let mut ctx = mock.create_ctx();
+        init_accounts(&mut ctx);
+
+        Accounts::set_role(ctx.runtime_state(), alice, Role::WhitelistedUser);
+        let mut params = Accounts::params(ctx.runtime_state());
+        params.transfer_limits.insert(
+            Role::WhitelistedUser,
+            BTreeMap::from([(Denomination::NATIVE, 1_000)]),
+        );
+        Accounts::set_params(ctx.runtime_state(), params);
+
+        Accounts::transfer(&mut ctx, alice, bob, &amount(600)).expect("transfer should succeed");
+        let result = Accounts::transfer(&mut ctx, alice, bob, &amount(401));
+        assert!(
+            matches!(result, Err(Error::TransferLimitExceeded)),
+            "transfers above the daily limit should be rejected"
+        );
+        Accounts::transfer(&mut ctx, alice, bob, &amount(400)).expect("transfer should succeed");
+
+        // Accounts without a limited role are not affected.
+        Accounts::transfer(&mut ctx, bob, alice, &amount(1_000)).expect("transfer should succeed");
+    }
+
+    // The limit is reset in the next window.
+    mock.runtime_header.timestamp = 2 * 86_400;
+    {
+        let mut ctx = mock.create_ctx();
+        Accounts::transfer(&mut ctx, alice, bob, &amount(1_000)).expect("transfer should succeed");
+        let result = Accounts::transfer(&mut ctx, alice, bob, &amount(1));
+        assert!(matches!(result, Err(Error::TransferLimitExceeded)));
+
+        // Holding an unlimited role lifts the limit.
+        Accounts::add_role_to_address(ctx.runtime_state(), alice, Role::Auditor);
+        Accounts::transfer(&mut ctx, alice, bob, &amount(1)).expect("transfer should succeed");
+    }
+}
+
 #[test]
 fn test_tx_transfer() {
     let mut mock = mock::Mock::default();
@@ -479,6 +847,7 @@ fn test_tx_transfer() {
             body: cbor::to_value(Transfer {
                 to: keys::bob::address(),
                 amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                memo: None,
             }),
             ..Default::default()
         },
@@ -532,82 +901,129 @@ fn test_tx_transfer() {
 }
 
 #[test]
-fn test_add_role_to_address() {
-    let mut mock = mock::Mock::default();
-    let mut ctx = mock.create_ctx();
-
-    init_accounts(&mut ctx);
-
-    Accounts::add_role_to_address(ctx.runtime_state(), keys::alice::address(), Role::MintVoter);
-    let roles = Accounts::get_addrsno_in_role(ctx.runtime_state(), Role::MintVoter);
-    assert_eq!(roles, 1, "should be only 1 role!");
-
-    let addresses = Accounts::get_addresses_in_role(ctx.runtime_state(), Role::MintVoter)
-        .expect("get_addresses_in_role should succeed");
-
-    println!("{:?}", addresses);
-    assert!(addresses.contains(&keys::alice::address()), "addresses should contain Alice's address");
-}
-
-#[test]
-fn test_get_role() {
+fn test_tx_transfer_batch() {
     let mut mock = mock::Mock::default();
     let mut ctx = mock.create_ctx();
 
     init_accounts(&mut ctx);
 
-    // GB: by default, the role is user.
-    let role = Accounts::get_role(ctx.runtime_state(), keys::alice::address()).unwrap();
-    assert_eq!(role, Role::User);
-
-    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintVoter);
-
-    // GB: set to minter.
-    let role = Accounts::get_role(ctx.runtime_state(), keys::alice::address()).unwrap();
-    assert_eq!(role, Role::MintVoter);
-}
-
-#[test]
-fn test_get_initstatus() {
-    let mut mock = mock::Mock::default();
-    let mut ctx = mock.create_ctx();
+    let tx_for = |transfers: Vec<Transfer>| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "accounts.TransferBatch".to_owned(),
+            body: cbor::to_value(TransferBatch { transfers }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
 
-    init_accounts(&mut ctx);
+    // An empty batch should be rejected.
+    ctx.with_tx(0, 0, tx_for(vec![]), |mut tx_ctx, call| {
+        let result = Accounts::tx_transfer_batch(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidArgument)));
+    });
 
-    // GB: by default, the role is user.
-    let initstatus = Accounts::get_initstatus(ctx.runtime_state(), keys::alice::address()).unwrap();
-    assert_eq!(initstatus, false);
+    // A batch where one of the transfers fails should not transfer anything.
+    let tx = tx_for(vec![
+        Transfer {
+            to: keys::bob::address(),
+            amount: BaseUnits::new(1_000, Denomination::NATIVE),
+            memo: None,
+        },
+        Transfer {
+            to: keys::charlie::address(),
+            amount: BaseUnits::new(1_000_000, Denomination::NATIVE),
+            memo: None,
+        },
+    ]);
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_transfer_batch(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InsufficientBalance)));
+    });
+    let bals = Accounts::get_balances(ctx.runtime_state(), keys::bob::address())
+        .expect("get_balances should succeed");
+    assert!(
+        bals.balances.is_empty(),
+        "failed batch should not transfer anything"
+    );
 
-    Accounts::set_initstatus(ctx.runtime_state(), keys::alice::address(), true);
+    // Transfer tokens to multiple accounts and check balances and events.
+    let tx = tx_for(vec![
+        Transfer {
+            to: keys::bob::address(),
+            amount: BaseUnits::new(1_000, Denomination::NATIVE),
+            memo: None,
+        },
+        Transfer {
+            to: keys::charlie::address(),
+            amount: BaseUnits::new(2_000, Denomination::NATIVE),
+            memo: None,
+        },
+    ]);
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_transfer_batch(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("batch transfer should succeed");
 
-    // GB: set to minter.
-    let initstatus = Accounts::get_initstatus(ctx.runtime_state(), keys::alice::address()).unwrap();
-    assert_eq!(initstatus, true);
+        let (etags, _) = tx_ctx.commit();
+        assert_eq!(
+            etags.get(&[b"accounts".as_slice(), &1u32.to_be_bytes()].concat()),
+            Some(&vec![
+                cbor::to_value(super::Event::Transfer {
+                    from: keys::alice::address(),
+                    to: keys::bob::address(),
+                    amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                    memo: None,
+                }),
+                cbor::to_value(super::Event::Transfer {
+                    from: keys::alice::address(),
+                    to: keys::charlie::address(),
+                    amount: BaseUnits::new(2_000, Denomination::NATIVE),
+                    memo: None,
+                }),
+            ]),
+            "a transfer event should be emitted for each transfer"
+        );
+    });
 
+    let bals = Accounts::get_balances(ctx.runtime_state(), keys::alice::address())
+        .expect("get_balances should succeed");
+    assert_eq!(bals.balances[&Denomination::NATIVE], 997_000);
+    let bals = Accounts::get_balances(ctx.runtime_state(), keys::bob::address())
+        .expect("get_balances should succeed");
+    assert_eq!(bals.balances[&Denomination::NATIVE], 1_000);
+    let bals = Accounts::get_balances(ctx.runtime_state(), keys::charlie::address())
+        .expect("get_balances should succeed");
+    assert_eq!(bals.balances[&Denomination::NATIVE], 2_000);
 }
 
 #[test]
-fn test_fee_disbursement() {
+fn test_tx_transfer_memo() {
     let mut mock = mock::Mock::default();
-
-    // Configure some good entities so they get the fees.
-    mock.runtime_round_results.good_compute_entities = vec![
-        keys::bob::pk_ed25519().into(),
-        keys::charlie::pk_ed25519().into(),
-    ];
-
     let mut ctx = mock.create_ctx();
 
     init_accounts(&mut ctx);
 
-    let tx = transaction::Transaction {
+    let tx_for = |memo: Vec<u8>| transaction::Transaction {
         version: 1,
         call: transaction::Call {
             format: transaction::CallFormat::Plain,
             method: "accounts.Transfer".to_owned(),
             body: cbor::to_value(Transfer {
                 to: keys::bob::address(),
-                amount: Default::default(),
+                amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                memo: Some(memo),
             }),
             ..Default::default()
         },
@@ -617,8 +1033,7 @@ fn test_fee_disbursement() {
                 0,
             )],
             fee: transaction::Fee {
-                // Use an amount that does not split nicely among the good compute entities.
-                amount: BaseUnits::new(1_001, Denomination::NATIVE),
+                amount: Default::default(),
                 gas: 1000,
                 consensus_messages: 0,
             },
@@ -626,17 +1041,2828 @@ fn test_fee_disbursement() {
         },
     };
 
-    // Authenticate transaction, fees should be moved to accumulator.
-    Accounts::authenticate_tx(&mut ctx, &tx).expect("transaction authentication should succeed");
-    // Run end block handler.
-    Accounts::end_block(&mut ctx);
+    // Memos over the size limit should be rejected.
+    let tx = tx_for(vec![0; MAX_TRANSFER_MEMO_SIZE + 1]);
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_transfer(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidArgument)));
+    });
 
-    // Check source account balances.
-    let bals = Accounts::get_balances(ctx.runtime_state(), keys::alice::address())
-        .expect("get_balances should succeed");
-    assert_eq!(
-        bals.balances[&Denomination::NATIVE],
-        998_999,
+    // The memo should be included in the transfer event.
+    let tx = tx_for(b"deposit-42".to_vec());
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_transfer(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("transfer should succeed");
+
+        let (etags, _) = tx_ctx.commit();
+        assert_eq!(
+            etags.get(&[b"accounts".as_slice(), &1u32.to_be_bytes()].concat()),
+            Some(&vec![cbor::to_value(super::Event::Transfer {
+                from: keys::alice::address(),
+                to: keys::bob::address(),
+                amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                memo: Some(b"deposit-42".to_vec()),
+            })]),
+            "transfer event should include the memo"
+        );
+    });
+}
+
+#[test]
+fn test_reserved_addresses() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let tx_for = |to: Address| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "accounts.Transfer".to_owned(),
+            body: cbor::to_value(Transfer {
+                to,
+                amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                memo: None,
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+
+    // Users may not transfer to addresses that can not spend.
+    for to in [
+        *ADDRESS_FEE_ACCUMULATOR,
+        *ADDRESS_PROPOSAL_DEPOSITS,
+        *crate::modules::consensus_accounts::ADDRESS_PENDING_WITHDRAWAL,
+    ] {
+        ctx.with_tx(0, 0, tx_for(to), |mut tx_ctx, call| {
+            let result = Accounts::tx_transfer(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            assert!(matches!(result, Err(Error::ReservedAddress)));
+        });
+    }
+
+    // The common pool can spend through governance, so it may receive transfers.
+    ctx.with_tx(0, 0, tx_for(*ADDRESS_COMMON_POOL), |mut tx_ctx, call| {
+        Accounts::tx_transfer(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("transfer to the common pool should succeed");
+    });
+
+    // Modules may still transfer to their own addresses.
+    Accounts::transfer(
+        &mut ctx,
+        keys::alice::address(),
+        *ADDRESS_PROPOSAL_DEPOSITS,
+        &BaseUnits::new(1_000, Denomination::NATIVE),
+    )
+    .expect("transfer initiated by the module should succeed");
+}
+
+#[test]
+fn test_account_history() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let alice = keys::alice::address();
+    let bob = keys::bob::address();
+    let round = ctx.runtime_header().round;
+    assert_eq!(
+        Accounts::get_activity_counter(ctx.runtime_state(), alice),
+        0
+    );
+
+    // Transfers should be recorded for both accounts, together with the transaction index.
+    let tx = transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "accounts.Transfer".to_owned(),
+            body: cbor::to_value(Transfer {
+                to: bob,
+                amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                memo: None,
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+    ctx.with_tx(3, 0, tx, |mut tx_ctx, call| {
+        Accounts::before_handle_call(&mut tx_ctx, &call)
+            .expect("before_handle_call should succeed");
+        Accounts::tx_transfer(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("transfer should succeed");
+        tx_ctx.commit();
+    });
+    Accounts::after_dispatch_tx(
+        &mut ctx,
+        &Default::default(),
+        &crate::module::CallResult::Ok(cbor::Value::Simple(cbor::SimpleValue::NullValue)),
+    );
+
+    // Activity outside of transactions should be recorded without a transaction index.
+    Accounts::mint(&mut ctx, bob, &BaseUnits::new(500, Denomination::NATIVE))
+        .expect("mint should succeed");
+    Accounts::burn(&mut ctx, bob, &BaseUnits::new(200, Denomination::NATIVE))
+        .expect("burn should succeed");
+
+    assert_eq!(
+        Accounts::get_activity_counter(ctx.runtime_state(), alice),
+        1
+    );
+    assert_eq!(Accounts::get_activity_counter(ctx.runtime_state(), bob), 3);
+
+    let history = Accounts::query_history(
+        &mut ctx,
+        HistoryQuery {
+            address: bob,
+            ..Default::default()
+        },
+    )
+    .expect("history query should succeed");
+    assert_eq!(
+        history,
+        vec![
+            Activity {
+                seq: 1,
+                round,
+                tx_index: Some(3),
+            },
+            Activity {
+                seq: 2,
+                round,
+                tx_index: None,
+            },
+            Activity {
+                seq: 3,
+                round,
+                tx_index: None,
+            },
+        ]
+    );
+
+    // Pagination should continue after the given sequence number.
+    let history = Accounts::query_history(
+        &mut ctx,
+        HistoryQuery {
+            address: bob,
+            start_after: 1,
+            limit: 1,
+        },
+    )
+    .expect("history query should succeed");
+    assert_eq!(history.iter().map(|a| a.seq).collect::<Vec<_>>(), vec![2]);
+
+    let history = Accounts::query_history(
+        &mut ctx,
+        HistoryQuery {
+            address: bob,
+            start_after: 3,
+            limit: 0,
+        },
+    )
+    .expect("history query should succeed");
+    assert!(
+        history.is_empty(),
+        "there should be no activity after the last entry"
+    );
+}
+
+#[test]
+fn test_min_transfer_amount() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.min_transfer_amounts.insert(Denomination::NATIVE, 100);
+    Accounts::set_params(ctx.runtime_state(), params);
+
+    let tx_for = |method: &str, body| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: method.to_owned(),
+            body,
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+    let transfer = |amount| Transfer {
+        to: keys::bob::address(),
+        amount: BaseUnits::new(amount, Denomination::NATIVE),
+        memo: None,
+    };
+
+    // Dust transfers should be rejected.
+    let tx = tx_for("accounts.Transfer", cbor::to_value(transfer(99)));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_transfer(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::BelowMinimumTransfer)));
+    });
+    let tx = tx_for(
+        "accounts.TransferBatch",
+        cbor::to_value(TransferBatch {
+            transfers: vec![transfer(100), transfer(1)],
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_transfer_batch(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::BelowMinimumTransfer)));
+    });
+
+    // Transfers of at least the minimum amount should succeed.
+    let tx = tx_for("accounts.Transfer", cbor::to_value(transfer(100)));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_transfer(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("transfer should succeed");
+        tx_ctx.commit();
+    });
+    let balance =
+        Accounts::get_balance(ctx.runtime_state(), keys::bob::address(), Denomination::NATIVE)
+            .unwrap();
+    assert_eq!(balance, 100);
+}
+
+#[test]
+fn test_allowance() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let alice = keys::alice::address();
+    let bob = keys::bob::address();
+    let charlie = keys::charlie::address();
+
+    Accounts::approve(
+        &mut ctx,
+        alice,
+        bob,
+        &BaseUnits::new(1_000, Denomination::NATIVE),
+    )
+    .expect("approve should succeed");
+    assert_eq!(
+        Accounts::get_allowance(ctx.runtime_state(), alice, bob, &Denomination::NATIVE),
+        1_000
+    );
+    assert!(matches!(
+        Accounts::approve(
+            &mut ctx,
+            alice,
+            alice,
+            &BaseUnits::new(1_000, Denomination::NATIVE)
+        ),
+        Err(Error::InvalidArgument)
+    ));
+
+    // Transfers can't exceed the allowance.
+    assert!(matches!(
+        Accounts::transfer_from(
+            &mut ctx,
+            bob,
+            alice,
+            charlie,
+            &BaseUnits::new(1_500, Denomination::NATIVE)
+        ),
+        Err(Error::InsufficientAllowance)
+    ));
+
+    Accounts::transfer_from(
+        &mut ctx,
+        bob,
+        alice,
+        charlie,
+        &BaseUnits::new(400, Denomination::NATIVE),
+    )
+    .expect("transfer from should succeed");
+    assert_eq!(
+        Accounts::get_balance(ctx.runtime_state(), charlie, Denomination::NATIVE).unwrap(),
+        400
+    );
+    assert_eq!(
+        Accounts::get_balance(ctx.runtime_state(), alice, Denomination::NATIVE).unwrap(),
+        999_600
+    );
+    let allowance = Accounts::query_allowance(
+        &mut ctx,
+        AllowanceQuery {
+            owner: alice,
+            spender: bob,
+            denomination: Denomination::NATIVE,
+        },
+    )
+    .unwrap();
+    assert_eq!(allowance, 600, "allowance should be spent");
+
+    // Blacklisted owners and spenders can't use allowances.
+    for blacklisted in [alice, bob] {
+        Accounts::set_role(ctx.runtime_state(), blacklisted, Role::BlacklistedUser);
+        assert!(matches!(
+            Accounts::transfer_from(
+                &mut ctx,
+                bob,
+                alice,
+                charlie,
+                &BaseUnits::new(100, Denomination::NATIVE)
+            ),
+            Err(Error::Forbidden)
+        ));
+        Accounts::set_role(ctx.runtime_state(), blacklisted, Role::User);
+    }
+
+    // Allowances can be revoked.
+    Accounts::approve(&mut ctx, alice, bob, &BaseUnits::new(0, Denomination::NATIVE))
+        .expect("approve should succeed");
+    assert_eq!(
+        Accounts::get_allowance(ctx.runtime_state(), alice, bob, &Denomination::NATIVE),
+        0
+    );
+}
+
+#[test]
+fn test_session_keys() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.max_session_key_rounds = 100;
+    Accounts::set_params(ctx.runtime_state(), params);
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintVoter);
+
+    // Alice authorizes a fresh key to vote on her behalf.
+    let session_key = SessionKey {
+        address: keys::frank::address(),
+        expiry: 10,
+        methods: vec!["accounts.VoteST".to_owned()],
+    };
+
+    // The session key must co-sign its registration.
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.AddSessionKey",
+        cbor::to_value(session_key.clone()),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_add_session_key(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::Forbidden)));
+    });
+
+    let mut tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.AddSessionKey",
+        cbor::to_value(session_key.clone()),
+    );
+    tx.auth_info
+        .signer_info
+        .push(transaction::SignerInfo::new_sigspec(
+            keys::frank::sigspec(),
+            0,
+        ));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_add_session_key(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("adding a session key should succeed");
+        tx_ctx.commit();
+    });
+    let info = Accounts::get_session_key(ctx.runtime_state(), keys::frank::address())
+        .expect("session key should be registered");
+    assert_eq!(info.owner, keys::alice::address());
+
+    // Users without a role may not register session keys.
+    let tx = tx_for(
+        &keys::charlie::sigspec(),
+        "accounts.AddSessionKey",
+        cbor::to_value(SessionKey {
+            address: keys::dave::address(),
+            ..session_key
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_add_session_key(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidRole)));
+    });
+
+    // The session key may only be used for the authorized methods.
+    let tx = tx_for(
+        &keys::frank::sigspec(),
+        "accounts.Transfer",
+        cbor::to_value(Transfer::default()),
+    );
+    let result = Accounts::authenticate_tx(&mut ctx, &tx);
+    assert!(matches!(result, Err(core::Error::NotAuthenticated)));
+
+    let tx = tx_for(
+        &keys::frank::sigspec(),
+        "accounts.VoteST",
+        cbor::to_value(VoteProposal::default()),
+    );
+    Accounts::authenticate_tx(&mut ctx, &tx).expect("session key should be accepted");
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, _call| {
+        assert_eq!(
+            Accounts::tx_role_caller(&mut tx_ctx),
+            keys::alice::address(),
+            "session key should act on behalf of its owner"
+        );
+    });
+
+    // Only the owner may revoke the session key.
+    let revoke = cbor::to_value(RevokeSessionKey {
+        address: keys::frank::address(),
+    });
+    let tx = tx_for(
+        &keys::frank::sigspec(),
+        "accounts.RevokeSessionKey",
+        revoke.clone(),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_revoke_session_key(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::Forbidden)));
+    });
+    let tx = tx_for(&keys::alice::sigspec(), "accounts.RevokeSessionKey", revoke);
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_revoke_session_key(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("revoking a session key should succeed");
+        tx_ctx.commit();
+    });
+    assert!(Accounts::get_session_key(ctx.runtime_state(), keys::frank::address()).is_none());
+}
+
+#[test]
+fn test_cancel_proposal() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::bob::address(), Role::WhitelistedUser);
+    Accounts::set_role(ctx.runtime_state(), keys::dave::address(), Role::Admin);
+
+    // Alice proposes two mints.
+    let content = ProposalContent {
+        action: Action::Mint,
+        data: ProposalData {
+            address: Some(keys::bob::address()),
+            amount: Some(BaseUnits::new(1_000, Denomination::NATIVE)),
+            ..Default::default()
+        },
+    };
+    for _ in 0..2 {
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.Propose",
+            cbor::to_value(content.clone()),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("proposing should succeed");
+            tx_ctx.commit();
+        });
+    }
+
+    fn cancel<C: BatchContext>(
+        ctx: &mut C,
+        signer: &SignatureAddressSpec,
+        id: u32,
+    ) -> Result<(), Error> {
+        let tx = tx_for(
+            signer,
+            "accounts.CancelProposal",
+            cbor::to_value(CancelProposal { id }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_cancel_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            if result.is_ok() {
+                tx_ctx.commit();
+            }
+            result
+        })
+    }
+
+    // Other accounts may not cancel the proposal.
+    let result = cancel(&mut ctx, &keys::charlie::sigspec(), 1);
+    assert!(matches!(result, Err(Error::InvalidRole)));
+
+    // The submitter may cancel the proposal, but only while it is active.
+    cancel(&mut ctx, &keys::alice::sigspec(), 1).expect("cancelling should succeed");
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(proposal.state, ProposalState::Cancelled);
+    let result = cancel(&mut ctx, &keys::alice::sigspec(), 1);
+    assert!(matches!(result, Err(Error::InvalidState)));
+
+    // Admins may cancel any proposal.
+    cancel(&mut ctx, &keys::dave::sigspec(), 2).expect("cancelling should succeed");
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 2).unwrap();
+    assert_eq!(proposal.state, ProposalState::Cancelled);
+
+    let result = cancel(&mut ctx, &keys::alice::sigspec(), 3);
+    assert!(matches!(result, Err(Error::NotFound)));
+}
+
+#[test]
+fn test_proposal_deposits() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::bob::address(), Role::MintProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::erin::address(), Role::WhitelistedUser);
+    for voter in [keys::charlie::address(), keys::dave::address()] {
+        Accounts::set_role(ctx.runtime_state(), voter, Role::MintVoter);
+        Accounts::add_role_to_address(ctx.runtime_state(), voter, Role::MintVoter);
+    }
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.proposal_deposit = Some(BaseUnits::new(100, Denomination::NATIVE));
+    Accounts::set_params(ctx.runtime_state(), params);
+
+    fn balance<C: Context>(ctx: &mut C, address: Address) -> u128 {
+        Accounts::get_balance(ctx.runtime_state(), address, Denomination::NATIVE).unwrap()
+    }
+
+    let content = cbor::to_value(ProposalContent {
+        action: Action::Mint,
+        data: ProposalData {
+            address: Some(keys::erin::address()),
+            amount: Some(BaseUnits::new(1_000, Denomination::NATIVE)),
+            ..Default::default()
+        },
+    });
+
+    // Proposers must be able to pay the deposit.
+    let tx = tx_for(&keys::bob::sigspec(), "accounts.Propose", content.clone());
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InsufficientBalance)));
+    });
+
+    for _ in 0..3 {
+        let tx = tx_for(&keys::alice::sigspec(), "accounts.Propose", content.clone());
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("proposing should succeed");
+            tx_ctx.commit();
+        });
+    }
+    assert_eq!(balance(&mut ctx, keys::alice::address()), 999_700);
+    assert_eq!(balance(&mut ctx, *ADDRESS_PROPOSAL_DEPOSITS), 300);
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(
+        proposal.deposit,
+        Some(BaseUnits::new(100, Denomination::NATIVE))
+    );
+
+    fn vote<C: BatchContext>(ctx: &mut C, signer: &SignatureAddressSpec, id: u32, option: Vote) {
+        let tx = tx_for(
+            signer,
+            "accounts.VoteST",
+            cbor::to_value(VoteProposal { id, option }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("voting should succeed");
+            tx_ctx.commit();
+        });
+    }
+
+    // Passed proposals refund the deposit.
+    vote(&mut ctx, &keys::charlie::sigspec(), 1, Vote::VoteYes);
+    vote(&mut ctx, &keys::dave::sigspec(), 1, Vote::VoteYes);
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(proposal.state, ProposalState::Passed);
+    assert_eq!(balance(&mut ctx, keys::alice::address()), 999_800);
+    assert_eq!(balance(&mut ctx, keys::erin::address()), 0);
+    assert_eq!(balance(&mut ctx, *ADDRESS_PROPOSAL_DEPOSITS), 200);
+
+    // The action is only applied once the proposal is executed.
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.ExecuteProposal",
+        cbor::to_value(ExecuteProposal { id: 1 }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+    assert_eq!(balance(&mut ctx, keys::erin::address()), 1_000);
+
+    // Rejected proposals burn the deposit.
+    vote(&mut ctx, &keys::charlie::sigspec(), 2, Vote::VoteNo);
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 2).unwrap();
+    assert_eq!(proposal.state, ProposalState::Rejected);
+    assert_eq!(balance(&mut ctx, keys::alice::address()), 999_800);
+    assert_eq!(balance(&mut ctx, *ADDRESS_PROPOSAL_DEPOSITS), 100);
+    assert_eq!(
+        Accounts::get_total_supply(ctx.runtime_state(), Denomination::NATIVE).unwrap(),
+        1_000_900
+    );
+
+    // Cancelled proposals refund the deposit.
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.CancelProposal",
+        cbor::to_value(CancelProposal { id: 3 }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_cancel_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("cancelling should succeed");
+        tx_ctx.commit();
+    });
+    assert_eq!(balance(&mut ctx, keys::alice::address()), 999_900);
+    assert_eq!(balance(&mut ctx, *ADDRESS_PROPOSAL_DEPOSITS), 0);
+}
+
+#[test]
+fn test_execute_proposal() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::BurnProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::erin::address(), Role::WhitelistedUser);
+    Accounts::set_role(ctx.runtime_state(), keys::charlie::address(), Role::BurnVoter);
+    Accounts::add_role_to_address(ctx.runtime_state(), keys::charlie::address(), Role::BurnVoter);
+
+    let execute = cbor::to_value(ExecuteProposal { id: 1 });
+
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(ProposalContent {
+            action: Action::Burn,
+            data: ProposalData {
+                address: Some(keys::erin::address()),
+                amount: Some(BaseUnits::new(500, Denomination::NATIVE)),
+                ..Default::default()
+            },
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+
+    // Active proposals can not be executed.
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.ExecuteProposal",
+        execute.clone(),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidState)));
+    });
+
+    let tx = tx_for(
+        &keys::charlie::sigspec(),
+        "accounts.VoteST",
+        cbor::to_value(VoteProposal {
+            id: 1,
+            option: Vote::VoteYes,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("voting should succeed");
+        tx_ctx.commit();
+    });
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(proposal.state, ProposalState::Passed);
+    assert!(!proposal.executed);
+
+    // Execution fails while there is nothing to burn, but the proposal remains passed.
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.ExecuteProposal",
+        execute.clone(),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InsufficientBalance)));
+    });
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(proposal.state, ProposalState::Passed);
+    assert!(!proposal.executed);
+
+    // Once funded, anyone may retry the execution.
+    Accounts::transfer(
+        &mut ctx,
+        keys::alice::address(),
+        keys::erin::address(),
+        &BaseUnits::new(500, Denomination::NATIVE),
+    )
+    .expect("transfer should succeed");
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.ExecuteProposal",
+        execute.clone(),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert!(proposal.executed);
+    assert_eq!(
+        Accounts::get_total_supply(ctx.runtime_state(), Denomination::NATIVE).unwrap(),
+        999_500
+    );
+
+    // Proposals can only be executed once.
+    let tx = tx_for(&keys::bob::sigspec(), "accounts.ExecuteProposal", execute);
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidState)));
+    });
+}
+
+#[test]
+fn test_spend_common_pool() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::dave::address(), Role::Admin);
+    Accounts::add_role_to_address(ctx.runtime_state(), keys::dave::address(), Role::Admin);
+    Accounts::transfer(
+        &mut ctx,
+        keys::alice::address(),
+        *ADDRESS_COMMON_POOL,
+        &BaseUnits::new(1_000, Denomination::NATIVE),
+    )
+    .expect("transfer should succeed");
+
+    let tx_for = |method: &str, body| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: method.to_owned(),
+            body,
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::dave::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+    let spend = |amount: u128| ProposalContent {
+        action: Action::SpendCommonPool,
+        data: ProposalData {
+            address: Some(keys::bob::address()),
+            amount: Some(BaseUnits::new(amount, Denomination::NATIVE)),
+            ..Default::default()
+        },
+    };
+
+    // Spending nothing is not allowed.
+    let tx = tx_for("accounts.Propose", cbor::to_value(spend(0)));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidArgument)));
+    });
+
+    let tx = tx_for("accounts.Propose", cbor::to_value(spend(600)));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+    let proposal_id = Accounts::get_proposal_id(ctx.runtime_state()).unwrap();
+    let tx = tx_for(
+        "accounts.VoteST",
+        cbor::to_value(VoteProposal {
+            id: proposal_id,
+            option: Vote::VoteYes,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("voting should succeed");
+        tx_ctx.commit();
+    });
+    let tx = tx_for(
+        "accounts.ExecuteProposal",
+        cbor::to_value(ExecuteProposal { id: proposal_id }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+
+    assert_eq!(
+        Accounts::get_balance(
+            ctx.runtime_state(),
+            *ADDRESS_COMMON_POOL,
+            Denomination::NATIVE
+        )
+        .unwrap(),
+        400,
+        "spent amount should be taken from the common pool"
+    );
+    assert_eq!(
+        Accounts::get_balance(
+            ctx.runtime_state(),
+            keys::bob::address(),
+            Denomination::NATIVE
+        )
+        .unwrap(),
+        600,
+        "spent amount should be transferred to the recipient"
+    );
+}
+
+#[test]
+fn test_proposal_votes_retention() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::erin::address(), Role::WhitelistedUser);
+    Accounts::set_role(ctx.runtime_state(), keys::charlie::address(), Role::MintVoter);
+    Accounts::add_role_to_address(ctx.runtime_state(), keys::charlie::address(), Role::MintVoter);
+
+    // Votes are discarded by default, but can be retained for audits.
+    for retain in [false, true] {
+        let mut params = Accounts::params(ctx.runtime_state());
+        params.retain_proposal_votes = retain;
+        Accounts::set_params(ctx.runtime_state(), params);
+
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.Propose",
+            cbor::to_value(ProposalContent {
+                action: Action::Mint,
+                data: ProposalData {
+                    address: Some(keys::erin::address()),
+                    amount: Some(BaseUnits::new(500, Denomination::NATIVE)),
+                    ..Default::default()
+                },
+            }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("proposing should succeed");
+            tx_ctx.commit();
+        });
+        let id = Accounts::get_proposal_id(ctx.runtime_state()).unwrap();
+        let tx = tx_for(
+            &keys::charlie::sigspec(),
+            "accounts.VoteST",
+            cbor::to_value(VoteProposal {
+                id,
+                option: Vote::VoteYes,
+            }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("voting should succeed");
+            tx_ctx.commit();
+        });
+        let proposal = Accounts::get_proposal(ctx.runtime_state(), id).unwrap();
+        assert_eq!(proposal.state, ProposalState::Passed);
+
+        let votes = Accounts::query_proposal_votes(&mut ctx, ProposalVotesQuery { id })
+            .expect("proposal votes query should succeed");
+        if retain {
+            assert_eq!(
+                votes,
+                BTreeMap::from_iter([(keys::charlie::address(), Vote::VoteYes)]),
+                "votes should be retained"
+            );
+        } else {
+            assert!(votes.is_empty(), "votes should be discarded");
+        }
+    }
+
+    let id = Accounts::get_proposal_id(ctx.runtime_state()).unwrap();
+    let result = Accounts::query_proposal_votes(&mut ctx, ProposalVotesQuery { id: id + 1 });
+    assert!(matches!(result, Err(Error::NotFound)));
+}
+
+#[test]
+fn test_active_proposal_limits() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    for admin in [keys::dave::address(), keys::frank::address()] {
+        Accounts::set_role(ctx.runtime_state(), admin, Role::Admin);
+        Accounts::add_role_to_address(ctx.runtime_state(), admin, Role::Admin);
+    }
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.max_active_proposals = 3;
+    params.max_active_proposals_per_submitter = 2;
+    Accounts::set_params(ctx.runtime_state(), params);
+
+    fn propose<C: BatchContext>(ctx: &mut C, signer: &SignatureAddressSpec) -> Result<(), Error> {
+        let content = ProposalContent {
+            action: Action::PauseContract,
+            data: ProposalData {
+                address: Some(keys::grace::address()),
+                ..Default::default()
+            },
+        };
+        let tx = tx_for(signer, "accounts.Propose", cbor::to_value(content));
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result = Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            if result.is_ok() {
+                tx_ctx.commit();
+            }
+            result
+        })
+    }
+
+    // Each submitter may only have a limited number of active proposals.
+    propose(&mut ctx, &keys::dave::sigspec()).expect("proposing should succeed");
+    propose(&mut ctx, &keys::dave::sigspec()).expect("proposing should succeed");
+    let result = propose(&mut ctx, &keys::dave::sigspec());
+    assert!(matches!(result, Err(Error::TooManyActiveProposals)));
+    assert_eq!(
+        Accounts::get_active_proposal_count(ctx.runtime_state(), keys::dave::address()),
+        2
+    );
+
+    // The number of all active proposals is limited as well.
+    propose(&mut ctx, &keys::frank::sigspec()).expect("proposing should succeed");
+    let result = propose(&mut ctx, &keys::frank::sigspec());
+    assert!(matches!(result, Err(Error::TooManyActiveProposals)));
+
+    // Cancelled proposals no longer count towards the limits.
+    let tx = tx_for(
+        &keys::dave::sigspec(),
+        "accounts.CancelProposal",
+        cbor::to_value(CancelProposal { id: 1 }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_cancel_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("cancelling should succeed");
+        tx_ctx.commit();
+    });
+    assert_eq!(
+        Accounts::get_active_proposal_count(ctx.runtime_state(), keys::dave::address()),
+        1
+    );
+    propose(&mut ctx, &keys::dave::sigspec()).expect("proposing should succeed");
+
+    // Migrating recounts the active proposals of each submitter.
+    Accounts::set_active_proposal_count(ctx.runtime_state(), keys::dave::address(), 0);
+    assert!(
+        Accounts::migrate(&mut ctx, 5),
+        "migration should be performed"
+    );
+    assert_eq!(
+        Accounts::get_active_proposal_count(ctx.runtime_state(), keys::dave::address()),
+        2
+    );
+    assert_eq!(
+        Accounts::get_active_proposal_count(ctx.runtime_state(), keys::frank::address()),
+        1
+    );
+}
+
+#[test]
+fn test_configurable_action_roles() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let dave = keys::dave::address();
+    let grace = keys::grace::address();
+    Accounts::set_role(ctx.runtime_state(), keys::erin::address(), Role::WhitelistedUser);
+    Accounts::set_role(ctx.runtime_state(), dave, Role::MintProposer);
+    Accounts::add_role_to_address(ctx.runtime_state(), dave, Role::MintProposer);
+    Accounts::add_role_to_address(ctx.runtime_state(), grace, Role::Auditor);
+
+    // Actions use the default roles unless configured otherwise.
+    assert_eq!(
+        Accounts::get_proposer_with_action(ctx.runtime_state(), Action::Mint),
+        Some(Role::MintProposer)
+    );
+    assert_eq!(
+        Accounts::get_voter_with_action(ctx.runtime_state(), Action::Mint),
+        Some(Role::MintVoter)
+    );
+
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.proposer_roles.insert(Action::Mint, Role::Auditor);
+    params.voter_roles.insert(Action::Mint, Role::Auditor);
+    Accounts::set_params(ctx.runtime_state(), params);
+    assert_eq!(
+        Accounts::get_proposer_with_action(ctx.runtime_state(), Action::Mint),
+        Some(Role::Auditor)
+    );
+    assert_eq!(
+        Accounts::get_voters_num_with_action(ctx.runtime_state(), Action::Mint).unwrap(),
+        1
+    );
+
+    fn propose<C: BatchContext>(ctx: &mut C, signer: &SignatureAddressSpec) -> Result<(), Error> {
+        let content = ProposalContent {
+            action: Action::Mint,
+            data: ProposalData {
+                address: Some(keys::erin::address()),
+                amount: Some(BaseUnits::new(1_000, Denomination::NATIVE)),
+                ..Default::default()
+            },
+        };
+        let tx = tx_for(signer, "accounts.Propose", cbor::to_value(content));
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result = Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            if result.is_ok() {
+                tx_ctx.commit();
+            }
+            result
+        })
+    }
+
+    // The default proposer role no longer suffices while the configured one does.
+    let result = propose(&mut ctx, &keys::dave::sigspec());
+    assert!(matches!(result, Err(Error::InvalidRole)));
+    propose(&mut ctx, &keys::grace::sigspec()).expect("proposing should succeed");
+
+    // Roles can not be configured for proposals without an action.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.voter_roles.insert(Action::NoAction, Role::Admin);
+    assert!(crate::module::Parameters::validate_basic(&params).is_err());
+
+    // Ordinary accounts can not be granted governance powers.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.proposer_roles.insert(Action::Mint, Role::User);
+    assert!(crate::module::Parameters::validate_basic(&params).is_err());
+    let mut params = Accounts::params(ctx.runtime_state());
+    params
+        .voter_roles
+        .insert(Action::Burn, Role::BlacklistedUser);
+    assert!(crate::module::Parameters::validate_basic(&params).is_err());
+
+    // The mapping can be updated through config proposals.
+    let bob = keys::bob::address();
+    Accounts::set_role(ctx.runtime_state(), bob, Role::Admin);
+    Accounts::add_role_to_address(ctx.runtime_state(), bob, Role::Admin);
+    let config = |roles: BTreeMap<Action, Role>| ProposalContent {
+        action: Action::Config,
+        data: ProposalData {
+            proposer_roles: Some(roles),
+            ..Default::default()
+        },
+    };
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(config(BTreeMap::from([(Action::Mint, Role::User)]))),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidArgument)));
+    });
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(config(BTreeMap::from([(Action::Mint, Role::MintProposer)]))),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+    let proposal_id = Accounts::get_proposal_id(ctx.runtime_state()).unwrap();
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.VoteST",
+        cbor::to_value(VoteProposal {
+            id: proposal_id,
+            option: Vote::VoteYes,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("voting should succeed");
+        tx_ctx.commit();
+    });
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.ExecuteProposal",
+        cbor::to_value(ExecuteProposal { id: proposal_id }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+    assert_eq!(
+        Accounts::get_proposer_with_action(ctx.runtime_state(), Action::Mint),
+        Some(Role::MintProposer)
+    );
+    assert_eq!(
+        Accounts::get_voter_with_action(ctx.runtime_state(), Action::Mint),
+        Some(Role::Auditor)
+    );
+}
+
+#[test]
+fn test_whitelist_expiry() {
+    let mut mock = mock::Mock::default();
+    let bob = keys::bob::address();
+    let erin = keys::erin::address();
+    let grace = keys::grace::address();
+
+    mock.runtime_header.round = 5;
+    {
+        let mut ctx = mock.create_ctx();
+        init_accounts(&mut ctx);
+
+        for address in [bob, erin, grace] {
+            Accounts::set_role(ctx.runtime_state(), address, Role::WhitelistedUser);
+            Accounts::add_role_to_address(ctx.runtime_state(), address, Role::WhitelistedUser);
+        }
+        Accounts::set_whitelist_expiry(ctx.runtime_state(), erin, Some(10));
+        // Replacing the expiry also replaces the round in which the whitelisting is revoked.
+        Accounts::set_whitelist_expiry(ctx.runtime_state(), bob, Some(10));
+        Accounts::set_whitelist_expiry(ctx.runtime_state(), bob, Some(30));
+
+        Accounts::end_block(&mut ctx);
+        assert_eq!(
+            Accounts::get_role(ctx.runtime_state(), erin).unwrap(),
+            Role::WhitelistedUser
+        );
+
+        // Whitelistings are valid up to and including their expiry round.
+        assert_eq!(
+            Accounts::get_effective_role(ctx.runtime_state(), erin, 10),
+            Role::WhitelistedUser
+        );
+        assert_eq!(
+            Accounts::get_effective_role(ctx.runtime_state(), erin, 11),
+            Role::User
+        );
+        assert_eq!(
+            Accounts::get_effective_role(ctx.runtime_state(), grace, 11),
+            Role::WhitelistedUser
+        );
+    }
+
+    // Expired whitelistings are revoked at the end of their expiry round.
+    mock.runtime_header.round = 10;
+    {
+        let mut ctx = mock.create_ctx();
+        Accounts::end_block(&mut ctx);
+
+        assert_eq!(
+            Accounts::get_role(ctx.runtime_state(), erin).unwrap(),
+            Role::User
+        );
+        assert!(!Accounts::has_role(
+            ctx.runtime_state(),
+            erin,
+            Role::WhitelistedUser
+        ));
+
+        for address in [bob, grace] {
+            assert_eq!(
+                Accounts::get_role(ctx.runtime_state(), address).unwrap(),
+                Role::WhitelistedUser
+            );
+        }
+    }
+}
+
+#[test]
+fn test_governance_events() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::bob::address(), Role::WhitelistedUser);
+    Accounts::set_role(ctx.runtime_state(), keys::charlie::address(), Role::MintVoter);
+
+    let event_key = |code: u32| [b"accounts".as_slice(), &code.to_be_bytes()].concat();
+
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(ProposalContent {
+            action: Action::Mint,
+            data: ProposalData {
+                address: Some(keys::bob::address()),
+                amount: Some(BaseUnits::new(1_000, Denomination::NATIVE)),
+                ..Default::default()
+            },
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        let (etags, _) = tx_ctx.commit();
+        assert_eq!(
+            etags.get(&event_key(7)),
+            Some(&vec![cbor::to_value(super::Event::ProposalSubmitted {
+                id: 1,
+                submitter: keys::alice::address(),
+                action: Action::Mint,
+            })]),
+            "submission event should be emitted"
+        );
+    });
+
+    // A single vote against rejects the proposal as the quorum requires all voters to agree.
+    let tx = tx_for(
+        &keys::charlie::sigspec(),
+        "accounts.VoteST",
+        cbor::to_value(VoteProposal {
+            id: 1,
+            option: Vote::VoteNo,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("voting should succeed");
+        let (etags, _) = tx_ctx.commit();
+        assert_eq!(
+            etags.get(&event_key(8)),
+            Some(&vec![cbor::to_value(super::Event::ProposalVoted {
+                id: 1,
+                voter: keys::charlie::address(),
+                option: Vote::VoteNo,
+            })]),
+            "vote event should be emitted"
+        );
+        assert_eq!(
+            etags.get(&event_key(9)),
+            Some(&vec![cbor::to_value(super::Event::ProposalFinalized {
+                id: 1,
+                state: ProposalState::Rejected,
+            })]),
+            "finalization event should be emitted"
+        );
+    });
+}
+
+#[test]
+fn test_frozen_account() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::BlacklistProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::charlie::address(), Role::BlacklistVoter);
+    Accounts::add_role_to_address(
+        ctx.runtime_state(),
+        keys::charlie::address(),
+        Role::BlacklistVoter,
+    );
+
+    let freeze = cbor::to_value(ProposalContent {
+        action: Action::Freeze,
+        data: ProposalData {
+            address: Some(keys::bob::address()),
+            ..Default::default()
+        },
+    });
+
+    // Freeze Bob's account through a proposal.
+    let tx = tx_for(&keys::alice::sigspec(), "accounts.Propose", freeze.clone());
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+    let tx = tx_for(
+        &keys::charlie::sigspec(),
+        "accounts.VoteST",
+        cbor::to_value(VoteProposal {
+            id: 1,
+            option: Vote::VoteYes,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("voting should succeed");
+        tx_ctx.commit();
+    });
+    let tx = tx_for(
+        &keys::charlie::sigspec(),
+        "accounts.ExecuteProposal",
+        cbor::to_value(ExecuteProposal { id: 1 }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+    assert!(Accounts::is_frozen(ctx.runtime_state(), keys::bob::address()));
+    assert_eq!(
+        Accounts::get_role(ctx.runtime_state(), keys::bob::address()).unwrap(),
+        Role::User,
+        "freezing should not change the account's role"
+    );
+
+    // Freezing an already frozen account should be rejected.
+    let tx = tx_for(&keys::alice::sigspec(), "accounts.Propose", freeze);
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidArgument)));
+    });
+
+    // Frozen accounts can't receive transfers.
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.Transfer",
+        cbor::to_value(Transfer {
+            to: keys::bob::address(),
+            amount: BaseUnits::new(1_000, Denomination::NATIVE),
+            memo: None,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_transfer(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::AccountFrozen)));
+    });
+
+    // Frozen accounts can't submit transactions.
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.Transfer",
+        cbor::to_value(Transfer {
+            to: keys::alice::address(),
+            amount: Default::default(),
+            memo: None,
+        }),
+    );
+    let result = Accounts::authenticate_tx(&mut ctx, &tx);
+    assert!(matches!(result, Err(core::Error::NotAuthenticated)));
+
+    // Unfreezing restores the account.
+    Accounts::set_frozen(ctx.runtime_state(), keys::bob::address(), false);
+    Accounts::authenticate_tx(&mut ctx, &tx).expect("transaction authentication should succeed");
+}
+
+#[test]
+fn test_multisig() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::dave::address(), Role::Admin);
+    Accounts::add_role_to_address(ctx.runtime_state(), keys::dave::address(), Role::Admin);
+
+    // Invalid thresholds should be rejected.
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.CreateMultisig",
+        cbor::to_value(CreateMultisig {
+            members: vec![keys::alice::address(), keys::bob::address()],
+            threshold: 3,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_create_multisig(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidArgument)));
+    });
+
+    // Create a 2-of-3 multisig account and fund it.
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.CreateMultisig",
+        cbor::to_value(CreateMultisig {
+            members: vec![
+                keys::alice::address(),
+                keys::bob::address(),
+                keys::charlie::address(),
+            ],
+            threshold: 2,
+        }),
+    );
+    let multisig = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let multisig =
+            Accounts::tx_create_multisig(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("creating a multisig should succeed");
+        tx_ctx.commit();
+        multisig
+    });
+    Accounts::transfer(
+        &mut ctx,
+        keys::alice::address(),
+        multisig,
+        &BaseUnits::new(1_000, Denomination::NATIVE),
+    )
+    .expect("funding the multisig should succeed");
+
+    let submit = cbor::to_value(MultisigSubmit {
+        multisig,
+        transfer: Transfer {
+            to: keys::dave::address(),
+            amount: BaseUnits::new(400, Denomination::NATIVE),
+            memo: None,
+        },
+    });
+
+    // Only members can submit transactions.
+    let tx = tx_for(
+        &keys::dave::sigspec(),
+        "accounts.MultisigSubmit",
+        submit.clone(),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_multisig_submit(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidRole)));
+    });
+
+    // The submitter's approval alone is below the threshold.
+    let tx = tx_for(&keys::alice::sigspec(), "accounts.MultisigSubmit", submit);
+    let id = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let id = Accounts::tx_multisig_submit(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("submitting should succeed");
+        tx_ctx.commit();
+        id
+    });
+    assert_eq!(id, 0);
+    let transaction =
+        Accounts::get_multisig_transaction(ctx.runtime_state(), multisig, id).unwrap();
+    assert_eq!(transaction.approvals, vec![keys::alice::address()]);
+    assert!(!transaction.executed);
+
+    let approve = cbor::to_value(MultisigApprove { multisig, id });
+
+    // Approving twice should be rejected.
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.MultisigApprove",
+        approve.clone(),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_multisig_approve(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::VoteDup)));
+    });
+
+    // The second approval executes the transfer.
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.MultisigApprove",
+        approve.clone(),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_multisig_approve(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("approving should succeed");
+        tx_ctx.commit();
+    });
+    assert_eq!(
+        Accounts::get_balance(ctx.runtime_state(), multisig, Denomination::NATIVE).unwrap(),
+        600
+    );
+    assert_eq!(
+        Accounts::get_balance(
+            ctx.runtime_state(),
+            keys::dave::address(),
+            Denomination::NATIVE
+        )
+        .unwrap(),
+        400
+    );
+
+    // Executed transactions can't be approved again.
+    let tx = tx_for(
+        &keys::charlie::sigspec(),
+        "accounts.MultisigApprove",
+        approve,
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_multisig_approve(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidState)));
+    });
+
+    // Transactions below the threshold can't be executed.
+    let submit = cbor::to_value(MultisigSubmit {
+        multisig,
+        transfer: Transfer {
+            to: keys::dave::address(),
+            amount: BaseUnits::new(100, Denomination::NATIVE),
+            memo: None,
+        },
+    });
+    let tx = tx_for(&keys::bob::sigspec(), "accounts.MultisigSubmit", submit);
+    let id = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let id = Accounts::tx_multisig_submit(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("submitting should succeed");
+        tx_ctx.commit();
+        id
+    });
+    assert_eq!(id, 1);
+    let execute = cbor::to_value(MultisigExecute { multisig, id });
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.MultisigExecute",
+        execute.clone(),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_multisig_execute(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidState)));
+    });
+
+    // Governance can replace the members and threshold.
+    let tx = tx_for(
+        &keys::dave::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(ProposalContent {
+            action: Action::SetMultisigMembers,
+            data: ProposalData {
+                address: Some(multisig),
+                multisig_members: Some(vec![keys::bob::address(), keys::charlie::address()]),
+                multisig_threshold: Some(1),
+                ..Default::default()
+            },
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+    let proposal_id = Accounts::get_proposal_id(ctx.runtime_state()).unwrap();
+    let tx = tx_for(
+        &keys::dave::sigspec(),
+        "accounts.VoteST",
+        cbor::to_value(VoteProposal {
+            id: proposal_id,
+            option: Vote::VoteYes,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("voting should succeed");
+        tx_ctx.commit();
+    });
+    let tx = tx_for(
+        &keys::dave::sigspec(),
+        "accounts.ExecuteProposal",
+        cbor::to_value(ExecuteProposal { id: proposal_id }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+    let account = Accounts::get_multisig(ctx.runtime_state(), multisig).unwrap();
+    assert!(!account.is_member(&keys::alice::address()));
+    assert_eq!(account.threshold, 1);
+    assert_eq!(account.next_id, 2);
+
+    // Pending transactions meeting the lowered threshold can be executed explicitly.
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.MultisigApprove",
+        cbor::to_value(MultisigApprove { multisig, id }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result =
+            Accounts::tx_multisig_approve(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::VoteDup)));
+    });
+    let tx = tx_for(&keys::bob::sigspec(), "accounts.MultisigExecute", execute);
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_multisig_execute(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+    assert!(
+        Accounts::get_multisig_transaction(ctx.runtime_state(), multisig, id)
+            .unwrap()
+            .executed
+    );
+    assert_eq!(
+        Accounts::get_balance(
+            ctx.runtime_state(),
+            keys::dave::address(),
+            Denomination::NATIVE
+        )
+        .unwrap(),
+        500
+    );
+}
+
+#[test]
+fn test_escrow() {
+    let mut mock = mock::Mock::default();
+    let alice = keys::alice::address();
+    let bob = keys::bob::address();
+    let charlie = keys::charlie::address();
+
+    let create = |arbiter, timeout| {
+        cbor::to_value(EscrowCreate {
+            to: bob,
+            amount: BaseUnits::new(1_000, Denomination::NATIVE),
+            arbiter,
+            timeout,
+        })
+    };
+
+    mock.runtime_header.round = 10;
+    {
+        let mut ctx = mock.create_ctx();
+        init_accounts(&mut ctx);
+
+        // Escrows need a release condition and a timeout that has not passed yet.
+        for body in [create(None, None), create(None, Some(9))] {
+            let tx = tx_for(&keys::alice::sigspec(), "accounts.EscrowCreate", body);
+            ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+                let result =
+                    Accounts::tx_escrow_create(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+                assert!(matches!(result, Err(Error::InvalidArgument)));
+            });
+        }
+
+        // An escrow with an arbiter is released by the arbiter.
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.EscrowCreate",
+            create(Some(charlie), None),
+        );
+        let id = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let id = Accounts::tx_escrow_create(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("creating an escrow should succeed");
+            tx_ctx.commit();
+            id
+        });
+        assert_eq!(id, 0);
+        assert_eq!(
+            Accounts::get_balance(ctx.runtime_state(), alice, Denomination::NATIVE).unwrap(),
+            999_000
+        );
+
+        // Only the creator and the arbiter can release the escrow.
+        let tx = tx_for(
+            &keys::bob::sigspec(),
+            "accounts.EscrowRelease",
+            cbor::to_value(EscrowRelease { id }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_escrow_release(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            assert!(matches!(result, Err(Error::InvalidRole)));
+        });
+
+        // Funds can't be released to a frozen recipient.
+        Accounts::set_frozen(ctx.runtime_state(), bob, true);
+        let tx = tx_for(
+            &keys::charlie::sigspec(),
+            "accounts.EscrowRelease",
+            cbor::to_value(EscrowRelease { id }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_escrow_release(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            assert!(matches!(result, Err(Error::AccountFrozen)));
+        });
+        Accounts::set_frozen(ctx.runtime_state(), bob, false);
+
+        let tx = tx_for(
+            &keys::charlie::sigspec(),
+            "accounts.EscrowRelease",
+            cbor::to_value(EscrowRelease { id }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_escrow_release(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("releasing should succeed");
+            tx_ctx.commit();
+        });
+        assert_eq!(
+            Accounts::get_balance(ctx.runtime_state(), bob, Denomination::NATIVE).unwrap(),
+            1_000
+        );
+        assert!(Accounts::get_escrow(ctx.runtime_state(), id).is_none());
+
+        // An escrow with a timeout can't be refunded to the creator before the timeout.
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.EscrowCreate",
+            create(None, Some(20)),
+        );
+        let id = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let id = Accounts::tx_escrow_create(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("creating an escrow should succeed");
+            tx_ctx.commit();
+            id
+        });
+        assert_eq!(id, 1);
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.EscrowRefund",
+            cbor::to_value(EscrowRefund { id }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_escrow_refund(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            assert!(matches!(result, Err(Error::InvalidRole)));
+        });
+    }
+
+    // Once the timeout has passed, the creator can reclaim the funds.
+    mock.runtime_header.round = 21;
+    {
+        let mut ctx = mock.create_ctx();
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.EscrowRefund",
+            cbor::to_value(EscrowRefund { id: 1 }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_escrow_refund(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("refunding should succeed");
+            tx_ctx.commit();
+        });
+        assert_eq!(
+            Accounts::get_balance(ctx.runtime_state(), alice, Denomination::NATIVE).unwrap(),
+            999_000
+        );
+        assert_eq!(
+            Accounts::get_balance(ctx.runtime_state(), *ADDRESS_ESCROW, Denomination::NATIVE)
+                .unwrap(),
+            0
+        );
+
+        // Closed escrows can't be released.
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.EscrowRelease",
+            cbor::to_value(EscrowRelease { id: 1 }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_escrow_release(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            assert!(matches!(result, Err(Error::NotFound)));
+        });
+    }
+}
+
+#[test]
+fn test_streams() {
+    let mut mock = mock::Mock::default();
+    let alice = keys::alice::address();
+    let bob = keys::bob::address();
+
+    let balance = |mock: &mut mock::Mock, address| {
+        let mut ctx = mock.create_ctx();
+        Accounts::get_balance(ctx.runtime_state(), address, Denomination::NATIVE).unwrap()
+    };
+
+    mock.runtime_header.round = 10;
+    {
+        let mut ctx = mock.create_ctx();
+        init_accounts(&mut ctx);
+
+        // Streams must end after they start.
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.StreamCreate",
+            cbor::to_value(StreamCreate {
+                to: bob,
+                amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                start: None,
+                end: 10,
+            }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_stream_create(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            assert!(matches!(result, Err(Error::InvalidArgument)));
+        });
+
+        // Stream 1000 units to Bob over ten rounds.
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.StreamCreate",
+            cbor::to_value(StreamCreate {
+                to: bob,
+                amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                start: None,
+                end: 20,
+            }),
+        );
+        let id = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let id = Accounts::tx_stream_create(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("creating a stream should succeed");
+            tx_ctx.commit();
+            id
+        });
+        assert_eq!(id, 0);
+        assert_eq!(
+            Accounts::get_stream(ctx.runtime_state(), id).unwrap().start,
+            10
+        );
+    }
+    assert_eq!(balance(&mut mock, alice), 999_000);
+
+    // Half way through, Bob can withdraw half of the amount.
+    mock.runtime_header.round = 15;
+    {
+        let mut ctx = mock.create_ctx();
+
+        // Only the recipient can withdraw.
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.StreamWithdraw",
+            cbor::to_value(StreamWithdraw { id: 0 }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_stream_withdraw(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            assert!(matches!(result, Err(Error::InvalidRole)));
+        });
+
+        let tx = tx_for(
+            &keys::bob::sigspec(),
+            "accounts.StreamWithdraw",
+            cbor::to_value(StreamWithdraw { id: 0 }),
+        );
+        let amount = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let amount =
+                Accounts::tx_stream_withdraw(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                    .expect("withdrawing should succeed");
+            tx_ctx.commit();
+            amount
+        });
+        assert_eq!(amount, BaseUnits::new(500, Denomination::NATIVE));
+        assert_eq!(
+            Accounts::get_stream(ctx.runtime_state(), 0)
+                .unwrap()
+                .withdrawn,
+            500
+        );
+    }
+    assert_eq!(balance(&mut mock, bob), 500);
+
+    // Cancelling settles the streamed amount and returns the remainder to the sender.
+    mock.runtime_header.round = 18;
+    {
+        let mut ctx = mock.create_ctx();
+
+        // Only the sender can cancel.
+        let tx = tx_for(
+            &keys::bob::sigspec(),
+            "accounts.StreamCancel",
+            cbor::to_value(StreamCancel { id: 0 }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_stream_cancel(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            assert!(matches!(result, Err(Error::InvalidRole)));
+        });
+
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.StreamCancel",
+            cbor::to_value(StreamCancel { id: 0 }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_stream_cancel(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("cancelling should succeed");
+            tx_ctx.commit();
+        });
+        assert!(Accounts::get_stream(ctx.runtime_state(), 0).is_none());
+    }
+    assert_eq!(balance(&mut mock, alice), 999_200);
+    assert_eq!(balance(&mut mock, bob), 800);
+    assert_eq!(balance(&mut mock, *ADDRESS_STREAMS), 0);
+
+    // Stream another 1000 units to Bob over ten rounds.
+    {
+        let mut ctx = mock.create_ctx();
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.StreamCreate",
+            cbor::to_value(StreamCreate {
+                to: bob,
+                amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                start: None,
+                end: 28,
+            }),
+        );
+        let id = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let id = Accounts::tx_stream_create(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("creating a stream should succeed");
+            tx_ctx.commit();
+            id
+        });
+        assert_eq!(id, 1);
+    }
+
+    // Cancelling refunds the sender even when the recipient is frozen, whose share stays
+    // claimable.
+    mock.runtime_header.round = 23;
+    {
+        let mut ctx = mock.create_ctx();
+        Accounts::set_frozen(ctx.runtime_state(), bob, true);
+
+        let tx = tx_for(
+            &keys::alice::sigspec(),
+            "accounts.StreamCancel",
+            cbor::to_value(StreamCancel { id: 1 }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_stream_cancel(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("cancelling should succeed");
+            tx_ctx.commit();
+        });
+        let stream = Accounts::get_stream(ctx.runtime_state(), 1).unwrap();
+        assert_eq!(stream.amount, BaseUnits::new(500, Denomination::NATIVE));
+        assert_eq!(stream.end, 23);
+    }
+    assert_eq!(balance(&mut mock, alice), 998_700);
+    assert_eq!(balance(&mut mock, bob), 800);
+    assert_eq!(balance(&mut mock, *ADDRESS_STREAMS), 500);
+
+    // Once unfrozen, Bob can withdraw the remaining share.
+    mock.runtime_header.round = 30;
+    {
+        let mut ctx = mock.create_ctx();
+        Accounts::set_frozen(ctx.runtime_state(), bob, false);
+
+        let tx = tx_for(
+            &keys::bob::sigspec(),
+            "accounts.StreamWithdraw",
+            cbor::to_value(StreamWithdraw { id: 1 }),
+        );
+        let amount = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let amount =
+                Accounts::tx_stream_withdraw(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                    .expect("withdrawing should succeed");
+            tx_ctx.commit();
+            amount
+        });
+        assert_eq!(amount, BaseUnits::new(500, Denomination::NATIVE));
+        assert!(Accounts::get_stream(ctx.runtime_state(), 1).is_none());
+    }
+    assert_eq!(balance(&mut mock, bob), 1_300);
+    assert_eq!(balance(&mut mock, *ADDRESS_STREAMS), 0);
+}
+
+#[test]
+fn test_quorums() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    // Quorums used to be stored in the proposals state.
+    {
+        let store = crate::storage::PrefixStore::new(ctx.runtime_state(), &"accounts");
+        let mut proposals = crate::storage::TypedStore::new(crate::storage::PrefixStore::new(
+            store,
+            &super::state::PROPOSALS,
+        ));
+        proposals.insert(b"proposal_mint_quorum", 60u8);
+        proposals.insert(b"proposal_config_quorum", 80u8);
+    }
+    assert!(
+        Accounts::migrate(&mut ctx, 3),
+        "migration should be performed"
+    );
+
+    let params = Accounts::params(ctx.runtime_state());
+    assert_eq!(
+        params.quorums,
+        BTreeMap::from([(Action::Mint, 60), (Action::Config, 80)])
+    );
+    assert_eq!(
+        Accounts::get_quorum(ctx.runtime_state(), Action::Mint).unwrap(),
+        60
+    );
+    assert_eq!(
+        Accounts::get_quorum(ctx.runtime_state(), Action::PauseContract).unwrap(),
+        80,
+        "related actions should share the quorum"
+    );
+
+    Accounts::set_quorum(ctx.runtime_state(), Action::Burn, 50).unwrap();
+    assert!(matches!(
+        Accounts::set_quorum(ctx.runtime_state(), Action::Freeze, 50),
+        Err(Error::NotFound)
+    ));
+    let quorums = Accounts::query_quorums(&mut ctx, ()).unwrap();
+    assert_eq!(
+        quorums,
+        BTreeMap::from([
+            (Action::Mint, 60),
+            (Action::Burn, 50),
+            (Action::Whitelist, 100),
+            (Action::Blacklist, 100),
+            (Action::Config, 80),
+        ])
+    );
+
+    // Quorums above 100% are rejected.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.quorums.insert(Action::Whitelist, 101);
+    assert!(crate::module::Parameters::validate_basic(&params).is_err());
+}
+
+#[test]
+fn test_abstain_quorum() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::bob::address(), Role::WhitelistedUser);
+    for voter in [keys::charlie::address(), keys::dave::address()] {
+        Accounts::set_role(ctx.runtime_state(), voter, Role::MintVoter);
+        Accounts::add_role_to_address(ctx.runtime_state(), voter, Role::MintVoter);
+    }
+    Accounts::set_abstain_quorum(ctx.runtime_state(), Action::Mint, 100).unwrap();
+    assert!(matches!(
+        Accounts::set_abstain_quorum(ctx.runtime_state(), Action::Freeze, 100),
+        Err(Error::NotFound)
+    ));
+    let quorums = Accounts::query_abstain_quorums(&mut ctx, ()).unwrap();
+    assert_eq!(quorums.get(&Action::Mint), Some(&100));
+    assert_eq!(quorums.get(&Action::Burn), Some(&50));
+
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(ProposalContent {
+            action: Action::Mint,
+            data: ProposalData {
+                address: Some(keys::bob::address()),
+                amount: Some(BaseUnits::new(1_000, Denomination::NATIVE)),
+                ..Default::default()
+            },
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+
+    let vote = cbor::to_value(VoteProposal {
+        id: 1,
+        option: Vote::VoteAbstain,
+    });
+    for signer in [keys::charlie::sigspec(), keys::dave::sigspec()] {
+        let tx = tx_for(&signer, "accounts.VoteST", vote.clone());
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("voting should succeed");
+            tx_ctx.commit();
+        });
+
+        // Half of the voters abstaining is not enough with an abstain quorum of 100%.
+        if signer == keys::charlie::sigspec() {
+            let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+            assert_eq!(proposal.state, ProposalState::Active);
+        }
+    }
+
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(proposal.state, ProposalState::Cancelled);
+
+    // Abstain quorums above 100% are rejected.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.abstain_quorums.insert(Action::Burn, 101);
+    assert!(crate::module::Parameters::validate_basic(&params).is_err());
+}
+
+#[test]
+fn test_weighted_voting() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::bob::address(), Role::WhitelistedUser);
+    for voter in [keys::charlie::address(), keys::dave::address()] {
+        Accounts::set_role(ctx.runtime_state(), voter, Role::MintVoter);
+        Accounts::add_role_to_address(ctx.runtime_state(), voter, Role::MintVoter);
+    }
+    Accounts::set_voter_weight(
+        ctx.runtime_state(),
+        keys::charlie::address(),
+        Role::MintVoter,
+        3,
+    );
+    Accounts::set_quorum(ctx.runtime_state(), Action::Mint, 50).unwrap();
+    assert_eq!(
+        Accounts::get_voters_weight_with_action(ctx.runtime_state(), Action::Mint).unwrap(),
+        4
+    );
+
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(ProposalContent {
+            action: Action::Mint,
+            data: ProposalData {
+                address: Some(keys::bob::address()),
+                amount: Some(BaseUnits::new(1_000, Denomination::NATIVE)),
+                ..Default::default()
+            },
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+
+    let vote = cbor::to_value(VoteProposal {
+        id: 1,
+        option: Vote::VoteYes,
+    });
+    for signer in [keys::dave::sigspec(), keys::charlie::sigspec()] {
+        let tx = tx_for(&signer, "accounts.VoteST", vote.clone());
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("voting should succeed");
+            tx_ctx.commit();
+        });
+
+        // Half of the voters is not enough when they only carry a quarter of the weight.
+        if signer == keys::dave::sigspec() {
+            let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+            assert_eq!(proposal.state, ProposalState::Active);
+        }
+    }
+
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(proposal.state, ProposalState::Passed);
+    let tx = tx_for(
+        &keys::dave::sigspec(),
+        "accounts.ExecuteProposal",
+        cbor::to_value(ExecuteProposal { id: 1 }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+    assert_eq!(
+        Accounts::get_balance(ctx.runtime_state(), keys::bob::address(), Denomination::NATIVE)
+            .unwrap(),
+        1_000
+    );
+
+    // Voters can't be assigned a zero weight.
+    Accounts::set_role(ctx.runtime_state(), keys::erin::address(), Role::Admin);
+    let tx = tx_for(
+        &keys::erin::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(ProposalContent {
+            action: Action::SetRoles,
+            data: ProposalData {
+                address: Some(keys::frank::address()),
+                role: Some(Role::MintVoter),
+                voter_weight: Some(0),
+                ..Default::default()
+            },
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidArgument)));
+    });
+}
+
+#[test]
+fn test_change_vote() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::bob::address(), Role::WhitelistedUser);
+    for voter in [keys::charlie::address(), keys::dave::address()] {
+        Accounts::set_role(ctx.runtime_state(), voter, Role::MintVoter);
+        Accounts::add_role_to_address(ctx.runtime_state(), voter, Role::MintVoter);
+    }
+    Accounts::set_voter_weight(
+        ctx.runtime_state(),
+        keys::charlie::address(),
+        Role::MintVoter,
+        3,
+    );
+    Accounts::set_quorum(ctx.runtime_state(), Action::Mint, 70).unwrap();
+
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(ProposalContent {
+            action: Action::Mint,
+            data: ProposalData {
+                address: Some(keys::bob::address()),
+                amount: Some(BaseUnits::new(1_000, Denomination::NATIVE)),
+                ..Default::default()
+            },
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+
+    let vote = |option| cbor::to_value(VoteProposal { id: 1, option });
+
+    // Changing a vote that was never cast should fail.
+    let tx = tx_for(&keys::dave::sigspec(), "accounts.ChangeVote", vote(Vote::VoteYes));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_change_vote(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::NotFound)));
+    });
+
+    let tx = tx_for(&keys::dave::sigspec(), "accounts.VoteST", vote(Vote::VoteNo));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("voting should succeed");
+        tx_ctx.commit();
+    });
+
+    // Changing to the same option should fail.
+    let tx = tx_for(&keys::dave::sigspec(), "accounts.ChangeVote", vote(Vote::VoteNo));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_change_vote(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::VoteDup)));
+    });
+
+    // Switching to yes should move the vote without passing the proposal yet.
+    let tx = tx_for(&keys::dave::sigspec(), "accounts.ChangeVote", vote(Vote::VoteYes));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_change_vote(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("changing the vote should succeed");
+        tx_ctx.commit();
+    });
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(proposal.state, ProposalState::Active);
+    let results = proposal.results.unwrap();
+    assert_eq!(results.get(&Vote::VoteYes), Some(&1));
+    assert_eq!(results.get(&Vote::VoteNo), Some(&0));
+
+    let tx = tx_for(&keys::charlie::sigspec(), "accounts.VoteST", vote(Vote::VoteYes));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("voting should succeed");
+        tx_ctx.commit();
+    });
+
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(proposal.state, ProposalState::Passed);
+    let results = proposal.results.unwrap();
+    assert_eq!(results.get(&Vote::VoteYes), Some(&2));
+    assert_eq!(results.get(&Vote::VoteNo), Some(&0));
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.ExecuteProposal",
+        cbor::to_value(ExecuteProposal { id: 1 }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+    assert_eq!(
+        Accounts::get_balance(ctx.runtime_state(), keys::bob::address(), Denomination::NATIVE)
+            .unwrap(),
+        1_000
+    );
+
+    // Votes on finalized proposals can no longer be changed.
+    let tx = tx_for(&keys::charlie::sigspec(), "accounts.ChangeVote", vote(Vote::VoteNo));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_change_vote(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidState)));
+    });
+}
+
+#[test]
+fn test_vote_delegation() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintProposer);
+    Accounts::set_role(ctx.runtime_state(), keys::erin::address(), Role::WhitelistedUser);
+    for voter in [
+        keys::bob::address(),
+        keys::charlie::address(),
+        keys::dave::address(),
+    ] {
+        Accounts::set_role(ctx.runtime_state(), voter, Role::MintVoter);
+        Accounts::add_role_to_address(ctx.runtime_state(), voter, Role::MintVoter);
+    }
+
+    let delegate = |delegate| {
+        cbor::to_value(DelegateVote {
+            action: Action::Mint,
+            delegate,
+        })
+    };
+
+    // Only voters may delegate, and only to other voters.
+    for (signer, to, invalid_role) in [
+        (keys::alice::sigspec(), keys::charlie::address(), true),
+        (keys::dave::sigspec(), keys::alice::address(), true),
+        (keys::dave::sigspec(), keys::dave::address(), false),
+    ] {
+        let tx = tx_for(&signer, "accounts.DelegateVote", delegate(Some(to)));
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_delegate_vote(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            if invalid_role {
+                assert!(matches!(result, Err(Error::InvalidRole)));
+            } else {
+                assert!(matches!(result, Err(Error::InvalidArgument)));
+            }
+        });
+    }
+
+    let tx = tx_for(
+        &keys::dave::sigspec(),
+        "accounts.DelegateVote",
+        delegate(Some(keys::charlie::address())),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_delegate_vote(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("delegating should succeed");
+        tx_ctx.commit();
+    });
+    let delegations = Accounts::query_vote_delegations(
+        &mut ctx,
+        VoteDelegationsQuery {
+            address: keys::dave::address(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        delegations,
+        BTreeMap::from([(Action::Mint, keys::charlie::address())])
+    );
+
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.Propose",
+        cbor::to_value(ProposalContent {
+            action: Action::Mint,
+            data: ProposalData {
+                address: Some(keys::erin::address()),
+                amount: Some(BaseUnits::new(1_000, Denomination::NATIVE)),
+                ..Default::default()
+            },
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+
+    let vote = cbor::to_value(VoteProposal {
+        id: 1,
+        option: Vote::VoteYes,
+    });
+    for signer in [keys::charlie::sigspec(), keys::bob::sigspec()] {
+        let tx = tx_for(&signer, "accounts.VoteST", vote.clone());
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+                .expect("voting should succeed");
+            tx_ctx.commit();
+        });
+
+        // The vote of charlie also counts for dave, but that is not enough for all voters.
+        if signer == keys::charlie::sigspec() {
+            let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+            assert_eq!(proposal.state, ProposalState::Active);
+        }
+    }
+
+    let proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    assert_eq!(proposal.state, ProposalState::Passed);
+
+    // Delegations can be removed.
+    let tx = tx_for(
+        &keys::dave::sigspec(),
+        "accounts.DelegateVote",
+        delegate(None),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_delegate_vote(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("removing the delegation should succeed");
+        tx_ctx.commit();
+    });
+    assert!(Accounts::get_vote_delegations(ctx.runtime_state(), keys::dave::address()).is_empty());
+}
+
+#[test]
+fn test_query_proposals() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let proposals = [
+        (Action::Mint, keys::alice::address(), ProposalState::Active),
+        (Action::Burn, keys::bob::address(), ProposalState::Cancelled),
+        (Action::Burn, keys::alice::address(), ProposalState::Active),
+        (Action::Mint, keys::bob::address(), ProposalState::Active),
+    ];
+    for (action, submitter, state) in proposals {
+        let id = Accounts::get_and_increment_proposal_id(ctx.runtime_state()).unwrap();
+        Accounts::insert_proposal(
+            ctx.runtime_state(),
+            Proposal {
+                id,
+                submitter,
+                state,
+                content: ProposalContent {
+                    action,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+    assert_eq!(
+        Accounts::get_active_proposal_ids(ctx.runtime_state()),
+        vec![1, 3, 4]
+    );
+
+    let ids = |proposals: Vec<Proposal>| proposals.iter().map(|p| p.id).collect::<Vec<_>>();
+
+    let result = Accounts::query_proposals(&mut ctx, ProposalsQuery::default()).unwrap();
+    assert_eq!(ids(result), vec![1, 2, 3, 4]);
+
+    let result = Accounts::query_proposals(
+        &mut ctx,
+        ProposalsQuery {
+            state: Some(ProposalState::Active),
+            action: Some(Action::Burn),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(result), vec![3]);
+
+    let result = Accounts::query_proposals(
+        &mut ctx,
+        ProposalsQuery {
+            submitter: Some(keys::bob::address()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(result), vec![2, 4]);
+
+    // Pagination.
+    let result = Accounts::query_proposals(
+        &mut ctx,
+        ProposalsQuery {
+            state: Some(ProposalState::Active),
+            limit: 1,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(result), vec![1]);
+    let result = Accounts::query_proposals(
+        &mut ctx,
+        ProposalsQuery {
+            state: Some(ProposalState::Active),
+            start_after: 1,
+            limit: 1,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(ids(result), vec![3]);
+
+    // Proposals leave the index once they are no longer active.
+    let mut proposal = Accounts::get_proposal(ctx.runtime_state(), 1).unwrap();
+    proposal.state = ProposalState::Passed;
+    Accounts::insert_proposal(ctx.runtime_state(), proposal).unwrap();
+    assert_eq!(
+        Accounts::get_active_proposal_ids(ctx.runtime_state()),
+        vec![3, 4]
+    );
+}
+
+#[test]
+fn test_add_role_to_address() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::add_role_to_address(ctx.runtime_state(), keys::alice::address(), Role::MintVoter);
+    let roles = Accounts::get_addrsno_in_role(ctx.runtime_state(), Role::MintVoter);
+    assert_eq!(roles, 1, "should be only 1 role!");
+
+    let addresses = Accounts::get_addresses_in_role(ctx.runtime_state(), Role::MintVoter)
+        .expect("get_addresses_in_role should succeed");
+
+    println!("{:?}", addresses);
+    assert!(addresses.contains(&keys::alice::address()), "addresses should contain Alice's address");
+}
+
+#[test]
+fn test_get_role() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    // GB: by default, the role is user.
+    let role = Accounts::get_role(ctx.runtime_state(), keys::alice::address()).unwrap();
+    assert_eq!(role, Role::User);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::MintVoter);
+
+    // GB: set to minter.
+    let role = Accounts::get_role(ctx.runtime_state(), keys::alice::address()).unwrap();
+    assert_eq!(role, Role::MintVoter);
+}
+
+#[test]
+fn test_get_roles() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let alice = keys::alice::address();
+    assert_eq!(
+        Accounts::get_roles(ctx.runtime_state(), alice),
+        BTreeSet::from_iter([Role::User])
+    );
+
+    // Roles accumulate.
+    Accounts::set_role(ctx.runtime_state(), alice, Role::MintVoter);
+    Accounts::add_role_to_address(ctx.runtime_state(), alice, Role::MintVoter);
+    Accounts::set_role(ctx.runtime_state(), alice, Role::BurnProposer);
+    Accounts::add_role_to_address(ctx.runtime_state(), alice, Role::BurnProposer);
+    assert_eq!(
+        Accounts::get_roles(ctx.runtime_state(), alice),
+        BTreeSet::from_iter([Role::MintVoter, Role::BurnProposer])
+    );
+    assert!(Accounts::has_role(ctx.runtime_state(), alice, Role::MintVoter));
+    assert!(!Accounts::has_role(ctx.runtime_state(), alice, Role::Admin));
+    assert_eq!(
+        Accounts::get_addresses_in_role(ctx.runtime_state(), Role::MintVoter).unwrap(),
+        vec![alice]
+    );
+
+    // Blacklisting drops all other roles.
+    Accounts::set_role(ctx.runtime_state(), alice, Role::BlacklistedUser);
+    Accounts::add_role_to_address(ctx.runtime_state(), alice, Role::BlacklistedUser);
+    assert_eq!(
+        Accounts::get_roles(ctx.runtime_state(), alice),
+        BTreeSet::from_iter([Role::BlacklistedUser])
+    );
+}
+
+#[test]
+fn test_revoke_role() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let alice = keys::alice::address();
+    for role in [Role::BurnProposer, Role::MintVoter] {
+        Accounts::set_role(ctx.runtime_state(), alice, role);
+        Accounts::add_role_to_address(ctx.runtime_state(), alice, role);
+        Accounts::add_address_to_roles(ctx.runtime_state(), alice, role).unwrap();
+    }
+    Accounts::set_role(ctx.runtime_state(), keys::dave::address(), Role::Admin);
+    Accounts::add_role_to_address(ctx.runtime_state(), keys::dave::address(), Role::Admin);
+
+    fn revoke<C: BatchContext>(
+        ctx: &mut C,
+        signer: &SignatureAddressSpec,
+        address: crate::types::address::Address,
+        role: Role,
+    ) -> Result<(), Error> {
+        let tx = tx_for(
+            signer,
+            "accounts.RevokeRole",
+            cbor::to_value(RoleAddress { address, role }),
+        );
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_revoke_role(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            if result.is_ok() {
+                tx_ctx.commit();
+            }
+            result
+        })
+    }
+
+    // Only admins may revoke roles.
+    let result = revoke(&mut ctx, &keys::bob::sigspec(), alice, Role::MintVoter);
+    assert!(matches!(result, Err(Error::InvalidRole)));
+
+    revoke(&mut ctx, &keys::dave::sigspec(), alice, Role::MintVoter)
+        .expect("revoking a role should succeed");
+    assert_eq!(
+        Accounts::get_roles(ctx.runtime_state(), alice),
+        BTreeSet::from_iter([Role::BurnProposer])
+    );
+    assert_eq!(
+        Accounts::get_role(ctx.runtime_state(), alice).unwrap(),
+        Role::BurnProposer,
+        "primary role should fall back to a remaining role"
+    );
+    assert!(Accounts::get_addresses_in_role(ctx.runtime_state(), Role::MintVoter)
+        .unwrap()
+        .is_empty());
+
+    // Roles that are not held can't be revoked.
+    let result = revoke(&mut ctx, &keys::dave::sigspec(), alice, Role::MintVoter);
+    assert!(matches!(result, Err(Error::InvalidArgument)));
+
+    // The last admin can't be revoked.
+    let result = revoke(
+        &mut ctx,
+        &keys::dave::sigspec(),
+        keys::dave::address(),
+        Role::Admin,
+    );
+    assert!(matches!(result, Err(Error::Forbidden)));
+
+    revoke(&mut ctx, &keys::dave::sigspec(), alice, Role::BurnProposer)
+        .expect("revoking a role should succeed");
+    assert_eq!(
+        Accounts::get_role(ctx.runtime_state(), alice).unwrap(),
+        Role::User
+    );
+}
+
+#[test]
+fn test_get_initstatus() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    // GB: by default, the role is user.
+    let initstatus = Accounts::get_initstatus(ctx.runtime_state(), keys::alice::address()).unwrap();
+    assert_eq!(initstatus, false);
+
+    Accounts::set_initstatus(ctx.runtime_state(), keys::alice::address(), true);
+
+    // GB: set to minter.
+    let initstatus = Accounts::get_initstatus(ctx.runtime_state(), keys::alice::address()).unwrap();
+    assert_eq!(initstatus, true);
+
+}
+
+#[test]
+fn test_contract_pause_list() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    let contract = keys::alice::address();
+    assert!(!Accounts::is_contract_paused(ctx.runtime_state(), contract));
+
+    Accounts::set_contract_paused(ctx.runtime_state(), contract, true);
+    assert!(Accounts::is_contract_paused(ctx.runtime_state(), contract));
+    assert!(!Accounts::is_contract_paused(ctx.runtime_state(), keys::bob::address()));
+
+    Accounts::set_contract_paused(ctx.runtime_state(), contract, false);
+    assert!(!Accounts::is_contract_paused(ctx.runtime_state(), contract));
+}
+
+#[test]
+fn test_fee_disbursement() {
+    let mut mock = mock::Mock::default();
+
+    // Configure some good entities so they get the fees.
+    mock.runtime_round_results.good_compute_entities = vec![
+        keys::bob::pk_ed25519().into(),
+        keys::charlie::pk_ed25519().into(),
+    ];
+
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    // Disable the common pool tax so that only the remainder goes to the common pool.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.fee_distribution.common_pool_share = 0;
+    Accounts::set_params(ctx.runtime_state(), params);
+
+    let tx = transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "accounts.Transfer".to_owned(),
+            body: cbor::to_value(Transfer {
+                to: keys::bob::address(),
+                amount: Default::default(),
+                memo: None,
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                // Use an amount that does not split nicely among the good compute entities.
+                amount: BaseUnits::new(1_001, Denomination::NATIVE),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+
+    // Authenticate transaction, fees should be moved to accumulator.
+    Accounts::authenticate_tx(&mut ctx, &tx).expect("transaction authentication should succeed");
+    // Run end block handler.
+    Accounts::end_block(&mut ctx);
+
+    // Check source account balances.
+    let bals = Accounts::get_balances(ctx.runtime_state(), keys::alice::address())
+        .expect("get_balances should succeed");
+    assert_eq!(
+        bals.balances[&Denomination::NATIVE],
+        998_999,
         "fees should be subtracted from source account"
     );
     // Nothing should be disbursed yet to good compute entity accounts.
@@ -696,6 +3922,425 @@ fn test_fee_disbursement() {
     );
 }
 
+#[test]
+fn test_undisbursed_fees() {
+    // Without any good entities there is nobody to disburse the fees to.
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let d1: Denomination = "den1".parse().unwrap();
+    Accounts::add_amount(
+        ctx.runtime_state(),
+        *ADDRESS_FEE_ACCUMULATOR,
+        &BaseUnits::new(1_000, Denomination::NATIVE),
+    )
+    .unwrap();
+    Accounts::add_amount(
+        ctx.runtime_state(),
+        *ADDRESS_FEE_ACCUMULATOR,
+        &BaseUnits::new(50, d1.clone()),
+    )
+    .unwrap();
+
+    Accounts::end_block(&mut ctx);
+
+    // Fees in all denominations should be drained into the common pool.
+    let bals = Accounts::get_balances(ctx.runtime_state(), *ADDRESS_FEE_ACCUMULATOR)
+        .expect("get_balances should succeed");
+    assert!(
+        bals.balances.values().all(|amount| *amount == 0),
+        "fee accumulator should be drained"
+    );
+    let bals = Accounts::get_balances(ctx.runtime_state(), *ADDRESS_COMMON_POOL)
+        .expect("get_balances should succeed");
+    assert_eq!(bals.balances[&Denomination::NATIVE], 1_000);
+    assert_eq!(bals.balances[&d1], 50);
+}
+
+#[test]
+fn test_fee_distribution() {
+    let mut mock = mock::Mock::default();
+
+    // Bob is reported twice and thus gets a double share under the proportional policy.
+    mock.runtime_round_results.good_compute_entities = vec![
+        keys::bob::pk_ed25519().into(),
+        keys::bob::pk_ed25519().into(),
+        keys::charlie::pk_ed25519().into(),
+    ];
+
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.fee_distribution = FeeDistribution {
+        common_pool_share: 10,
+        burn_share: 20,
+        policy: FeeDistributionPolicy::Proportional,
+    };
+    Accounts::set_params(ctx.runtime_state(), params);
+
+    let tx = transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "accounts.Transfer".to_owned(),
+            body: cbor::to_value(Transfer {
+                to: keys::bob::address(),
+                amount: Default::default(),
+                memo: None,
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: BaseUnits::new(1_000, Denomination::NATIVE),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+
+    // Collect the fees in one block and disburse them in the next one.
+    Accounts::authenticate_tx(&mut ctx, &tx).expect("transaction authentication should succeed");
+    Accounts::end_block(&mut ctx);
+    Accounts::end_block(&mut ctx);
+
+    // 10% tax, 20% burned and the remaining 700 split 2:1 between Bob and Charlie.
+    let mut balances = [
+        keys::bob::address(),
+        keys::charlie::address(),
+        *ADDRESS_COMMON_POOL,
+        *ADDRESS_FEE_ACCUMULATOR,
+    ]
+    .into_iter()
+    .map(|address| {
+        Accounts::get_balance(ctx.runtime_state(), address, Denomination::NATIVE).unwrap()
+    });
+    assert_eq!(balances.next(), Some(466), "bob should receive a double share");
+    assert_eq!(balances.next(), Some(233), "charlie should receive a single share");
+    assert_eq!(
+        balances.next(),
+        Some(101),
+        "common pool should receive the tax and the remainder"
+    );
+    assert_eq!(balances.next(), Some(0), "fee accumulator should be drained");
+
+    let total_supplies = Accounts::get_total_supplies(ctx.runtime_state()).unwrap();
+    assert_eq!(
+        total_supplies[&Denomination::NATIVE],
+        999_800,
+        "burned fees should be removed from the total supply"
+    );
+
+    // Shares exceeding 100% are rejected.
+    let params = Parameters {
+        fee_distribution: FeeDistribution {
+            common_pool_share: 50,
+            burn_share: 51,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert!(crate::module::Parameters::validate_basic(&params).is_err());
+}
+
+#[test]
+fn test_reward_address() {
+    let mut mock = mock::Mock::default();
+
+    mock.runtime_round_results.good_compute_entities = vec![
+        keys::bob::pk_ed25519().into(),
+        keys::charlie::pk_ed25519().into(),
+    ];
+
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.fee_distribution.common_pool_share = 0;
+    Accounts::set_params(ctx.runtime_state(), params);
+
+    let tx_for = |signer: &crate::types::address::SignatureAddressSpec, method: &str, body, fee| {
+        transaction::Transaction {
+            version: 1,
+            call: transaction::Call {
+                format: transaction::CallFormat::Plain,
+                method: method.to_owned(),
+                body,
+                ..Default::default()
+            },
+            auth_info: transaction::AuthInfo {
+                signer_info: vec![transaction::SignerInfo::new_sigspec(signer.clone(), 0)],
+                fee: transaction::Fee {
+                    amount: BaseUnits::new(fee, Denomination::NATIVE),
+                    gas: 1000,
+                    consensus_messages: 0,
+                },
+                ..Default::default()
+            },
+        }
+    };
+
+    // Bob redirects its fees to Dave.
+    let tx = tx_for(
+        &keys::bob::sigspec(),
+        "accounts.SetRewardAddress",
+        cbor::to_value(SetRewardAddress {
+            address: Some(keys::dave::address()),
+        }),
+        0,
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_set_reward_address(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("setting the reward address should succeed");
+        tx_ctx.commit();
+    });
+
+    let reward_address = Accounts::query_reward_address(
+        &mut ctx,
+        RewardAddressQuery {
+            address: keys::bob::address(),
+        },
+    )
+    .expect("reward address query should succeed");
+    assert_eq!(reward_address, keys::dave::address());
+    let reward_address = Accounts::query_reward_address(
+        &mut ctx,
+        RewardAddressQuery {
+            address: keys::charlie::address(),
+        },
+    )
+    .expect("reward address query should succeed");
+    assert_eq!(
+        reward_address,
+        keys::charlie::address(),
+        "entities without a reward address should receive the fees themselves"
+    );
+
+    // Collect the fees in one block and disburse them in the next one.
+    let tx = tx_for(
+        &keys::alice::sigspec(),
+        "accounts.Transfer",
+        cbor::to_value(Transfer {
+            to: keys::bob::address(),
+            amount: Default::default(),
+            memo: None,
+        }),
+        1_000,
+    );
+    Accounts::authenticate_tx(&mut ctx, &tx).expect("transaction authentication should succeed");
+    Accounts::end_block(&mut ctx);
+    Accounts::end_block(&mut ctx);
+
+    let balance =
+        Accounts::get_balance(ctx.runtime_state(), keys::dave::address(), Denomination::NATIVE)
+            .unwrap();
+    assert_eq!(balance, 500, "fees should be disbursed to the reward address");
+    let balance =
+        Accounts::get_balance(ctx.runtime_state(), keys::bob::address(), Denomination::NATIVE)
+            .unwrap();
+    assert_eq!(balance, 0, "fees should not be disbursed to the entity");
+    let balance =
+        Accounts::get_balance(ctx.runtime_state(), keys::charlie::address(), Denomination::NATIVE)
+            .unwrap();
+    assert_eq!(balance, 500, "fees should be disbursed to the entity");
+}
+
+#[test]
+fn test_account_metadata() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    fn set_metadata<C: BatchContext>(
+        ctx: &mut C,
+        metadata: Option<AccountMetadata>,
+    ) -> Result<(), Error> {
+        let tx = transaction::Transaction {
+            version: 1,
+            call: transaction::Call {
+                format: transaction::CallFormat::Plain,
+                method: "accounts.SetMetadata".to_owned(),
+                body: cbor::to_value(SetMetadata { metadata }),
+                ..Default::default()
+            },
+            auth_info: transaction::AuthInfo {
+                signer_info: vec![transaction::SignerInfo::new_sigspec(
+                    keys::alice::sigspec(),
+                    0,
+                )],
+                fee: transaction::Fee {
+                    amount: Default::default(),
+                    gas: 1000,
+                    consensus_messages: 0,
+                },
+                ..Default::default()
+            },
+        };
+        ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+            let result =
+                Accounts::tx_set_metadata(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+            if result.is_ok() {
+                tx_ctx.commit();
+            }
+            result
+        })
+    }
+
+    let query = MetadataQuery {
+        address: keys::alice::address(),
+    };
+    let metadata = AccountMetadata {
+        name: "Alice".to_string(),
+        uri: Some("https://example.com/alice.json".to_string()),
+        hash: Some(oasis_core_runtime::common::crypto::hash::Hash::digest_bytes(b"profile")),
+    };
+    set_metadata(&mut ctx, Some(metadata.clone())).expect("setting metadata should succeed");
+    let result = Accounts::query_metadata(&mut ctx, query.clone()).unwrap();
+    assert_eq!(result, Some(metadata));
+    let result = Accounts::query_metadata(
+        &mut ctx,
+        MetadataQuery {
+            address: keys::bob::address(),
+        },
+    )
+    .unwrap();
+    assert_eq!(result, None, "accounts without metadata should have none");
+
+    // Metadata exceeding the size limits is rejected.
+    let result = set_metadata(
+        &mut ctx,
+        Some(AccountMetadata {
+            name: "A".repeat(MAX_ACCOUNT_NAME_LENGTH + 1),
+            ..Default::default()
+        }),
+    );
+    assert!(matches!(result, Err(Error::InvalidArgument)));
+
+    // Owners can remove their metadata again.
+    set_metadata(&mut ctx, None).expect("removing metadata should succeed");
+    let result = Accounts::query_metadata(&mut ctx, query).unwrap();
+    assert_eq!(result, None);
+}
+
+#[test]
+fn test_query_total_supply() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let supply = Accounts::query_total_supply(
+        &mut ctx,
+        TotalSupplyQuery {
+            denomination: Denomination::NATIVE,
+        },
+    )
+    .expect("total supply query should succeed");
+    assert_eq!(supply, 1_000_000);
+
+    let supply = Accounts::query_total_supply(
+        &mut ctx,
+        TotalSupplyQuery {
+            denomination: "unknown".parse().unwrap(),
+        },
+    )
+    .expect("total supply query should succeed");
+    assert_eq!(supply, 0, "unknown denominations should have no supply");
+
+    let supplies =
+        Accounts::query_total_supplies(&mut ctx, ()).expect("total supplies query should succeed");
+    assert_eq!(supplies.len(), 1);
+    assert_eq!(supplies[&Denomination::NATIVE], 1_000_000);
+}
+
+#[test]
+fn test_query_all_balances() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    let dn = Denomination::NATIVE;
+    let d1: Denomination = "den1".parse().unwrap();
+
+    let gen = Genesis {
+        balances: {
+            let mut balances = BTreeMap::new();
+            // Alice.
+            balances.insert(keys::alice::address(), {
+                let mut denominations = BTreeMap::new();
+                denominations.insert(dn.clone(), 1_000_000);
+                denominations.insert(d1.clone(), 1_000);
+                denominations
+            });
+            // Bob.
+            balances.insert(keys::bob::address(), {
+                let mut denominations = BTreeMap::new();
+                denominations.insert(d1.clone(), 2_000);
+                denominations
+            });
+            // Charlie.
+            balances.insert(keys::charlie::address(), {
+                let mut denominations = BTreeMap::new();
+                denominations.insert(dn.clone(), 3_000);
+                denominations
+            });
+            balances
+        },
+        total_supplies: {
+            let mut total_supplies = BTreeMap::new();
+            total_supplies.insert(dn, 1_003_000);
+            total_supplies.insert(d1, 3_000);
+            total_supplies
+        },
+        ..Default::default()
+    };
+    let expected = gen.balances.clone();
+
+    Accounts::init(&mut ctx, gen);
+
+    // Page through all balances one address at a time.
+    let mut all_balances = BTreeMap::new();
+    let mut start_after = None;
+    loop {
+        let page = Accounts::query_all_balances(
+            &mut ctx,
+            AllBalancesQuery {
+                start_after,
+                limit: 1,
+            },
+        )
+        .expect("all balances query should succeed");
+        assert!(page.len() <= 1, "page should respect the limit");
+        let last = match page.last() {
+            Some(last) => last.address,
+            None => break,
+        };
+        for entry in page {
+            assert!(
+                all_balances.insert(entry.address, entry.balances).is_none(),
+                "addresses should not be returned twice"
+            );
+        }
+        start_after = Some(last);
+    }
+    assert_eq!(all_balances, expected, "all balances should be returned");
+
+    // Without a limit, all addresses fit into a single page.
+    let page = Accounts::query_all_balances(&mut ctx, AllBalancesQuery::default())
+        .expect("all balances query should succeed");
+    assert_eq!(page.len(), 3);
+}
+
 #[test]
 fn test_query_addresses() {
     let mut mock = mock::Mock::default();
@@ -1040,33 +4685,161 @@ fn test_check_invariants_more() {
     );
 
     assert!(
-        Accounts::inc_total_supply(ctx.runtime_state(), &BaseUnits::new(300, d4)).is_ok(),
-        "increasing total supply should succeed"
+        Accounts::inc_total_supply(ctx.runtime_state(), &BaseUnits::new(300, d4)).is_ok(),
+        "increasing total supply should succeed"
+    );
+    assert!(
+        Accounts::check_invariants(&mut ctx).is_ok(),
+        "inv chk 4 should succeed"
+    );
+
+    let d5: Denomination = "den5".parse().unwrap();
+
+    assert!(
+        Accounts::inc_total_supply(ctx.runtime_state(), &BaseUnits::new(123, d5.clone())).is_ok(),
+        "increasing total supply should succeed"
+    );
+    assert!(
+        Accounts::check_invariants(&mut ctx).is_err(),
+        "inv chk 5 should fail"
+    );
+
+    assert!(
+        Accounts::add_amount(ctx.runtime_state(), charlie, &BaseUnits::new(123, d5)).is_ok(),
+        "giving Charlie more money should succeed"
+    );
+    assert!(
+        Accounts::check_invariants(&mut ctx).is_ok(),
+        "inv chk 6 should succeed"
+    );
+}
+
+#[test]
+fn test_check_invariants_deep() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    // Let the running totals drift from the actual balances.
+    Accounts::update_balance_total(ctx.runtime_state(), &Denomination::NATIVE, 0, 1);
+    Accounts::inc_total_supply(
+        ctx.runtime_state(),
+        &BaseUnits::new(1, Denomination::NATIVE),
+    )
+    .expect("increasing total supply should succeed");
+    assert!(
+        Accounts::check_invariants(&mut ctx).is_ok(),
+        "running totals should match the total supply"
+    );
+
+    // Only the deep check sums up the balances of all accounts.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.deep_invariant_checks = true;
+    Accounts::set_params(ctx.runtime_state(), params);
+    assert!(
+        Accounts::check_invariants(&mut ctx).is_err(),
+        "balances should not match the total supply"
     );
-    assert!(
-        Accounts::check_invariants(&mut ctx).is_ok(),
-        "inv chk 4 should succeed"
+}
+
+#[test]
+fn test_fee_conversion() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    let alice = keys::alice::address();
+    let stable: Denomination = "USD".parse().unwrap();
+    Accounts::mint(&mut ctx, alice, &BaseUnits::new(1_000, stable.clone()))
+        .expect("mint should succeed");
+    Accounts::mint(
+        &mut ctx,
+        *ADDRESS_COMMON_POOL,
+        &BaseUnits::new(150, Denomination::NATIVE),
+    )
+    .expect("mint should succeed");
+
+    // Two units of the stablecoin are worth three native units.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.fee_conversion_rates.insert(
+        stable.clone(),
+        FeeConversionRate {
+            native_amount: 3,
+            amount: 2,
+        },
     );
+    Accounts::set_params(ctx.runtime_state(), params);
 
-    let d5: Denomination = "den5".parse().unwrap();
+    let mut tx = transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "accounts.Transfer".to_owned(),
+            body: cbor::to_value(Transfer {
+                to: keys::bob::address(),
+                amount: Default::default(),
+                memo: None,
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: BaseUnits::new(100, stable.clone()),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
 
-    assert!(
-        Accounts::inc_total_supply(ctx.runtime_state(), &BaseUnits::new(123, d5.clone())).is_ok(),
-        "increasing total supply should succeed"
+    // The common pool receives the fee and pays its native equivalent into the accumulator.
+    Accounts::authenticate_tx(&mut ctx, &tx).expect("transaction authentication should succeed");
+
+    // Refunds are exchanged back through the common pool.
+    ctx.with_tx(0, 0, tx.clone(), |mut tx_ctx, _call| {
+        Accounts::refund_fee(&mut tx_ctx, &BaseUnits::new(40, stable.clone()))
+            .expect("refunding fees should succeed");
+        tx_ctx.commit();
+    });
+    Accounts::end_block(&mut ctx);
+
+    fn balance<C: Context>(ctx: &mut C, address: Address, denomination: &Denomination) -> u128 {
+        Accounts::get_balance(ctx.runtime_state(), address, denomination.clone()).unwrap()
+    }
+    assert_eq!(balance(&mut ctx, alice, &stable), 940);
+    assert_eq!(balance(&mut ctx, *ADDRESS_COMMON_POOL, &stable), 60);
+    assert_eq!(
+        balance(&mut ctx, *ADDRESS_COMMON_POOL, &Denomination::NATIVE),
+        60
     );
-    assert!(
-        Accounts::check_invariants(&mut ctx).is_err(),
-        "inv chk 5 should fail"
+    assert_eq!(
+        balance(&mut ctx, *ADDRESS_FEE_ACCUMULATOR, &Denomination::NATIVE),
+        90
     );
+    assert_eq!(balance(&mut ctx, *ADDRESS_FEE_ACCUMULATOR, &stable), 0);
 
-    assert!(
-        Accounts::add_amount(ctx.runtime_state(), charlie, &BaseUnits::new(123, d5)).is_ok(),
-        "giving Charlie more money should succeed"
-    );
-    assert!(
-        Accounts::check_invariants(&mut ctx).is_ok(),
-        "inv chk 6 should succeed"
+    // Fees can not be converted once the common pool runs out of native units.
+    tx.auth_info.signer_info[0].nonce = 1;
+    let err = Accounts::authenticate_tx(&mut ctx, &tx).expect_err("fee should not be convertible");
+    assert!(matches!(err, core::Error::InsufficientFeeBalance));
+    assert_eq!(balance(&mut ctx, alice, &stable), 940);
+
+    // Fees in the native denomination are never converted.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.fee_conversion_rates.insert(
+        Denomination::NATIVE,
+        FeeConversionRate {
+            native_amount: 1,
+            amount: 1,
+        },
     );
+    assert!(crate::module::Parameters::validate_basic(&params).is_err());
 }
 
 #[test]
@@ -1170,6 +4943,232 @@ fn test_get_set_nonce() {
     assert_eq!(nonce, 2);
 }
 
+#[test]
+fn test_nonce_future_delta() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_check_ctx();
+
+    init_accounts(&mut ctx);
+
+    let tx_for = |nonce| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "accounts.Transfer".to_owned(),
+            body: cbor::to_value(Transfer::default()),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                nonce,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+
+    // Future nonces are rejected by default.
+    let result = Accounts::authenticate_tx(&mut ctx, &tx_for(1));
+    assert!(matches!(result, Err(core::Error::InvalidNonce(0, 1))));
+
+    // Nonces within the configured delta are accepted during checks.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.max_check_nonce_future_delta = 2;
+    Accounts::set_params(ctx.runtime_state(), params);
+    Accounts::authenticate_tx(&mut ctx, &tx_for(2))
+        .expect("transaction authentication should succeed");
+
+    // Nonces beyond the configured delta are still rejected.
+    let result = Accounts::authenticate_tx(&mut ctx, &tx_for(4));
+    assert!(matches!(result, Err(core::Error::InvalidNonce(0, 4))));
+
+    let ok = crate::module::CallResult::Ok(cbor::Value::Simple(cbor::SimpleValue::NullValue));
+    Accounts::after_dispatch_tx(&mut ctx, &tx_for(2).auth_info, &ok);
+
+    // Future nonces are not tracked, so another transaction with the same nonce is accepted too.
+    Accounts::authenticate_tx(&mut ctx, &tx_for(2))
+        .expect("transaction authentication should succeed");
+    Accounts::after_dispatch_tx(&mut ctx, &tx_for(2).auth_info, &ok);
+
+    // Queued transactions do not advance the nonce so that the preceding ones are still accepted
+    // in any order.
+    for nonce in [0, 1] {
+        Accounts::authenticate_tx(&mut ctx, &tx_for(nonce))
+            .expect("transaction authentication should succeed");
+        Accounts::after_dispatch_tx(&mut ctx, &tx_for(nonce).auth_info, &ok);
+    }
+    assert_eq!(
+        Accounts::get_nonce(ctx.runtime_state(), keys::alice::address()).unwrap(),
+        2
+    );
+
+    // Once the preceding transactions have been checked, the queued one is next in line.
+    Accounts::authenticate_tx(&mut ctx, &tx_for(2))
+        .expect("transaction authentication should succeed");
+    let result = Accounts::authenticate_tx(&mut ctx, &tx_for(0));
+    assert!(matches!(result, Err(core::Error::InvalidNonce(2, 0))));
+}
+
+#[test]
+fn test_method_restrictions() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::bob::address(), Role::BlacklistedUser);
+
+    // Without restrictions all methods are allowed.
+    let authorizer = Accounts::method_authorizer(ctx.runtime_state(), keys::bob::address());
+    assert!(authorizer("evm.Call"));
+
+    let mut params = Accounts::params(ctx.runtime_state());
+    params.method_restrictions = BTreeMap::from([(
+        Role::BlacklistedUser,
+        vec!["evm.*".to_string(), "accounts.Transfer".to_string()],
+    )]);
+    crate::module::Parameters::validate_basic(&params).expect("parameters should be valid");
+    Accounts::set_params(ctx.runtime_state(), params);
+
+    let authorizer = Accounts::method_authorizer(ctx.runtime_state(), keys::bob::address());
+    assert!(!authorizer("evm.Call"));
+    assert!(!authorizer("evm.Create"));
+    assert!(!authorizer("accounts.Transfer"));
+    assert!(authorizer("accounts.TransferFrom"));
+    assert!(authorizer("consensus.Deposit"));
+
+    // Accounts without restricted roles are not affected.
+    let authorizer = Accounts::method_authorizer(ctx.runtime_state(), keys::alice::address());
+    assert!(authorizer("evm.Call"));
+    assert!(authorizer("accounts.Transfer"));
+
+    // Restrictions apply to every signer of a transaction, not only the first one.
+    let tx = transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "evm.Call".to_owned(),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![
+                transaction::SignerInfo::new_sigspec(keys::alice::sigspec(), 0),
+                transaction::SignerInfo::new_sigspec(keys::bob::sigspec(), 0),
+            ],
+            ..Default::default()
+        },
+    };
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::authorize_call(&mut tx_ctx, &call.method);
+        assert!(matches!(result, Err(core::Error::Forbidden)));
+        Accounts::authorize_call(&mut tx_ctx, "accounts.TransferFrom")
+            .expect("unrestricted methods should be allowed");
+    });
+
+    // Wildcards are only allowed at the end of a pattern.
+    let mut params = Accounts::params(ctx.runtime_state());
+    params
+        .method_restrictions
+        .insert(Role::User, vec!["*.Call".to_string()]);
+    assert!(crate::module::Parameters::validate_basic(&params).is_err());
+}
+
+#[test]
+fn test_prune_accounts() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::Admin);
+    Accounts::add_role_to_address(ctx.runtime_state(), keys::alice::address(), Role::Admin);
+
+    // Bob's account has been swept, Charlie's account has been used to sign transactions.
+    Accounts::set_balance(
+        ctx.runtime_state(),
+        keys::bob::address(),
+        &BaseUnits::new(0, Denomination::NATIVE),
+    );
+    Accounts::set_nonce(ctx.runtime_state(), keys::bob::address(), 0);
+    Accounts::set_balance(
+        ctx.runtime_state(),
+        keys::charlie::address(),
+        &BaseUnits::new(0, Denomination::NATIVE),
+    );
+    Accounts::set_nonce(ctx.runtime_state(), keys::charlie::address(), 1);
+
+    let tx_for = |signer: &SignatureAddressSpec| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: "accounts.PruneAccounts".to_owned(),
+            body: cbor::to_value(PruneAccounts {
+                addresses: vec![
+                    keys::alice::address(),
+                    keys::bob::address(),
+                    keys::charlie::address(),
+                    keys::dave::address(),
+                ],
+            }),
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(signer.clone(), 0)],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+
+    // Only admins can prune accounts.
+    ctx.with_tx(0, 0, tx_for(&keys::bob::sigspec()), |mut tx_ctx, call| {
+        let result = Accounts::tx_prune_accounts(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidRole)));
+    });
+
+    ctx.with_tx(0, 0, tx_for(&keys::alice::sigspec()), |mut tx_ctx, call| {
+        let pruned = Accounts::tx_prune_accounts(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("pruning should succeed");
+        assert_eq!(
+            pruned, 2,
+            "only accounts with prunable state should be counted"
+        );
+        tx_ctx.commit();
+    });
+
+    let bals = Accounts::get_balances(ctx.runtime_state(), keys::bob::address()).unwrap();
+    assert!(bals.balances.is_empty(), "zero balances should be pruned");
+    let bals = Accounts::get_balances(ctx.runtime_state(), keys::charlie::address()).unwrap();
+    assert!(bals.balances.is_empty(), "zero balances should be pruned");
+    assert_eq!(
+        Accounts::get_nonce(ctx.runtime_state(), keys::charlie::address()).unwrap(),
+        1,
+        "accounts holding a nonce should be kept"
+    );
+    assert_eq!(
+        Accounts::get_balance(
+            ctx.runtime_state(),
+            keys::alice::address(),
+            Denomination::NATIVE
+        )
+        .unwrap(),
+        1_000_000,
+        "accounts with a balance should be kept"
+    );
+    assert!(!Accounts::prune_account(
+        ctx.runtime_state(),
+        keys::bob::address()
+    ));
+}
+
 #[test]
 fn test_get_set_balance() {
     let mut mock = mock::Mock::default();
@@ -1274,6 +5273,103 @@ fn test_query_denomination_info() {
     .unwrap_err();
 }
 
+#[test]
+fn test_denomination_metadata_proposal() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+
+    init_accounts(&mut ctx);
+
+    Accounts::set_role(ctx.runtime_state(), keys::alice::address(), Role::Admin);
+    Accounts::add_role_to_address(ctx.runtime_state(), keys::alice::address(), Role::Admin);
+
+    let tx_for = |method: &str, body| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: method.to_owned(),
+            body,
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 0,
+            },
+            ..Default::default()
+        },
+    };
+    let proposal_for = |info: DenominationInfo| ProposalContent {
+        action: Action::Config,
+        data: ProposalData {
+            denomination_infos: Some(BTreeMap::from([(Denomination::NATIVE, info)])),
+            ..Default::default()
+        },
+    };
+    let info = DenominationInfo {
+        decimals: 9,
+        symbol: "HLUSD".to_string(),
+        name: "Hela USD".to_string(),
+        logo_uri: Some("https://example.com/hlusd.png".to_string()),
+    };
+
+    // Metadata over the size limits should be rejected.
+    let tx = tx_for(
+        "accounts.Propose",
+        cbor::to_value(proposal_for(DenominationInfo {
+            symbol: "X".repeat(MAX_DENOMINATION_SYMBOL_LENGTH + 1),
+            ..info.clone()
+        })),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap());
+        assert!(matches!(result, Err(Error::InvalidArgument)));
+    });
+
+    let tx = tx_for("accounts.Propose", cbor::to_value(proposal_for(info.clone())));
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_propose(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("proposing should succeed");
+        tx_ctx.commit();
+    });
+    let id = Accounts::get_proposal_id(ctx.runtime_state()).unwrap();
+    let tx = tx_for(
+        "accounts.VoteST",
+        cbor::to_value(VoteProposal {
+            id,
+            option: Vote::VoteYes,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_votest(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("voting should succeed");
+        tx_ctx.commit();
+    });
+    let tx = tx_for(
+        "accounts.ExecuteProposal",
+        cbor::to_value(ExecuteProposal { id }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Accounts::tx_execute_proposal(&mut tx_ctx, cbor::from_value(call.body).unwrap())
+            .expect("executing should succeed");
+        tx_ctx.commit();
+    });
+
+    let di = Accounts::query_denomination_info(
+        &mut ctx,
+        DenominationInfoQuery {
+            denomination: Denomination::NATIVE,
+        },
+    )
+    .unwrap();
+    assert_eq!(di, info, "denomination info should be updated");
+}
+
 #[test]
 fn test_transaction_expiry() {
     let mut mock = mock::Mock::default();
@@ -1289,6 +5385,7 @@ fn test_transaction_expiry() {
             body: cbor::to_value(Transfer {
                 to: keys::bob::address(),
                 amount: Default::default(),
+                memo: None,
             }),
             ..Default::default()
         },
@@ -1328,3 +5425,89 @@ fn test_transaction_expiry() {
     let err = Accounts::authenticate_tx(&mut ctx, &tx).expect_err("tx should be expired");
     assert!(matches!(err, core::Error::ExpiredTransaction));
 }
+
+/// Transfer hook that vetoes any balance changes of Dave's account.
+struct DaveVetoHook;
+
+impl TransferHook for DaveVetoHook {
+    fn before_transfer<C: Context>(
+        _ctx: &mut C,
+        from: Address,
+        to: Address,
+        _amount: &BaseUnits,
+    ) -> Result<(), Error> {
+        if from == keys::dave::address() || to == keys::dave::address() {
+            return Err(Error::Forbidden);
+        }
+        Ok(())
+    }
+
+    fn before_mint<C: Context>(
+        _ctx: &mut C,
+        to: Address,
+        _amount: &BaseUnits,
+    ) -> Result<(), Error> {
+        if to == keys::dave::address() {
+            return Err(Error::Forbidden);
+        }
+        Ok(())
+    }
+}
+
+struct TransferHookRuntime;
+
+impl Runtime for TransferHookRuntime {
+    const VERSION: Version = Version::new(0, 0, 0);
+
+    type Core = core::Module<mock::Config>;
+    type TransferHooks = ((), DaveVetoHook);
+
+    type Modules = (core::Module<mock::Config>, Accounts);
+
+    fn genesis_state() -> <Self::Modules as MigrationHandler>::Genesis {
+        Default::default()
+    }
+}
+
+#[test]
+fn test_transfer_hooks() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx_for_runtime::<TransferHookRuntime>(Mode::ExecuteTx);
+
+    init_accounts(&mut ctx);
+
+    let amount = BaseUnits::new(1_000, Denomination::NATIVE);
+    let result = Accounts::transfer(
+        &mut ctx,
+        keys::alice::address(),
+        keys::dave::address(),
+        &amount,
+    );
+    assert!(
+        matches!(result, Err(Error::Forbidden)),
+        "hooks should be able to veto transfers"
+    );
+    let result = Accounts::mint(&mut ctx, keys::dave::address(), &amount);
+    assert!(
+        matches!(result, Err(Error::Forbidden)),
+        "hooks should be able to veto mints"
+    );
+
+    Accounts::transfer(
+        &mut ctx,
+        keys::alice::address(),
+        keys::bob::address(),
+        &amount,
+    )
+    .expect("transfer should succeed");
+    Accounts::mint(&mut ctx, keys::bob::address(), &amount).expect("mint should succeed");
+    assert_eq!(
+        Accounts::get_balance(
+            ctx.runtime_state(),
+            keys::bob::address(),
+            Denomination::NATIVE
+        )
+        .unwrap(),
+        2_000
+    );
+}
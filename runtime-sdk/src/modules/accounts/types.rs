@@ -1,14 +1,375 @@
 //! Account module types.
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use crate::types::{address::Address, role::Role, token, proposal, vote};
+use oasis_core_runtime::common::crypto::hash::Hash;
 
+use crate::{
+    modules::core,
+    types::{address::Address, role::Role, token, proposal, vote},
+};
+
+
+/// Maximum size (in bytes) of a transfer memo.
+pub const MAX_TRANSFER_MEMO_SIZE: usize = 256;
 
 /// Transfer call.
-#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
 pub struct Transfer {
     pub to: Address,
     pub amount: token::BaseUnits,
+    /// Optional memo attached to the transfer and emitted in the transfer event, e.g. for
+    /// identifying deposits. At most [`MAX_TRANSFER_MEMO_SIZE`] bytes.
+    #[cbor(optional)]
+    pub memo: Option<Vec<u8>>,
+}
+
+impl Transfer {
+    /// Whether the memo, if any, is within the size limit.
+    pub fn has_valid_memo(&self) -> bool {
+        self.memo
+            .as_ref()
+            .map_or(true, |memo| memo.len() <= MAX_TRANSFER_MEMO_SIZE)
+    }
+}
+
+/// Batch transfer call.
+///
+/// Executes all transfers atomically, either all of them succeed or none does.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct TransferBatch {
+    pub transfers: Vec<Transfer>,
+}
+
+/// Account pruning call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct PruneAccounts {
+    pub addresses: Vec<Address>,
+}
+
+/// Approve call.
+///
+/// Allows the spender to transfer up to the given amount on behalf of the caller.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct Approve {
+    pub spender: Address,
+    pub amount: token::BaseUnits,
+}
+
+/// Transfer from call.
+///
+/// Transfers an amount on behalf of another account, spending the allowance given to the caller.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct TransferFrom {
+    pub from: Address,
+    pub to: Address,
+    pub amount: token::BaseUnits,
+}
+
+/// Fee grant call.
+///
+/// Allows the grantee to have its transaction fees paid by the caller, up to the given spend limit
+/// and until the given round.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct GrantFee {
+    pub grantee: Address,
+    pub spend_limit: token::BaseUnits,
+    pub expiration: u64,
+}
+
+/// Fee grant revocation call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct RevokeFeeGrant {
+    pub grantee: Address,
+}
+
+/// Maximum number of members of a multisig account.
+pub const MAX_MULTISIG_MEMBERS: usize = 32;
+
+/// Multisig account creation call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct CreateMultisig {
+    pub members: Vec<Address>,
+    pub threshold: u16,
+}
+
+/// Multisig account.
+///
+/// Funds held by a multisig account can only be moved by transactions that have been approved by
+/// at least `threshold` of its members. Multisig transactions are limited to transfers of the
+/// account's funds; arbitrary inner calls are not supported as there is no way to dispatch calls
+/// on behalf of the multisig address.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct MultisigAccount {
+    pub members: Vec<Address>,
+    pub threshold: u16,
+    /// Id of the next multisig transaction.
+    pub next_id: u64,
+}
+
+impl MultisigAccount {
+    /// Whether the members and threshold form a valid multisig account.
+    pub fn is_valid(members: &[Address], threshold: u16) -> bool {
+        let unique = members.iter().collect::<BTreeSet<_>>().len();
+        !members.is_empty()
+            && members.len() <= MAX_MULTISIG_MEMBERS
+            && unique == members.len()
+            && threshold > 0
+            && threshold as usize <= members.len()
+    }
+
+    /// Whether the address is a member of the multisig account.
+    pub fn is_member(&self, address: &Address) -> bool {
+        self.members.contains(address)
+    }
+}
+
+/// Multisig transaction submission call.
+///
+/// Submitting a transaction counts as an approval by the submitter.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct MultisigSubmit {
+    pub multisig: Address,
+    pub transfer: Transfer,
+}
+
+/// Multisig transaction approval call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct MultisigApprove {
+    pub multisig: Address,
+    pub id: u64,
+}
+
+/// Multisig transaction execution call.
+///
+/// Executes a transaction whose approvals meet the current threshold, e.g. after governance
+/// lowered the threshold or replaced members.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct MultisigExecute {
+    pub multisig: Address,
+    pub id: u64,
+}
+
+/// Multisig transaction.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct MultisigTransaction {
+    /// Transfer from the multisig account executed once enough members approve.
+    pub transfer: Transfer,
+    /// Members that approved the transaction.
+    pub approvals: Vec<Address>,
+    /// Whether the transaction has been executed.
+    #[cbor(optional)]
+    pub executed: bool,
+}
+
+/// Vote delegation call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct DelegateVote {
+    /// Action for which the voting power is delegated.
+    pub action: vote::Action,
+    /// Address voting on behalf of the caller. Delegations are not transitive. If not set, any
+    /// previous delegation for the action is removed.
+    #[cbor(optional)]
+    pub delegate: Option<Address>,
+}
+
+/// Arguments for the ProposalVotes query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct ProposalVotesQuery {
+    pub id: u32,
+}
+
+/// Arguments for the VoteDelegations query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct VoteDelegationsQuery {
+    pub address: Address,
+}
+
+/// Arguments for the History query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct HistoryQuery {
+    pub address: Address,
+    /// Sequence number after which to start returning activity entries.
+    #[cbor(optional)]
+    pub start_after: u64,
+    /// Maximum number of activity entries to return. Zero means the maximum allowed.
+    #[cbor(optional)]
+    pub limit: u16,
+}
+
+/// An entry in the activity index of an account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct Activity {
+    /// Sequence number of the entry, starting at one for the first activity of the account.
+    pub seq: u64,
+    /// Round in which the activity took place.
+    pub round: u64,
+    /// Index of the transaction within the round, if the activity was caused by a transaction.
+    #[cbor(optional)]
+    pub tx_index: Option<u32>,
+}
+
+/// Arguments for the Multisig query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct MultisigQuery {
+    pub address: Address,
+}
+
+/// Arguments for the MultisigTransaction query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct MultisigTransactionQuery {
+    pub multisig: Address,
+    pub id: u64,
+}
+
+/// Escrow creation call.
+///
+/// At least one of the arbiter and the timeout must be set.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct EscrowCreate {
+    /// Recipient of the escrowed funds once released.
+    pub to: Address,
+    pub amount: token::BaseUnits,
+    /// Address that may release or refund the escrow at any time.
+    #[cbor(optional)]
+    pub arbiter: Option<Address>,
+    /// Round after which the creator may reclaim the escrowed funds.
+    #[cbor(optional)]
+    pub timeout: Option<u64>,
+}
+
+/// Escrowed funds held by the module until released to the recipient or refunded to the
+/// creator.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct Escrow {
+    pub creator: Address,
+    pub to: Address,
+    pub amount: token::BaseUnits,
+    #[cbor(optional)]
+    pub arbiter: Option<Address>,
+    #[cbor(optional)]
+    pub timeout: Option<u64>,
+}
+
+impl Escrow {
+    /// Whether the escrowed funds may be released to the recipient by the given address.
+    pub fn can_release(&self, caller: &Address) -> bool {
+        caller == &self.creator || self.arbiter.as_ref() == Some(caller)
+    }
+
+    /// Whether the escrowed funds may be refunded to the creator by the given address in the
+    /// given round.
+    pub fn can_refund(&self, caller: &Address, round: u64) -> bool {
+        if self.arbiter.as_ref() == Some(caller) {
+            return true;
+        }
+        caller == &self.creator && matches!(self.timeout, Some(timeout) if round > timeout)
+    }
+}
+
+/// Escrow release call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct EscrowRelease {
+    pub id: u64,
+}
+
+/// Escrow refund call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct EscrowRefund {
+    pub id: u64,
+}
+
+/// Arguments for the Escrow query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct EscrowQuery {
+    pub id: u64,
+}
+
+/// Payment stream creation call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct StreamCreate {
+    /// Recipient of the streamed funds.
+    pub to: Address,
+    /// Total amount streamed between the start and end rounds.
+    pub amount: token::BaseUnits,
+    /// Round in which streaming starts. If not set, streaming starts in the current round.
+    #[cbor(optional)]
+    pub start: Option<u64>,
+    /// Round in which the whole amount has been streamed.
+    pub end: u64,
+}
+
+/// Payment stream.
+///
+/// The amount becomes claimable by the recipient linearly between the start and end rounds. The
+/// stream is only settled when the recipient withdraws or the sender cancels it.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct Stream {
+    pub sender: Address,
+    pub to: Address,
+    pub amount: token::BaseUnits,
+    pub start: u64,
+    pub end: u64,
+    /// Amount already withdrawn by the recipient.
+    #[cbor(optional)]
+    pub withdrawn: u128,
+}
+
+impl Stream {
+    /// Amount streamed up to the given round, including any withdrawn amount.
+    pub fn streamed(&self, round: u64) -> u128 {
+        VestingSchedule {
+            amount: self.amount.clone(),
+            start: self.start,
+            cliff: self.start,
+            end: self.end,
+        }
+        .vested(round)
+    }
+
+    /// Amount the recipient can withdraw in the given round.
+    pub fn withdrawable(&self, round: u64) -> u128 {
+        self.streamed(round).saturating_sub(self.withdrawn)
+    }
+}
+
+/// Payment stream withdrawal call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct StreamWithdraw {
+    pub id: u64,
+}
+
+/// Payment stream cancellation call.
+///
+/// The recipient receives the amount streamed so far and the remainder is returned to the sender.
+/// In case the recipient's account is frozen, the stream is kept with the amount streamed so far
+/// so that the recipient can withdraw it later.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct StreamCancel {
+    pub id: u64,
+}
+
+/// Arguments for the Stream query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct StreamQuery {
+    pub id: u64,
+}
+
+/// Information about a fee grant.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct FeeGrantInfo {
+    /// Remaining amount that the granter will pay for the grantee's transaction fees.
+    pub spend_limit: token::BaseUnits,
+    /// Last round in which the grant may be used.
+    pub expiration: u64,
+}
+
+impl FeeGrantInfo {
+    /// Whether the grant covers the given fee in the given round.
+    pub fn covers(&self, fee: &token::BaseUnits, round: u64) -> bool {
+        round <= self.expiration
+            && fee.denomination() == self.spend_limit.denomination()
+            && fee.amount() <= self.spend_limit.amount()
+    }
 }
 
 
@@ -49,7 +410,50 @@ pub struct ProposalData {
     pub blacklist_quorum: Option<u8>,
     #[cbor(optional)]
     pub config_quorum: Option<u8>,
+    /// New abstain quorums of actions for a config proposal.
+    #[cbor(optional)]
+    pub abstain_quorums: Option<BTreeMap<vote::Action, u8>>,
+    /// New fee distribution for a config proposal.
+    #[cbor(optional)]
+    pub fee_distribution: Option<FeeDistribution>,
+    /// New or updated denomination information for a config proposal.
+    #[cbor(optional)]
+    pub denomination_infos: Option<BTreeMap<token::Denomination, DenominationInfo>>,
+    /// New or updated transfer limits of roles for a config proposal. Roles with empty limits
+    /// are no longer limited.
+    #[cbor(optional)]
+    pub transfer_limits: Option<BTreeMap<Role, BTreeMap<token::Denomination, u128>>>,
+    /// New members of the multisig account for a SetMultisigMembers proposal.
+    #[cbor(optional)]
+    pub multisig_members: Option<Vec<Address>>,
+    /// New threshold of the multisig account for a SetMultisigMembers proposal.
+    #[cbor(optional)]
+    pub multisig_threshold: Option<u16>,
     // GB: setRoles_quorum is omit here, which means it is 100 by default.
+    /// New core module parameters for a config proposal.
+    #[cbor(optional)]
+    pub core_parameters: Option<core::Parameters>,
+    /// Round at which the new core module parameters become active. If not set or already
+    /// reached when the proposal passes, they become active in the next round.
+    #[cbor(optional)]
+    pub activation_round: Option<u64>,
+    /// Voting weight of the address for the role assigned by a SetRoles proposal.
+    #[cbor(optional)]
+    pub voter_weight: Option<u16>,
+    /// Round after which the whitelisting of a Whitelist proposal expires. Whitelistings without
+    /// an expiry do not expire.
+    #[cbor(optional)]
+    pub whitelist_expiry: Option<u64>,
+    /// New or updated fee conversion rates for a config proposal. Fees in denominations whose
+    /// rate has a zero native amount are no longer converted.
+    #[cbor(optional)]
+    pub fee_conversion_rates: Option<BTreeMap<token::Denomination, FeeConversionRate>>,
+    /// New or updated proposer roles of actions for a config proposal.
+    #[cbor(optional)]
+    pub proposer_roles: Option<BTreeMap<vote::Action, Role>>,
+    /// New or updated voter roles of actions for a config proposal.
+    #[cbor(optional)]
+    pub voter_roles: Option<BTreeMap<vote::Action, Role>>,
 }
 
 
@@ -73,6 +477,14 @@ pub struct Proposal {
 
     // Record the addresses voted.
     pub voteOption: Option<HashMap<Address, vote::Vote>>,
+
+    /// Deposit held while the proposal is active.
+    #[cbor(optional)]
+    pub deposit: Option<token::BaseUnits>,
+
+    /// Whether the action of the passed proposal has been executed.
+    #[cbor(optional)]
+    pub executed: bool,
 }
 
 impl Proposal {
@@ -90,6 +502,13 @@ impl Proposal {
         // Return the updated count.
         *count
     }
+
+    /// Removes a previously added vote from the results.
+    pub fn remove_vote(&mut self, vote: vote::Vote) {
+        if let Some(count) = self.results.as_mut().and_then(|results| results.get_mut(&vote)) {
+            *count = count.saturating_sub(1);
+        }
+    }
 }
 
 
@@ -99,6 +518,18 @@ pub struct VoteProposal {
     pub option: vote::Vote,
 }
 
+/// Proposal cancellation call.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct CancelProposal {
+    pub id: u32,
+}
+
+/// Proposal execution call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct ExecuteProposal {
+    pub id: u32,
+}
+
 
 // GB: insert mintst.
 // Mint call.
@@ -118,8 +549,139 @@ pub struct BurnST {
 }
 
 
-/// Account metadata.
+/// Session key registration call.
+///
+/// Allows a role holder to authorize another key to make selected governance calls on its behalf.
+/// The session key must co-sign the registration transaction.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct SessionKey {
+    /// Address of the session key.
+    pub address: Address,
+    /// Last round in which the session key may be used.
+    pub expiry: u64,
+    /// Methods that the session key may call.
+    pub methods: Vec<String>,
+}
+
+/// Session key revocation call.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct RevokeSessionKey {
+    pub address: Address,
+}
+
+/// Policy for distributing fees among the good compute entities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+#[repr(u8)]
+#[cbor(with_default)]
+pub enum FeeDistributionPolicy {
+    /// Each good compute entity receives an equal share.
+    Equal = 0,
+    /// Each good compute entity receives a share proportional to the number of times it is
+    /// reported among the good compute entities of the round.
+    Proportional = 1,
+}
+
+impl Default for FeeDistributionPolicy {
+    fn default() -> Self {
+        Self::Equal
+    }
+}
+
+/// Distribution of the fees collected in a block.
+#[derive(Clone, Debug, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct FeeDistribution {
+    /// Percentage of the fees transferred to the common pool.
+    pub common_pool_share: u8,
+    /// Percentage of the fees burned.
+    #[cbor(optional)]
+    pub burn_share: u8,
+    /// Policy for distributing the remaining fees among the good compute entities.
+    #[cbor(optional)]
+    pub policy: FeeDistributionPolicy,
+}
+
+impl Default for FeeDistribution {
+    fn default() -> Self {
+        Self {
+            common_pool_share: 10,
+            burn_share: 0,
+            policy: FeeDistributionPolicy::Equal,
+        }
+    }
+}
+
+impl FeeDistribution {
+    /// Whether the shares add up to at most 100%.
+    pub fn is_valid(&self) -> bool {
+        self.common_pool_share as u16 + self.burn_share as u16 <= 100
+    }
+
+    /// Split the given fees into the common pool tax, the burned amount and the amount to be
+    /// distributed among the good compute entities.
+    pub fn split(&self, fees: u128) -> (u128, u128, u128) {
+        let share = |percentage: u8| {
+            let percentage = percentage as u128;
+            (fees / 100) * percentage + (fees % 100) * percentage / 100
+        };
+        let tax = share(self.common_pool_share);
+        let burn = share(self.burn_share);
+        (tax, burn, fees - tax - burn)
+    }
+}
+
+/// Rate at which fees paid in a non-native denomination are converted into the native
+/// denomination.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct FeeConversionRate {
+    /// Amount of native base units that `amount` is worth.
+    pub native_amount: u128,
+    /// Amount of base units of the non-native denomination.
+    pub amount: u128,
+}
+
+impl FeeConversionRate {
+    /// Whether both sides of the rate are non-zero.
+    pub fn is_valid(&self) -> bool {
+        self.native_amount > 0 && self.amount > 0
+    }
+
+    /// Convert the given amount into native base units, rounding down.
+    pub fn convert(&self, amount: u128) -> u128 {
+        let whole = (amount / self.amount).saturating_mul(self.native_amount);
+        let rest = (amount % self.amount).saturating_mul(self.native_amount) / self.amount;
+        whole.saturating_add(rest)
+    }
+}
+
+/// Information about a registered session key.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct SessionKeyInfo {
+    /// Role holder on whose behalf the session key acts.
+    pub owner: Address,
+    /// Last round in which the session key may be used.
+    pub expiry: u64,
+    /// Methods that the session key may call.
+    pub methods: Vec<String>,
+}
+
+impl SessionKeyInfo {
+    /// Whether the session key may call the given method in the given round.
+    pub fn allows(&self, method: &str, round: u64) -> bool {
+        round <= self.expiry && self.methods.iter().any(|m| m == method)
+    }
+}
+
+/// Amounts transferred by an account within a transfer limit window.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct TransferVolume {
+    /// Index of the window, i.e. the block timestamp divided by the window length.
+    pub window: u64,
+    /// Amounts transferred within the window, per denomination.
+    pub amounts: BTreeMap<token::Denomination, u128>,
+}
+
+/// Account metadata.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
 pub struct Account {
     #[cbor(optional)]
     pub nonce: u64,
@@ -131,6 +693,103 @@ pub struct Account {
     // GB: set bool var to be true, after the chainInitiator set the in
     #[cbor(optional)]
     pub init: bool,
+
+    /// Vesting schedule of the account's locked balance, if any.
+    #[cbor(optional)]
+    pub vesting: Option<VestingSchedule>,
+
+    /// Whether the account has been frozen through a governance proposal. Frozen accounts can
+    /// neither send nor receive transfers, independently of the roles they hold.
+    #[cbor(optional)]
+    pub frozen: bool,
+
+    /// Round after which the account's whitelisting expires, if it is time-limited.
+    #[cbor(optional)]
+    pub whitelist_expiry: Option<u64>,
+
+    /// Profile metadata set by the owner of the account, if any.
+    #[cbor(optional)]
+    pub metadata: Option<AccountMetadata>,
+}
+
+/// Maximum length (in bytes) of an account display name.
+pub const MAX_ACCOUNT_NAME_LENGTH: usize = 64;
+/// Maximum length (in bytes) of an account metadata URI.
+pub const MAX_ACCOUNT_URI_LENGTH: usize = 512;
+
+/// Profile metadata of an account, e.g. used by explorers to label known addresses.
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
+pub struct AccountMetadata {
+    /// Human readable name of the account.
+    #[cbor(optional)]
+    pub name: String,
+    /// URI of further information about the account, if any.
+    #[cbor(optional)]
+    pub uri: Option<String>,
+    /// Hash of the document at the URI, if any.
+    #[cbor(optional)]
+    pub hash: Option<Hash>,
+}
+
+impl AccountMetadata {
+    /// Whether the metadata is within the size limits.
+    pub fn is_valid(&self) -> bool {
+        self.name.len() <= MAX_ACCOUNT_NAME_LENGTH
+            && self
+                .uri
+                .as_ref()
+                .map_or(true, |uri| uri.len() <= MAX_ACCOUNT_URI_LENGTH)
+    }
+}
+
+/// A vesting schedule.
+///
+/// Nothing is released before the cliff round. Afterwards the amount is released linearly between
+/// the start and end rounds.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct VestingSchedule {
+    /// Total amount subject to vesting.
+    pub amount: token::BaseUnits,
+    /// Round in which vesting starts.
+    pub start: u64,
+    /// Round before which nothing is released.
+    pub cliff: u64,
+    /// Round in which the whole amount is released.
+    pub end: u64,
+}
+
+impl VestingSchedule {
+    /// Whether the schedule is well-formed.
+    pub fn is_valid(&self) -> bool {
+        self.start <= self.cliff && self.cliff <= self.end && self.start < self.end
+    }
+
+    /// Amount released in the given round.
+    pub fn vested(&self, round: u64) -> u128 {
+        let total = self.amount.amount();
+        if round < self.cliff {
+            return 0;
+        }
+        if round >= self.end {
+            return total;
+        }
+        let elapsed = (round - self.start) as u128;
+        let duration = (self.end - self.start) as u128;
+        // Avoid overflowing for large amounts by splitting into whole and fractional parts.
+        total / duration * elapsed + total % duration * elapsed / duration
+    }
+
+    /// Amount that is still locked in the given round.
+    pub fn locked(&self, round: u64) -> u128 {
+        self.amount.amount() - self.vested(round)
+    }
+}
+
+/// Vested and locked amounts of an account's vesting schedule.
+#[derive(Clone, Debug, Default, PartialEq, cbor::Encode, cbor::Decode)]
+pub struct VestingStatus {
+    pub vested: token::BaseUnits,
+    pub locked: token::BaseUnits,
 }
 
 
@@ -140,12 +799,87 @@ pub struct NonceQuery {
     pub address: Address,
 }
 
+/// Arguments for the Allowance query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct AllowanceQuery {
+    pub owner: Address,
+    pub spender: Address,
+    pub denomination: token::Denomination,
+}
+
 /// Arguments for the Role query.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
 pub struct RoleQuery {
     pub address: Address,
 }
 
+/// Arguments for the SessionKey query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct SessionKeyQuery {
+    pub address: Address,
+}
+
+/// Arguments for the Proposals query.
+///
+/// Proposals are returned in increasing id order, starting after the given id.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct ProposalsQuery {
+    #[cbor(optional)]
+    pub state: Option<proposal::ProposalState>,
+    #[cbor(optional)]
+    pub action: Option<vote::Action>,
+    #[cbor(optional)]
+    pub submitter: Option<Address>,
+    #[cbor(optional)]
+    pub start_after: u32,
+    /// Maximum number of proposals to return. Zero means the maximum allowed.
+    #[cbor(optional)]
+    pub limit: u16,
+}
+
+/// Arguments for the Vesting query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct VestingQuery {
+    pub address: Address,
+}
+
+/// Set reward address call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct SetRewardAddress {
+    /// Address that should receive the fees disbursed to the caller. If not set, any previously
+    /// registered reward address is removed.
+    #[cbor(optional)]
+    pub address: Option<Address>,
+}
+
+/// Arguments for the RewardAddress query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct RewardAddressQuery {
+    pub address: Address,
+}
+
+/// Set metadata call.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct SetMetadata {
+    /// New profile metadata of the caller's account. If not set, any previously set metadata is
+    /// removed.
+    #[cbor(optional)]
+    pub metadata: Option<AccountMetadata>,
+}
+
+/// Arguments for the Metadata query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct MetadataQuery {
+    pub address: Address,
+}
+
+/// Arguments for the FeeGrant query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct FeeGrantQuery {
+    pub granter: Address,
+    pub grantee: Address,
+}
+
 /// Arguments for the InitStatus query.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
 pub struct InitInfoQuery {
@@ -187,15 +921,68 @@ pub struct AccountBalances {
     pub balances: BTreeMap<token::Denomination, u128>,
 }
 
+/// Arguments for the AllBalances query.
+///
+/// Balances are returned in increasing address order, starting after the given address.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct AllBalancesQuery {
+    #[cbor(optional)]
+    pub start_after: Option<Address>,
+    /// Maximum number of addresses to return. Zero means the maximum allowed.
+    #[cbor(optional)]
+    pub limit: u16,
+}
+
+/// Balances of an address, as returned by the AllBalances query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct AddressBalances {
+    pub address: Address,
+    pub balances: BTreeMap<token::Denomination, u128>,
+}
+
+/// Arguments for the TotalSupply query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct TotalSupplyQuery {
+    pub denomination: token::Denomination,
+}
+
 /// Arguments for the DenominationInfo query.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
 pub struct DenominationInfoQuery {
     pub denomination: token::Denomination,
 }
 
+/// Maximum length (in bytes) of a denomination symbol.
+pub const MAX_DENOMINATION_SYMBOL_LENGTH: usize = 32;
+/// Maximum length (in bytes) of a denomination display name.
+pub const MAX_DENOMINATION_NAME_LENGTH: usize = 64;
+/// Maximum length (in bytes) of a denomination logo URI.
+pub const MAX_DENOMINATION_LOGO_URI_LENGTH: usize = 512;
+
 /// Information about a denomination.
-#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
 pub struct DenominationInfo {
     /// Number of decimals that the denomination is using.
     pub decimals: u8,
+    /// Ticker symbol of the denomination.
+    #[cbor(optional)]
+    pub symbol: String,
+    /// Human readable name of the denomination.
+    #[cbor(optional)]
+    pub name: String,
+    /// URI of the denomination's logo, if any.
+    #[cbor(optional)]
+    pub logo_uri: Option<String>,
+}
+
+impl DenominationInfo {
+    /// Whether the metadata is within the size limits.
+    pub fn is_valid(&self) -> bool {
+        self.symbol.len() <= MAX_DENOMINATION_SYMBOL_LENGTH
+            && self.name.len() <= MAX_DENOMINATION_NAME_LENGTH
+            && self
+                .logo_uri
+                .as_ref()
+                .map_or(true, |uri| uri.len() <= MAX_DENOMINATION_LOGO_URI_LENGTH)
+    }
 }
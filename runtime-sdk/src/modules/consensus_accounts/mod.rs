@@ -7,7 +7,10 @@ use std::{collections::BTreeSet, convert::TryInto};
 use once_cell::sync::Lazy;
 use thiserror::Error;
 
-use oasis_core_runtime::consensus::staking::Account as ConsensusAccount;
+use oasis_core_runtime::consensus::{
+    beacon::EpochTime,
+    staking::{Account as ConsensusAccount, AddEscrowResult, ReclaimEscrowResult},
+};
 use oasis_runtime_sdk_macros::{handler, sdk_derive};
 
 use crate::{
@@ -19,7 +22,7 @@ use crate::{
     module::{MethodHandler},
     modules::core::{Error as CoreError, API as _},
     runtime::Runtime,
-    storage::Prefix,
+    storage::{self, Prefix},
     types::{
         address::Address,
         message::{MessageEvent, MessageEventHookInvocation},
@@ -49,6 +52,14 @@ pub enum Error {
     #[sdk_error(code = 3)]
     InsufficientWithdrawBalance,
 
+    #[error("delegate: insufficient runtime balance")]
+    #[sdk_error(code = 4)]
+    InsufficientDelegateBalance,
+
+    #[error("undelegate: insufficient delegated shares")]
+    #[sdk_error(code = 5)]
+    InsufficientDelegationShares,
+
     #[error("consensus: {0}")]
     #[sdk_error(transparent)]
     Consensus(#[from] modules::consensus::Error),
@@ -69,6 +80,10 @@ pub struct CallParam {
 pub struct GasCosts {
     pub tx_deposit: u64,
     pub tx_withdraw: u64,
+    #[cbor(optional)]
+    pub tx_delegate: u64,
+    #[cbor(optional)]
+    pub tx_undelegate: u64,
 }
 
 /// Parameters for the consensus module.
@@ -106,6 +121,35 @@ pub enum Event {
         #[cbor(optional)]
         error: Option<types::ConsensusError>,
     },
+
+    #[sdk_event(code = 3)]
+    Delegate {
+        from: Address,
+        nonce: u64,
+        to: Address,
+        amount: token::BaseUnits,
+        #[cbor(optional)]
+        error: Option<types::ConsensusError>,
+    },
+
+    #[sdk_event(code = 4)]
+    UndelegateStart {
+        from: Address,
+        nonce: u64,
+        to: Address,
+        shares: u128,
+        #[cbor(optional)]
+        debond_end_time: EpochTime,
+        #[cbor(optional)]
+        error: Option<types::ConsensusError>,
+    },
+
+    #[sdk_event(code = 5)]
+    UndelegateDone {
+        from: Address,
+        to: Address,
+        amount: token::BaseUnits,
+    },
 }
 
 /// Genesis state for the consensus module.
@@ -146,9 +190,36 @@ pub trait API {
         amount: token::BaseUnits,
     ) -> Result<(), Error>;
 
-    // TODO:
-    //  - Add/reclaim deposited escrow.
-    //      - need a way to get escrow events in runtime: https://github.com/oasisprotocol/oasis-core/issues/3862
+    /// Delegate from runtime account to consensus staking account.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce`: A caller-provided sequence number that will help identify the success/fail events.
+    ///   When called from a delegate transaction, we use the signer nonce.
+    fn delegate<C: TxContext>(
+        ctx: &mut C,
+        from: Address,
+        nonce: u64,
+        to: Address,
+        amount: token::BaseUnits,
+    ) -> Result<(), Error>;
+
+    /// Reclaim shares delegated to a consensus staking account back into a runtime account.
+    ///
+    /// The reclaimed tokens are credited to the runtime account once the consensus layer
+    /// debonding period has passed.
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce`: A caller-provided sequence number that will help identify the success/fail events.
+    ///   When called from an undelegate transaction, we use the signer nonce.
+    fn undelegate<C: TxContext>(
+        ctx: &mut C,
+        from: Address,
+        nonce: u64,
+        to: Address,
+        shares: u128,
+    ) -> Result<(), Error>;
 }
 
 pub struct Module<Accounts: modules::accounts::API, Consensus: modules::consensus::API> {
@@ -160,8 +231,52 @@ pub struct Module<Accounts: modules::accounts::API, Consensus: modules::consensu
 pub static ADDRESS_PENDING_WITHDRAWAL: Lazy<Address> =
     Lazy::new(|| Address::from_module(MODULE_NAME, "pending-withdrawal"));
 
+/// Module's address that has the tokens pending delegation.
+pub static ADDRESS_PENDING_DELEGATION: Lazy<Address> =
+    Lazy::new(|| Address::from_module(MODULE_NAME, "pending-delegation"));
+
 pub const CONSENSUS_TRANSFER_HANDLER: &str = "consensus.TransferFromRuntime";
 pub const CONSENSUS_WITHDRAW_HANDLER: &str = "consensus.WithdrawIntoRuntime";
+pub const CONSENSUS_DELEGATE_HANDLER: &str = "consensus.DelegateFromRuntime";
+pub const CONSENSUS_UNDELEGATE_HANDLER: &str = "consensus.UndelegateFromRuntime";
+
+/// State schema constants.
+pub mod state {
+    /// Map of runtime account addresses to map of consensus accounts to delegated shares.
+    pub const DELEGATIONS: &[u8] = &[0x01];
+    /// Set of undelegations waiting for debonding to end, keyed by the debonding end epoch, the
+    /// receiving runtime account and the consensus account the shares were reclaimed from.
+    pub const UNDELEGATIONS: &[u8] = &[0x02];
+}
+
+/// Key of the undelegation queue. Epochs are encoded in big-endian so that undelegations
+/// iterate in the order in which their debonding ends.
+#[derive(Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
+struct UndelegationKey(EpochTime, Address, Address);
+
+impl UndelegationKey {
+    fn encode(&self) -> Vec<u8> {
+        [&self.0.to_be_bytes()[..], self.1.as_ref(), self.2.as_ref()].concat()
+    }
+}
+
+impl TryFrom<&[u8]> for UndelegationKey {
+    type Error = crate::types::address::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.len() != 8 + 2 * Address::SIZE {
+            return Err(crate::types::address::Error::MalformedAddress);
+        }
+        let (epoch, addresses) = bytes.split_at(8);
+        let (to, from) = addresses.split_at(Address::SIZE);
+        let epoch = EpochTime::from_be_bytes(epoch.try_into().expect("length checked above"));
+        Ok(UndelegationKey(
+            epoch,
+            Address::from_bytes(to)?,
+            Address::from_bytes(from)?,
+        ))
+    }
+}
 
 impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API> API
     for Module<Accounts, Consensus>
@@ -253,6 +368,158 @@ impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API> API
             _ => Err(Error::InsufficientWithdrawBalance)
         }
     }
+
+    fn delegate<C: TxContext>(
+        ctx: &mut C,
+        from: Address,
+        nonce: u64,
+        to: Address,
+        amount: token::BaseUnits,
+    ) -> Result<(), Error> {
+        // Escrow from the runtime account and update the account state if successful.
+        Consensus::escrow(
+            ctx,
+            to,
+            &amount,
+            MessageEventHookInvocation::new(
+                CONSENSUS_DELEGATE_HANDLER.to_string(),
+                types::ConsensusDelegateContext {
+                    from,
+                    nonce,
+                    to,
+                    amount: amount.clone(),
+                },
+            ),
+        )?;
+
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        // Transfer the given amount to the module's delegation account to make sure the tokens
+        // remain available until actually delegated.
+        Accounts::transfer(ctx, from, *ADDRESS_PENDING_DELEGATION, &amount)
+            .map_err(|_| Error::InsufficientDelegateBalance)?;
+
+        Ok(())
+    }
+
+    fn undelegate<C: TxContext>(
+        ctx: &mut C,
+        from: Address,
+        nonce: u64,
+        to: Address,
+        shares: u128,
+    ) -> Result<(), Error> {
+        // Only shares previously delegated by the runtime account may be reclaimed.
+        let delegated = Self::get_delegation(ctx.runtime_state(), to, from);
+        let remaining = delegated
+            .checked_sub(shares)
+            .ok_or(Error::InsufficientDelegationShares)?;
+
+        // Reclaim the escrow and queue the tokens for the runtime account if successful.
+        Consensus::reclaim_escrow(
+            ctx,
+            from,
+            shares,
+            MessageEventHookInvocation::new(
+                CONSENSUS_UNDELEGATE_HANDLER.to_string(),
+                types::ConsensusUndelegateContext {
+                    from,
+                    nonce,
+                    to,
+                    shares,
+                },
+            ),
+        )?;
+
+        if ctx.is_check_only() {
+            return Ok(());
+        }
+
+        // Deduct the shares right away so that they can not be reclaimed twice while pending.
+        Self::set_delegation(ctx.runtime_state(), to, from, remaining);
+
+        Ok(())
+    }
+}
+
+impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API>
+    Module<Accounts, Consensus>
+{
+    /// Shares delegated by the runtime account to the consensus account.
+    pub fn get_delegation<S: storage::Store>(state: S, from: Address, to: Address) -> u128 {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let delegations = storage::PrefixStore::new(store, &state::DELEGATIONS);
+        let delegations = storage::TypedStore::new(storage::PrefixStore::new(delegations, &from));
+        delegations.get(to).unwrap_or_default()
+    }
+
+    fn set_delegation<S: storage::Store>(state: S, from: Address, to: Address, shares: u128) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let delegations = storage::PrefixStore::new(store, &state::DELEGATIONS);
+        let mut delegations =
+            storage::TypedStore::new(storage::PrefixStore::new(delegations, &from));
+        if shares == 0 {
+            delegations.remove(to);
+        } else {
+            delegations.insert(to, shares);
+        }
+    }
+
+    /// Queue reclaimed tokens to be credited to the runtime account once debonding ends.
+    fn queue_undelegation<S: storage::Store>(
+        state: S,
+        key: UndelegationKey,
+        amount: &token::BaseUnits,
+    ) {
+        let store = storage::PrefixStore::new(state, &MODULE_NAME);
+        let mut undelegations =
+            storage::TypedStore::new(storage::PrefixStore::new(store, &state::UNDELEGATIONS));
+        // Multiple undelegations of the same shares may end debonding in the same epoch.
+        let queued = undelegations
+            .get::<_, token::BaseUnits>(key.encode())
+            .map(|queued| queued.amount())
+            .unwrap_or_default();
+        undelegations.insert(
+            key.encode(),
+            token::BaseUnits::new(
+                queued.saturating_add(amount.amount()),
+                amount.denomination().clone(),
+            ),
+        );
+    }
+
+    /// Credit all undelegations whose debonding has ended by the given epoch.
+    fn process_undelegations<C: Context>(ctx: &mut C, epoch: EpochTime) {
+        let done: Vec<(UndelegationKey, token::BaseUnits)> = {
+            let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+            let undelegations =
+                storage::TypedStore::new(storage::PrefixStore::new(store, &state::UNDELEGATIONS));
+            undelegations
+                .iter()
+                .take_while(|(key, _): &(UndelegationKey, token::BaseUnits)| key.0 <= epoch)
+                .collect()
+        };
+
+        for (key, amount) in done {
+            {
+                let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+                let mut undelegations = storage::TypedStore::new(storage::PrefixStore::new(
+                    store,
+                    &state::UNDELEGATIONS,
+                ));
+                undelegations.remove(key.encode());
+            }
+
+            // The debonded tokens have been returned to the runtime account in the consensus
+            // layer, so they can now be minted in the runtime.
+            let UndelegationKey(_, to, from) = key;
+            Accounts::mint(ctx, to, &amount).expect("should be able to mint debonded tokens");
+
+            ctx.emit_event(Event::UndelegateDone { from, to, amount });
+        }
+    }
 }
 
 #[sdk_derive(MethodHandler)]
@@ -270,7 +537,12 @@ impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API>
 
         let address = signer.address_spec.address();
         let nonce = signer.nonce;
-        Self::deposit(ctx, address, nonce, body.to.unwrap_or(address), body.eth_to, body.amount)
+        let to = body.to.unwrap_or(address);
+        // Deposits to reserved addresses could never be spent.
+        if modules::accounts::Module::is_reserved_address(to) {
+            return Err(Error::InvalidArgument);
+        }
+        Self::deposit(ctx, address, nonce, to, body.eth_to, body.amount)
     }
 
     /// Withdraw from the runtime.
@@ -312,6 +584,49 @@ impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API>
         Self::withdraw(ctx, address, body.eth_from, nonce, body.to.unwrap_or(address), body.amount)
     }
 
+    /// Delegate from the runtime.
+    #[handler(prefetch = "consensus.Delegate")]
+    fn prefetch_delegate(
+        add_prefix: &mut dyn FnMut(Prefix),
+        _body: cbor::Value,
+        auth_info: &AuthInfo,
+    ) -> Result<(), error::RuntimeError> {
+        // Prefetch delegating account balance.
+        let addr = auth_info.signer_info[0].address_spec.address();
+        add_prefix(Prefix::from(
+            [
+                modules::accounts::Module::NAME.as_bytes(),
+                modules::accounts::state::BALANCES,
+                addr.as_ref(),
+            ]
+            .concat(),
+        ));
+        Ok(())
+    }
+
+    #[handler(call = "consensus.Delegate")]
+    fn tx_delegate<C: TxContext>(ctx: &mut C, body: types::Delegate) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_delegate)?;
+
+        let signer = &ctx.tx_auth_info().signer_info[0];
+        let address = signer.address_spec.address();
+        let nonce = signer.nonce;
+        Self::delegate(ctx, address, nonce, body.to, body.amount)
+    }
+
+    /// Undelegate into the runtime.
+    #[handler(call = "consensus.Undelegate")]
+    fn tx_undelegate<C: TxContext>(ctx: &mut C, body: types::Undelegate) -> Result<(), Error> {
+        let params = Self::params(ctx.runtime_state());
+        <C::Runtime as Runtime>::Core::use_tx_gas(ctx, params.gas_costs.tx_undelegate)?;
+
+        let signer = &ctx.tx_auth_info().signer_info[0];
+        let address = signer.address_spec.address();
+        let nonce = signer.nonce;
+        Self::undelegate(ctx, body.from, nonce, address, body.shares)
+    }
+
     #[handler(query = "consensus.Balance")]
     fn query_balance<C: Context>(
         ctx: &mut C,
@@ -328,6 +643,15 @@ impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API>
         Ok(types::AccountBalance { balance })
     }
 
+    #[handler(query = "consensus.Delegation")]
+    fn query_delegation<C: Context>(
+        ctx: &mut C,
+        args: types::DelegationQuery,
+    ) -> Result<types::DelegationInfo, Error> {
+        let shares = Self::get_delegation(ctx.runtime_state(), args.from, args.to);
+        Ok(types::DelegationInfo { shares })
+    }
+
     #[handler(query = "consensus.Account")]
     fn query_consensus_account<C: Context>(
         ctx: &mut C,
@@ -344,7 +668,9 @@ impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API>
     ) {
         if !me.is_success() {
             // Transfer out failed, refund the balance.
-            Accounts::transfer(
+            // Refunds must not fail due to the state of the account, e.g. when it was frozen while
+            // the operation was pending.
+            Accounts::transfer_unchecked(
                 ctx,
                 *ADDRESS_PENDING_WITHDRAWAL,
                 context.address,
@@ -411,6 +737,116 @@ impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API>
             error: None,
         });
     }
+
+    #[handler(message_result = CONSENSUS_DELEGATE_HANDLER)]
+    fn message_result_delegate<C: Context>(
+        ctx: &mut C,
+        me: MessageEvent,
+        context: types::ConsensusDelegateContext,
+    ) {
+        if !me.is_success() {
+            // Delegation failed, refund the balance.
+            // Refunds must not fail due to the state of the account, e.g. when it was frozen while
+            // the operation was pending.
+            Accounts::transfer_unchecked(
+                ctx,
+                *ADDRESS_PENDING_DELEGATION,
+                context.from,
+                &context.amount,
+            )
+            .expect("should have enough balance");
+
+            // Emit delegation failed event.
+            ctx.emit_event(Event::Delegate {
+                from: context.from,
+                nonce: context.nonce,
+                to: context.to,
+                amount: context.amount.clone(),
+                error: Some(me.into()),
+            });
+            return;
+        }
+
+        // Burn the delegated tokens as they are now escrowed in the consensus layer.
+        Accounts::burn(ctx, *ADDRESS_PENDING_DELEGATION, &context.amount)
+            .expect("should have enough balance");
+
+        // Record the shares received so that they can later be reclaimed.
+        let result: AddEscrowResult = me
+            .result
+            .and_then(|result| cbor::from_value(result).ok())
+            .expect("successful escrow should report its result");
+        let new_shares: u128 = result.new_shares.try_into().unwrap_or(u128::MAX);
+        let shares = Self::get_delegation(ctx.runtime_state(), context.from, context.to);
+        Self::set_delegation(
+            ctx.runtime_state(),
+            context.from,
+            context.to,
+            shares.saturating_add(new_shares),
+        );
+
+        // Emit delegation successful event.
+        ctx.emit_event(Event::Delegate {
+            from: context.from,
+            nonce: context.nonce,
+            to: context.to,
+            amount: context.amount.clone(),
+            error: None,
+        });
+    }
+
+    #[handler(message_result = CONSENSUS_UNDELEGATE_HANDLER)]
+    fn message_result_undelegate<C: Context>(
+        ctx: &mut C,
+        me: MessageEvent,
+        context: types::ConsensusUndelegateContext,
+    ) {
+        if !me.is_success() {
+            // Reclaiming failed, restore the delegated shares.
+            let shares = Self::get_delegation(ctx.runtime_state(), context.to, context.from);
+            Self::set_delegation(
+                ctx.runtime_state(),
+                context.to,
+                context.from,
+                shares.saturating_add(context.shares),
+            );
+
+            // Emit undelegation failed event.
+            ctx.emit_event(Event::UndelegateStart {
+                from: context.from,
+                nonce: context.nonce,
+                to: context.to,
+                shares: context.shares,
+                debond_end_time: Default::default(),
+                error: Some(me.into()),
+            });
+            return;
+        }
+
+        // Queue the debonding tokens, they are credited once they return to the runtime account.
+        let result: ReclaimEscrowResult = me
+            .result
+            .and_then(|result| cbor::from_value(result).ok())
+            .expect("successful reclaim should report its result");
+        let denomination = Consensus::consensus_denomination(ctx).unwrap();
+        let amount = result.amount.try_into().unwrap_or(u128::MAX);
+        let amount = Consensus::amount_from_consensus(ctx, amount).unwrap();
+        Self::queue_undelegation(
+            ctx.runtime_state(),
+            UndelegationKey(result.debond_end_time, context.to, context.from),
+            &token::BaseUnits::new(amount, denomination),
+        );
+
+        // Emit undelegation started event.
+        ctx.emit_event(Event::UndelegateStart {
+            from: context.from,
+            nonce: context.nonce,
+            to: context.to,
+            shares: context.shares,
+            debond_end_time: result.debond_end_time,
+            error: None,
+        });
+    }
 }
 
 impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API> module::Module
@@ -457,6 +893,10 @@ impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API>
 impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API> module::BlockHandler
     for Module<Accounts, Consensus>
 {
+    fn end_block<C: Context>(ctx: &mut C) {
+        let epoch = ctx.epoch();
+        Self::process_undelegations(ctx, epoch);
+    }
 }
 
 impl<Accounts: modules::accounts::API, Consensus: modules::consensus::API> module::InvariantHandler
@@ -13,15 +13,19 @@ use oasis_core_runtime::{
 use crate::{
     context::BatchContext,
     event::IntoTags,
-    module::{MethodHandler, MigrationHandler},
+    module::{BlockHandler, MethodHandler, MigrationHandler},
     modules::{
-        accounts::{Genesis as AccountsGenesis, Module as Accounts, API},
+        accounts::{
+            types::Account, Genesis as AccountsGenesis, Module as Accounts,
+            Parameters as AccountsParameters, API,
+        },
         consensus::{Error as ConsensusError, Module as Consensus},
         core::types::Metadata,
     },
     testing::{keys, mock},
     types::{
         address::SignatureAddressSpec,
+        role::Role,
         token::{BaseUnits, Denomination},
         transaction,
     },
@@ -441,7 +445,7 @@ fn test_api_withdraw_incompatible_signer() {
 }
 
 fn test_api_withdraw(signer_sigspec: SignatureAddressSpec) {
-    let signer_address = Address::from_sigspec(&signer_sigspec);
+    let signer_address = Address::from_sigspec(&signer_sigspec).unwrap();
 
     let denom: Denomination = Denomination::from_str("TEST").unwrap();
     let mut mock = mock::Mock::default();
@@ -837,6 +841,276 @@ fn test_consensus_withdraw_handler() {
     )
 }
 
+#[test]
+fn test_refund_frozen_account() {
+    let denom: Denomination = Denomination::from_str("TEST").unwrap();
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+    let mut meta = Metadata {
+        ..Default::default()
+    };
+
+    // Alice has been frozen while her withdrawal and delegation were pending and all accounts are
+    // subject to a transfer limit.
+    Accounts::init_or_migrate(
+        &mut ctx,
+        &mut meta,
+        AccountsGenesis {
+            parameters: AccountsParameters {
+                transfer_limits: {
+                    let mut transfer_limits = BTreeMap::new();
+                    transfer_limits.insert(Role::User, {
+                        let mut limits = BTreeMap::new();
+                        limits.insert(denom.clone(), 1);
+                        limits
+                    });
+                    transfer_limits
+                },
+                ..Default::default()
+            },
+            accounts: {
+                let mut accounts = BTreeMap::new();
+                accounts.insert(
+                    keys::alice::address(),
+                    Account {
+                        frozen: true,
+                        ..Default::default()
+                    },
+                );
+                accounts
+            },
+            balances: {
+                let mut balances = BTreeMap::new();
+                balances.insert(*ADDRESS_PENDING_WITHDRAWAL, {
+                    let mut denominations = BTreeMap::new();
+                    denominations.insert(denom.clone(), 1_000);
+                    denominations
+                });
+                balances.insert(*ADDRESS_PENDING_DELEGATION, {
+                    let mut denominations = BTreeMap::new();
+                    denominations.insert(denom.clone(), 1_000);
+                    denominations
+                });
+                balances
+            },
+            total_supplies: {
+                let mut total_supplies = BTreeMap::new();
+                total_supplies.insert(denom.clone(), 2_000);
+                total_supplies
+            },
+            ..Default::default()
+        },
+    );
+    Module::<Accounts, Consensus>::init_or_migrate(&mut ctx, &mut meta, Default::default());
+
+    // Refunds of failed operations must not be rejected by the user transfer checks.
+    let failed = || MessageEvent {
+        module: "staking".to_string(),
+        code: 1,
+        index: 0,
+        result: None,
+    };
+    Module::<Accounts, Consensus>::message_result_transfer(
+        &mut ctx,
+        failed(),
+        types::ConsensusTransferContext {
+            address: keys::alice::address(),
+            nonce: 0,
+            to: keys::bob::address(),
+            amount: BaseUnits::new(1_000, denom.clone()),
+            ..Default::default()
+        },
+    );
+    Module::<Accounts, Consensus>::message_result_delegate(
+        &mut ctx,
+        failed(),
+        types::ConsensusDelegateContext {
+            from: keys::alice::address(),
+            nonce: 0,
+            to: keys::bob::address(),
+            amount: BaseUnits::new(1_000, denom.clone()),
+        },
+    );
+
+    let balance =
+        Accounts::get_balance(ctx.runtime_state(), keys::alice::address(), denom.clone()).unwrap();
+    assert_eq!(balance, 2_000, "pending amounts should be refunded");
+}
+
+#[test]
+fn test_api_undelegate() {
+    let denom: Denomination = Denomination::from_str("TEST").unwrap();
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+    let mut meta = Metadata {
+        ..Default::default()
+    };
+
+    Accounts::init_or_migrate(
+        &mut ctx,
+        &mut meta,
+        AccountsGenesis {
+            balances: {
+                let mut balances = BTreeMap::new();
+                // Alice.
+                balances.insert(keys::alice::address(), {
+                    let mut denominations = BTreeMap::new();
+                    denominations.insert(denom.clone(), 1_000);
+                    denominations
+                });
+                balances
+            },
+            total_supplies: {
+                let mut total_supplies = BTreeMap::new();
+                total_supplies.insert(denom.clone(), 1_000);
+                total_supplies
+            },
+            ..Default::default()
+        },
+    );
+    Module::<Accounts, Consensus>::init_or_migrate(&mut ctx, &mut meta, Default::default());
+
+    let tx_for = |method: &str, body: cbor::Value| transaction::Transaction {
+        version: 1,
+        call: transaction::Call {
+            format: transaction::CallFormat::Plain,
+            method: method.to_owned(),
+            body,
+            ..Default::default()
+        },
+        auth_info: transaction::AuthInfo {
+            signer_info: vec![transaction::SignerInfo::new_sigspec(
+                keys::alice::sigspec(),
+                0,
+            )],
+            fee: transaction::Fee {
+                amount: Default::default(),
+                gas: 1000,
+                consensus_messages: 1,
+            },
+            ..Default::default()
+        },
+    };
+
+    // Delegate to Bob and simulate the escrow succeeding.
+    let tx = tx_for(
+        "consensus.Delegate",
+        cbor::to_value(types::Delegate {
+            to: keys::bob::address(),
+            amount: BaseUnits::new(1_000, denom.clone()),
+        }),
+    );
+    let hook = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Module::<Accounts, Consensus>::tx_delegate(
+            &mut tx_ctx,
+            cbor::from_value(call.body).unwrap(),
+        )
+        .expect("delegate tx should succeed");
+
+        let (_, mut msgs) = tx_ctx.commit();
+        let (_, hook) = msgs.pop().unwrap();
+        hook
+    });
+    Module::<Accounts, Consensus>::message_result_delegate(
+        &mut ctx,
+        MessageEvent {
+            result: Some(cbor::to_value(staking::AddEscrowResult {
+                new_shares: 500u128.into(),
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+        cbor::from_value(hook.payload).unwrap(),
+    );
+    let shares = Module::<Accounts, Consensus>::get_delegation(
+        ctx.runtime_state(),
+        keys::alice::address(),
+        keys::bob::address(),
+    );
+    assert_eq!(shares, 500, "received shares should be recorded");
+
+    // Only delegated shares can be reclaimed.
+    let tx = tx_for(
+        "consensus.Undelegate",
+        cbor::to_value(types::Undelegate {
+            from: keys::bob::address(),
+            shares: 501,
+        }),
+    );
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        let result = Module::<Accounts, Consensus>::tx_undelegate(
+            &mut tx_ctx,
+            cbor::from_value(call.body).unwrap(),
+        );
+        assert!(matches!(result, Err(Error::InsufficientDelegationShares)));
+    });
+
+    let tx = tx_for(
+        "consensus.Undelegate",
+        cbor::to_value(types::Undelegate {
+            from: keys::bob::address(),
+            shares: 500,
+        }),
+    );
+    let hook = ctx.with_tx(0, 0, tx, |mut tx_ctx, call| {
+        Module::<Accounts, Consensus>::tx_undelegate(
+            &mut tx_ctx,
+            cbor::from_value(call.body).unwrap(),
+        )
+        .expect("undelegate tx should succeed");
+
+        let (_, mut msgs) = tx_ctx.commit();
+        assert_eq!(1, msgs.len(), "one message should be emitted");
+        let (msg, hook) = msgs.pop().unwrap();
+
+        assert_eq!(
+            Message::Staking(Versioned::new(
+                0,
+                StakingMessage::ReclaimEscrow(staking::ReclaimEscrow {
+                    account: keys::bob::address().into(),
+                    shares: 500u128.into(),
+                })
+            )),
+            msg,
+            "emitted message should match"
+        );
+
+        hook
+    });
+    let shares = Module::<Accounts, Consensus>::get_delegation(
+        ctx.runtime_state(),
+        keys::alice::address(),
+        keys::bob::address(),
+    );
+    assert_eq!(shares, 0, "reclaimed shares should be deducted");
+
+    // Simulate the reclaim succeeding, the tokens are only credited once debonding ends.
+    Module::<Accounts, Consensus>::message_result_undelegate(
+        &mut ctx,
+        MessageEvent {
+            result: Some(cbor::to_value(staking::ReclaimEscrowResult {
+                amount: 1_000u128.into(),
+                debond_end_time: 2,
+                ..Default::default()
+            })),
+            ..Default::default()
+        },
+        cbor::from_value(hook.payload).unwrap(),
+    );
+    Module::<Accounts, Consensus>::end_block(&mut ctx);
+    let balance =
+        Accounts::get_balance(ctx.runtime_state(), keys::alice::address(), denom.clone()).unwrap();
+    assert_eq!(balance, 0, "debonding tokens should not be credited yet");
+
+    drop(ctx);
+    mock.epoch = 2;
+    let mut ctx = mock.create_ctx();
+    Module::<Accounts, Consensus>::end_block(&mut ctx);
+    let balance =
+        Accounts::get_balance(ctx.runtime_state(), keys::alice::address(), denom.clone()).unwrap();
+    assert_eq!(balance, 1_000, "debonded tokens should be credited");
+}
+
 #[test]
 fn test_prefetch() {
     let mut mock = mock::Mock::default();
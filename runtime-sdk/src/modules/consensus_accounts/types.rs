@@ -24,6 +24,24 @@ pub struct Withdraw {
     pub amount: token::BaseUnits,
 }
 
+/// Delegate from runtime call.
+/// Escrow tokens from an account in this runtime to a consensus staking account.
+/// The `to` address consensus staking account receives the delegation.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct Delegate {
+    pub to: Address,
+    pub amount: token::BaseUnits,
+}
+
+/// Undelegate into runtime call.
+/// Reclaim shares delegated from this runtime to a consensus staking account.
+/// The tokens are credited to the transaction signer once debonding ends.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct Undelegate {
+    pub from: Address,
+    pub shares: u128,
+}
+
 /// Balance query.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
 pub struct BalanceQuery {
@@ -41,6 +59,18 @@ pub struct AccountBalance {
     pub balance: u128,
 }
 
+/// Delegation query.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct DelegationQuery {
+    pub from: Address,
+    pub to: Address,
+}
+
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct DelegationInfo {
+    pub shares: u128,
+}
+
 /// Context for consensus transfer message handler.
 #[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
 pub struct ConsensusTransferContext {
@@ -65,6 +95,24 @@ pub struct ConsensusWithdrawContext {
     pub amount: token::BaseUnits,
 }
 
+/// Context for consensus delegate message handler.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct ConsensusDelegateContext {
+    pub from: Address,
+    pub nonce: u64,
+    pub to: Address,
+    pub amount: token::BaseUnits,
+}
+
+/// Context for consensus undelegate message handler.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct ConsensusUndelegateContext {
+    pub from: Address,
+    pub nonce: u64,
+    pub to: Address,
+    pub shares: u128,
+}
+
 /// Error details from the consensus layer.
 #[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
 pub struct ConsensusError {
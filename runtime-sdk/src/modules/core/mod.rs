@@ -14,12 +14,13 @@ use crate::{
     context::{BatchContext, Context, TxContext},
     dispatcher,
     error::Error as SDKError,
-    keymanager,
+    keymanager, metrics,
     module::{
         self, CallResult, InvariantHandler as _, MethodHandler as _, Module as _,
-        ModuleInfoHandler as _,
+        ModuleInfoHandler as _, Parameters as _,
     },
     sender::SenderMeta,
+    storage::{self, Store as _},
     types::{
         token,
         transaction::{self, AddressSpec, AuthProof, Call, CallFormat, UnverifiedTransaction},
@@ -51,9 +52,9 @@ pub enum Error {
     #[sdk_error(code = 3)]
     InvalidMethod(String),
 
-    #[error("invalid nonce")]
+    #[error("invalid nonce (expected: {0} provided: {1})")]
     #[sdk_error(code = 4)]
-    InvalidNonce,
+    InvalidNonce(u64, u64),
 
     #[error("insufficient balance to pay fees")]
     #[sdk_error(code = 5)]
@@ -131,9 +132,17 @@ pub enum Error {
     #[sdk_error(code = 25)]
     ReadOnlyTransaction,
 
-    #[error("future nonce")]
+    #[error("future nonce (expected: {0} provided: {1})")]
     #[sdk_error(code = 26)]
-    FutureNonce,
+    FutureNonce(u64, u64),
+
+    #[error("events too large: {0} bytes exceeds the limit of {1} bytes")]
+    #[sdk_error(code = 27)]
+    EventsTooLarge(u64, u64),
+
+    #[error("invalid activation round: {0}")]
+    #[sdk_error(code = 28)]
+    InvalidActivationRound(u64),
 
     #[error("{0}")]
     #[sdk_error(transparent)]
@@ -209,10 +218,13 @@ impl TryFrom<CallResult> for TxSimulationFailure {
 pub enum Event {
     #[sdk_event(code = 1)]
     GasUsed { amount: u64 },
+
+    #[sdk_event(code = 2)]
+    ParametersActivated { round: u64 },
 }
 
 /// Gas costs.
-#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
 pub struct GasCosts {
     pub tx_byte: u64,
 
@@ -220,10 +232,14 @@ pub struct GasCosts {
     pub auth_multisig_signer: u64,
 
     pub callformat_x25519_deoxysii: u64,
+
+    /// Cost of each byte of events emitted by a transaction.
+    #[cbor(optional)]
+    pub event_byte: u64,
 }
 
 /// Parameters for the core module.
-#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, cbor::Encode, cbor::Decode)]
 pub struct Parameters {
     pub max_batch_gas: u64,
     pub max_tx_size: u32,
@@ -231,6 +247,10 @@ pub struct Parameters {
     pub max_multisig_signers: u32,
     pub gas_costs: GasCosts,
     pub min_gas_price: BTreeMap<token::Denomination, u128>,
+    /// Maximum total size in bytes of events emitted by a transaction. A value of `0` indicates
+    /// no limit.
+    #[cbor(optional)]
+    pub max_tx_events_size: u64,
 }
 
 impl module::Parameters for Parameters {
@@ -281,6 +301,19 @@ pub trait API {
     /// Returns the configured max iterations in the binary search for the estimate
     /// gas.
     fn estimate_gas_search_max_iters<C: Context>(ctx: &C) -> u64;
+
+    /// Charge gas for the events emitted by the transaction since the last call and enforce the
+    /// per-transaction event size limit. Returns the amount of gas charged.
+    fn charge_event_gas<C: TxContext>(ctx: &mut C) -> Result<u64, Error>;
+
+    /// Schedule the given parameters to become active at the start of the given round, replacing
+    /// any parameters already scheduled for that round. The round must be in the future and the
+    /// parameters must be valid.
+    fn schedule_params<C: Context>(
+        ctx: &mut C,
+        round: u64,
+        params: Parameters,
+    ) -> Result<(), Error>;
 }
 
 /// Genesis state for the accounts module.
@@ -318,6 +351,8 @@ pub mod state {
     pub const METADATA: &[u8] = &[0x01];
     /// Map of message idx to message handlers for messages emitted in previous round.
     pub const MESSAGE_HANDLERS: &[u8] = &[0x02];
+    /// Map of activation rounds to parameters scheduled to become active at that round.
+    pub const SCHEDULED_PARAMETERS: &[u8] = &[0x03];
 }
 
 /// Module configuration.
@@ -355,6 +390,7 @@ pub struct Module<Cfg: Config> {
 const CONTEXT_KEY_GAS_USED: &str = "core.GasUsed";
 const CONTEXT_KEY_PRIORITY: &str = "core.Priority";
 const CONTEXT_KEY_SENDER_META: &str = "core.SenderMeta";
+const CONTEXT_KEY_EVENTS_SIZE_CHARGED: &str = "core.EventsSizeCharged";
 
 impl<Cfg: Config> Module<Cfg> {
     /// Initialize state from genesis.
@@ -476,6 +512,48 @@ impl<Cfg: Config> API for Module<Cfg> {
             .map(|cfg: &LocalConfig| cfg.estimate_gas_search_max_iters)
             .unwrap_or(Cfg::DEFAULT_LOCAL_ESTIMATE_GAS_SEARCH_MAX_ITERS)
     }
+
+    fn charge_event_gas<C: TxContext>(ctx: &mut C) -> Result<u64, Error> {
+        let params = Self::params(ctx.runtime_state());
+        let size = ctx.emitted_events_size();
+        if params.max_tx_events_size > 0 && size > params.max_tx_events_size {
+            return Err(Error::EventsTooLarge(size, params.max_tx_events_size));
+        }
+
+        // Only charge for events emitted since the last call.
+        let charged = *ctx.tx_value::<u64>(CONTEXT_KEY_EVENTS_SIZE_CHARGED).or_default();
+        let gas = params
+            .gas_costs
+            .event_byte
+            .checked_mul(size.saturating_sub(charged))
+            .ok_or(Error::GasOverflow)?;
+        Self::use_tx_gas(ctx, gas)?;
+        ctx.tx_value::<u64>(CONTEXT_KEY_EVENTS_SIZE_CHARGED).set(size.max(charged));
+
+        Ok(gas)
+    }
+
+    fn schedule_params<C: Context>(
+        ctx: &mut C,
+        round: u64,
+        params: Parameters,
+    ) -> Result<(), Error> {
+        if round <= ctx.runtime_header().round {
+            return Err(Error::InvalidActivationRound(round));
+        }
+        params
+            .validate_basic()
+            .map_err(|err| Error::InvalidArgument(err.into()))?;
+
+        let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+        let mut scheduled = storage::TypedStore::new(storage::PrefixStore::new(
+            store,
+            &state::SCHEDULED_PARAMETERS,
+        ));
+        scheduled.insert(round.to_be_bytes(), params);
+
+        Ok(())
+    }
 }
 
 #[sdk_derive(MethodHandler)]
@@ -732,6 +810,15 @@ impl<Cfg: Config> Module<Cfg> {
         Ok(mgp)
     }
 
+    /// Return parameters that are scheduled to become active in future rounds.
+    #[handler(query = "core.ScheduledParameters")]
+    fn query_scheduled_parameters<C: Context>(
+        ctx: &mut C,
+        _args: (),
+    ) -> Result<Vec<types::ScheduledParameters>, Error> {
+        Ok(Self::scheduled_params(ctx))
+    }
+
     /// Return basic information about the module and the containing runtime.
     #[handler(query = "core.RuntimeInfo")]
     fn query_runtime_info<C: Context>(
@@ -745,6 +832,12 @@ impl<Cfg: Config> Module<Cfg> {
         })
     }
 
+    /// Return the metrics collected by the local runtime instance.
+    #[handler(query = "core.Metrics")]
+    fn query_metrics<C: Context>(_ctx: &mut C, _args: ()) -> Result<metrics::Metrics, Error> {
+        Ok(metrics::snapshot())
+    }
+
     /// Execute a read-only transaction in an interactive mode.
     ///
     /// # Warning
@@ -806,6 +899,20 @@ impl<Cfg: Config> Module<Cfg> {
 }
 
 impl<Cfg: Config> Module<Cfg> {
+    /// Parameters scheduled to become active, ordered by activation round.
+    fn scheduled_params<C: Context>(ctx: &mut C) -> Vec<types::ScheduledParameters> {
+        let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+        let scheduled = storage::PrefixStore::new(store, &state::SCHEDULED_PARAMETERS);
+        scheduled
+            .iter()
+            .filter_map(|(key, value)| {
+                let round = u64::from_be_bytes(key.try_into().ok()?);
+                let parameters = cbor::from_slice(&value).ok()?;
+                Some(types::ScheduledParameters { round, parameters })
+            })
+            .collect()
+    }
+
     fn get_local_min_gas_price<C: Context>(ctx: &mut C, denom: &token::Denomination) -> u128 {
         #[allow(clippy::borrow_interior_mutable_const)]
         ctx.local_config(MODULE_NAME)
@@ -960,6 +1067,12 @@ impl<Cfg: Config> module::TransactionHandler for Module<Cfg> {
     }
 
     fn after_handle_call<C: TxContext>(ctx: &mut C) -> Result<(), Error> {
+        // Charge gas for emitted events. Internally generated transactions are charged as part of
+        // the transaction that generated them.
+        if !ctx.is_internal() {
+            Self::charge_event_gas(ctx)?;
+        }
+
         // Emit gas used event.
         if Cfg::EMIT_GAS_USED_EVENTS {
             let used_gas = Self::used_tx_gas(ctx);
@@ -991,5 +1104,36 @@ impl<Cfg: Config> module::MigrationHandler for Module<Cfg> {
     }
 }
 
-impl<Cfg: Config> module::BlockHandler for Module<Cfg> {}
+impl<Cfg: Config> module::BlockHandler for Module<Cfg> {
+    fn begin_block<C: Context>(ctx: &mut C) {
+        // Activate any scheduled parameters whose activation round has been reached. Keys are
+        // big-endian rounds so parameters are applied in round order.
+        let round = ctx.runtime_header().round;
+        let due: Vec<(u64, Vec<u8>)> = {
+            let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+            let scheduled = storage::PrefixStore::new(store, &state::SCHEDULED_PARAMETERS);
+            scheduled
+                .iter()
+                .filter_map(|(key, value)| Some((u64::from_be_bytes(key.try_into().ok()?), value)))
+                .take_while(|(scheduled_round, _)| *scheduled_round <= round)
+                .collect()
+        };
+        for (scheduled_round, value) in due {
+            let store = storage::PrefixStore::new(ctx.runtime_state(), &MODULE_NAME);
+            let mut store = storage::PrefixStore::new(store, &state::SCHEDULED_PARAMETERS);
+            store.remove(&scheduled_round.to_be_bytes());
+
+            // Entries that can't be decoded are dropped instead of being retried forever.
+            let parameters: Parameters = match cbor::from_slice(&value) {
+                Ok(parameters) => parameters,
+                Err(_) => continue,
+            };
+            Self::set_params(ctx.runtime_state(), parameters);
+
+            ctx.emit_event(Event::ParametersActivated {
+                round: scheduled_round,
+            });
+        }
+    }
+}
 impl<Cfg: Config> module::InvariantHandler for Module<Cfg> {}
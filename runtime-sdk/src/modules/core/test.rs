@@ -38,6 +38,7 @@ fn test_use_gas() {
                 mgp.insert(token::Denomination::NATIVE, 0);
                 mgp
             },
+            max_tx_events_size: 0,
         },
     );
 
@@ -138,6 +139,7 @@ fn test_query_min_gas_price() {
                 mgp.insert("SMALLER".parse().unwrap(), 1000);
                 mgp
             },
+            max_tx_events_size: 0,
         },
     );
 
@@ -291,6 +293,7 @@ impl Runtime for GasWasterRuntime {
     const VERSION: Version = Version::new(0, 0, 0);
 
     type Core = Core;
+    type TransferHooks = ();
 
     type Modules = (Core, GasWasterModule);
 
@@ -307,12 +310,14 @@ impl Runtime for GasWasterRuntime {
                         auth_signature: Self::AUTH_SIGNATURE_GAS,
                         auth_multisig_signer: Self::AUTH_MULTISIG_GAS,
                         callformat_x25519_deoxysii: 0,
+                        event_byte: 0,
                     },
                     min_gas_price: {
                         let mut mgp = BTreeMap::new();
                         mgp.insert(token::Denomination::NATIVE, 0);
                         mgp
                     },
+                    max_tx_events_size: 0,
                 },
             },
             (),
@@ -669,6 +674,7 @@ fn test_approve_unverified_tx() {
                 mgp.insert(token::Denomination::NATIVE, 0);
                 mgp
             },
+            max_tx_events_size: 0,
         },
     );
     let dummy_bytes = b"you look, you die".to_vec();
@@ -786,6 +792,7 @@ fn test_min_gas_price() {
                 auth_signature: GasWasterRuntime::AUTH_SIGNATURE_GAS,
                 auth_multisig_signer: GasWasterRuntime::AUTH_MULTISIG_GAS,
                 callformat_x25519_deoxysii: 0,
+                event_byte: 0,
             },
             min_gas_price: {
                 let mut mgp = BTreeMap::new();
@@ -793,6 +800,7 @@ fn test_min_gas_price() {
                 mgp.insert("SMALLER".parse().unwrap(), 100);
                 mgp
             },
+            max_tx_events_size: 0,
         },
     );
 
@@ -968,6 +976,7 @@ fn test_gas_used_events() {
                 mgp.insert(token::Denomination::NATIVE, 0);
                 mgp
             },
+            max_tx_events_size: 0,
         },
     );
 
@@ -999,6 +1008,123 @@ fn test_gas_used_events() {
     assert_eq!(tags[0].value, expected, "expected events emitted");
 }
 
+#[test]
+fn test_event_gas() {
+    let mut mock = mock::Mock::default();
+    let mut ctx = mock.create_ctx();
+    Core::set_params(
+        ctx.runtime_state(),
+        Parameters {
+            max_batch_gas: 1_000_000,
+            gas_costs: super::GasCosts {
+                event_byte: 10,
+                ..Default::default()
+            },
+            max_tx_events_size: 100,
+            ..Default::default()
+        },
+    );
+
+    let mut tx = mock::transaction();
+    tx.auth_info.fee.gas = 100_000;
+
+    ctx.with_tx(0, 0, tx, |mut tx_ctx, _call| {
+        tx_ctx.emit_event(Event::GasUsed { amount: 1 });
+        let size = tx_ctx.emitted_events_size();
+        assert!(size > 0, "emitted events should have a size");
+
+        let gas = Core::charge_event_gas(&mut tx_ctx).expect("charging events should succeed");
+        assert_eq!(gas, 10 * size);
+        assert_eq!(Core::used_tx_gas(&mut tx_ctx), gas);
+
+        // Events should only be charged once.
+        assert_eq!(Core::charge_event_gas(&mut tx_ctx).unwrap(), 0);
+
+        for i in 0..100 {
+            tx_ctx.emit_event(Event::GasUsed { amount: i });
+        }
+        assert!(matches!(
+            Core::charge_event_gas(&mut tx_ctx),
+            Err(super::Error::EventsTooLarge(..))
+        ));
+    });
+}
+
+#[test]
+fn test_scheduled_params() {
+    let mut mock = mock::Mock::default();
+    mock.runtime_header.round = 10;
+
+    let params = |max_batch_gas| Parameters {
+        max_batch_gas,
+        ..Default::default()
+    };
+
+    {
+        let mut ctx = mock.create_ctx();
+        Core::set_params(ctx.runtime_state(), params(1_000));
+
+        assert!(matches!(
+            Core::schedule_params(&mut ctx, 10, params(2_000)),
+            Err(super::Error::InvalidActivationRound(10))
+        ));
+        Core::schedule_params(&mut ctx, 11, params(2_000)).expect("scheduling should succeed");
+        Core::schedule_params(&mut ctx, 12, params(3_000)).expect("scheduling should succeed");
+
+        let scheduled = Core::query_scheduled_parameters(&mut ctx, ()).unwrap();
+        assert_eq!(scheduled.len(), 2);
+        assert_eq!(scheduled[0].round, 11);
+        assert_eq!(scheduled[1].round, 12);
+
+        // Nothing should be activated before the activation round.
+        <Core as module::BlockHandler>::begin_block(&mut ctx);
+        assert_eq!(Core::params(ctx.runtime_state()), params(1_000));
+    }
+
+    mock.runtime_header.round = 11;
+    {
+        let mut ctx = mock.create_ctx();
+        <Core as module::BlockHandler>::begin_block(&mut ctx);
+        assert_eq!(Core::params(ctx.runtime_state()), params(2_000));
+
+        let scheduled = Core::query_scheduled_parameters(&mut ctx, ()).unwrap();
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!(scheduled[0].round, 12);
+
+        let (etags, _) = ctx.commit();
+        let tags = etags.into_tags();
+        assert_eq!(tags.len(), 1, "1 emitted tag expected");
+
+        let expected = cbor::to_vec(vec![Event::ParametersActivated { round: 11 }]);
+        assert_eq!(tags[0].value, expected, "expected events emitted");
+    }
+
+    // Entries that can't be decoded are dropped on activation.
+    {
+        use crate::storage::{self, Store as _};
+
+        let mut ctx = mock.create_ctx();
+        let store = storage::PrefixStore::new(ctx.runtime_state(), &super::MODULE_NAME);
+        let mut store = storage::PrefixStore::new(store, &super::state::SCHEDULED_PARAMETERS);
+        store.insert(&13u64.to_be_bytes(), b"invalid");
+    }
+    mock.runtime_header.round = 13;
+    {
+        let mut ctx = mock.create_ctx();
+        <Core as module::BlockHandler>::begin_block(&mut ctx);
+        assert_eq!(Core::params(ctx.runtime_state()), params(3_000));
+        assert!(Core::query_scheduled_parameters(&mut ctx, ())
+            .unwrap()
+            .is_empty());
+
+        let (etags, _) = ctx.commit();
+        let tags = etags.into_tags();
+        assert_eq!(tags.len(), 1, "1 emitted tag expected");
+        let expected = cbor::to_vec(vec![Event::ParametersActivated { round: 12 }]);
+        assert_eq!(tags[0].value, expected, "expected events emitted");
+    }
+}
+
 /// Constructs a BTreeMap using a `btreemap! { key => value, ... }` syntax.
 macro_rules! btreemap {
     // allow trailing comma
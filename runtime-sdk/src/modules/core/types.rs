@@ -15,6 +15,15 @@ pub struct Metadata {
     pub versions: BTreeMap<String, u32>,
 }
 
+/// Parameters scheduled to become active at a future round.
+#[derive(Clone, Debug, Default, cbor::Encode, cbor::Decode)]
+pub struct ScheduledParameters {
+    /// Round at the start of which the parameters become active.
+    pub round: u64,
+    /// Parameters to activate.
+    pub parameters: super::Parameters,
+}
+
 /// Arguments for the EstimateGas query.
 #[derive(Clone, Debug, cbor::Encode, cbor::Decode)]
 #[cbor(no_default)]
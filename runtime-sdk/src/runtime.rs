@@ -19,7 +19,7 @@ use crate::{
         BlockHandler, InvariantHandler, MethodHandler, MigrationHandler, ModuleInfoHandler,
         TransactionHandler,
     },
-    modules, storage,
+    modules, storage, types,
 };
 
 /// A runtime.
@@ -35,9 +35,16 @@ pub trait Runtime {
     /// Runtime schedule control configuration.
     const SCHEDULE_CONTROL: config::ScheduleControl = config::ScheduleControl::default();
 
+    /// Human readable part used for Bech32-encoded addresses.
+    const ADDRESS_BECH32_HRP: &'static str = types::address::ADDRESS_BECH32_HRP;
+
     /// Module that provides the core API.
     type Core: modules::core::API;
 
+    /// Hooks invoked by the accounts module around every transfer, mint and burn. Use `()` in
+    /// case no hooks are needed.
+    type TransferHooks: modules::accounts::TransferHook;
+
     /// Supported modules.
     type Modules: TransactionHandler
         + MigrationHandler
@@ -150,6 +157,8 @@ pub trait Runtime {
                 hi.runtime_id,
                 &hi.consensus_chain_context,
             );
+            // Configure the human readable part of Bech32-encoded addresses.
+            types::address::set_bech32_hrp(Self::ADDRESS_BECH32_HRP);
 
             // Cobble together a keymanager client.
             let key_manager = Self::trusted_policy_signers().map(|signers| {
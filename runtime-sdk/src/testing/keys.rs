@@ -39,7 +39,7 @@ macro_rules! test_key_ed25519 {
             #[doc=$doc]
             #[doc = "."]
             pub fn address() -> Address {
-                Address::from_sigspec(&sigspec())
+                Address::from_sigspec(&sigspec()).unwrap()
             }
         }
     };
@@ -82,7 +82,7 @@ macro_rules! test_key_secp256k1 {
             #[doc=$doc]
             #[doc = "."]
             pub fn address() -> Address {
-                Address::from_sigspec(&sigspec())
+                Address::from_sigspec(&sigspec()).unwrap()
             }
         }
     };
@@ -125,7 +125,7 @@ macro_rules! test_key_sr25519 {
             #[doc=$doc]
             #[doc = "."]
             pub fn address() -> Address {
-                Address::from_sigspec(&sigspec())
+                Address::from_sigspec(&sigspec()).unwrap()
             }
         }
     };
@@ -32,6 +32,7 @@ impl Runtime for EmptyRuntime {
     const VERSION: Version = Version::new(0, 0, 0);
 
     type Core = modules::core::Module<Config>;
+    type TransferHooks = ();
 
     type Modules = modules::core::Module<Config>;
 
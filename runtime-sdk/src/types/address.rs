@@ -1,8 +1,9 @@
 //! Account address type.
-use std::{convert::TryFrom, fmt};
+use std::{convert::TryFrom, fmt, sync::Mutex};
 
 use bech32::{self, FromBase32, ToBase32, Variant};
 use digest::Digest;
+use once_cell::sync::Lazy;
 use thiserror::Error;
 
 use oasis_core_runtime::{
@@ -39,11 +40,38 @@ pub const ADDRESS_RUNTIME_V0_VERSION: u8 = 0;
 /// V0 multisig address context.
 pub const ADDRESS_V0_MULTISIG_CONTEXT: &[u8] = b"oasis-runtime-sdk/address: multisig";
 
-/// Human readable part for Bech32-encoded addresses.
+/// Default human readable part for Bech32-encoded addresses.
 // MZ, change it to hela
 pub const ADDRESS_BECH32_HRP: &str = "hela0";
 // pub const ADDRESS_BECH32_HRP: &str = "oasis";
 
+static BECH32_HRP: Lazy<Mutex<Option<&'static str>>> = Lazy::new(Default::default);
+
+/// Return the globally configured human readable part for Bech32-encoded addresses.
+///
+/// In case no human readable part has been configured using `set_bech32_hrp`, the default
+/// `ADDRESS_BECH32_HRP` is returned.
+pub fn bech32_hrp() -> &'static str {
+    BECH32_HRP.lock().unwrap().unwrap_or(ADDRESS_BECH32_HRP)
+}
+
+/// Configure the global human readable part for Bech32-encoded addresses.
+///
+/// # Panics
+///
+/// This function will panic in case a different human readable part was already set.
+///
+pub fn set_bech32_hrp(hrp: &'static str) {
+    let mut guard = BECH32_HRP.lock().unwrap();
+    match *guard {
+        Some(existing) if existing != hrp => {
+            drop(guard); // Avoid poisioning the global lock.
+            panic!("bech32 human readable part already set: {existing}");
+        }
+        _ => *guard = Some(hrp),
+    }
+}
+
 /// Information for signature-based authentication and public key-based address derivation.
 #[derive(Clone, Debug, PartialEq, Eq, cbor::Encode, cbor::Decode)]
 pub enum SignatureAddressSpec {
@@ -150,8 +178,10 @@ impl Address {
     }
 
     /// Creates a new address from a public key.
-    pub fn from_sigspec(spec: &SignatureAddressSpec) -> Self {
-        match spec {
+    ///
+    /// Fails in case a Secp256k1 public key is not a valid curve point.
+    pub fn from_sigspec(spec: &SignatureAddressSpec) -> Result<Self, Error> {
+        Ok(match spec {
             SignatureAddressSpec::Ed25519(pk) => Address::new(
                 ADDRESS_V0_ED25519_CONTEXT,
                 ADDRESS_V0_VERSION,
@@ -162,17 +192,20 @@ impl Address {
                 ADDRESS_V0_VERSION,
                 // Use a scheme such that we can compute Secp256k1 addresses from Ethereum
                 // addresses as this makes things more interoperable.
-                &sha3::Keccak256::digest(pk.to_uncompressed_untagged_bytes())[32 - 20..],
+                &sha3::Keccak256::digest(
+                    pk.try_to_uncompressed_untagged_bytes()
+                        .map_err(|_| Error::MalformedAddress)?,
+                )[32 - 20..],
             ),
             SignatureAddressSpec::Sr25519(pk) => Address::new(
                 ADDRESS_V0_SR25519_CONTEXT,
                 ADDRESS_V0_VERSION,
                 pk.as_bytes(),
             ),
-        }
+        })
     }
-    pub fn eth_from_sigspec(spec: &SignatureAddressSpec) -> Self {
-        match spec {
+    pub fn eth_from_sigspec(spec: &SignatureAddressSpec) -> Result<Self, Error> {
+        Ok(match spec {
             SignatureAddressSpec::Ed25519(pk) => Address::new(
                 ADDRESS_V0_ED25519_CONTEXT,
                 ADDRESS_V0_VERSION,
@@ -183,14 +216,17 @@ impl Address {
                 ADDRESS_V0_VERSION,
                 // Use a scheme such that we can compute Secp256k1 addresses from Ethereum
                 // addresses as this makes things more interoperable.
-                &sha3::Keccak256::digest(&pk.to_uncompressed_untagged_bytes())[32 - 20..],
+                &sha3::Keccak256::digest(
+                    pk.try_to_uncompressed_untagged_bytes()
+                        .map_err(|_| Error::MalformedAddress)?,
+                )[32 - 20..],
             ),
             SignatureAddressSpec::Sr25519(pk) => Address::new(
                 ADDRESS_V0_SR25519_CONTEXT,
                 ADDRESS_V0_VERSION,
                 pk.as_bytes(),
             ),
-        }
+        })
     }
 
 
@@ -202,8 +238,13 @@ impl Address {
 
     /// Tries to create a new address from Bech32-encoded string.
     pub fn from_bech32(data: &str) -> Result<Self, Error> {
+        Self::from_bech32_with_hrp(data, bech32_hrp())
+    }
+
+    /// Tries to create a new address from Bech32-encoded string with the given human readable part.
+    pub fn from_bech32_with_hrp(data: &str, expected_hrp: &str) -> Result<Self, Error> {
         let (hrp, data, variant) = bech32::decode(data).map_err(|_| Error::MalformedAddress)?;
-        if hrp != ADDRESS_BECH32_HRP {
+        if hrp != expected_hrp {
             return Err(Error::MalformedAddress);
         }
         if variant != Variant::Bech32 {
@@ -216,7 +257,12 @@ impl Address {
 
     /// Converts an address to Bech32 representation.
     pub fn to_bech32(self) -> String {
-        bech32::encode(ADDRESS_BECH32_HRP, self.0.to_base32(), Variant::Bech32).unwrap()
+        self.to_bech32_with_hrp(bech32_hrp())
+    }
+
+    /// Converts an address to Bech32 representation with the given human readable part.
+    pub fn to_bech32_with_hrp(self, hrp: &str) -> String {
+        bech32::encode(hrp, self.0.to_base32(), Variant::Bech32).unwrap()
     }
 }
 
@@ -300,7 +346,7 @@ mod test {
         let spec =
             SignatureAddressSpec::Ed25519("utrdHlX///////////////////////////////////8=".into());
 
-        let addr = Address::from_sigspec(&spec);
+        let addr = Address::from_sigspec(&spec).unwrap();
         assert_eq!(
             addr.to_bech32(),
             "oasis1qryqqccycvckcxp453tflalujvlf78xymcdqw4vz"
@@ -313,13 +359,30 @@ mod test {
             "Arra3R5V////////////////////////////////////".into(),
         );
 
-        let addr = Address::from_sigspec(&spec);
+        let addr = Address::from_sigspec(&spec).unwrap();
         assert_eq!(
             addr.to_bech32(),
             "oasis1qzd7akz24n6fxfhdhtk977s5857h3c6gf5583mcg"
         );
     }
 
+    #[test]
+    fn test_address_secp256k1eth_invalid() {
+        // The x coordinate is not a field element, so this is not a valid curve point.
+        let pk = secp256k1::PublicKey::from_bytes(&[[0x02].as_slice(), &[0xff; 32]].concat())
+            .expect("encoding should be valid");
+        let spec = SignatureAddressSpec::Secp256k1Eth(pk);
+
+        assert!(matches!(
+            Address::from_sigspec(&spec),
+            Err(Error::MalformedAddress)
+        ));
+        assert!(matches!(
+            Address::eth_from_sigspec(&spec),
+            Err(Error::MalformedAddress)
+        ));
+    }
+
     #[test]
     fn test_address_multisig() {
         let config = multisig::Config {
@@ -363,6 +426,22 @@ mod test {
         ));
     }
 
+    #[test]
+    fn test_address_bech32_custom_hrp() {
+        let addr = Address::from_bytes(&[42u8; ADDRESS_SIZE]).unwrap();
+        let encoded = addr.to_bech32_with_hrp("helatest");
+        assert!(encoded.starts_with("helatest1"));
+        assert_eq!(
+            Address::from_bech32_with_hrp(&encoded, "helatest").unwrap(),
+            addr,
+            "address should round-trip with a custom hrp"
+        );
+        assert!(matches!(
+            Address::from_bech32_with_hrp(&encoded, ADDRESS_BECH32_HRP).unwrap_err(),
+            Error::MalformedAddress,
+        ));
+    }
+
     #[test]
     fn test_address_from_bech32_invalid_variant() {
         let b = vec![42u8; ADDRESS_SIZE];
@@ -385,7 +464,7 @@ mod test {
     fn test_address_into_consensus_address() {
         let spec =
             SignatureAddressSpec::Ed25519("utrdHlX///////////////////////////////////8=".into());
-        let addr = Address::from_sigspec(&spec);
+        let addr = Address::from_sigspec(&spec).unwrap();
 
         let consensus_addr: ConsensusAddress = addr.into();
         assert_eq!(addr.to_bech32(), consensus_addr.to_bech32())
@@ -26,6 +26,9 @@ pub enum Role {
     BlacklistedUser,
 
     User,
+
+    // Auditors may request decryption of historical confidential transactions.
+    Auditor,
 }
 
 ///Sifei: Error.
@@ -58,6 +61,7 @@ impl Role {
             Role::WhitelistedUser => data[0] = 9,
             Role::BlacklistedUser => data[0] = 10,
             Role::User => data[0] = 11,
+            Role::Auditor => data[0] = 12,
         }
         data
     }
@@ -76,6 +80,7 @@ impl Role {
             Role::WhitelistedUser => String::from("WhitelistedUser"),
             Role::BlacklistedUser => String::from("BlacklistedUser"),
             Role::User => String::from("User"),
+            Role::Auditor => String::from("Auditor"),
         }
     }
 
@@ -101,6 +106,7 @@ impl Role {
             9 => Ok(Role::WhitelistedUser),
             10 => Ok(Role::BlacklistedUser),
             11 => Ok(Role::User),
+            12 => Ok(Role::Auditor),
             _ => Err(Error::MalformedRole),
         };
         role
@@ -148,6 +154,7 @@ impl cbor::Decode for Role {
                     9 => Ok(Role::WhitelistedUser),
                     10 => Ok(Role::BlacklistedUser),
                     11 => Ok(Role::User),
+                    12 => Ok(Role::Auditor),
                     _ => Err(cbor::DecodeError::UnexpectedType),
                 }
             }
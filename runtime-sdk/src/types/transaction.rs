@@ -170,6 +170,9 @@ pub struct AuthInfo {
     /// Latest round when the transaction is valid.
     #[cbor(optional)]
     pub not_after: Option<u64>,
+    /// Account that pays the transaction fee through a fee grant given to the fee payer.
+    #[cbor(optional)]
+    pub fee_granter: Option<Address>,
 }
 
 /// Transaction fee.
@@ -235,16 +238,25 @@ pub enum AddressSpec {
 
 impl AddressSpec {
     /// Derives the address.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the address can't be derived from a signature address specification, which is
+    /// rejected when verifying the transaction, see [`AddressSpec::batch`].
     pub fn address(&self) -> Address {
         match self {
-            AddressSpec::Signature(spec) => Address::from_sigspec(spec),
+            AddressSpec::Signature(spec) => {
+                Address::from_sigspec(spec).expect("signer address should be derivable")
+            }
             AddressSpec::Multisig(config) => Address::from_multisig(config.clone()),
             AddressSpec::Internal(caller) => caller.address(),
         }
     }
     pub fn eth_address(&self) -> Address {
         match self {
-            AddressSpec::Signature(spec) => Address::eth_from_sigspec(spec),
+            AddressSpec::Signature(spec) => {
+                Address::eth_from_sigspec(spec).expect("signer address should be derivable")
+            }
             AddressSpec::Multisig(config) => Address::from_multisig(config.clone()),
             AddressSpec::Internal(caller) => caller.address(),
         }
@@ -255,6 +267,7 @@ impl AddressSpec {
     pub fn batch(&self, auth_proof: &AuthProof) -> Result<(Vec<PublicKey>, Vec<Signature>), Error> {
         match (self, auth_proof) {
             (AddressSpec::Signature(spec), AuthProof::Signature(signature)) => {
+                Address::from_sigspec(spec).map_err(|e| Error::MalformedTransaction(e.into()))?;
                 Ok((vec![spec.public_key()], vec![signature.clone()]))
             }
             (AddressSpec::Multisig(config), AuthProof::Multisig(signature_set)) => Ok(config
@@ -54,6 +54,18 @@ pub enum Action {
     Whitelist,
     Blacklist,
     Config,
+    /// Pause an EVM contract so that it can no longer be called.
+    PauseContract,
+    /// Resume a previously paused EVM contract.
+    UnpauseContract,
+    /// Freeze an account so that it can no longer send or receive transfers.
+    Freeze,
+    /// Unfreeze a previously frozen account.
+    Unfreeze,
+    /// Replace the members and threshold of a multisig account.
+    SetMultisigMembers,
+    /// Transfer an amount from the common pool to an account.
+    SpendCommonPool,
 }
 
 impl Action {
@@ -68,6 +80,12 @@ impl Action {
             Action::Whitelist => [4],
             Action::Blacklist => [5],
             Action::Config => [6],
+            Action::PauseContract => [7],
+            Action::UnpauseContract => [8],
+            Action::Freeze => [9],
+            Action::Unfreeze => [10],
+            Action::SetMultisigMembers => [11],
+            Action::SpendCommonPool => [12],
         }
     }
 }
@@ -98,6 +116,12 @@ impl cbor::Decode for Action {
                     4 => Ok(Action::Whitelist),
                     5 => Ok(Action::Blacklist),
                     6 => Ok(Action::Config),
+                    7 => Ok(Action::PauseContract),
+                    8 => Ok(Action::UnpauseContract),
+                    9 => Ok(Action::Freeze),
+                    10 => Ok(Action::Unfreeze),
+                    11 => Ok(Action::SetMultisigMembers),
+                    12 => Ok(Action::SpendCommonPool),
                     _ => Err(cbor::DecodeError::UnexpectedType),
                 }
             }
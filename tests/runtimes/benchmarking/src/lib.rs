@@ -25,6 +25,7 @@ impl sdk::Runtime for Runtime {
     const VERSION: Version = sdk::version_from_cargo!();
 
     type Core = modules::core::Module<Config>;
+    type TransferHooks = ();
 
     #[allow(clippy::type_complexity)]
     type Modules = (
@@ -57,6 +58,7 @@ impl sdk::Runtime for Runtime {
                         mgp.insert(Denomination::NATIVE, 0);
                         mgp
                     },
+                    max_tx_events_size: 0,
                 },
             },
             modules::accounts::Genesis {
@@ -69,6 +71,7 @@ impl sdk::Runtime for Runtime {
                             modules::accounts::types::DenominationInfo {
                                 // Consistent with EVM ecosystem.
                                 decimals: 18,
+                                ..Default::default()
                             },
                         );
                         denomination_infos
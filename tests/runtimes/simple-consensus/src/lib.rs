@@ -21,6 +21,7 @@ impl sdk::Runtime for Runtime {
     };
 
     type Core = modules::core::Module<Config>;
+    type TransferHooks = ();
 
     type Modules = (
         modules::accounts::Module,
@@ -39,6 +40,7 @@ impl sdk::Runtime for Runtime {
                             "TEST".parse().unwrap(),
                             modules::accounts::types::DenominationInfo {
                                 decimals: 12, // Consensus layer has 9 and we use a scaling factor of 1000.
+                                ..Default::default()
                             },
                         );
                         denomination_infos
@@ -74,6 +76,7 @@ impl sdk::Runtime for Runtime {
                         mgp.insert(Denomination::NATIVE, 0);
                         mgp
                     },
+                    max_tx_events_size: 0,
                 },
             },
         )
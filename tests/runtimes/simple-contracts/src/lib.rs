@@ -31,6 +31,7 @@ impl sdk::Runtime for Runtime {
     };
 
     type Core = modules::core::Module<Config>;
+    type TransferHooks = ();
 
     type Modules = (
         modules::accounts::Module,
@@ -85,6 +86,7 @@ impl sdk::Runtime for Runtime {
                         mgp.insert(Denomination::NATIVE, 0);
                         mgp
                     },
+                    max_tx_events_size: 0,
                 },
             },
             contracts::Genesis {
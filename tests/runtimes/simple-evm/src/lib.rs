@@ -35,6 +35,7 @@ impl sdk::Runtime for Runtime {
     };
 
     type Core = modules::core::Module<Config>;
+    type TransferHooks = ();
 
     type Modules = (
         modules::accounts::Module,
@@ -90,12 +91,15 @@ impl sdk::Runtime for Runtime {
                         mgp.insert(Denomination::NATIVE, 0);
                         mgp
                     },
+                    max_tx_events_size: 0,
                 },
             },
             evm::Genesis {
                 parameters: evm::Parameters {
                     gas_costs: Default::default(),
+                    ..Default::default()
                 },
+                ..Default::default()
             },
         )
     }
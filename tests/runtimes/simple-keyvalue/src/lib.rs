@@ -37,6 +37,7 @@ impl sdk::Runtime for Runtime {
     };
 
     type Core = modules::core::Module<Config>;
+    type TransferHooks = ();
 
     type Modules = (
         keyvalue::Module,
@@ -67,7 +68,7 @@ impl sdk::Runtime for Runtime {
             },
             modules::accounts::Genesis {
                 parameters: modules::accounts::Parameters {
-                    gas_costs: modules::accounts::GasCosts { tx_transfer: 100,  tx_managest: 0},
+                    gas_costs: modules::accounts::GasCosts { tx_transfer: 100,  tx_managest: 0, tx_set_metadata: 0},
                     ..Default::default()
                 },
                 balances: {
@@ -134,12 +135,14 @@ impl sdk::Runtime for Runtime {
                         auth_signature: 10,
                         auth_multisig_signer: 10,
                         callformat_x25519_deoxysii: 50,
+                        event_byte: 0,
                     },
                     min_gas_price: {
                         let mut mgp = BTreeMap::new();
                         mgp.insert(Denomination::NATIVE, 0);
                         mgp
                     },
+                    max_tx_events_size: 0,
                 },
             },
         )
@@ -25,6 +25,7 @@ fn test_impl_for_tuple() {
                 mgp.insert(token::Denomination::NATIVE, 0);
                 mgp
             },
+            max_tx_events_size: 0,
         },
     );
     let dummy_bytes = b"you look, you die".to_vec();